@@ -6,11 +6,17 @@
 //! - Constraint satisfaction
 //! - Undo/redo recording
 
-use crate::Target;
+use crate::{PageRef, Target};
 use glam::Vec2;
 use gpui::Hsla;
-use node::{CrossAxisAlignment, LayoutDirection, MainAxisAlignment, Padding, ShapeKind, SizingMode};
-use serde::{Deserialize, Serialize};
+use node::{
+    AlignTo, Alignment, BlendMode, Constraint, CrossAxisAlignment, Fill, FlipAxis, Gradient, GradientKind,
+    GradientStop, GuideAxis, LayoutDirection, LineAlignment, MainAxisAlignment, Padding, Paint, PathData, ShapeKind,
+    DistributeAxis, SizingMode, Stroke, StrokeAlign, StrokeCap, StrokeJoin, Text, TextAlign, TextSizing,
+    DEFAULT_TIDY_SPACING,
+};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::path::{Path, PathBuf};
 
 /// A command that modifies Luna canvas state.
 ///
@@ -34,6 +40,14 @@ pub enum Command {
         stroke: Option<StrokeValue>,
         #[serde(default, skip_serializing_if = "Option::is_none")]
         corner_radius: Option<f32>,
+        /// Content and typography for Text shapes. Omitted fields take
+        /// their defaults.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        text: Option<Text>,
+        /// Geometry for Path shapes, relative to `position`. The shape's
+        /// box is fitted to the path, so `size` is ignored.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        path: Option<PathData>,
     },
 
     /// Duplicate target shapes with an offset.
@@ -93,21 +107,82 @@ pub enum Command {
         factor: Vec2,
     },
 
+    /// Rotate shapes about their own centers, clockwise in degrees.
+    /// With `relative`, adds to the current rotation instead of replacing it.
+    Rotate {
+        #[serde(default)]
+        target: Target,
+        degrees: f32,
+        #[serde(default)]
+        relative: bool,
+    },
+
+    /// Mirror shapes across their own centers.
+    Flip {
+        #[serde(default)]
+        target: Target,
+        axis: FlipAxis,
+    },
+
+    /// Line shapes up by an edge or center, against the box around them,
+    /// each shape's parent frame, or a key shape that stays put. Shapes
+    /// placed by a layout don't move.
+    Align {
+        #[serde(default)]
+        target: Target,
+        alignment: Alignment,
+        #[serde(default)]
+        relative_to: AlignTo,
+    },
+
+    /// Even out the spacing between shapes along an axis, keeping the
+    /// outermost where they are. Shapes placed by a layout don't move.
+    Distribute {
+        #[serde(default)]
+        target: Target,
+        axis: DistributeAxis,
+    },
+
+    /// Arrange shapes into a grid in reading order, from the top-left of
+    /// the box around them. Shapes placed by a layout don't move.
+    Tidy {
+        #[serde(default)]
+        target: Target,
+        #[serde(default = "default_tidy_spacing")]
+        spacing: f32,
+    },
+
     // === Style ===
-    /// Set fill color.
+    /// Set fill: a color or a gradient, in place of any others. None
+    /// removes them all.
     SetFill {
         #[serde(default)]
         target: Target,
-        fill: Option<ColorValue>,
+        fill: Option<FillValue>,
+    },
+
+    /// Set every fill, bottom first.
+    SetFills {
+        #[serde(default)]
+        target: Target,
+        fills: Vec<FillLayerValue>,
     },
 
-    /// Set stroke style.
+    /// Set stroke style, in place of any other strokes. None removes them
+    /// all.
     SetStroke {
         #[serde(default)]
         target: Target,
         stroke: Option<StrokeValue>,
     },
 
+    /// Set every stroke, bottom first.
+    SetStrokes {
+        #[serde(default)]
+        target: Target,
+        strokes: Vec<StrokeValue>,
+    },
+
     /// Set corner radius (rectangles).
     SetCornerRadius {
         #[serde(default)]
@@ -115,6 +190,46 @@ pub enum Command {
         radius: f32,
     },
 
+    /// Set how much shapes show, from 0 to 1, each with everything inside
+    /// it as one group.
+    SetOpacity {
+        #[serde(default)]
+        target: Target,
+        opacity: f32,
+    },
+
+    /// Set how shapes mix with what's beneath them. The canvas paints every
+    /// mode as normal; exports blend.
+    SetBlendMode {
+        #[serde(default)]
+        target: Target,
+        blend_mode: BlendMode,
+    },
+
+    // === Text ===
+    /// Set the content and typography of text shapes.
+    /// Omitted fields are left unchanged; non-text shapes are skipped.
+    SetText {
+        #[serde(default)]
+        target: Target,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        content: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        font_family: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        font_weight: Option<u16>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        font_size: Option<f32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        line_height: Option<f32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        letter_spacing: Option<f32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        align: Option<TextAlign>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        sizing: Option<TextSizing>,
+    },
+
     // === Hierarchy ===
     /// Add a shape as a child of a frame.
     /// Converts the child's position to relative coordinates.
@@ -130,6 +245,41 @@ pub enum Command {
         target: Target,
     },
 
+    /// Move shapes to a position in the stacking order among their
+    /// siblings, counted from the back among the siblings that aren't
+    /// moving. An index past the end puts them in front. Shapes keep their
+    /// parent and their order relative to each other.
+    Reorder {
+        #[serde(default)]
+        target: Target,
+        index: usize,
+    },
+
+    /// Move shapes one step toward the front, past the next sibling that
+    /// isn't moving.
+    BringForward {
+        #[serde(default)]
+        target: Target,
+    },
+
+    /// Move shapes one step toward the back.
+    SendBackward {
+        #[serde(default)]
+        target: Target,
+    },
+
+    /// Move shapes in front of all their siblings.
+    BringToFront {
+        #[serde(default)]
+        target: Target,
+    },
+
+    /// Move shapes behind all their siblings.
+    SendToBack {
+        #[serde(default)]
+        target: Target,
+    },
+
     /// Set whether a frame clips its children.
     SetClipChildren {
         #[serde(default)]
@@ -137,6 +287,62 @@ pub enum Command {
         clip: bool,
     },
 
+    // === Layers ===
+    /// Rename shapes. Names needn't be unique.
+    Rename {
+        #[serde(default)]
+        target: Target,
+        name: String,
+    },
+
+    /// Show or hide shapes. Hidden shapes aren't drawn, picked, laid out or
+    /// exported, and leave the selection. `Target::All` reaches hidden and
+    /// locked shapes here.
+    SetVisible {
+        #[serde(default)]
+        target: Target,
+        visible: bool,
+    },
+
+    /// Lock or unlock shapes. Locked shapes can't be picked on the canvas.
+    /// `Target::All` reaches hidden and locked shapes here.
+    SetLocked {
+        #[serde(default)]
+        target: Target,
+        locked: bool,
+    },
+
+    // === Components ===
+    /// Make frames main components. Shapes that aren't frames, or are
+    /// already part of a component, are skipped.
+    CreateComponent {
+        #[serde(default)]
+        target: Target,
+    },
+
+    /// Place an instance of a main component. It's put beside the main
+    /// without a position, and selected.
+    CreateInstance {
+        component: node::ShapeId,
+        /// Top-left corner of the instance.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        position: Option<Vec2>,
+    },
+
+    /// Unlink instances from their mains, leaving plain shapes that look
+    /// the same.
+    DetachInstance {
+        #[serde(default)]
+        target: Target,
+    },
+
+    /// Drop the fill, stroke and text overrides of shapes in instances, so
+    /// they follow their main again.
+    ResetOverrides {
+        #[serde(default)]
+        target: Target,
+    },
+
     // === Layout ===
     /// Enable or disable autolayout on a frame.
     /// When layout is Some, children are automatically positioned.
@@ -178,7 +384,20 @@ pub enum Command {
         cross_axis: Option<CrossAxisAlignment>,
     },
 
-    /// Set sizing mode for a child shape in a layout.
+    /// Set line wrapping for a layout frame.
+    SetLayoutWrap {
+        #[serde(default)]
+        target: Target,
+        wrap: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cross_gap: Option<f32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        line_alignment: Option<LineAlignment>,
+    },
+
+    /// Set sizing mode and size limits for a child shape in a layout.
+    ///
+    /// Omitted fields are left unchanged. A limit set to `null` is removed.
     SetChildSizing {
         #[serde(default)]
         target: Target,
@@ -186,6 +405,26 @@ pub enum Command {
         width: Option<SizingMode>,
         #[serde(default, skip_serializing_if = "Option::is_none")]
         height: Option<SizingMode>,
+        #[serde(default, deserialize_with = "present", skip_serializing_if = "Option::is_none")]
+        min_width: Option<Option<f32>>,
+        #[serde(default, deserialize_with = "present", skip_serializing_if = "Option::is_none")]
+        max_width: Option<Option<f32>>,
+        #[serde(default, deserialize_with = "present", skip_serializing_if = "Option::is_none")]
+        min_height: Option<Option<f32>>,
+        #[serde(default, deserialize_with = "present", skip_serializing_if = "Option::is_none")]
+        max_height: Option<Option<f32>>,
+    },
+
+    /// Take a child out of its parent's layout flow (or put it back), and
+    /// set how it follows the parent's size while absolute.
+    SetAbsolute {
+        #[serde(default)]
+        target: Target,
+        absolute: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        horizontal: Option<Constraint>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        vertical: Option<Constraint>,
     },
 
     // === Canvas ===
@@ -202,11 +441,114 @@ pub enum Command {
     /// Reset viewport to default.
     ResetView,
 
+    /// Zoom to fit every shown shape in the view.
+    ZoomToFit,
+
+    /// Zoom to fit target shapes in the view, centered on them.
+    ZoomToShapes {
+        #[serde(default)]
+        target: Target,
+    },
+
+    /// Pan just far enough to show target shapes, keeping the zoom.
+    ScrollIntoView {
+        #[serde(default)]
+        target: Target,
+    },
+
+    /// Set the zoom, where 1.0 is 100%, within the zoom limits. `center`,
+    /// in screen pixels, stays put; the middle of the view does without it.
+    SetZoom {
+        zoom: f32,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        center: Option<Vec2>,
+    },
+
+    /// Change what moved, resized and drawn shapes snap to. Omitted fields
+    /// are left unchanged. A grid set to `null` is removed.
+    SetSnapping {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        enabled: Option<bool>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        to_shapes: Option<bool>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        to_guides: Option<bool>,
+        #[serde(default, deserialize_with = "present", skip_serializing_if = "Option::is_none")]
+        grid: Option<Option<f32>>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        to_pixels: Option<bool>,
+        /// In screen pixels.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        threshold: Option<f32>,
+    },
+
+    // === Guides ===
+    /// Add a guide to the active page, after its other guides.
+    AddGuide { axis: GuideAxis, position: f32 },
+
+    /// Move guide `index` on the active page to `position`.
+    MoveGuide { index: usize, position: f32 },
+
+    /// Remove guide `index` from the active page. Later guides move down an
+    /// index.
+    RemoveGuide { index: usize },
+
+    /// Remove every guide from the active page.
+    ClearGuides,
+
     // === Tool ===
     /// Switch the active tool.
     SetTool { tool: ToolKind },
 
-    // === History (future) ===
+    // === Pages ===
+    /// Add an empty page after the last and switch to it. Unnamed pages are
+    /// numbered after the existing ones.
+    AddPage {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+    },
+
+    /// Make a page the one shapes are read from and edited on.
+    SwitchPage { page: PageRef },
+
+    /// Rename a page.
+    RenamePage { page: PageRef, name: String },
+
+    /// Move a page to position `index` in the page list.
+    MovePage { page: PageRef, index: usize },
+
+    /// Delete a page and its shapes. The last page can't be deleted.
+    DeletePage { page: PageRef },
+
+    // === Import ===
+    /// Add the shapes drawn by an SVG document, in a frame the size of its
+    /// viewport, and select that frame. Parts of the document that can't
+    /// be imported are listed in the result's warnings.
+    ImportSvg {
+        /// The document's source text.
+        svg: String,
+        /// Where the frame's center goes. When omitted, the document keeps
+        /// its own coordinates, its viewport's top-left at the origin.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        center: Option<Vec2>,
+    },
+
+    // === Export ===
+    /// Write target shapes, with everything inside them, to an image file.
+    /// Target all shapes to export the whole page.
+    Export {
+        #[serde(default)]
+        target: Target,
+        path: PathBuf,
+        /// Taken from the path's extension when omitted.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        format: Option<ExportFormat>,
+        /// Pixels per canvas unit, for PNG.
+        #[serde(default = "default_export_scale")]
+        scale: f32,
+    },
+
+    // === History ===
     /// Undo the last command.
     Undo,
 
@@ -218,6 +560,39 @@ pub enum Command {
     Batch { commands: Vec<Command> },
 }
 
+impl Command {
+    /// Whether this command can change the document and so belongs in undo
+    /// history. Selection, viewport and tool changes are not recorded, and
+    /// neither are page changes: each page keeps its own history of edits
+    /// to its shapes.
+    pub fn is_undoable(&self) -> bool {
+        match self {
+            Command::Select { .. }
+            | Command::ClearSelection
+            | Command::SelectAll
+            | Command::Pan { .. }
+            | Command::Zoom { .. }
+            | Command::ResetView
+            | Command::ZoomToFit
+            | Command::ZoomToShapes { .. }
+            | Command::ScrollIntoView { .. }
+            | Command::SetZoom { .. }
+            | Command::SetSnapping { .. }
+            | Command::SetTool { .. }
+            | Command::AddPage { .. }
+            | Command::SwitchPage { .. }
+            | Command::RenamePage { .. }
+            | Command::MovePage { .. }
+            | Command::DeletePage { .. }
+            | Command::Export { .. }
+            | Command::Undo
+            | Command::Redo => false,
+            Command::Batch { commands } => commands.iter().any(Command::is_undoable),
+            _ => true,
+        }
+    }
+}
+
 /// Color value for fill/stroke.
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 #[serde(untagged)]
@@ -303,11 +678,113 @@ impl ColorValue {
     }
 }
 
-/// Stroke style value.
+/// Fill value: a color, or a gradient.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum FillValue {
+    Color(ColorValue),
+    Gradient(GradientValue),
+}
+
+/// Gradient fill value. Handles are placed relative to the shape's box,
+/// (0, 0) at its top-left and (1, 1) at its bottom-right, and default to
+/// running left to right across it, or out from or around its center.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GradientValue {
+    pub gradient: GradientKind,
+    pub stops: Vec<GradientStopValue>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start: Option<Vec2>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<Vec2>,
+}
+
+/// A gradient's color at an offset from 0 at its start to 1 at its end.
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct GradientStopValue {
+    pub offset: f32,
+    pub color: ColorValue,
+}
+
+impl FillValue {
+    /// Convert to a shape's fill. Stops may come in any order, but there
+    /// must be at least one.
+    pub fn to_fill(&self) -> Result<Fill, String> {
+        let value = match self {
+            FillValue::Color(color) => return Ok(Fill::new(color.to_hsla())),
+            FillValue::Gradient(value) => value,
+        };
+        if value.stops.is_empty() {
+            return Err("A gradient needs at least one stop".to_string());
+        }
+        let mut gradient = Gradient::new(value.gradient, gpui::black(), gpui::white());
+        gradient.start = value.start.unwrap_or(gradient.start);
+        gradient.end = value.end.unwrap_or(gradient.end);
+        gradient.stops = value
+            .stops
+            .iter()
+            .map(|stop| GradientStop::new(stop.offset, stop.color.to_hsla()))
+            .collect();
+        gradient.sort_stops();
+        Ok(Fill::from(Paint::Gradient(gradient)))
+    }
+}
+
+/// One of a stack of fills.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FillLayerValue {
+    pub paint: FillValue,
+    #[serde(default = "visible_by_default")]
+    pub visible: bool,
+    /// From 0 to 1, multiplying the paint's own alpha.
+    #[serde(default = "opaque")]
+    pub opacity: f32,
+}
+
+impl FillLayerValue {
+    pub fn to_fill(&self) -> Result<Fill, String> {
+        let mut fill = self.paint.to_fill()?;
+        fill.visible = self.visible;
+        fill.opacity = self.opacity.clamp(0.0, 1.0);
+        Ok(fill)
+    }
+}
+
+/// Stroke style value. Without an alignment, strokes go where they do on
+/// the shape's kind by default: inside boxes, and centered on paths.
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct StrokeValue {
     pub color: ColorValue,
     pub width: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub align: Option<StrokeAlign>,
+    /// Lengths of dashes and the gaps between them, in turn. Empty for a
+    /// solid line.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dash: Vec<f32>,
+    #[serde(default)]
+    pub cap: StrokeCap,
+    #[serde(default)]
+    pub join: StrokeJoin,
+    #[serde(default = "visible_by_default")]
+    pub visible: bool,
+    /// From 0 to 1, multiplying the color's own alpha.
+    #[serde(default = "opaque")]
+    pub opacity: f32,
+}
+
+impl StrokeValue {
+    /// Convert to a stroke on a shape of `kind`.
+    pub fn to_stroke(&self, kind: ShapeKind) -> Stroke {
+        let align = self.align.unwrap_or(StrokeAlign::for_kind(kind));
+        let mut stroke = Stroke::new(self.color.to_hsla(), self.width).with_align(align);
+        stroke.dash = self.dash.clone();
+        stroke.cap = self.cap;
+        stroke.join = self.join;
+        stroke.visible = self.visible;
+        stroke.opacity = self.opacity.clamp(0.0, 1.0);
+        stroke
+    }
 }
 
 /// Layout configuration value for API commands.
@@ -328,6 +805,15 @@ pub struct LayoutValue {
     /// Padding inside the frame.
     #[serde(default)]
     pub padding: Padding,
+    /// Whether children wrap onto new lines.
+    #[serde(default)]
+    pub wrap: bool,
+    /// Gap between wrapped lines.
+    #[serde(default)]
+    pub cross_gap: f32,
+    /// Distribution of wrapped lines on the cross axis.
+    #[serde(default)]
+    pub line_alignment: LineAlignment,
 }
 
 impl Default for LayoutValue {
@@ -338,6 +824,9 @@ impl Default for LayoutValue {
             cross_axis_alignment: CrossAxisAlignment::Start,
             gap: 0.0,
             padding: Padding::default(),
+            wrap: false,
+            cross_gap: 0.0,
+            line_alignment: LineAlignment::Start,
         }
     }
 }
@@ -350,6 +839,9 @@ impl From<LayoutValue> for node::FrameLayout {
             cross_axis_alignment: value.cross_axis_alignment,
             gap: value.gap,
             padding: value.padding,
+            wrap: value.wrap,
+            cross_gap: value.cross_gap,
+            line_alignment: value.line_alignment,
         }
     }
 }
@@ -363,13 +855,60 @@ pub enum ToolKind {
     Rectangle,
     Ellipse,
     Frame,
-    // Future: Text, Pen, etc.
+    Text,
+    Pen,
+}
+
+/// Image formats for [`Command::Export`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    Svg,
+    Png,
+}
+
+impl ExportFormat {
+    /// The format named by `path`'s extension, defaulting to SVG.
+    pub fn for_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("png") => Self::Png,
+            _ => Self::Svg,
+        }
+    }
 }
 
 fn default_duplicate_offset() -> Vec2 {
     Vec2::new(20.0, 20.0)
 }
 
+fn default_export_scale() -> f32 {
+    1.0
+}
+
+fn visible_by_default() -> bool {
+    true
+}
+
+fn opaque() -> f32 {
+    1.0
+}
+
+fn default_tidy_spacing() -> f32 {
+    DEFAULT_TIDY_SPACING
+}
+
+/// Deserialize a field that is present (even as `null`) into `Some`.
+///
+/// Paired with `#[serde(default)]`, this tells "leave unchanged" (missing)
+/// apart from "clear" (`null`).
+fn present<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
 /// Result of executing a command.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(tag = "status", rename_all = "snake_case")]
@@ -385,6 +924,10 @@ pub enum CommandResult {
         /// IDs of shapes deleted, if any.
         #[serde(default, skip_serializing_if = "Vec::is_empty")]
         deleted: Vec<node::ShapeId>,
+        /// What the command couldn't do in full, such as parts of an
+        /// imported file that were skipped.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        warnings: Vec<String>,
     },
     /// Command failed.
     Error {
@@ -398,6 +941,7 @@ impl CommandResult {
             created: vec![],
             modified: vec![],
             deleted: vec![],
+            warnings: vec![],
         }
     }
 
@@ -406,6 +950,7 @@ impl CommandResult {
             created: ids,
             modified: vec![],
             deleted: vec![],
+            warnings: vec![],
         }
     }
 
@@ -414,6 +959,7 @@ impl CommandResult {
             created: vec![],
             modified: ids,
             deleted: vec![],
+            warnings: vec![],
         }
     }
 
@@ -422,6 +968,7 @@ impl CommandResult {
             created: vec![],
             modified: vec![],
             deleted: ids,
+            warnings: vec![],
         }
     }
 
@@ -435,6 +982,8 @@ impl CommandResult {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ShapeQuery;
+    use node::HandleMode;
 
     #[test]
     fn create_shape_serializes_with_type_field() {
@@ -445,6 +994,8 @@ mod tests {
             fill: None,
             stroke: None,
             corner_radius: None,
+            text: None,
+            path: None,
         };
         let json: serde_json::Value = serde_json::to_value(&cmd).unwrap();
 
@@ -461,6 +1012,8 @@ mod tests {
             fill: None,
             stroke: None,
             corner_radius: None,
+            text: None,
+            path: None,
         };
         let json: serde_json::Value = serde_json::to_value(&cmd).unwrap();
 
@@ -477,6 +1030,8 @@ mod tests {
             fill: None,
             stroke: None,
             corner_radius: None,
+            text: None,
+            path: None,
         };
         let json: serde_json::Value = serde_json::to_value(&cmd).unwrap();
 
@@ -493,6 +1048,8 @@ mod tests {
             fill: None,
             stroke: None,
             corner_radius: None,
+            text: None,
+            path: None,
         };
         let json: serde_json::Value = serde_json::to_value(&cmd).unwrap();
 
@@ -500,6 +1057,7 @@ mod tests {
         assert!(json.get("fill").is_none());
         assert!(json.get("stroke").is_none());
         assert!(json.get("corner_radius").is_none());
+        assert!(json.get("text").is_none());
     }
 
     #[test]
@@ -541,6 +1099,8 @@ mod tests {
             fill: Some(ColorValue::Hex(HexColor { r: 255, g: 128, b: 0 })),
             stroke: None,
             corner_radius: None,
+            text: None,
+            path: None,
         };
         let json: serde_json::Value = serde_json::to_value(&cmd).unwrap();
 
@@ -575,7 +1135,7 @@ mod tests {
     fn command_roundtrips_through_json() {
         let original = Command::SetFill {
             target: Target::Selection,
-            fill: Some(ColorValue::Hsla { h: 0.5, s: 1.0, l: 0.5, a: 1.0 }),
+            fill: Some(FillValue::Color(ColorValue::Hsla { h: 0.5, s: 1.0, l: 0.5, a: 1.0 })),
         };
         let json = serde_json::to_string(&original).unwrap();
         let restored: Command = serde_json::from_str(&json).unwrap();
@@ -585,4 +1145,396 @@ mod tests {
         let restored_json = serde_json::to_value(&restored).unwrap();
         assert_eq!(original_json, restored_json);
     }
+
+    #[test]
+    fn gradient_fills_deserialize_alongside_colors() {
+        let json = r##"{
+            "type": "set_fill",
+            "fill": {
+                "gradient": "radial",
+                "stops": [
+                    { "offset": 1, "color": "#0000FF" },
+                    { "offset": 0, "color": { "h": 0, "s": 1, "l": 0.5, "a": 1 } }
+                ],
+                "end": [0.5, 1]
+            }
+        }"##;
+        let Command::SetFill { fill: Some(fill), .. } = serde_json::from_str(json).unwrap() else {
+            panic!("Expected SetFill with a fill");
+        };
+        let fill = fill.to_fill().unwrap();
+        let gradient = fill.gradient().unwrap();
+        assert_eq!(gradient.kind, GradientKind::Radial);
+        assert_eq!(gradient.start, Vec2::splat(0.5));
+        assert_eq!(gradient.end, Vec2::new(0.5, 1.0));
+        assert_eq!(gradient.stops[0].color, gpui::hsla(0.0, 1.0, 0.5, 1.0));
+
+        let color: Command = serde_json::from_str(r##"{"type": "set_fill", "fill": "#FF0000"}"##).unwrap();
+        assert!(matches!(color, Command::SetFill { fill: Some(FillValue::Color(_)), .. }));
+    }
+
+    #[test]
+    fn fill_and_stroke_stacks_fill_in_defaults() {
+        let json = r##"{
+            "type": "set_fills",
+            "fills": [{ "paint": "#FF0000" }, { "paint": "#0000FF", "opacity": 0.5 }]
+        }"##;
+        let Command::SetFills { fills, .. } = serde_json::from_str(json).unwrap() else {
+            panic!("Expected SetFills");
+        };
+        let fills: Vec<Fill> = fills.iter().map(|fill| fill.to_fill().unwrap()).collect();
+        assert!(fills[0].visible && fills[0].opacity == 1.0);
+        assert_eq!(fills[1].opacity, 0.5);
+
+        let json = r##"{
+            "type": "set_strokes",
+            "strokes": [
+                { "color": "#000000", "width": 1 },
+                { "color": "#000000", "width": 2, "align": "outside", "dash": [4, 2], "cap": "round" }
+            ]
+        }"##;
+        let Command::SetStrokes { strokes, .. } = serde_json::from_str(json).unwrap() else {
+            panic!("Expected SetStrokes");
+        };
+        // Left unaligned, a stroke goes where its shape's kind puts them
+        assert_eq!(strokes[0].to_stroke(ShapeKind::Path).align, StrokeAlign::Center);
+        assert_eq!(strokes[0].to_stroke(ShapeKind::Ellipse).align, StrokeAlign::Inside);
+        let dashed = strokes[1].to_stroke(ShapeKind::Path);
+        assert_eq!(dashed.align, StrokeAlign::Outside);
+        assert_eq!(dashed.dash, [4.0, 2.0]);
+        assert_eq!((dashed.cap, dashed.join), (StrokeCap::Round, StrokeJoin::Miter));
+    }
+
+    #[test]
+    fn view_commands_are_not_undoable() {
+        assert!(!Command::Pan { delta: Vec2::ZERO }.is_undoable());
+        assert!(!Command::SelectAll.is_undoable());
+        assert!(!Command::Undo.is_undoable());
+        assert!(Command::Delete { target: Target::Selection }.is_undoable());
+    }
+
+    #[test]
+    fn export_infers_format_and_scale() {
+        let json = r#"{"type": "export", "target": "all", "path": "page.PNG"}"#;
+        let cmd: Command = serde_json::from_str(json).unwrap();
+        assert!(!cmd.is_undoable());
+
+        match cmd {
+            Command::Export { path, format, scale, .. } => {
+                assert_eq!(format, None);
+                assert_eq!(ExportFormat::for_path(&path), ExportFormat::Png);
+                assert_eq!(scale, 1.0);
+            }
+            _ => panic!("Expected Export command"),
+        }
+        assert_eq!(ExportFormat::for_path(Path::new("page")), ExportFormat::Svg);
+    }
+
+    #[test]
+    fn import_svg_center_is_optional() {
+        let json = r#"{"type": "import_svg", "svg": "<svg/>"}"#;
+        let cmd: Command = serde_json::from_str(json).unwrap();
+        assert!(cmd.is_undoable());
+        match cmd {
+            Command::ImportSvg { svg, center } => {
+                assert_eq!(svg, "<svg/>");
+                assert_eq!(center, None);
+            }
+            _ => panic!("Expected ImportSvg command"),
+        }
+    }
+
+    #[test]
+    fn snapping_grid_can_be_left_or_removed() {
+        let json = r#"{"type": "set_snapping", "enabled": false}"#;
+        let cmd: Command = serde_json::from_str(json).unwrap();
+        assert!(!cmd.is_undoable());
+        assert!(matches!(cmd, Command::SetSnapping { enabled: Some(false), grid: None, .. }));
+
+        let json = r#"{"type": "set_snapping", "grid": null}"#;
+        let cmd: Command = serde_json::from_str(json).unwrap();
+        assert!(matches!(cmd, Command::SetSnapping { enabled: None, grid: Some(None), .. }));
+    }
+
+    #[test]
+    fn guides_are_addressed_by_index() {
+        let json = r#"{"type": "add_guide", "axis": "vertical", "position": 120}"#;
+        let cmd: Command = serde_json::from_str(json).unwrap();
+        assert!(cmd.is_undoable());
+        assert!(matches!(cmd, Command::AddGuide { axis: GuideAxis::Vertical, .. }));
+
+        let json = r#"{"type": "move_guide", "index": 1, "position": -40}"#;
+        let cmd: Command = serde_json::from_str(json).unwrap();
+        assert!(matches!(cmd, Command::MoveGuide { index: 1, position } if position == -40.0));
+    }
+
+    #[test]
+    fn warnings_are_omitted_when_empty() {
+        let json = serde_json::to_value(CommandResult::success()).unwrap();
+        assert!(json.get("warnings").is_none());
+    }
+
+    #[test]
+    fn pages_are_referenced_by_index_or_name() {
+        let json = r#"{"type": "switch_page", "page": 2}"#;
+        let cmd: Command = serde_json::from_str(json).unwrap();
+        assert!(matches!(cmd, Command::SwitchPage { page: PageRef::Index(2) }));
+
+        let json = r#"{"type": "rename_page", "page": "Page 1", "name": "Cover"}"#;
+        let cmd: Command = serde_json::from_str(json).unwrap();
+        match cmd {
+            Command::RenamePage { page: PageRef::Name(page), name } => {
+                assert_eq!(page, "Page 1");
+                assert_eq!(name, "Cover");
+            }
+            _ => panic!("Expected RenamePage command"),
+        }
+
+        let cmd: Command = serde_json::from_str(r#"{"type": "add_page"}"#).unwrap();
+        assert!(matches!(cmd, Command::AddPage { name: None }));
+        assert!(!cmd.is_undoable());
+    }
+
+    #[test]
+    fn rename_targets_shapes_by_name() {
+        let json = r#"{"type": "rename", "target": {"query": {"by_name": {"prefix": "Rectangle"}}}, "name": "Card"}"#;
+        let cmd: Command = serde_json::from_str(json).unwrap();
+        match cmd {
+            Command::Rename { target: Target::Query(ShapeQuery::ByName(pattern)), name } => {
+                assert!(pattern.matches("Rectangle 3"));
+                assert_eq!(name, "Card");
+            }
+            _ => panic!("Expected Rename command"),
+        }
+        assert!(serde_json::from_str::<Command>(
+            r#"{"type": "rename", "target": {"query": {"by_name": {"regex": "["}}}, "name": "Card"}"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn reordering_defaults_to_the_selection() {
+        let cmd: Command = serde_json::from_str(r#"{"type": "reorder", "index": 2}"#).unwrap();
+        assert!(matches!(cmd, Command::Reorder { target: Target::Selection, index: 2 }));
+        assert!(cmd.is_undoable());
+
+        let cmd: Command = serde_json::from_str(r#"{"type": "bring_to_front", "target": "all"}"#).unwrap();
+        assert!(matches!(cmd, Command::BringToFront { target: Target::All }));
+        let cmd: Command = serde_json::from_str(r#"{"type": "send_backward"}"#).unwrap();
+        assert!(matches!(cmd, Command::SendBackward { target: Target::Selection }));
+    }
+
+    #[test]
+    fn visibility_and_locking_default_to_the_selection() {
+        let cmd: Command = serde_json::from_str(r#"{"type": "set_visible", "visible": false}"#).unwrap();
+        assert!(matches!(cmd, Command::SetVisible { target: Target::Selection, visible: false }));
+        assert!(cmd.is_undoable());
+
+        let json = r#"{"type": "set_locked", "target": "all", "locked": true}"#;
+        let cmd: Command = serde_json::from_str(json).unwrap();
+        assert!(matches!(cmd, Command::SetLocked { target: Target::All, locked: true }));
+    }
+
+    #[test]
+    fn opacity_and_blend_mode_default_to_the_selection() {
+        let cmd: Command = serde_json::from_str(r#"{"type": "set_opacity", "opacity": 0.4}"#).unwrap();
+        assert!(matches!(cmd, Command::SetOpacity { target: Target::Selection, opacity } if opacity == 0.4));
+        assert!(cmd.is_undoable());
+
+        let json = r#"{"type": "set_blend_mode", "target": "all", "blend_mode": "color_dodge"}"#;
+        let cmd: Command = serde_json::from_str(json).unwrap();
+        assert!(matches!(
+            cmd,
+            Command::SetBlendMode { target: Target::All, blend_mode: BlendMode::ColorDodge }
+        ));
+    }
+
+    #[test]
+    fn create_instance_position_is_optional() {
+        let id = node::ShapeId::new();
+        let json = format!(r#"{{"type": "create_instance", "component": "{}"}}"#, id.to_uuid_string());
+        let cmd: Command = serde_json::from_str(&json).unwrap();
+        match cmd {
+            Command::CreateInstance { component, position } => {
+                assert_eq!(component, id);
+                assert!(position.is_none());
+            }
+            _ => panic!("Expected CreateInstance command"),
+        }
+
+        let cmd: Command = serde_json::from_str(r#"{"type": "detach_instance"}"#).unwrap();
+        assert!(matches!(cmd, Command::DetachInstance { target: Target::Selection }));
+        assert!(cmd.is_undoable());
+    }
+
+    #[test]
+    fn batch_is_undoable_if_any_command_is() {
+        let view_only = Command::Batch {
+            commands: vec![Command::ResetView, Command::ClearSelection],
+        };
+        assert!(!view_only.is_undoable());
+
+        let mixed = Command::Batch {
+            commands: vec![Command::ResetView, Command::Delete { target: Target::All }],
+        };
+        assert!(mixed.is_undoable());
+    }
+
+    #[test]
+    fn framing_defaults_to_the_selection() {
+        let cmd: Command = serde_json::from_str(r#"{"type": "zoom_to_shapes"}"#).unwrap();
+        assert!(matches!(cmd, Command::ZoomToShapes { target: Target::Selection }));
+        assert!(!cmd.is_undoable());
+        let cmd: Command = serde_json::from_str(r#"{"type": "scroll_into_view", "target": "all"}"#).unwrap();
+        assert!(matches!(cmd, Command::ScrollIntoView { target: Target::All }));
+
+        let cmd: Command = serde_json::from_str(r#"{"type": "set_zoom", "zoom": 2}"#).unwrap();
+        assert!(matches!(cmd, Command::SetZoom { zoom, center: None } if zoom == 2.0));
+    }
+
+    #[test]
+    fn child_sizing_distinguishes_missing_and_null_limits() {
+        let json = r#"{"type": "set_child_sizing", "min_width": 40, "max_width": null}"#;
+        let cmd: Command = serde_json::from_str(json).unwrap();
+
+        match cmd {
+            Command::SetChildSizing { min_width, max_width, min_height, .. } => {
+                assert_eq!(min_width, Some(Some(40.0)));
+                assert_eq!(max_width, Some(None));
+                assert_eq!(min_height, None);
+            }
+            _ => panic!("Expected SetChildSizing command"),
+        }
+    }
+
+    #[test]
+    fn layout_value_wrap_fields_are_optional() {
+        let json = r#"{"direction": "row", "gap": 8}"#;
+        let layout: node::FrameLayout = serde_json::from_str::<LayoutValue>(json).unwrap().into();
+
+        assert!(!layout.wrap);
+        assert_eq!(layout.gap, 8.0);
+    }
+
+    #[test]
+    fn set_absolute_deserializes_constraints() {
+        let json = r#"{"type": "set_absolute", "absolute": true, "horizontal": "end"}"#;
+        let cmd: Command = serde_json::from_str(json).unwrap();
+
+        match cmd {
+            Command::SetAbsolute { absolute, horizontal, vertical, .. } => {
+                assert!(absolute);
+                assert_eq!(horizontal, Some(Constraint::End));
+                assert_eq!(vertical, None);
+            }
+            _ => panic!("Expected SetAbsolute command"),
+        }
+    }
+
+    #[test]
+    fn rotate_defaults_to_absolute_and_flip_takes_snake_case_axis() {
+        let cmd: Command = serde_json::from_str(r#"{"type": "rotate", "degrees": 45}"#).unwrap();
+        match cmd {
+            Command::Rotate { target, degrees, relative } => {
+                assert!(matches!(target, Target::Selection));
+                assert_eq!(degrees, 45.0);
+                assert!(!relative);
+            }
+            _ => panic!("Expected Rotate command"),
+        }
+
+        let cmd: Command = serde_json::from_str(r#"{"type": "flip", "axis": "vertical"}"#).unwrap();
+        assert!(matches!(cmd, Command::Flip { axis: FlipAxis::Vertical, .. }));
+    }
+
+    #[test]
+    fn align_defaults_to_the_selection_bounds() {
+        let cmd: Command = serde_json::from_str(r#"{"type": "align", "alignment": "center"}"#).unwrap();
+        assert!(matches!(
+            cmd,
+            Command::Align {
+                target: Target::Selection,
+                alignment: Alignment::Center,
+                relative_to: AlignTo::Selection,
+            }
+        ));
+
+        let key = node::ShapeId::new();
+        let json = format!(
+            r#"{{"type": "align", "alignment": "top", "relative_to": {{"shape": "{}"}}}}"#,
+            key.to_uuid_string()
+        );
+        let cmd: Command = serde_json::from_str(&json).unwrap();
+        assert!(matches!(cmd, Command::Align { relative_to: AlignTo::Shape(id), .. } if id == key));
+
+        let cmd: Command = serde_json::from_str(r#"{"type": "tidy"}"#).unwrap();
+        assert!(matches!(cmd, Command::Tidy { spacing, .. } if spacing == DEFAULT_TIDY_SPACING));
+        let cmd: Command = serde_json::from_str(r#"{"type": "distribute", "axis": "vertical"}"#).unwrap();
+        assert!(matches!(cmd, Command::Distribute { axis: DistributeAxis::Vertical, .. }));
+    }
+
+    #[test]
+    fn create_text_takes_partial_typography() {
+        let json = r#"{
+            "type": "create_shape",
+            "kind": "Text",
+            "position": [0, 0],
+            "size": [0, 0],
+            "text": {"content": "Hello", "font_size": 32}
+        }"#;
+        let cmd: Command = serde_json::from_str(json).unwrap();
+
+        match cmd {
+            Command::CreateShape { kind, text: Some(text), .. } => {
+                assert_eq!(kind, ShapeKind::Text);
+                assert_eq!(text.content, "Hello");
+                assert_eq!(text.font_size, 32.0);
+                assert_eq!(text.align, TextAlign::Left);
+            }
+            _ => panic!("Expected CreateShape command with text"),
+        }
+    }
+
+    #[test]
+    fn create_path_takes_vertices_with_default_handles() {
+        let json = r#"{
+            "type": "create_shape",
+            "kind": "Path",
+            "position": [10, 10],
+            "size": [0, 0],
+            "path": {
+                "vertices": [
+                    {"point": [0, 0]},
+                    {"point": [50, 0], "handle_in": [-20, 0], "mode": "mirrored"}
+                ]
+            }
+        }"#;
+        let cmd: Command = serde_json::from_str(json).unwrap();
+
+        match cmd {
+            Command::CreateShape { path: Some(path), .. } => {
+                assert!(!path.closed);
+                assert_eq!(path.vertices[0].handle_out, Vec2::ZERO);
+                assert_eq!(path.vertices[1].handle_in, Vec2::new(-20.0, 0.0));
+                assert_eq!(path.vertices[1].mode, HandleMode::Mirrored);
+            }
+            _ => panic!("Expected CreateShape command with a path"),
+        }
+    }
+
+    #[test]
+    fn set_text_leaves_missing_fields_unset() {
+        let json = r#"{"type": "set_text", "align": "center", "sizing": "fixed"}"#;
+        let cmd: Command = serde_json::from_str(json).unwrap();
+
+        match cmd {
+            Command::SetText { content, align, sizing, font_size, .. } => {
+                assert_eq!(content, None);
+                assert_eq!(font_size, None);
+                assert_eq!(align, Some(TextAlign::Center));
+                assert_eq!(sizing, Some(TextSizing::Fixed));
+            }
+            _ => panic!("Expected SetText command"),
+        }
+    }
 }
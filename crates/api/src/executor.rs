@@ -4,15 +4,15 @@
 //! Canvas implementation, executing operations and returning results.
 
 use crate::{
-    Command, CommandResult, Query, QueryResult, ShapeInfo, ShapeKindFilter, ShapeQuery, Target,
-    ToolKind,
+    Command, CommandResult, ExportFormat, FillLayerValue, PageInfo, PageRef, Query, QueryResult, ShapeInfo,
+    ShapeKindFilter, ShapeQuery, Target, ToolKind,
 };
-use canvas::{Canvas, Tool};
+use canvas::{Arrange, Canvas, Tool, Viewport};
 use glam::Vec2;
 use gpui::{Context, Entity};
-use node::{
-    compute_layout, CanvasPoint, CanvasSize, Fill, LayoutInput, Shape, ShapeId, ShapeKind, Stroke,
-};
+use interchange::{export_png, export_svg, import_svg};
+use node::{normalize_degrees, AlignTo, Guide, CanvasPoint, CanvasSize, Fill, Shape, ShapeId, ShapeKind};
+use std::collections::HashSet;
 
 /// Execute a command against a canvas.
 pub fn execute_command(
@@ -20,7 +20,7 @@ pub fn execute_command(
     command: Command,
     cx: &mut gpui::App,
 ) -> CommandResult {
-    canvas.update(cx, |canvas, cx| execute_command_inner(canvas, command, cx))
+    canvas.update(cx, |canvas, cx| execute_recorded(canvas, command, cx))
 }
 
 /// Execute a command against a canvas from within a view context.
@@ -30,7 +30,31 @@ pub fn execute_command_in_context<T: 'static>(
     command: Command,
     cx: &mut Context<T>,
 ) -> CommandResult {
-    canvas.update(cx, |canvas, cx| execute_command_inner(canvas, command, cx))
+    canvas.update(cx, |canvas, cx| execute_recorded(canvas, command, cx))
+}
+
+/// Execute a command, recording it as a single undo step.
+///
+/// Recording happens here rather than per variant so a batch undoes as one
+/// unit, including a batch that fails partway through. A batch that
+/// switches pages isn't recorded: its step would belong to two histories.
+fn execute_recorded(canvas: &mut Canvas, command: Command, cx: &mut Context<Canvas>) -> CommandResult {
+    if !command.is_undoable() {
+        return execute_command_inner(canvas, command, cx);
+    }
+    // Instances follow edits to their mains within the command, so undo
+    // takes both back together
+    canvas.sync_components();
+    let before = canvas.snapshot();
+    let page = canvas.pages().active();
+    let result = execute_command_inner(canvas, command, cx);
+    canvas.sync_components();
+    if canvas.pages().active() == page {
+        canvas.push_undo(before);
+    }
+    // Queries that follow before the next frame see the shapes' new bounds
+    canvas.compute_world_positions();
+    result
 }
 
 fn execute_command_inner(canvas: &mut Canvas, command: Command, cx: &mut Context<Canvas>) -> CommandResult {
@@ -42,17 +66,30 @@ fn execute_command_inner(canvas: &mut Canvas, command: Command, cx: &mut Context
             fill,
             stroke,
             corner_radius,
+            text,
+            path,
         } => {
             let mut shape = Shape::new(kind, CanvasPoint(position), CanvasSize(size));
             // Frames clip children by default
             if kind == ShapeKind::Frame {
                 shape.clip_children = true;
             }
+            // Text is filled with its color and has no outline unless asked
+            if kind == ShapeKind::Text {
+                shape.text = Some(text.unwrap_or_default());
+                shape.fills = vec![Fill::new(gpui::black())];
+                shape.strokes.clear();
+            }
+            // A path's box comes from its geometry
+            if kind == ShapeKind::Path {
+                shape.path = Some(path.unwrap_or_default());
+                shape.fit_path();
+            }
             if let Some(fill) = fill {
-                shape.fill = Some(Fill::new(fill.to_hsla()));
+                shape.fills = vec![Fill::new(fill.to_hsla())];
             }
             if let Some(stroke) = stroke {
-                shape.stroke = Some(Stroke::new(stroke.color.to_hsla(), stroke.width));
+                shape.strokes = vec![stroke.to_stroke(kind)];
             }
             if let Some(radius) = corner_radius {
                 shape.corner_radius = radius;
@@ -123,10 +160,7 @@ fn execute_command_inner(canvas: &mut Canvas, command: Command, cx: &mut Context
         }
 
         Command::SelectAll => {
-            canvas.selection.clear();
-            for shape in &canvas.shapes {
-                canvas.selection.insert(shape.id);
-            }
+            canvas.selection = resolve_target(canvas, &Target::All).into_iter().collect();
             cx.notify();
             CommandResult::success()
         }
@@ -162,10 +196,11 @@ fn execute_command_inner(canvas: &mut Canvas, command: Command, cx: &mut Context
             let mut modified = Vec::new();
             for shape in &mut canvas.shapes {
                 if ids.contains(&shape.id) {
-                    shape.size = CanvasSize(size);
+                    shape.resize(CanvasSize(size));
                     modified.push(shape.id);
                 }
             }
+            apply_layouts(canvas);
             cx.notify();
             CommandResult::modified(modified)
         }
@@ -175,7 +210,55 @@ fn execute_command_inner(canvas: &mut Canvas, command: Command, cx: &mut Context
             let mut modified = Vec::new();
             for shape in &mut canvas.shapes {
                 if ids.contains(&shape.id) {
-                    shape.size.0 *= factor;
+                    shape.resize(CanvasSize(shape.size.0 * factor));
+                    modified.push(shape.id);
+                }
+            }
+            apply_layouts(canvas);
+            cx.notify();
+            CommandResult::modified(modified)
+        }
+
+        Command::Rotate { target, degrees, relative } => {
+            let ids = resolve_target(canvas, &target);
+            let mut modified = Vec::new();
+            for shape in &mut canvas.shapes {
+                if ids.contains(&shape.id) {
+                    let base = if relative { shape.rotation } else { 0.0 };
+                    shape.rotation = normalize_degrees(base + degrees);
+                    modified.push(shape.id);
+                }
+            }
+            cx.notify();
+            CommandResult::modified(modified)
+        }
+
+        Command::Align { target, alignment, relative_to } => {
+            if let AlignTo::Shape(key) = relative_to {
+                if canvas.get_shape(key).is_none() {
+                    return CommandResult::error(format!("Shape {} not found", key));
+                }
+            }
+            let ids = resolve_target(canvas, &target);
+            CommandResult::modified(canvas.align(&ids, alignment, relative_to, cx))
+        }
+
+        Command::Distribute { target, axis } => {
+            let ids = resolve_target(canvas, &target);
+            CommandResult::modified(canvas.distribute(&ids, axis, cx))
+        }
+
+        Command::Tidy { target, spacing } => {
+            let ids = resolve_target(canvas, &target);
+            CommandResult::modified(canvas.tidy(&ids, spacing, cx))
+        }
+
+        Command::Flip { target, axis } => {
+            let ids = resolve_target(canvas, &target);
+            let mut modified = Vec::new();
+            for shape in &mut canvas.shapes {
+                if ids.contains(&shape.id) {
+                    shape.flip(axis);
                     modified.push(shape.id);
                 }
             }
@@ -184,11 +267,32 @@ fn execute_command_inner(canvas: &mut Canvas, command: Command, cx: &mut Context
         }
 
         Command::SetFill { target, fill } => {
+            let fill = match fill.map(|fill| fill.to_fill()).transpose() {
+                Ok(fill) => fill,
+                Err(message) => return CommandResult::error(message),
+            };
             let ids = resolve_target(canvas, &target);
             let mut modified = Vec::new();
             for shape in &mut canvas.shapes {
                 if ids.contains(&shape.id) {
-                    shape.fill = fill.map(|f| Fill::new(f.to_hsla()));
+                    shape.fills = fill.iter().cloned().collect();
+                    modified.push(shape.id);
+                }
+            }
+            cx.notify();
+            CommandResult::modified(modified)
+        }
+
+        Command::SetFills { target, fills } => {
+            let fills = match fills.iter().map(FillLayerValue::to_fill).collect::<Result<Vec<_>, _>>() {
+                Ok(fills) => fills,
+                Err(message) => return CommandResult::error(message),
+            };
+            let ids = resolve_target(canvas, &target);
+            let mut modified = Vec::new();
+            for shape in &mut canvas.shapes {
+                if ids.contains(&shape.id) {
+                    shape.fills = fills.clone();
                     modified.push(shape.id);
                 }
             }
@@ -201,7 +305,20 @@ fn execute_command_inner(canvas: &mut Canvas, command: Command, cx: &mut Context
             let mut modified = Vec::new();
             for shape in &mut canvas.shapes {
                 if ids.contains(&shape.id) {
-                    shape.stroke = stroke.map(|s| Stroke::new(s.color.to_hsla(), s.width));
+                    shape.strokes = stroke.iter().map(|s| s.to_stroke(shape.kind)).collect();
+                    modified.push(shape.id);
+                }
+            }
+            cx.notify();
+            CommandResult::modified(modified)
+        }
+
+        Command::SetStrokes { target, strokes } => {
+            let ids = resolve_target(canvas, &target);
+            let mut modified = Vec::new();
+            for shape in &mut canvas.shapes {
+                if ids.contains(&shape.id) {
+                    shape.strokes = strokes.iter().map(|s| s.to_stroke(shape.kind)).collect();
                     modified.push(shape.id);
                 }
             }
@@ -224,6 +341,82 @@ fn execute_command_inner(canvas: &mut Canvas, command: Command, cx: &mut Context
             CommandResult::modified(modified)
         }
 
+        Command::SetOpacity { target, opacity } => {
+            let ids = resolve_target(canvas, &target);
+            let mut modified = Vec::new();
+            for shape in &mut canvas.shapes {
+                if ids.contains(&shape.id) {
+                    shape.opacity = opacity.clamp(0.0, 1.0);
+                    modified.push(shape.id);
+                }
+            }
+            cx.notify();
+            CommandResult::modified(modified)
+        }
+
+        Command::SetBlendMode { target, blend_mode } => {
+            let ids = resolve_target(canvas, &target);
+            let mut modified = Vec::new();
+            for shape in &mut canvas.shapes {
+                if ids.contains(&shape.id) {
+                    shape.blend_mode = blend_mode;
+                    modified.push(shape.id);
+                }
+            }
+            cx.notify();
+            CommandResult::modified(modified)
+        }
+
+        Command::SetText {
+            target,
+            content,
+            font_family,
+            font_weight,
+            font_size,
+            line_height,
+            letter_spacing,
+            align,
+            sizing,
+        } => {
+            let ids = resolve_target(canvas, &target);
+            let mut modified = Vec::new();
+            for shape in &mut canvas.shapes {
+                if !ids.contains(&shape.id) {
+                    continue;
+                }
+                let Some(text) = shape.text.as_mut() else {
+                    continue;
+                };
+                if let Some(content) = &content {
+                    text.content = content.clone();
+                }
+                if let Some(family) = &font_family {
+                    text.font_family = family.clone();
+                }
+                if let Some(weight) = font_weight {
+                    text.font_weight = weight;
+                }
+                if let Some(size) = font_size {
+                    text.font_size = size;
+                }
+                if let Some(line_height) = line_height {
+                    text.line_height = line_height;
+                }
+                if let Some(spacing) = letter_spacing {
+                    text.letter_spacing = spacing;
+                }
+                if let Some(align) = align {
+                    text.align = align;
+                }
+                if let Some(sizing) = sizing {
+                    text.sizing = sizing;
+                }
+                modified.push(shape.id);
+            }
+            cx.notify();
+            CommandResult::modified(modified)
+        }
+
         Command::AddChild { child, parent } => {
             canvas.add_child(child, parent, cx);
             CommandResult::modified(vec![child, parent])
@@ -243,6 +436,77 @@ fn execute_command_inner(canvas: &mut Canvas, command: Command, cx: &mut Context
             CommandResult::modified(modified)
         }
 
+        Command::Reorder { target, index } => {
+            let ids = resolve_target(canvas, &target);
+            CommandResult::modified(canvas.arrange(&ids, Arrange::Index(index), cx))
+        }
+
+        Command::BringForward { target } => {
+            let ids = resolve_target(canvas, &target);
+            CommandResult::modified(canvas.arrange(&ids, Arrange::Forward, cx))
+        }
+
+        Command::SendBackward { target } => {
+            let ids = resolve_target(canvas, &target);
+            CommandResult::modified(canvas.arrange(&ids, Arrange::Backward, cx))
+        }
+
+        Command::BringToFront { target } => {
+            let ids = resolve_target(canvas, &target);
+            CommandResult::modified(canvas.arrange(&ids, Arrange::Front, cx))
+        }
+
+        Command::SendToBack { target } => {
+            let ids = resolve_target(canvas, &target);
+            CommandResult::modified(canvas.arrange(&ids, Arrange::Back, cx))
+        }
+
+        Command::Rename { target, name } => {
+            let ids = resolve_target(canvas, &target);
+            let mut modified = Vec::new();
+            for shape in &mut canvas.shapes {
+                if ids.contains(&shape.id) {
+                    shape.name = name.clone();
+                    modified.push(shape.id);
+                }
+            }
+            cx.notify();
+            CommandResult::modified(modified)
+        }
+
+        Command::SetVisible { target, visible } => {
+            let ids = resolve_layer_target(canvas, &target);
+            CommandResult::modified(canvas.set_visible(&ids, visible, cx))
+        }
+
+        Command::SetLocked { target, locked } => {
+            let ids = resolve_layer_target(canvas, &target);
+            CommandResult::modified(canvas.set_locked(&ids, locked, cx))
+        }
+
+        Command::CreateComponent { target } => {
+            let ids = resolve_target(canvas, &target);
+            CommandResult::modified(canvas.make_components(&ids, cx))
+        }
+
+        Command::CreateInstance { component, position } => {
+            match canvas.create_instance(component, position.map(CanvasPoint), cx) {
+                Some(root) => CommandResult::created(vec![root]),
+                None => CommandResult::error(format!("Shape {} is not a main component", component)),
+            }
+        }
+
+        Command::DetachInstance { target } => {
+            let ids = resolve_target(canvas, &target);
+            let detached = ids.into_iter().filter(|&id| canvas.detach_instance(id, cx)).collect();
+            CommandResult::modified(detached)
+        }
+
+        Command::ResetOverrides { target } => {
+            let ids = resolve_target(canvas, &target);
+            CommandResult::modified(canvas.reset_overrides(&ids, cx))
+        }
+
         Command::SetClipChildren { target, clip } => {
             let ids = resolve_target(canvas, &target);
             let mut modified = Vec::new();
@@ -268,6 +532,10 @@ fn execute_command_inner(canvas: &mut Canvas, command: Command, cx: &mut Context
             // Apply layout to reposition children
             if layout.is_some() {
                 apply_layouts(canvas);
+            } else {
+                for id in &modified {
+                    canvas.clear_layout_for_frame(*id);
+                }
             }
             cx.notify();
             CommandResult::modified(modified)
@@ -305,6 +573,28 @@ fn execute_command_inner(canvas: &mut Canvas, command: Command, cx: &mut Context
             CommandResult::modified(modified)
         }
 
+        Command::SetLayoutWrap { target, wrap, cross_gap, line_alignment } => {
+            let ids = resolve_target(canvas, &target);
+            let mut modified = Vec::new();
+            for shape in &mut canvas.shapes {
+                if ids.contains(&shape.id) && shape.kind == ShapeKind::Frame {
+                    if let Some(ref mut layout) = shape.layout {
+                        layout.wrap = wrap;
+                        if let Some(gap) = cross_gap {
+                            layout.cross_gap = gap;
+                        }
+                        if let Some(alignment) = line_alignment {
+                            layout.line_alignment = alignment;
+                        }
+                        modified.push(shape.id);
+                    }
+                }
+            }
+            apply_layouts(canvas);
+            cx.notify();
+            CommandResult::modified(modified)
+        }
+
         Command::SetLayoutPadding { target, padding } => {
             let ids = resolve_target(canvas, &target);
             let mut modified = Vec::new();
@@ -342,16 +632,37 @@ fn execute_command_inner(canvas: &mut Canvas, command: Command, cx: &mut Context
             CommandResult::modified(modified)
         }
 
-        Command::SetChildSizing { target, width, height } => {
+        Command::SetChildSizing {
+            target,
+            width,
+            height,
+            min_width,
+            max_width,
+            min_height,
+            max_height,
+        } => {
             let ids = resolve_target(canvas, &target);
             let mut modified = Vec::new();
             for shape in &mut canvas.shapes {
                 if ids.contains(&shape.id) {
+                    let child_layout = &mut shape.child_layout;
                     if let Some(w) = width {
-                        shape.child_layout.width_mode = w;
+                        child_layout.width_mode = w;
                     }
                     if let Some(h) = height {
-                        shape.child_layout.height_mode = h;
+                        child_layout.height_mode = h;
+                    }
+                    if let Some(limit) = min_width {
+                        child_layout.min_width = limit;
+                    }
+                    if let Some(limit) = max_width {
+                        child_layout.max_width = limit;
+                    }
+                    if let Some(limit) = min_height {
+                        child_layout.min_height = limit;
+                    }
+                    if let Some(limit) = max_height {
+                        child_layout.max_height = limit;
                     }
                     modified.push(shape.id);
                 }
@@ -361,25 +672,125 @@ fn execute_command_inner(canvas: &mut Canvas, command: Command, cx: &mut Context
             CommandResult::modified(modified)
         }
 
-        Command::Pan { delta } => {
-            canvas.viewport.pan(delta);
+        Command::SetAbsolute { target, absolute, horizontal, vertical } => {
+            let ids = resolve_target(canvas, &target);
+            let mut modified = Vec::new();
+            for id in ids {
+                // Constraints mean nothing outside a layout frame
+                if !canvas.has_layout_parent(id) {
+                    continue;
+                }
+                if let Some(shape) = canvas.get_shape_mut(id) {
+                    if let Some(h) = horizontal {
+                        shape.child_layout.horizontal_constraint = h;
+                    }
+                    if let Some(v) = vertical {
+                        shape.child_layout.vertical_constraint = v;
+                    }
+                }
+                if canvas.set_absolute(id, absolute) {
+                    modified.push(id);
+                }
+            }
             cx.notify();
+            CommandResult::modified(modified)
+        }
+
+        Command::Pan { delta } => {
+            canvas.pan(delta, cx);
             CommandResult::success()
         }
 
         Command::Zoom { factor, center } => {
             let center = center.unwrap_or(Vec2::ZERO);
-            canvas.viewport.zoom_at(gpui::point(center.x, center.y), factor);
-            cx.notify();
+            canvas.zoom_at(gpui::point(center.x, center.y), factor, cx);
             CommandResult::success()
         }
 
         Command::ResetView => {
-            canvas.viewport.reset();
+            canvas.set_viewport(Viewport::new(), false, cx);
+            CommandResult::success()
+        }
+
+        Command::ZoomToFit => {
+            if !canvas.zoom_to_fit(false, cx) {
+                return CommandResult::error("Nothing to zoom to");
+            }
+            CommandResult::success()
+        }
+
+        Command::ZoomToShapes { target } => {
+            let ids = resolve_target(canvas, &target);
+            if !canvas.zoom_to_shapes(&ids, false, cx) {
+                return CommandResult::error("No shapes to zoom to");
+            }
+            CommandResult::success()
+        }
+
+        Command::ScrollIntoView { target } => {
+            let ids = resolve_target(canvas, &target);
+            if !canvas.scroll_into_view(&ids, false, cx) {
+                return CommandResult::error("No shapes to scroll to");
+            }
+            CommandResult::success()
+        }
+
+        Command::SetZoom { zoom, center } => {
+            if zoom.is_nan() || zoom <= 0.0 {
+                return CommandResult::error(format!("Zoom must be positive, not {zoom}"));
+            }
+            match center {
+                Some(center) => {
+                    let mut viewport = canvas.viewport.clone();
+                    viewport.set_zoom(zoom, gpui::point(center.x, center.y));
+                    canvas.set_viewport(viewport, false, cx);
+                }
+                None => canvas.set_zoom(zoom, false, cx),
+            }
+            CommandResult::success()
+        }
+
+        Command::SetSnapping { enabled, to_shapes, to_guides, grid, to_pixels, threshold } => {
+            if let Some(grid) = grid.flatten().filter(|g| g.is_nan() || *g <= 0.0) {
+                return CommandResult::error(format!("Grid spacing must be positive, not {grid}"));
+            }
+            if let Some(threshold) = threshold.filter(|t| t.is_nan() || *t < 0.0) {
+                return CommandResult::error(format!("Snap threshold can't be {threshold}"));
+            }
+            let settings = &mut canvas.snapping;
+            settings.enabled = enabled.unwrap_or(settings.enabled);
+            settings.to_shapes = to_shapes.unwrap_or(settings.to_shapes);
+            settings.to_guides = to_guides.unwrap_or(settings.to_guides);
+            settings.grid = grid.unwrap_or(settings.grid);
+            settings.to_pixels = to_pixels.unwrap_or(settings.to_pixels);
+            settings.threshold = threshold.unwrap_or(settings.threshold);
             cx.notify();
             CommandResult::success()
         }
 
+        Command::AddGuide { axis, position } => {
+            canvas.add_guide(Guide::new(axis, position), cx);
+            CommandResult::success()
+        }
+
+        Command::MoveGuide { index, position } => {
+            if canvas.move_guide(index, position, cx) {
+                CommandResult::success()
+            } else {
+                CommandResult::error(format!("No guide {}", index))
+            }
+        }
+
+        Command::RemoveGuide { index } => match canvas.remove_guide(index, cx) {
+            Some(_) => CommandResult::success(),
+            None => CommandResult::error(format!("No guide {}", index)),
+        },
+
+        Command::ClearGuides => {
+            canvas.clear_guides(cx);
+            CommandResult::success()
+        }
+
         Command::SetTool { tool } => {
             canvas.tool = match tool {
                 ToolKind::Select => Tool::Select,
@@ -387,25 +798,148 @@ fn execute_command_inner(canvas: &mut Canvas, command: Command, cx: &mut Context
                 ToolKind::Rectangle => Tool::Rectangle,
                 ToolKind::Ellipse => Tool::Ellipse,
                 ToolKind::Frame => Tool::Frame,
+                ToolKind::Text => Tool::Text,
+                ToolKind::Pen => Tool::Pen,
             };
             cx.notify();
             CommandResult::success()
         }
 
+        Command::AddPage { name } => {
+            canvas.add_page(name, cx);
+            CommandResult::success()
+        }
+
+        Command::SwitchPage { page } => {
+            let index = match resolve_page(canvas, &page) {
+                Ok(index) => index,
+                Err(message) => return CommandResult::error(message),
+            };
+            canvas.switch_page(index, cx);
+            CommandResult::success()
+        }
+
+        Command::RenamePage { page, name } => {
+            let index = match resolve_page(canvas, &page) {
+                Ok(index) => index,
+                Err(message) => return CommandResult::error(message),
+            };
+            canvas.rename_page(index, name, cx);
+            CommandResult::success()
+        }
+
+        Command::MovePage { page, index: to } => {
+            let from = match resolve_page(canvas, &page) {
+                Ok(index) => index,
+                Err(message) => return CommandResult::error(message),
+            };
+            if canvas.move_page(from, to, cx) {
+                CommandResult::success()
+            } else {
+                CommandResult::error(format!("No page position {}", to))
+            }
+        }
+
+        Command::DeletePage { page } => {
+            let index = match resolve_page(canvas, &page) {
+                Ok(index) => index,
+                Err(message) => return CommandResult::error(message),
+            };
+            let deleted = canvas.page_shapes(index).unwrap_or_default().iter().map(|s| s.id).collect();
+            if canvas.delete_page(index, cx) {
+                CommandResult::deleted(deleted)
+            } else {
+                CommandResult::error("Can't delete the only page")
+            }
+        }
+
+        Command::ImportSvg { svg, center } => {
+            let import = match import_svg(&svg) {
+                Ok(import) => import,
+                Err(e) => return CommandResult::error(e.to_string()),
+            };
+            let frame = &import.shapes[0];
+            let offset = center.map_or(Vec2::ZERO, |center| {
+                center - frame.size.0 / 2.0 - frame.position.0
+            });
+            let created: Vec<ShapeId> = import.shapes.iter().map(|s| s.id).collect();
+            // Parents come first, so each child finds its parent placed
+            for mut shape in import.shapes {
+                if shape.parent.is_none() {
+                    shape.position.0 += offset;
+                }
+                canvas.add_shape(shape, cx);
+            }
+
+            canvas.selection.clear();
+            canvas.selection.insert(created[0]);
+            cx.notify();
+
+            CommandResult::Success {
+                created,
+                modified: vec![],
+                deleted: vec![],
+                warnings: import.warnings,
+            }
+        }
+
+        Command::Export {
+            target,
+            path,
+            format,
+            scale,
+        } => {
+            // All is the whole page, locked layers included, as saved
+            // documents export it
+            let ids: Vec<ShapeId> = match target {
+                Target::All => canvas
+                    .shapes
+                    .iter()
+                    .filter(|s| s.parent.is_none() && s.visible)
+                    .map(|s| s.id)
+                    .collect(),
+                target => resolve_target(canvas, &target)
+                    .into_iter()
+                    .filter(|id| canvas.get_shape(*id).is_some())
+                    .collect(),
+            };
+            if ids.is_empty() {
+                return CommandResult::error("Nothing to export");
+            }
+            let bytes = match format.unwrap_or_else(|| ExportFormat::for_path(&path)) {
+                ExportFormat::Svg => export_svg(&canvas.shapes, &ids).into_bytes(),
+                ExportFormat::Png => match export_png(&canvas.shapes, &ids, scale) {
+                    Ok(bytes) => bytes,
+                    Err(e) => return CommandResult::error(e.to_string()),
+                },
+            };
+            match std::fs::write(&path, bytes) {
+                Ok(()) => CommandResult::success(),
+                Err(e) => CommandResult::error(format!("Failed to write {}: {}", path.display(), e)),
+            }
+        }
+
         Command::Undo => {
-            // TODO: Implement when undo/redo is added
-            CommandResult::error("Undo not yet implemented")
+            if canvas.undo(cx) {
+                CommandResult::success()
+            } else {
+                CommandResult::error("Nothing to undo")
+            }
         }
 
         Command::Redo => {
-            // TODO: Implement when undo/redo is added
-            CommandResult::error("Redo not yet implemented")
+            if canvas.redo(cx) {
+                CommandResult::success()
+            } else {
+                CommandResult::error("Nothing to redo")
+            }
         }
 
         Command::Batch { commands } => {
             let mut all_created = Vec::new();
             let mut all_modified = Vec::new();
             let mut all_deleted = Vec::new();
+            let mut all_warnings = Vec::new();
 
             for cmd in commands {
                 match execute_command_inner(canvas, cmd, cx) {
@@ -413,10 +947,12 @@ fn execute_command_inner(canvas: &mut Canvas, command: Command, cx: &mut Context
                         created,
                         modified,
                         deleted,
+                        warnings,
                     } => {
                         all_created.extend(created);
                         all_modified.extend(modified);
                         all_deleted.extend(deleted);
+                        all_warnings.extend(warnings);
                     }
                     CommandResult::Error { message } => {
                         return CommandResult::error(format!("Batch failed: {}", message));
@@ -428,6 +964,7 @@ fn execute_command_inner(canvas: &mut Canvas, command: Command, cx: &mut Context
                 created: all_created,
                 modified: all_modified,
                 deleted: all_deleted,
+                warnings: all_warnings,
             }
         }
     }
@@ -472,7 +1009,7 @@ fn execute_query_inner(canvas: &Canvas, query: Query) -> QueryResult {
         }
 
         Query::GetShape { id } => QueryResult::Shape {
-            shape: canvas.get_shape(id).map(shape_to_info),
+            shape: canvas.get_shape(id).map(|shape| Box::new(shape_to_info(shape))),
         },
 
         Query::GetCanvasBounds => {
@@ -503,6 +1040,14 @@ fn execute_query_inner(canvas: &Canvas, query: Query) -> QueryResult {
             zoom: canvas.viewport.zoom,
         },
 
+        Query::GetGuides => QueryResult::Guides {
+            guides: canvas.guides().to_vec(),
+        },
+
+        Query::GetSnapping => QueryResult::Snapping {
+            settings: canvas.snapping,
+        },
+
         Query::GetTool => QueryResult::Tool {
             tool: format!("{:?}", canvas.tool),
         },
@@ -510,11 +1055,54 @@ fn execute_query_inner(canvas: &Canvas, query: Query) -> QueryResult {
         Query::GetShapeCount => QueryResult::Count {
             count: canvas.shapes.len(),
         },
+
+        Query::GetPages => {
+            let pages = canvas.pages();
+            QueryResult::Pages {
+                pages: pages
+                    .names()
+                    .enumerate()
+                    .map(|(index, name)| PageInfo {
+                        name: name.to_string(),
+                        shape_count: canvas.page_shapes(index).map_or(0, <[Shape]>::len),
+                    })
+                    .collect(),
+                active: pages.active(),
+            }
+        }
+
+        Query::GetPageShapes { page } => match resolve_page(canvas, &page) {
+            Ok(index) => QueryResult::Shapes {
+                shapes: canvas
+                    .page_shapes(index)
+                    .unwrap_or_default()
+                    .iter()
+                    .map(shape_to_info)
+                    .collect(),
+            },
+            Err(message) => QueryResult::Error { message },
+        },
+    }
+}
+
+/// Resolve a page reference to the page's index.
+fn resolve_page(canvas: &Canvas, page: &PageRef) -> Result<usize, String> {
+    let pages = canvas.pages();
+    match page {
+        PageRef::Index(index) if *index < pages.len() => Ok(*index),
+        PageRef::Index(index) => Err(format!("No page {}", index)),
+        PageRef::Name(name) => pages.find(name).ok_or_else(|| format!("No page named '{}'", name)),
     }
 }
 
 /// Resolve a target to a list of shape IDs (mutable canvas access).
-fn resolve_target(canvas: &Canvas, target: &Target) -> Vec<ShapeId> {
+///
+/// Queries by bounds read the spatial index, which earlier commands in a
+/// batch may have left behind, so it's refreshed first.
+fn resolve_target(canvas: &mut Canvas, target: &Target) -> Vec<ShapeId> {
+    if matches!(target, Target::Query(_)) {
+        canvas.compute_world_positions();
+    }
     resolve_target_readonly(canvas, target)
 }
 
@@ -524,11 +1112,25 @@ fn resolve_target_readonly(canvas: &Canvas, target: &Target) -> Vec<ShapeId> {
         Target::Selection => canvas.selection.iter().copied().collect(),
         Target::Shape(id) => vec![*id],
         Target::Shapes(ids) => ids.clone(),
-        Target::All => canvas.shapes.iter().map(|s| s.id).collect(),
+        Target::All => canvas
+            .shapes
+            .iter()
+            .filter(|s| canvas.is_pickable(s.id))
+            .map(|s| s.id)
+            .collect(),
         Target::Query(query) => resolve_shape_query(canvas, query),
     }
 }
 
+/// Resolve a target for showing, hiding, locking or unlocking, where all
+/// shapes means every shape, or nothing hidden or locked could be reached.
+fn resolve_layer_target(canvas: &mut Canvas, target: &Target) -> Vec<ShapeId> {
+    match target {
+        Target::All => canvas.shapes.iter().map(|s| s.id).collect(),
+        target => resolve_target(canvas, target),
+    }
+}
+
 /// Resolve a shape query to matching IDs.
 fn resolve_shape_query(canvas: &Canvas, query: &ShapeQuery) -> Vec<ShapeId> {
     match query {
@@ -537,6 +1139,8 @@ fn resolve_shape_query(canvas: &Canvas, query: &ShapeQuery) -> Vec<ShapeId> {
                 ShapeKindFilter::Rectangle => ShapeKind::Rectangle,
                 ShapeKindFilter::Ellipse => ShapeKind::Ellipse,
                 ShapeKindFilter::Frame => ShapeKind::Frame,
+                ShapeKindFilter::Text => ShapeKind::Text,
+                ShapeKindFilter::Path => ShapeKind::Path,
             };
             canvas
                 .shapes
@@ -545,29 +1149,26 @@ fn resolve_shape_query(canvas: &Canvas, query: &ShapeQuery) -> Vec<ShapeId> {
                 .map(|s| s.id)
                 .collect()
         }
-        ShapeQuery::ByName(_name) => {
-            // Future: when shapes have names
-            vec![]
-        }
+        ShapeQuery::ByName(pattern) => canvas
+            .shapes
+            .iter()
+            .filter(|s| pattern.matches(&s.name))
+            .map(|s| s.id)
+            .collect(),
         ShapeQuery::InBounds {
             x,
             y,
             width,
             height,
         } => {
-            let bounds_min = Vec2::new(*x, *y);
-            let bounds_max = Vec2::new(x + width, y + height);
+            let bounds_min = CanvasPoint::new(*x, *y);
+            let bounds_max = CanvasPoint::new(x + width, y + height);
+            let hits: HashSet<_> = canvas.shapes_in_bounds(bounds_min, bounds_max).into_iter().collect();
+            // Report in z-order, as the other queries do
             canvas
                 .shapes
                 .iter()
-                .filter(|s| {
-                    let (shape_min, shape_max) = s.bounds();
-                    // Check if shape intersects bounds
-                    shape_min.x() < bounds_max.x
-                        && shape_max.x() > bounds_min.x
-                        && shape_min.y() < bounds_max.y
-                        && shape_max.y() > bounds_min.y
-                })
+                .filter(|s| hits.contains(&s.id))
                 .map(|s| s.id)
                 .collect()
         }
@@ -598,62 +1199,12 @@ fn resolve_shape_query(canvas: &Canvas, query: &ShapeQuery) -> Vec<ShapeId> {
 }
 
 /// Apply layouts to all frames with autolayout enabled.
-/// This computes child positions and sizes based on layout settings.
+///
+/// Children get computed positions and sizes, and Hug frames are resized to
+/// fit their content. Shares the canvas implementation so shapes edited over
+/// the API lay out exactly as they do when edited in the app.
 fn apply_layouts(canvas: &mut Canvas) {
-    // Collect frame IDs with layout (we need to process bottom-up for nested layouts)
-    let layout_frame_ids: Vec<ShapeId> = canvas
-        .shapes
-        .iter()
-        .filter(|s| s.kind == ShapeKind::Frame && s.layout.is_some())
-        .map(|s| s.id)
-        .collect();
-
-    // Process each layout frame
-    for frame_id in layout_frame_ids {
-        apply_single_layout(canvas, frame_id);
-    }
-}
-
-/// Apply layout to a single frame.
-fn apply_single_layout(canvas: &mut Canvas, frame_id: ShapeId) {
-    // Get frame info and children order
-    let (frame_size, layout, children_ids) = {
-        let Some(frame) = canvas.get_shape(frame_id) else {
-            return;
-        };
-        let Some(layout) = &frame.layout else {
-            return;
-        };
-        (frame.size, layout.clone(), frame.children.clone())
-    };
-
-    // Gather child inputs in the order specified by frame.children
-    let children: Vec<LayoutInput> = children_ids
-        .iter()
-        .filter_map(|child_id| {
-            canvas.get_shape(*child_id).map(|s| LayoutInput {
-                id: s.id,
-                size: s.size,
-                width_mode: s.child_layout.width_mode,
-                height_mode: s.child_layout.height_mode,
-            })
-        })
-        .collect();
-
-    if children.is_empty() {
-        return;
-    }
-
-    // Compute layout
-    let outputs = compute_layout(frame_size, &layout, &children);
-
-    // Apply results
-    for output in outputs {
-        if let Some(shape) = canvas.get_shape_mut(output.id) {
-            shape.position = output.position;
-            shape.size = output.size;
-        }
-    }
+    canvas.apply_all_layouts();
 }
 
 /// Convert a Shape to ShapeInfo for query results.
@@ -663,28 +1214,119 @@ fn shape_to_info(shape: &Shape) -> ShapeInfo {
     ShapeInfo {
         id: shape.id,
         kind: shape.kind,
-        position: shape.position.0,
-        size: shape.size.0,
-        fill: shape.fill.map(|f| FillInfo {
-            color: ColorInfo::from(f.color),
-        }),
-        stroke: shape.stroke.map(|s| StrokeInfo {
-            color: ColorInfo::from(s.color),
-            width: s.width,
-        }),
+        name: shape.name.clone(),
+        visible: shape.visible,
+        locked: shape.locked,
+        position: shape.effective_position().0,
+        size: shape.effective_size().0,
+        opacity: shape.opacity,
+        blend_mode: shape.blend_mode,
+        fills: shape
+            .fills
+            .iter()
+            .map(|f| FillInfo {
+                color: ColorInfo::from(f.color()),
+                gradient: f.gradient().cloned(),
+                visible: f.visible,
+                opacity: f.opacity,
+            })
+            .collect(),
+        strokes: shape
+            .strokes
+            .iter()
+            .map(|s| StrokeInfo {
+                color: ColorInfo::from(s.color),
+                width: s.width,
+                align: s.align,
+                dash: s.dash.clone(),
+                cap: s.cap,
+                join: s.join,
+                visible: s.visible,
+                opacity: s.opacity,
+            })
+            .collect(),
         corner_radius: shape.corner_radius,
         parent: shape.parent,
         children: shape.children.clone(),
         clip_children: shape.clip_children,
+        text: shape.text.clone(),
+        rotation: shape.rotation,
+        flip_x: shape.flip_x,
+        flip_y: shape.flip_y,
+        path: shape.sized_path(),
+        component: shape.component,
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use gpui::{AppContext, TestAppContext};
+    use node::{PathData, PathVertex};
+    use theme::Theme;
+
+    fn test_canvas(cx: &mut TestAppContext) -> Entity<Canvas> {
+        let window = cx.add_empty_window();
+        window.update(|window, cx| {
+            let text_system = window.text_system().clone();
+            cx.new(|cx| Canvas::new(Theme::default(), text_system, cx))
+        })
+    }
+
+    fn add_path(canvas: &Entity<Canvas>, cx: &mut TestAppContext) -> ShapeId {
+        let path = PathData::new(
+            vec![PathVertex::corner(Vec2::ZERO), PathVertex::corner(Vec2::new(100.0, 50.0))],
+            false,
+        );
+        let shape = Shape::path(path);
+        let id = shape.id;
+        canvas.update(cx, |canvas, cx| canvas.add_shape(shape, cx));
+        id
+    }
+
+    fn path_end(canvas: &Entity<Canvas>, id: ShapeId, cx: &mut TestAppContext) -> Vec2 {
+        canvas.read_with(cx, |canvas, _| {
+            let path = canvas.get_shape(id).unwrap().path.as_ref().unwrap();
+            path.vertices[1].point
+        })
+    }
+
+    #[gpui::test]
+    fn set_size_scales_path_vertices(cx: &mut TestAppContext) {
+        let canvas = test_canvas(cx);
+        let id = add_path(&canvas, cx);
+        cx.update(|cx| {
+            let command = Command::SetSize { target: Target::Shape(id), size: Vec2::new(200.0, 25.0) };
+            execute_command(&canvas, command, cx)
+        });
+        assert_eq!(path_end(&canvas, id, cx), Vec2::new(200.0, 25.0));
+    }
+
+    #[gpui::test]
+    fn exporting_everything_includes_locked_layers(cx: &mut TestAppContext) {
+        let canvas = test_canvas(cx);
+        let mut background = Shape::rectangle(Vec2::ZERO, Vec2::new(100.0, 100.0));
+        background.locked = true;
+        canvas.update(cx, |canvas, cx| canvas.add_shape(background, cx));
+
+        let path = std::env::temp_dir().join("luna_test_export_locked.svg");
+        let result = cx.update(|cx| {
+            let command = Command::Export { target: Target::All, path: path.clone(), format: None, scale: 1.0 };
+            execute_command(&canvas, command, cx)
+        });
+        assert!(matches!(result, CommandResult::Success { .. }));
+        let svg = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert!(svg.contains("<rect"));
+    }
 
-    // Note: Full tests require GPUI test context
-    // These are basic unit tests for helper functions
+    #[gpui::test]
+    fn scale_scales_path_vertices(cx: &mut TestAppContext) {
+        let canvas = test_canvas(cx);
+        let id = add_path(&canvas, cx);
+        cx.update(|cx| execute_command(&canvas, Command::Scale { target: Target::Shape(id), factor: Vec2::splat(2.0) }, cx));
+        assert_eq!(path_end(&canvas, id, cx), Vec2::new(200.0, 100.0));
+    }
 
     #[test]
     fn test_shape_to_info() {
@@ -694,4 +1336,18 @@ mod tests {
         assert_eq!(info.size, Vec2::new(100.0, 50.0));
         assert!(matches!(info.kind, ShapeKind::Rectangle));
     }
+
+    #[test]
+    fn shape_info_omits_default_transforms() {
+        let mut shape = Shape::rectangle(Vec2::ZERO, Vec2::new(10.0, 10.0));
+        let json = serde_json::to_value(shape_to_info(&shape)).unwrap();
+        assert!(json.get("rotation").is_none());
+        assert!(json.get("flip_x").is_none());
+
+        shape.rotation = 30.0;
+        shape.flip_y = true;
+        let json = serde_json::to_value(shape_to_info(&shape)).unwrap();
+        assert_eq!(json["rotation"], 30.0);
+        assert_eq!(json["flip_y"], true);
+    }
 }
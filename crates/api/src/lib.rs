@@ -17,6 +17,8 @@
 //!     fill: None,
 //!     stroke: None,
 //!     corner_radius: None,
+//!     text: None,
+//!     path: None,
 //! };
 //! let result = execute_command(&canvas, cmd, cx);
 //! ```
@@ -3,10 +3,14 @@
 //! Queries allow inspecting the canvas without modifying it.
 //! Useful for agents to understand current state before issuing commands.
 
-use crate::Target;
+use crate::{PageRef, Target};
+use canvas::SnapSettings;
 use glam::Vec2;
 use gpui::Hsla;
-use node::{ShapeId, ShapeKind};
+use node::{
+    BlendMode, ComponentLink, Gradient, Guide, PathData, ShapeId, ShapeKind, StrokeAlign, StrokeCap, StrokeJoin,
+    Text,
+};
 use serde::{Deserialize, Serialize};
 
 /// A query for canvas state (read-only).
@@ -31,11 +35,23 @@ pub enum Query {
     /// Get the current viewport state.
     GetViewport,
 
+    /// Get what moved, resized and drawn shapes snap to.
+    GetSnapping,
+
+    /// Get the active page's guides, in index order.
+    GetGuides,
+
     /// Get the current tool.
     GetTool,
 
     /// Get shape count.
     GetShapeCount,
+
+    /// Get the document's pages and which is active.
+    GetPages,
+
+    /// Get all shapes on a page, active or not.
+    GetPageShapes { page: PageRef },
 }
 
 /// Response to a query.
@@ -49,7 +65,7 @@ pub enum QueryResult {
     Shapes { shapes: Vec<ShapeInfo> },
 
     /// Single shape result.
-    Shape { shape: Option<ShapeInfo> },
+    Shape { shape: Option<Box<ShapeInfo>> },
 
     /// Bounds result.
     Bounds {
@@ -60,12 +76,21 @@ pub enum QueryResult {
     /// Viewport result.
     Viewport { offset: Vec2, zoom: f32 },
 
+    /// Snapping settings result.
+    Snapping { settings: SnapSettings },
+
+    /// Guides result, in index order.
+    Guides { guides: Vec<Guide> },
+
     /// Tool result.
     Tool { tool: String },
 
     /// Count result.
     Count { count: usize },
 
+    /// Pages result, in order. `active` indexes `pages`.
+    Pages { pages: Vec<PageInfo>, active: usize },
+
     /// Error result.
     Error { message: String },
 }
@@ -75,14 +100,34 @@ pub enum QueryResult {
 pub struct ShapeInfo {
     pub id: ShapeId,
     pub kind: ShapeKind,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub name: String,
+    #[serde(default = "visible_by_default", skip_serializing_if = "is_true")]
+    pub visible: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub locked: bool,
     pub position: Vec2,
     pub size: Vec2,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub fill: Option<FillInfo>,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub stroke: Option<StrokeInfo>,
+    /// Applies to the shape and everything inside it as one group.
+    #[serde(default = "opaque", skip_serializing_if = "is_opaque")]
+    pub opacity: f32,
+    #[serde(default, skip_serializing_if = "is_normal")]
+    pub blend_mode: BlendMode,
+    /// Bottom first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fills: Vec<FillInfo>,
+    /// Bottom first, over the fills.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub strokes: Vec<StrokeInfo>,
     #[serde(default, skip_serializing_if = "is_zero")]
     pub corner_radius: f32,
+    /// Clockwise degrees about the center of the box at `position`/`size`.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub rotation: f32,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub flip_x: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub flip_y: bool,
     // Hierarchy
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub parent: Option<ShapeId>,
@@ -90,16 +135,58 @@ pub struct ShapeInfo {
     pub children: Vec<ShapeId>,
     #[serde(default, skip_serializing_if = "std::ops::Not::not")]
     pub clip_children: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<Text>,
+    /// Path geometry relative to `position`, at the shape's current size.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathData>,
+    /// The shape's part in a component: a main, an instance of one, or a
+    /// copy of a shape within one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub component: Option<ComponentLink>,
+}
+
+/// Serializable page information.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PageInfo {
+    pub name: String,
+    pub shape_count: usize,
 }
 
 fn is_zero(f: &f32) -> bool {
     *f == 0.0
 }
 
-/// Serializable fill info.
+fn is_true(b: &bool) -> bool {
+    *b
+}
+
+fn visible_by_default() -> bool {
+    true
+}
+
+fn is_opaque(opacity: &f32) -> bool {
+    *opacity == 1.0
+}
+
+fn opaque() -> f32 {
+    1.0
+}
+
+fn is_normal(mode: &BlendMode) -> bool {
+    *mode == BlendMode::Normal
+}
+
+/// Serializable fill info. A gradient's color is its first stop's.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct FillInfo {
     pub color: ColorInfo,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gradient: Option<Gradient>,
+    #[serde(default = "visible_by_default", skip_serializing_if = "is_true")]
+    pub visible: bool,
+    #[serde(default = "opaque", skip_serializing_if = "is_opaque")]
+    pub opacity: f32,
 }
 
 /// Serializable stroke info.
@@ -107,6 +194,15 @@ pub struct FillInfo {
 pub struct StrokeInfo {
     pub color: ColorInfo,
     pub width: f32,
+    pub align: StrokeAlign,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dash: Vec<f32>,
+    pub cap: StrokeCap,
+    pub join: StrokeJoin,
+    #[serde(default = "visible_by_default", skip_serializing_if = "is_true")]
+    pub visible: bool,
+    #[serde(default = "opaque", skip_serializing_if = "is_opaque")]
+    pub opacity: f32,
 }
 
 /// Serializable color info (always HSLA for consistency).
@@ -5,6 +5,7 @@
 //! current selection, specific IDs, or queries.
 
 use node::ShapeId;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 
 /// Specifies which shapes a command targets.
@@ -20,7 +21,9 @@ pub enum Target {
     /// Multiple specific shapes by ID.
     Shapes(Vec<ShapeId>),
 
-    /// All shapes on the canvas.
+    /// All shapes on the canvas that can be picked: hidden and locked
+    /// shapes, and those inside them, are left out, except by the commands
+    /// that show and unlock them.
     All,
 
     /// Shapes matching a query (future: by name, type, property, etc.).
@@ -45,6 +48,29 @@ impl From<Vec<ShapeId>> for Target {
     }
 }
 
+/// Identifies a page, by position or by name.
+///
+/// Serialized as a bare number or string: `"page": 0` or `"page": "Cover"`.
+/// A name refers to the first page called that.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PageRef {
+    Index(usize),
+    Name(String),
+}
+
+impl From<usize> for PageRef {
+    fn from(index: usize) -> Self {
+        Self::Index(index)
+    }
+}
+
+impl From<&str> for PageRef {
+    fn from(name: &str) -> Self {
+        Self::Name(name.to_string())
+    }
+}
+
 /// Query to find shapes by properties.
 /// Extensible for future scene graph features.
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -53,8 +79,8 @@ pub enum ShapeQuery {
     /// Shapes of a specific kind.
     ByKind(ShapeKindFilter),
 
-    /// Shapes with a specific name (future).
-    ByName(String),
+    /// Shapes whose names match a pattern.
+    ByName(NamePattern),
 
     /// Shapes within a bounding box.
     InBounds {
@@ -71,6 +97,95 @@ pub enum ShapeQuery {
     ParentOf(Box<Target>),
 }
 
+/// Matches shape names.
+///
+/// Serialized as a bare string for an exact match, or as `{"prefix": ...}`,
+/// `{"glob": ...}` or `{"regex": ...}`. Globs match the whole name, with `*`
+/// for any run of characters and `?` for one; regexes match anywhere in it
+/// unless anchored. Patterns are checked when parsed, so a command with an
+/// invalid one is rejected rather than matching nothing.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(try_from = "NamePatternSpec", into = "NamePatternSpec")]
+pub struct NamePattern {
+    spec: NamePatternSpec,
+    regex: Regex,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+enum NamePatternSpec {
+    Exact(String),
+    Prefix { prefix: String },
+    Glob { glob: String },
+    Regex { regex: String },
+}
+
+impl NamePattern {
+    pub fn exact(name: impl Into<String>) -> Self {
+        Self::from_literal(NamePatternSpec::Exact(name.into()))
+    }
+
+    pub fn prefix(prefix: impl Into<String>) -> Self {
+        Self::from_literal(NamePatternSpec::Prefix { prefix: prefix.into() })
+    }
+
+    pub fn glob(glob: impl Into<String>) -> Self {
+        Self::from_literal(NamePatternSpec::Glob { glob: glob.into() })
+    }
+
+    pub fn regex(regex: impl Into<String>) -> Result<Self, regex::Error> {
+        Self::try_from(NamePatternSpec::Regex { regex: regex.into() })
+    }
+
+    pub fn matches(&self, name: &str) -> bool {
+        self.regex.is_match(name)
+    }
+
+    /// For patterns built from escaped text, which always compile.
+    fn from_literal(spec: NamePatternSpec) -> Self {
+        Self::try_from(spec).expect("escaped patterns are valid regexes")
+    }
+}
+
+impl TryFrom<NamePatternSpec> for NamePattern {
+    type Error = regex::Error;
+
+    fn try_from(spec: NamePatternSpec) -> Result<Self, Self::Error> {
+        let source = match &spec {
+            NamePatternSpec::Exact(name) => format!("^{}$", regex::escape(name)),
+            NamePatternSpec::Prefix { prefix } => format!("^{}", regex::escape(prefix)),
+            NamePatternSpec::Glob { glob } => {
+                let pattern: String = glob
+                    .chars()
+                    .map(|c| match c {
+                        '*' => ".*".to_string(),
+                        '?' => ".".to_string(),
+                        c => regex::escape(c.encode_utf8(&mut [0; 4])),
+                    })
+                    .collect();
+                format!("^{}$", pattern)
+            }
+            NamePatternSpec::Regex { regex } => regex.clone(),
+        };
+        Ok(Self {
+            regex: Regex::new(&source)?,
+            spec,
+        })
+    }
+}
+
+impl From<NamePattern> for NamePatternSpec {
+    fn from(pattern: NamePattern) -> Self {
+        pattern.spec
+    }
+}
+
+impl From<&str> for NamePattern {
+    fn from(name: &str) -> Self {
+        Self::exact(name)
+    }
+}
+
 /// Filter for shape kinds.
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -78,5 +193,46 @@ pub enum ShapeKindFilter {
     Rectangle,
     Ellipse,
     Frame,
-    // Future: Text, Path, Group, etc.
+    Text,
+    Path,
+    // Future: Group, etc.
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn name_patterns_parse_from_strings_and_objects() {
+        let query: ShapeQuery = serde_json::from_str(r#"{"by_name": "Button"}"#).unwrap();
+        let ShapeQuery::ByName(exact) = query else { panic!("Expected ByName") };
+        assert!(exact.matches("Button"));
+        assert!(!exact.matches("Button 2"));
+
+        let prefix: NamePattern = serde_json::from_str(r#"{"prefix": "Button"}"#).unwrap();
+        assert!(prefix.matches("Button 2"));
+        assert!(!prefix.matches("Big Button"));
+
+        let glob: NamePattern = serde_json::from_str(r#"{"glob": "Icon/*.svg?"}"#).unwrap();
+        assert!(glob.matches("Icon/arrow.svgz"));
+        assert!(!glob.matches("Icon/arrowXsvgz"));
+
+        let regex: NamePattern = serde_json::from_str(r#"{"regex": "Row \\d+"}"#).unwrap();
+        assert!(regex.matches("Table Row 12"));
+        assert!(!regex.matches("Row x"));
+    }
+
+    #[test]
+    fn invalid_regexes_are_rejected_when_parsed() {
+        let result = serde_json::from_str::<NamePattern>(r#"{"regex": "("}"#);
+        assert!(result.is_err());
+        assert!(NamePattern::regex("(").is_err());
+    }
+
+    #[test]
+    fn name_patterns_serialize_as_written() {
+        let json = serde_json::to_string(&NamePattern::glob("Icon/*")).unwrap();
+        assert_eq!(json, r#"{"glob":"Icon/*"}"#);
+        assert_eq!(serde_json::to_string(&NamePattern::exact("Logo")).unwrap(), r#""Logo""#);
+    }
 }
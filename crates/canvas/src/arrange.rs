@@ -0,0 +1,133 @@
+//! Stacking order among siblings.
+//!
+//! Siblings are listed back to front, as they're painted. Shapes moved
+//! together keep their order relative to each other, and only ever move
+//! past shapes that aren't moving, so bringing a selection forward twice
+//! moves it past two others however it's spread out.
+
+use node::ShapeId;
+use std::collections::HashSet;
+
+/// Where to move shapes among their siblings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Arrange {
+    /// To this position, counted back to front among the siblings that
+    /// aren't moving. Past the end is the front.
+    Index(usize),
+    /// One step toward the front.
+    Forward,
+    /// One step toward the back.
+    Backward,
+    Front,
+    Back,
+}
+
+/// Rearrange `siblings`, back to front, moving those in `moving`. Returns
+/// whether the order changed.
+pub fn arrange(
+    siblings: &mut Vec<ShapeId>,
+    moving: &HashSet<ShapeId>,
+    arrangement: Arrange,
+) -> bool {
+    let before = siblings.clone();
+    match arrangement {
+        Arrange::Front => return arrange(siblings, moving, Arrange::Index(usize::MAX)),
+        Arrange::Back => return arrange(siblings, moving, Arrange::Index(0)),
+        Arrange::Index(index) => {
+            let (moved, mut rest): (Vec<_>, Vec<_>) =
+                siblings.iter().partition(|id| moving.contains(id));
+            let index = index.min(rest.len());
+            rest.splice(index..index, moved);
+            *siblings = rest;
+        }
+        Arrange::Forward => {
+            // From the front, so a run of moving shapes hops as one
+            for i in (0..siblings.len().saturating_sub(1)).rev() {
+                if moving.contains(&siblings[i]) && !moving.contains(&siblings[i + 1]) {
+                    let passed = siblings.remove(i + 1);
+                    let start = run_start(siblings, moving, i);
+                    siblings.insert(start, passed);
+                }
+            }
+        }
+        Arrange::Backward => {
+            for i in 1..siblings.len() {
+                if moving.contains(&siblings[i]) && !moving.contains(&siblings[i - 1]) {
+                    let passed = siblings.remove(i - 1);
+                    let end = run_end(siblings, moving, i - 1);
+                    siblings.insert(end + 1, passed);
+                }
+            }
+        }
+    }
+    *siblings != before
+}
+
+/// The first index of the run of moving shapes ending at `end`.
+fn run_start(siblings: &[ShapeId], moving: &HashSet<ShapeId>, end: usize) -> usize {
+    let mut start = end;
+    while start > 0 && moving.contains(&siblings[start - 1]) {
+        start -= 1;
+    }
+    start
+}
+
+/// The last index of the run of moving shapes starting at `start`.
+fn run_end(siblings: &[ShapeId], moving: &HashSet<ShapeId>, start: usize) -> usize {
+    let mut end = start;
+    while end + 1 < siblings.len() && moving.contains(&siblings[end + 1]) {
+        end += 1;
+    }
+    end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids() -> Vec<ShapeId> {
+        (0..5).map(ShapeId::from_u128).collect()
+    }
+
+    /// Arrange ids 0-4 moving `moving`, returning the new order as numbers.
+    fn order(moving: &[u128], arrangement: Arrange) -> Vec<u128> {
+        let all = ids();
+        let mut siblings = all.clone();
+        let moving = moving.iter().map(|&n| ShapeId::from_u128(n)).collect();
+        arrange(&mut siblings, &moving, arrangement);
+        siblings
+            .iter()
+            .map(|id| all.iter().position(|other| other == id).unwrap() as u128)
+            .collect()
+    }
+
+    #[test]
+    fn index_counts_the_shapes_that_stay() {
+        assert_eq!(order(&[0, 3], Arrange::Index(1)), [1, 0, 3, 2, 4]);
+        assert_eq!(order(&[4], Arrange::Index(0)), [4, 0, 1, 2, 3]);
+        assert_eq!(order(&[1], Arrange::Index(99)), [0, 2, 3, 4, 1]);
+    }
+
+    #[test]
+    fn front_and_back_keep_the_moving_order() {
+        assert_eq!(order(&[3, 1], Arrange::Front), [0, 2, 4, 1, 3]);
+        assert_eq!(order(&[3, 1], Arrange::Back), [1, 3, 0, 2, 4]);
+    }
+
+    #[test]
+    fn forward_and_backward_pass_one_shape() {
+        assert_eq!(order(&[1, 2], Arrange::Forward), [0, 3, 1, 2, 4]);
+        assert_eq!(order(&[0, 3], Arrange::Forward), [1, 0, 2, 4, 3]);
+        assert_eq!(order(&[4], Arrange::Forward), [0, 1, 2, 3, 4]);
+        assert_eq!(order(&[2, 3], Arrange::Backward), [0, 2, 3, 1, 4]);
+        assert_eq!(order(&[0, 2], Arrange::Backward), [0, 2, 1, 3, 4]);
+    }
+
+    #[test]
+    fn unchanged_order_is_reported() {
+        let mut siblings = ids();
+        let moving = HashSet::from([siblings[4]]);
+        assert!(!arrange(&mut siblings, &moving, Arrange::Front));
+        assert!(arrange(&mut siblings, &moving, Arrange::Back));
+    }
+}
@@ -1,13 +1,52 @@
-use crate::Viewport;
+use crate::arrange::{arrange, Arrange};
+use crate::gradient::{self, GradientHandle};
+use crate::marquee::{marquee_selection, Marquee, MarqueeMode};
+use crate::pen::{self, PathEdit, PathPart};
+use crate::snap::{SnapGuide, SnapSettings, Snapper};
+use crate::spatial::SpatialIndex;
+use crate::text::TextLayout;
+use crate::viewport::{step_zoom, ViewAnimation};
+use crate::{History, PageState, Pages, Snapshot, Viewport, DEFAULT_PAGE_NAME, RULER_SIZE};
 use glam::Vec2;
-use gpui::{Context, EventEmitter, FocusHandle, Focusable, Hsla, Point};
+use gpui::{
+    Bounds, Context, EventEmitter, FocusHandle, Focusable, Hsla, Pixels, Point, WindowTextSystem,
+};
+use node::align;
 use node::{
-    compute_layout, CanvasDelta, CanvasPoint, CanvasSize, LayoutInput, ScreenPoint, Shape, ShapeId,
-    ShapeKind, Stroke,
+    apply_constraints, AlignTo, Guide, GuideAxis, Alignment, DistributeAxis, compute_layout, detach_instance, instantiate, name_unnamed, nests_in_itself,
+    next_name, normalize_degrees, resolve_frame_size, CanvasDelta, CanvasPoint, CanvasSize, ComponentLink, ComponentSync, Fill, FlipAxis, Gradient,
+    HandleMode, HandleSide, LayoutInput, PathData, PathVertex, Placement, ScreenPoint, Shape, ShapeId,
+    ShapeKind, SizingMode, Stroke, StrokeAlign, Text, TextSizing,
 };
 use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 use theme::Theme;
 
+/// Text boxes drawn narrower than this are treated as a click, and grow
+/// with their content instead of wrapping.
+const MIN_TEXT_BOX_WIDTH: f32 = 4.0;
+
+/// How close, in screen pixels, the pointer must be to a path's outline,
+/// vertices or handles to hit them.
+pub const PATH_HIT_RADIUS: f32 = 4.0;
+
+/// How close, in screen pixels, the pointer must be to a guide to pick it up.
+const GUIDE_HIT_RADIUS: f32 = 3.0;
+
+/// How close, in screen pixels, the pointer must be to a gradient's handle
+/// or stop to pick it up.
+const GRADIENT_HIT_RADIUS: f32 = 6.0;
+
+/// Space, in screen pixels, left around shapes the view zooms to.
+const ZOOM_PADDING: f32 = 48.0;
+
+/// Size of the view before the canvas is first laid out, as in scripted
+/// sessions without a window.
+const DEFAULT_VIEW_SIZE: (f32, f32) = (1280.0, 800.0);
+
+/// Step, in degrees, that rotation snaps to while Shift is held.
+pub const ROTATION_SNAP: f32 = 15.0;
+
 /// Current tool mode.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum Tool {
@@ -17,6 +56,8 @@ pub enum Tool {
     Rectangle,
     Ellipse,
     Frame,
+    Text,
+    Pen,
 }
 
 /// Events emitted by the canvas.
@@ -26,6 +67,10 @@ pub enum CanvasEvent {
     ShapeRemoved(ShapeId),
     SelectionChanged,
     ContentChanged,
+    /// A text shape entered in-place editing.
+    TextEditStarted(ShapeId),
+    /// A page was added, removed, renamed, moved or switched to.
+    PagesChanged,
 }
 
 /// Resize handle positions.
@@ -54,21 +99,55 @@ pub enum DragState {
     MovingShapes {
         start_mouse: CanvasPoint,
         start_positions: Vec<(ShapeId, CanvasPoint)>,
+        /// The box around the moving shapes on canvas, which snaps.
+        start_bounds: Option<(CanvasPoint, CanvasPoint)>,
     },
     /// Resizing selected shapes
     ResizingShapes {
         handle: ResizeHandle,
         start_mouse: CanvasPoint,
-        start_bounds: (CanvasPoint, CanvasPoint), // (min, max) in canvas coords
+        /// The box being resized, in the shapes' parent space: a single
+        /// shape's own unflipped box, so handles follow its rotation, or the
+        /// selection's bounds.
+        frame: Placement,
+        /// Maps the pointer into the frame's space: the single shape's
+        /// parent placement, or the identity.
+        space: Placement,
         shape_ids: Vec<ShapeId>,
         start_shape_data: Vec<(ShapeId, CanvasPoint, CanvasSize)>, // (id, position, size)
     },
+    /// Rotating selected shapes about `center`, in canvas space
+    RotatingShapes {
+        center: Vec2,
+        /// Angle of the pointer about the center when the drag started, in radians
+        start_angle: f32,
+        start_shape_data: Vec<(ShapeId, Vec2, f32)>, // (id, world center, rotation)
+    },
     /// Drawing a new shape
     DrawingShape { shape_id: ShapeId, start: CanvasPoint },
     /// Panning the canvas
     Panning { last_screen_pos: ScreenPoint },
-    /// Drag-selecting shapes
-    Selecting { start: CanvasPoint },
+    /// Drag-selecting shapes with a marquee from `start` to `current`.
+    /// `base` is the selection the marquee adds to, empty unless Shift was
+    /// held when the drag started.
+    Selecting {
+        start: CanvasPoint,
+        current: CanvasPoint,
+        base: Vec<ShapeId>,
+    },
+    /// Dragging a vertex or handle of the path being edited. `pulling` is
+    /// set for the handle of a vertex the pen just placed, which becomes
+    /// smooth as it's dragged out.
+    DraggingPathPart {
+        shape_id: ShapeId,
+        vertex: usize,
+        part: PathPart,
+        pulling: bool,
+    },
+    /// Dragging one of the page's guides, maybe just pulled out of a ruler
+    DraggingGuide { index: usize },
+    /// Dragging a handle or stop of a shape's gradient fill
+    DraggingGradient { shape_id: ShapeId, handle: GradientHandle },
 }
 
 /// The canvas state.
@@ -79,8 +158,11 @@ pub struct Canvas {
     /// Index from ShapeId to position in shapes vec for O(1) lookup.
     shape_index: HashMap<ShapeId, usize>,
 
-    /// Cached world positions for all shapes. Computed once per frame.
-    world_position_cache: HashMap<ShapeId, CanvasPoint>,
+    /// Cached world placements for all shapes. Computed once per frame.
+    placement_cache: HashMap<ShapeId, Placement>,
+
+    /// World bounds of every shape, refreshed with the placement cache.
+    spatial_index: SpatialIndex,
 
     /// Currently selected shape IDs.
     pub selection: HashSet<ShapeId>,
@@ -91,12 +173,23 @@ pub struct Canvas {
     /// Viewport (pan/zoom) state.
     pub viewport: Viewport,
 
+    /// Where the canvas was last laid out in the window. Empty until the
+    /// first frame.
+    pub bounds: Bounds<Pixels>,
+
     /// Current tool.
     pub tool: Tool,
 
     /// Active drag operation.
     pub drag: Option<DragState>,
 
+    /// Text shape being edited in place. The canvas doesn't paint its text;
+    /// the editor overlaid on it does.
+    pub editing_text: Option<ShapeId>,
+
+    /// Path being drawn or edited with the pen.
+    pub editing_path: Option<PathEdit>,
+
     /// Default stroke for new shapes.
     pub default_stroke: Stroke,
 
@@ -108,63 +201,118 @@ pub struct Canvas {
 
     /// Focus handle for keyboard events.
     focus_handle: FocusHandle,
+
+    /// The window's font system, which auto-sized text is measured with.
+    text_system: Arc<WindowTextSystem>,
+
+    /// Undo/redo stacks.
+    history: History,
+
+    /// State captured when the current drag gesture started, committed to
+    /// history when the gesture finishes so a whole drag is one undo step.
+    gesture_start: Option<Snapshot>,
+
+    /// Size of each layout frame at its last layout pass. When a frame's size
+    /// changes, its absolute children follow by their constraints.
+    layout_sizes: HashMap<ShapeId, CanvasSize>,
+
+    /// The document's pages. The active page's state is the canvas's own.
+    pages: Pages,
+
+    /// Keeps component instances in step with their mains.
+    component_sync: ComponentSync,
+
+    /// The shape the rest of the selection aligns to, while it's selected.
+    key_shape: Option<ShapeId>,
+
+    /// What moved, resized and drawn shapes snap to.
+    pub snapping: SnapSettings,
+
+    /// Snaps the gesture under way, built from the shapes in view when it
+    /// started.
+    snapper: Option<Snapper>,
+
+    /// Guides for what the gesture under way last snapped to.
+    snap_guides: Vec<SnapGuide>,
+
+    /// The active page's guides.
+    guides: Vec<Guide>,
+
+    /// The change of view under way, stepped each frame.
+    view_animation: Option<ViewAnimation>,
 }
 
 impl Canvas {
-    pub fn new(theme: Theme, cx: &mut Context<Self>) -> Self {
+    pub fn new(theme: Theme, text_system: Arc<WindowTextSystem>, cx: &mut Context<Self>) -> Self {
         Self {
             shapes: Vec::new(),
             shape_index: HashMap::new(),
-            world_position_cache: HashMap::new(),
+            placement_cache: HashMap::new(),
+            spatial_index: SpatialIndex::new(),
             selection: HashSet::new(),
             hovered: None,
             viewport: Viewport::new(),
+            bounds: Bounds::default(),
             tool: Tool::Select,
             drag: None,
+            editing_text: None,
+            editing_path: None,
             default_stroke: Stroke::new(theme.default_stroke, 2.0),
             default_fill: None,
             theme,
             focus_handle: cx.focus_handle(),
+            text_system,
+            history: History::new(),
+            gesture_start: None,
+            layout_sizes: HashMap::new(),
+            pages: Pages::new(DEFAULT_PAGE_NAME),
+            component_sync: ComponentSync::new(),
+            key_shape: None,
+            snapping: SnapSettings::default(),
+            snapper: None,
+            snap_guides: Vec::new(),
+            guides: Vec::new(),
+            view_animation: None,
         }
     }
 
-    /// Compute and cache world positions for all shapes.
+    /// Compute and cache world placements for all shapes, and bring the
+    /// spatial index up to date with them.
     /// Call this once per frame before rendering.
     pub fn compute_world_positions(&mut self) {
-        self.world_position_cache.clear();
-        self.world_position_cache.reserve(self.shapes.len());
+        self.placement_cache.clear();
+        self.placement_cache.reserve(self.shapes.len());
 
         // Process shapes in order - parents before children ensures cache hits
-        // when computing child positions
+        // when computing child placements
         for shape in &self.shapes {
-            let world_pos = self.compute_world_position_cached(shape.id, shape.parent, shape.effective_position());
-            self.world_position_cache.insert(shape.id, world_pos);
+            let placement = self.compute_placement_cached(shape);
+            self.placement_cache.insert(shape.id, placement);
+            let (min, max) = index_bounds(shape, &placement);
+            self.spatial_index.update(shape.id, min, max);
         }
+
+        let shape_index = &self.shape_index;
+        self.spatial_index.retain(|id| shape_index.contains_key(&id));
     }
 
-    /// Compute world position using the cache for parent lookups.
-    fn compute_world_position_cached(
-        &self,
-        _id: ShapeId,
-        parent: Option<ShapeId>,
-        effective_pos: CanvasPoint,
-    ) -> CanvasPoint {
-        match parent {
-            None => effective_pos,
+    /// Compute a world placement using the cache for parent lookups.
+    fn compute_placement_cached(&self, shape: &Shape) -> Placement {
+        let local = shape.local_placement();
+        match shape.parent {
+            None => local,
             Some(parent_id) => {
-                // Look up parent's world position from cache (O(1))
+                // Look up parent's placement from cache (O(1))
                 // If not cached yet, fall back to computing (shouldn't happen with proper ordering)
-                let parent_world = self
-                    .world_position_cache
+                let parent = self
+                    .placement_cache
                     .get(&parent_id)
                     .copied()
-                    .unwrap_or_else(|| {
-                        // Fallback: compute parent's world position
-                        self.get_shape(parent_id)
-                            .map(|p| p.world_position(&self.shapes))
-                            .unwrap_or(CanvasPoint::new(0.0, 0.0))
-                    });
-                CanvasPoint(effective_pos.0 + parent_world.0)
+                    .or_else(|| self.get_shape(parent_id).map(|p| p.world_placement(&self.shapes)));
+                match parent {
+                    Some(parent) => parent.place_child(&local),
+                    None => local,
+                }
             }
         }
     }
@@ -172,20 +320,52 @@ impl Canvas {
     /// Get cached world position for a shape.
     /// Returns None if not cached (call compute_world_positions first).
     pub fn get_cached_world_position(&self, id: ShapeId) -> Option<CanvasPoint> {
-        self.world_position_cache.get(&id).copied()
+        self.placement_cache.get(&id).map(|p| p.position)
     }
 
-    /// Get the world position cache for rendering.
-    pub fn world_position_cache(&self) -> &HashMap<ShapeId, CanvasPoint> {
-        &self.world_position_cache
+    /// Get cached world placement for a shape, including rotation and flips.
+    /// Returns None if not cached (call compute_world_positions first).
+    pub fn get_cached_placement(&self, id: ShapeId) -> Option<Placement> {
+        self.placement_cache.get(&id).copied()
+    }
+
+    /// Get the placement cache for rendering.
+    pub fn placement_cache(&self) -> &HashMap<ShapeId, Placement> {
+        &self.placement_cache
+    }
+
+    /// A shape's world placement, from the cache if it's there.
+    fn placement_of(&self, shape: &Shape) -> Placement {
+        self.placement_cache
+            .get(&shape.id)
+            .copied()
+            .unwrap_or_else(|| shape.world_placement(&self.shapes))
+    }
+
+    /// The canvas point under a position in the window, such as where
+    /// something was dropped.
+    pub fn window_to_canvas(&self, position: Point<Pixels>) -> CanvasPoint {
+        let local = position - self.bounds.origin;
+        self.viewport.screen_to_canvas(ScreenPoint::new(local.x.into(), local.y.into()))
     }
 
-    /// Add a shape to the canvas.
-    pub fn add_shape(&mut self, shape: Shape, cx: &mut Context<Self>) {
+    /// The canvas point in the middle of the view.
+    pub fn view_center(&self) -> CanvasPoint {
+        self.window_to_canvas(self.bounds.center())
+    }
+
+    /// Add a shape to the canvas. Unnamed shapes get a default name.
+    pub fn add_shape(&mut self, mut shape: Shape, cx: &mut Context<Self>) {
+        if shape.name.is_empty() {
+            shape.name = next_name(&self.shapes, shape.kind);
+        }
         let id = shape.id;
         let index = self.shapes.len();
         self.shapes.push(shape);
         self.shape_index.insert(id, index);
+        let shape = &self.shapes[index];
+        let (min, max) = index_bounds(shape, &self.placement_of(shape));
+        self.spatial_index.update(id, min, max);
         cx.emit(CanvasEvent::ShapeAdded(id));
         cx.emit(CanvasEvent::ContentChanged);
         cx.notify();
@@ -196,6 +376,7 @@ impl Canvas {
         if let Some(&pos) = self.shape_index.get(&id) {
             self.shapes.remove(pos);
             self.shape_index.remove(&id);
+            self.spatial_index.remove(id);
             // Update indices for shapes that shifted down
             for shape in &self.shapes[pos..] {
                 if let Some(idx) = self.shape_index.get_mut(&shape.id) {
@@ -227,46 +408,61 @@ impl Canvas {
     /// For frames with children, recursively checks children first to find
     /// the deepest (most nested) shape at the point.
     pub fn shape_at_point(&self, point: CanvasPoint) -> Option<ShapeId> {
-        self.shape_at_point_recursive(point, None)
+        // Paths are hit a few pixels off their curve
+        let slop = Vec2::splat(PATH_HIT_RADIUS / self.viewport.zoom);
+        let candidates: HashSet<ShapeId> =
+            self.spatial_index.query(point.0 - slop, point.0 + slop).into_iter().collect();
+        self.shape_at_point_recursive(point, None, &candidates)
     }
 
     /// Recursive helper for shape_at_point.
-    /// If parent_id is Some, only checks children of that parent.
+    /// If parent_id is Some, only checks children of that parent. Shapes
+    /// that aren't `candidates` can't be hit and are skipped.
     fn shape_at_point_recursive(
         &self,
         point: CanvasPoint,
         parent_id: Option<ShapeId>,
+        candidates: &HashSet<ShapeId>,
     ) -> Option<ShapeId> {
         // Get shape IDs at this level
         let shape_ids: Vec<ShapeId> = match parent_id {
-            // Root level: filter shapes with no parent
-            None => self.shapes.iter().filter(|s| s.parent.is_none()).map(|s| s.id).collect(),
+            // Root level: candidates with no parent, in z-order
+            None => {
+                let mut roots: Vec<_> = candidates
+                    .iter()
+                    .filter_map(|id| self.shape_index.get(id).map(|&index| (index, *id)))
+                    .filter(|&(index, _)| self.shapes[index].parent.is_none())
+                    .collect();
+                roots.sort_unstable_by_key(|&(index, _)| index);
+                roots.into_iter().map(|(_, id)| id).collect()
+            }
             // Child level: use parent's children list (already in z-order)
-            Some(pid) => self.get_shape(pid).map(|p| p.children.clone()).unwrap_or_default(),
+            Some(pid) => self
+                .get_shape(pid)
+                .map(|p| p.children.iter().copied().filter(|id| candidates.contains(id)).collect())
+                .unwrap_or_default(),
         };
 
         // Iterate in reverse for z-order (top to bottom)
         for shape_id in shape_ids.iter().rev() {
             let Some(shape) = self.get_shape(*shape_id) else { continue };
+            // Hidden and locked shapes take their children with them
+            if !shape.visible || shape.locked {
+                continue;
+            }
 
-            // Get world position from cache (O(1)) or compute if not cached
-            let world_pos = self
-                .world_position_cache
-                .get(&shape.id)
-                .copied()
-                .unwrap_or_else(|| shape.world_position(&self.shapes));
-            let world_bounds_min = world_pos;
-            let world_bounds_max = CanvasPoint(world_pos.0 + shape.effective_size().0);
+            // Get world placement from cache (O(1)) or compute if not cached
+            let placement = self.placement_of(shape);
 
-            let hit = point.0.x >= world_bounds_min.0.x
-                && point.0.x <= world_bounds_max.0.x
-                && point.0.y >= world_bounds_min.0.y
-                && point.0.y <= world_bounds_max.0.y;
+            let hit = match shape.path_in_canvas(&placement) {
+                Some(path) => self.path_hit(shape, &path, point),
+                None => placement.contains(point),
+            };
 
             if hit {
                 // If this shape has children, check them first (they render on top)
                 if !shape.children.is_empty() {
-                    if let Some(child_hit) = self.shape_at_point_recursive(point, Some(shape.id)) {
+                    if let Some(child_hit) = self.shape_at_point_recursive(point, Some(shape.id), candidates) {
                         return Some(child_hit);
                     }
                 }
@@ -278,8 +474,95 @@ impl Canvas {
         None
     }
 
-    /// Select a shape, optionally adding to selection.
+    /// Whether `point` is on a path shape's curve, within its stroke plus a
+    /// few screen pixels, or inside it if it's filled.
+    fn path_hit(&self, shape: &Shape, path: &PathData, point: CanvasPoint) -> bool {
+        if shape.fills.iter().any(|fill| fill.visible) && path.encloses(point.0) {
+            return true;
+        }
+        // Strokes inside or outside the curve lie wholly to one side of it
+        let reach = shape
+            .strokes
+            .iter()
+            .filter(|stroke| stroke.visible)
+            .map(|stroke| match stroke.align {
+                StrokeAlign::Center => stroke.width / 2.0,
+                StrokeAlign::Inside | StrokeAlign::Outside => stroke.width,
+            })
+            .fold(0.0, f32::max);
+        let tolerance = reach + PATH_HIT_RADIUS / self.viewport.zoom;
+        path.nearest(point.0)
+            .is_some_and(|(_, _, distance)| distance <= tolerance)
+    }
+
+    /// Whether a shape is drawn: it and all its ancestors are visible.
+    pub fn is_shown(&self, id: ShapeId) -> bool {
+        self.self_and_ancestors(id).all(|shape| shape.visible)
+    }
+
+    /// Whether a shape can be picked on the canvas: it's shown and neither
+    /// it nor any ancestor is locked.
+    pub fn is_pickable(&self, id: ShapeId) -> bool {
+        self.self_and_ancestors(id).all(|shape| shape.visible && !shape.locked)
+    }
+
+    fn self_and_ancestors(&self, id: ShapeId) -> impl Iterator<Item = &Shape> {
+        std::iter::successors(self.get_shape(id), |shape| self.get_shape(shape.parent?))
+    }
+
+    /// Show or hide shapes. Hidden shapes leave the selection, and their
+    /// layouts close up around them. Returns the shapes that changed.
+    pub fn set_visible(&mut self, ids: &[ShapeId], visible: bool, cx: &mut Context<Self>) -> Vec<ShapeId> {
+        let mut changed = Vec::new();
+        for shape in &mut self.shapes {
+            if ids.contains(&shape.id) && shape.visible != visible {
+                shape.visible = visible;
+                changed.push(shape.id);
+            }
+        }
+        if changed.is_empty() {
+            return changed;
+        }
+        let hidden: Vec<_> = self.selection.iter().copied().filter(|id| !self.is_shown(*id)).collect();
+        if !hidden.is_empty() {
+            for id in hidden {
+                self.selection.remove(&id);
+            }
+            cx.emit(CanvasEvent::SelectionChanged);
+        }
+        if self.hovered.is_some_and(|id| !self.is_shown(id)) {
+            self.hovered = None;
+        }
+        self.apply_all_layouts();
+        cx.emit(CanvasEvent::ContentChanged);
+        cx.notify();
+        changed
+    }
+
+    /// Lock or unlock shapes. Locked shapes stay selected if they were, and
+    /// can still be selected from the layer list. Returns the shapes that
+    /// changed.
+    pub fn set_locked(&mut self, ids: &[ShapeId], locked: bool, cx: &mut Context<Self>) -> Vec<ShapeId> {
+        let mut changed = Vec::new();
+        for shape in &mut self.shapes {
+            if ids.contains(&shape.id) && shape.locked != locked {
+                shape.locked = locked;
+                changed.push(shape.id);
+            }
+        }
+        if !changed.is_empty() {
+            cx.emit(CanvasEvent::ContentChanged);
+            cx.notify();
+        }
+        changed
+    }
+
+    /// Select a shape, optionally adding to selection. Hidden shapes can't
+    /// be selected.
     pub fn select(&mut self, id: ShapeId, add_to_selection: bool, cx: &mut Context<Self>) {
+        if !self.is_shown(id) {
+            return;
+        }
         if !add_to_selection {
             self.selection.clear();
         }
@@ -288,6 +571,30 @@ impl Canvas {
         cx.notify();
     }
 
+    /// The selected shape the rest of the selection aligns to, if one was
+    /// picked.
+    pub fn key_shape(&self) -> Option<ShapeId> {
+        self.key_shape.filter(|id| self.selection.contains(id))
+    }
+
+    /// Make a selected shape the key shape, or stop it being the key shape
+    /// if it already is.
+    pub fn toggle_key_shape(&mut self, id: ShapeId, cx: &mut Context<Self>) {
+        if !self.selection.contains(&id) {
+            return;
+        }
+        self.key_shape = if self.key_shape() == Some(id) { None } else { Some(id) };
+        cx.notify();
+    }
+
+    /// Take a shape out of the selection.
+    pub fn deselect(&mut self, id: ShapeId, cx: &mut Context<Self>) {
+        if self.selection.remove(&id) {
+            cx.emit(CanvasEvent::SelectionChanged);
+            cx.notify();
+        }
+    }
+
     /// Clear the selection.
     pub fn clear_selection(&mut self, cx: &mut Context<Self>) {
         if !self.selection.is_empty() {
@@ -297,25 +604,39 @@ impl Canvas {
         }
     }
 
-    /// Add a shape as a child of a frame.
+    /// Add a shape as a child of a frame, taking it out of any frame it's
+    /// already in.
     ///
     /// Converts the child's position from absolute canvas coordinates to
     /// relative coordinates (relative to parent's origin).
+    ///
+    /// Refused if it would put a shape inside itself, or an instance inside
+    /// its own main.
     pub fn add_child(&mut self, child_id: ShapeId, parent_id: ShapeId, cx: &mut Context<Self>) {
-        // Get parent's world position
+        if self.self_and_ancestors(parent_id).any(|shape| shape.id == child_id)
+            || nests_in_itself(&self.shapes, child_id, parent_id)
+        {
+            return;
+        }
+        let old_parent = self.get_shape(child_id).and_then(|child| child.parent);
+        if old_parent.is_some_and(|old| old != parent_id) {
+            self.unparent(child_id, cx);
+        }
+
+        // Get parent's world placement
         let parent_world = self
             .get_shape(parent_id)
-            .map(|p| p.world_position(&self.shapes));
+            .map(|p| p.world_placement(&self.shapes));
 
         let Some(parent_world) = parent_world else {
             return;
         };
 
-        // Update child: convert position to relative and set parent
+        // Update child: convert placement to relative and set parent, so it
+        // stays where it is on canvas inside a turned parent too
         if let Some(child) = self.get_shape_mut(child_id) {
-            // Convert absolute position to relative (subtract parent's world position)
-            let child_world = child.position;
-            child.position = CanvasPoint(child_world.0 - parent_world.0);
+            let child_world = child.local_placement();
+            child.set_local_placement(parent_world.local_child(&child_world));
             child.parent = Some(parent_id);
         }
 
@@ -346,12 +667,12 @@ impl Canvas {
             let Some(parent_id) = child.parent else {
                 return; // Already unparented
             };
-            (parent_id, child.world_position(&self.shapes))
+            (parent_id, child.world_placement(&self.shapes))
         };
 
-        // Update child: convert position to absolute and clear parent
+        // Update child: convert placement to absolute and clear parent
         if let Some(child) = self.get_shape_mut(child_id) {
-            child.position = child_world;
+            child.set_local_placement(child_world);
             child.parent = None;
         }
 
@@ -360,168 +681,1060 @@ impl Canvas {
             parent.children.retain(|&id| id != child_id);
         }
 
+        // The child leaves the parent's flow, and a hugging parent shrinks
+        if let Some(child) = self.get_shape_mut(child_id) {
+            child.clear_computed();
+        }
+        self.apply_layout_for_frame(parent_id);
+
         cx.emit(CanvasEvent::ContentChanged);
         cx.notify();
     }
 
-    /// Find the topmost frame that fully contains a shape's bounds.
-    ///
-    /// Returns None if no frame contains the shape.
-    fn find_containing_frame(&self, shape_id: ShapeId) -> Option<ShapeId> {
-        let shape = self.get_shape(shape_id)?;
-        let shape_min = shape.position.0;
-        let shape_max = shape.position.0 + shape.size.0;
-
-        // Find frames (in reverse z-order to get topmost first)
-        self.shapes
-            .iter()
-            .rev()
-            .filter(|s| s.kind == ShapeKind::Frame && s.id != shape_id)
-            .find(|frame| {
-                let frame_world = frame.world_position(&self.shapes);
-                let frame_min = frame_world.0;
-                let frame_max = frame_world.0 + frame.size.0;
+    // === Arrange ===
 
-                // Check if shape is fully inside frame
-                shape_min.x >= frame_min.x
-                    && shape_min.y >= frame_min.y
-                    && shape_max.x <= frame_max.x
-                    && shape_max.y <= frame_max.y
-            })
-            .map(|f| f.id)
+    /// The children of `parent`, or the root shapes without one, back to
+    /// front.
+    pub fn siblings(&self, parent: Option<ShapeId>) -> Vec<ShapeId> {
+        match parent {
+            Some(parent) => self.get_shape(parent).map(|p| p.children.clone()).unwrap_or_default(),
+            None => self.shapes.iter().filter(|s| s.parent.is_none()).map(|s| s.id).collect(),
+        }
     }
 
-    /// Auto-parent a shape if it's fully inside a frame.
-    ///
-    /// Called after drawing a new shape to automatically nest it
-    /// inside any containing frame.
-    fn auto_parent_if_inside_frame(&mut self, shape_id: ShapeId, cx: &mut Context<Self>) {
-        if let Some(frame_id) = self.find_containing_frame(shape_id) {
-            self.add_child(shape_id, frame_id, cx);
+    /// Restack shapes among their siblings. Shapes with different parents
+    /// are restacked among their own siblings. Returns the shapes whose
+    /// siblings changed order.
+    pub fn arrange(&mut self, ids: &[ShapeId], arrangement: Arrange, cx: &mut Context<Self>) -> Vec<ShapeId> {
+        let mut groups: Vec<(Option<ShapeId>, HashSet<ShapeId>)> = Vec::new();
+        for shape in self.shapes.iter().filter(|s| ids.contains(&s.id)) {
+            match groups.iter_mut().find(|(parent, _)| *parent == shape.parent) {
+                Some((_, moving)) => {
+                    moving.insert(shape.id);
+                }
+                None => groups.push((shape.parent, HashSet::from([shape.id]))),
+            }
         }
-    }
 
-    /// Delete selected shapes.
-    pub fn delete_selected(&mut self, cx: &mut Context<Self>) {
-        let to_remove: Vec<_> = self.selection.iter().copied().collect();
-        for id in to_remove {
-            self.remove_shape(id, cx);
+        let mut arranged = Vec::new();
+        for (parent, moving) in groups {
+            let mut siblings = self.siblings(parent);
+            if arrange(&mut siblings, &moving, arrangement) {
+                self.set_siblings(parent, siblings);
+                arranged.extend(moving);
+            }
         }
+        if !arranged.is_empty() {
+            self.compute_world_positions();
+            cx.emit(CanvasEvent::ContentChanged);
+            cx.notify();
+        }
+        arranged
     }
 
-    /// Duplicate selected shapes with a slight offset.
-    pub fn duplicate_selected(&mut self, cx: &mut Context<Self>) {
-        let offset = CanvasDelta::new(20.0, 20.0);
-        let to_duplicate: Vec<_> = self
+    /// Move shapes into `parent`, or out to the root without one, at `index`
+    /// among the siblings that aren't moving, counted back to front. Shapes
+    /// that change parent stay where they are on canvas. Returns the shapes
+    /// that moved; a shape can't move into itself, and moving a shape
+    /// carries its children along rather than moving them separately.
+    pub fn move_to(
+        &mut self,
+        ids: &[ShapeId],
+        parent: Option<ShapeId>,
+        index: usize,
+        cx: &mut Context<Self>,
+    ) -> Vec<ShapeId> {
+        if parent.is_some_and(|p| self.get_shape(p).is_none_or(|p| p.kind != ShapeKind::Frame)) {
+            return Vec::new();
+        }
+        let moving: Vec<ShapeId> = self
             .shapes
             .iter()
-            .filter(|s| self.selection.contains(&s.id))
-            .cloned()
+            .filter(|s| ids.contains(&s.id))
+            .filter(|s| {
+                // Along with an ancestor that's moving too
+                !self.self_and_ancestors(s.id).skip(1).any(|a| ids.contains(&a.id))
+            })
+            .filter(|s| match parent {
+                Some(parent) => {
+                    !self.self_and_ancestors(parent).any(|a| a.id == s.id)
+                        && !nests_in_itself(&self.shapes, s.id, parent)
+                }
+                None => true,
+            })
+            .map(|s| s.id)
             .collect();
-
-        if to_duplicate.is_empty() {
-            return;
+        if moving.is_empty() {
+            return moving;
         }
 
-        // Clear current selection
-        self.selection.clear();
-
-        // Create duplicates with new IDs and offset positions
-        for mut shape in to_duplicate {
-            shape.id = ShapeId::new();
-            shape.position = shape.position + offset;
-            let new_id = shape.id;
-            let index = self.shapes.len();
-            self.shapes.push(shape);
-            self.shape_index.insert(new_id, index);
-            self.selection.insert(new_id);
-            cx.emit(CanvasEvent::ShapeAdded(new_id));
+        for &id in &moving {
+            if self.get_shape(id).is_some_and(|s| s.parent == parent) {
+                continue;
+            }
+            match parent {
+                Some(parent) => self.add_child(id, parent, cx),
+                None => self.unparent(id, cx),
+            }
         }
 
-        cx.emit(CanvasEvent::SelectionChanged);
+        let mut siblings = self.siblings(parent);
+        arrange(&mut siblings, &moving.iter().copied().collect(), Arrange::Index(index));
+        self.set_siblings(parent, siblings);
+        self.compute_world_positions();
         cx.emit(CanvasEvent::ContentChanged);
         cx.notify();
+        moving
     }
 
-    /// Move selected shapes by a delta.
-    /// Shapes in autolayout frames are skipped.
-    pub fn move_selected(&mut self, delta: CanvasDelta, cx: &mut Context<Self>) {
-        // Collect IDs of shapes that are in autolayout (can't move)
-        let in_layout: std::collections::HashSet<_> = self
-            .selection
-            .iter()
-            .filter(|id| self.is_in_autolayout(**id))
-            .copied()
-            .collect();
-
-        for shape in &mut self.shapes {
-            if self.selection.contains(&shape.id) && !in_layout.contains(&shape.id) {
-                shape.translate(delta);
+    /// Put the children of `parent`, or the root shapes, in this order.
+    fn set_siblings(&mut self, parent: Option<ShapeId>, siblings: Vec<ShapeId>) {
+        match parent {
+            Some(parent) => {
+                if let Some(frame) = self.get_shape_mut(parent) {
+                    frame.children = siblings;
+                }
+                // Flow order is stacking order
+                self.apply_layout_for_frame(parent);
+            }
+            None => {
+                // Roots paint in the order they're stored, so the shapes
+                // are sorted by their root. The sort is stable, keeping
+                // every parent before its children.
+                let rank: HashMap<ShapeId, usize> =
+                    siblings.iter().enumerate().map(|(rank, &id)| (id, rank)).collect();
+                let roots: HashMap<ShapeId, usize> = self
+                    .shapes
+                    .iter()
+                    .map(|s| {
+                        let root = self.self_and_ancestors(s.id).last().map_or(s.id, |r| r.id);
+                        (s.id, rank.get(&root).copied().unwrap_or(usize::MAX))
+                    })
+                    .collect();
+                self.shapes.sort_by_key(|s| roots[&s.id]);
+                self.rebuild_index();
             }
         }
-        cx.emit(CanvasEvent::ContentChanged);
-        cx.notify();
     }
 
-    /// Start drawing a new shape.
-    pub fn start_draw(&mut self, kind: ShapeKind, start: CanvasPoint, cx: &mut Context<Self>) {
-        let mut shape = Shape::new(kind, start, CanvasSize::new(0.0, 0.0));
-        shape.stroke = Some(self.default_stroke);
-        shape.fill = self.default_fill.map(|c| node::Fill::new(c));
-        // Frames clip children by default
-        if kind == ShapeKind::Frame {
-            shape.clip_children = true;
-        }
+    // === Align ===
 
-        let id = shape.id;
-        let index = self.shapes.len();
-        self.shapes.push(shape);
-        self.shape_index.insert(id, index);
-        self.drag = Some(DragState::DrawingShape {
-            shape_id: id,
-            start,
-        });
-        cx.notify();
+    /// What the selection lines up against without being told: the key
+    /// shape, a lone shape's parent frame, or else the selection itself.
+    pub fn align_reference(&self) -> AlignTo {
+        match (self.key_shape(), self.selection.len()) {
+            (Some(key), _) => AlignTo::Shape(key),
+            (None, 1) => AlignTo::Parent,
+            _ => AlignTo::Selection,
+        }
     }
 
-    /// Update the shape being drawn.
-    pub fn update_draw(&mut self, current: CanvasPoint, cx: &mut Context<Self>) {
-        // Copy data from drag state to avoid borrow issues
-        let drag_info = match &self.drag {
-            Some(DragState::DrawingShape { shape_id, start }) => Some((*shape_id, *start)),
-            _ => None,
+    /// Line shapes up against `to`. Shapes placed by a layout, and shapes
+    /// aligned to a parent they don't have, stay put. Returns the shapes
+    /// that moved.
+    pub fn align(
+        &mut self,
+        ids: &[ShapeId],
+        alignment: Alignment,
+        to: AlignTo,
+        cx: &mut Context<Self>,
+    ) -> Vec<ShapeId> {
+        let mut free = self.free_shapes(ids);
+        let target = match to {
+            AlignTo::Selection => self.union_bounds(&free),
+            AlignTo::Shape(key) => {
+                free.retain(|&id| id != key);
+                self.world_bounds(key)
+            }
+            AlignTo::Parent => None,
         };
+        let moves = free
+            .into_iter()
+            .filter_map(|id| {
+                let shape = self.get_shape(id)?;
+                let target = match to {
+                    AlignTo::Parent => self.world_bounds(shape.parent?)?,
+                    _ => target?,
+                };
+                let bounds = self.world_bounds(id)?;
+                Some((id, align::align(&[bounds], target, alignment)[0]))
+            })
+            .collect();
+        self.shift_shapes(moves, cx)
+    }
 
-        if let Some((shape_id, start)) = drag_info {
-            if let Some(shape) = self.get_shape_mut(shape_id) {
-                // Calculate size and position (handle negative drag)
-                let min_x = start.x().min(current.x());
-                let min_y = start.y().min(current.y());
-                let max_x = start.x().max(current.x());
-                let max_y = start.y().max(current.y());
-                shape.position = CanvasPoint::new(min_x, min_y);
-                shape.size = CanvasSize::new(max_x - min_x, max_y - min_y);
-                cx.notify();
-            }
-        }
+    /// Even out the spacing between shapes along an axis, keeping the
+    /// outermost where they are. Shapes placed by a layout stay put.
+    /// Returns the shapes that moved.
+    pub fn distribute(
+        &mut self,
+        ids: &[ShapeId],
+        axis: DistributeAxis,
+        cx: &mut Context<Self>,
+    ) -> Vec<ShapeId> {
+        let free = self.free_shapes(ids);
+        let boxes: Vec<align::Bounds> = free.iter().filter_map(|&id| self.world_bounds(id)).collect();
+        let moves = free.into_iter().zip(align::distribute(&boxes, axis)).collect();
+        self.shift_shapes(moves, cx)
     }
 
-    /// Finish drawing a shape.
-    pub fn finish_draw(&mut self, cx: &mut Context<Self>) {
-        if let Some(DragState::DrawingShape { shape_id, .. }) = self.drag.take() {
-            // Auto-parent to containing frame if applicable
-            self.auto_parent_if_inside_frame(shape_id, cx);
+    /// Arrange shapes into a grid, `spacing` apart. Shapes placed by a
+    /// layout stay put. Returns the shapes that moved.
+    pub fn tidy(&mut self, ids: &[ShapeId], spacing: f32, cx: &mut Context<Self>) -> Vec<ShapeId> {
+        let free = self.free_shapes(ids);
+        let boxes: Vec<align::Bounds> = free.iter().filter_map(|&id| self.world_bounds(id)).collect();
+        let moves = free.into_iter().zip(align::tidy(&boxes, spacing)).collect();
+        self.shift_shapes(moves, cx)
+    }
 
-            // Select the newly drawn shape
-            self.selection.clear();
-            self.selection.insert(shape_id);
-            // Switch back to Select tool
-            self.tool = Tool::Select;
-            cx.emit(CanvasEvent::ShapeAdded(shape_id));
-            cx.emit(CanvasEvent::SelectionChanged);
+    /// The shapes among `ids` that can be moved on their own: those that
+    /// exist, aren't placed by a layout and aren't carried along by a
+    /// moving ancestor.
+    fn free_shapes(&self, ids: &[ShapeId]) -> Vec<ShapeId> {
+        self.shapes
+            .iter()
+            .filter(|s| ids.contains(&s.id) && !self.is_in_autolayout(s.id))
+            .filter(|s| !self.self_and_ancestors(s.id).skip(1).any(|a| ids.contains(&a.id)))
+            .map(|s| s.id)
+            .collect()
+    }
+
+    /// The box around a shape as it's turned, on canvas.
+    fn world_bounds(&self, id: ShapeId) -> Option<align::Bounds> {
+        self.get_shape(id).map(|shape| self.placement_of(shape).bounds())
+    }
+
+    fn union_bounds(&self, ids: &[ShapeId]) -> Option<align::Bounds> {
+        ids.iter()
+            .filter_map(|&id| self.world_bounds(id))
+            .reduce(|(min, max), (other_min, other_max)| {
+                (CanvasPoint(min.0.min(other_min.0)), CanvasPoint(max.0.max(other_max.0)))
+            })
+    }
+
+    /// Move shapes by offsets on canvas, whichever way their parents are
+    /// turned. Returns the shapes that moved.
+    fn shift_shapes(
+        &mut self,
+        moves: Vec<(ShapeId, CanvasDelta)>,
+        cx: &mut Context<Self>,
+    ) -> Vec<ShapeId> {
+        let mut moved = Vec::new();
+        for (id, delta) in moves {
+            if delta.0.length_squared() < 1e-6 {
+                continue;
+            }
+            let Some(shape) = self.get_shape(id) else {
+                continue;
+            };
+            let mut placement = self.placement_of(shape);
+            placement.position = placement.position + delta;
+            let local = self.parent_placement(shape).local_child(&placement);
+            if let Some(shape) = self.get_shape_mut(id) {
+                shape.set_local_placement(local);
+                moved.push(id);
+            }
+        }
+        if !moved.is_empty() {
+            self.compute_world_positions();
             cx.emit(CanvasEvent::ContentChanged);
             cx.notify();
         }
+        moved
+    }
+
+    // === Snapping ===
+
+    /// Guides for what the gesture under way snapped to, in canvas space.
+    pub fn snap_guides(&self) -> &[SnapGuide] {
+        &self.snap_guides
+    }
+
+    /// Get ready to snap the shapes in `moving` against everything else in
+    /// view, their parent frames included, and the page's guides but for
+    /// `moving_guide`.
+    fn start_snapping(&mut self, moving: &HashSet<ShapeId>, moving_guide: Option<usize>) {
+        let view_min = self.window_to_canvas(self.bounds.origin);
+        let view_max = self.window_to_canvas(self.bounds.bottom_right());
+        let targets = self
+            .shapes_in_bounds(view_min, view_max)
+            .into_iter()
+            .filter(|&id| self.is_shown(id))
+            .filter(|&id| !self.self_and_ancestors(id).any(|s| moving.contains(&s.id)))
+            .filter_map(|id| self.world_bounds(id))
+            .map(|(min, max)| (min.0, max.0))
+            .collect();
+        let guides: Vec<_> = self
+            .guides
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| Some(*index) != moving_guide)
+            .map(|(_, guide)| *guide)
+            .collect();
+        let snapper = Snapper::new(self.snapping, targets, self.viewport.zoom).with_guides(&guides);
+        self.snapper = Some(snapper);
+        self.snap_guides.clear();
+    }
+
+    /// How far to shift a dragged point on canvas to snap it, remembering
+    /// the guides. Nothing snaps without `snap`.
+    fn snap_point(&mut self, point: CanvasPoint, snap: bool) -> Vec2 {
+        let (offset, guides) = match self.snapper.as_ref().filter(|_| snap) {
+            Some(snapper) => snapper.snap_point(point.0),
+            None => (Vec2::ZERO, Vec::new()),
+        };
+        self.snap_guides = guides;
+        offset
+    }
+
+    fn stop_snapping(&mut self) {
+        self.snapper = None;
+        self.snap_guides.clear();
+    }
+
+    // === Guides ===
+
+    /// The active page's guides, in the order they were added.
+    pub fn guides(&self) -> &[Guide] {
+        &self.guides
+    }
+
+    /// Add a guide to the active page. Returns its index.
+    pub fn add_guide(&mut self, guide: Guide, cx: &mut Context<Self>) -> usize {
+        self.guides.push(guide);
+        cx.emit(CanvasEvent::ContentChanged);
+        cx.notify();
+        self.guides.len() - 1
+    }
+
+    /// Move guide `index` to `position` along the axis it crosses. Returns
+    /// false if there's no such guide.
+    pub fn move_guide(&mut self, index: usize, position: f32, cx: &mut Context<Self>) -> bool {
+        let Some(guide) = self.guides.get_mut(index) else {
+            return false;
+        };
+        guide.position = position;
+        cx.emit(CanvasEvent::ContentChanged);
+        cx.notify();
+        true
+    }
+
+    /// Remove guide `index`. Later guides move down an index.
+    pub fn remove_guide(&mut self, index: usize, cx: &mut Context<Self>) -> Option<Guide> {
+        if index >= self.guides.len() {
+            return None;
+        }
+        let guide = self.guides.remove(index);
+        cx.emit(CanvasEvent::ContentChanged);
+        cx.notify();
+        Some(guide)
+    }
+
+    /// Remove every guide from the active page. Returns how many there were.
+    pub fn clear_guides(&mut self, cx: &mut Context<Self>) -> usize {
+        let count = self.guides.len();
+        if count > 0 {
+            self.guides.clear();
+            cx.emit(CanvasEvent::ContentChanged);
+            cx.notify();
+        }
+        count
+    }
+
+    /// The guide nearest `point`, if it's within reach of the pointer.
+    pub fn guide_at(&self, point: CanvasPoint) -> Option<usize> {
+        let reach = GUIDE_HIT_RADIUS / self.viewport.zoom;
+        let distance = |guide: &Guide| (guide_along(guide.axis, point) - guide.position).abs();
+        self.guides
+            .iter()
+            .enumerate()
+            .filter(|(_, guide)| distance(guide) <= reach)
+            .min_by(|(_, a), (_, b)| distance(a).total_cmp(&distance(b)))
+            .map(|(index, _)| index)
+    }
+
+    /// Pull a new guide out of a ruler at `point` and start dragging it.
+    pub fn pull_guide(&mut self, axis: GuideAxis, point: CanvasPoint, cx: &mut Context<Self>) {
+        self.begin_gesture();
+        let index = self.add_guide(Guide::new(axis, guide_along(axis, point).round()), cx);
+        self.start_snapping(&HashSet::new(), Some(index));
+        self.drag = Some(DragState::DraggingGuide { index });
+    }
+
+    /// Start dragging guide `index`. Returns false if there's no such guide.
+    pub fn start_guide_drag(&mut self, index: usize, _cx: &mut Context<Self>) -> bool {
+        if index >= self.guides.len() {
+            return false;
+        }
+        self.begin_gesture();
+        self.start_snapping(&HashSet::new(), Some(index));
+        self.drag = Some(DragState::DraggingGuide { index });
+        true
+    }
+
+    /// Move the dragged guide to `point`. With `snap`, it snaps to shapes,
+    /// other guides and the grid like a shape's edge would.
+    pub fn update_guide_drag(&mut self, point: CanvasPoint, snap: bool, cx: &mut Context<Self>) {
+        let Some(DragState::DraggingGuide { index }) = self.drag else {
+            return;
+        };
+        let Some(axis) = self.guides.get(index).map(|guide| guide.axis) else {
+            return;
+        };
+        let point = CanvasPoint(point.0 + self.snap_point(point, snap));
+        // Only lines along the guide say what it snapped to
+        let along_guide = |from: Vec2, to: Vec2| match axis {
+            GuideAxis::Horizontal => from.y == to.y,
+            GuideAxis::Vertical => from.x == to.x,
+        };
+        self.snap_guides.retain(|guide| match *guide {
+            SnapGuide::Align { from, to } | SnapGuide::Spacing { from, to } => along_guide(from, to),
+        });
+        self.move_guide(index, guide_along(axis, point), cx);
+    }
+
+    /// Drop the dragged guide, or with `remove`, throw it away, as when
+    /// it's dropped back on its ruler.
+    pub fn finish_guide_drag(&mut self, remove: bool, cx: &mut Context<Self>) {
+        if let Some(DragState::DraggingGuide { index }) = self.drag.take() {
+            self.stop_snapping();
+            if remove {
+                self.remove_guide(index, cx);
+            }
+            self.commit_gesture();
+            cx.emit(CanvasEvent::ContentChanged);
+            cx.notify();
+        }
+    }
+
+    // === Gradients ===
+
+    /// The shape whose gradient handles are shown, with its placement and
+    /// gradient: the one selected shape, when its top fill is a gradient and
+    /// the select tool is in hand.
+    pub fn gradient_target(&self) -> Option<(ShapeId, Placement, &Gradient)> {
+        if self.tool != Tool::Select {
+            return None;
+        }
+        let shape = self.selected_shape()?;
+        let gradient = shape.fills[shape.top_fill()?].gradient()?;
+        Some((shape.id, self.placement_of(shape), gradient))
+    }
+
+    /// The gradient handle or stop under `point`, if any.
+    pub fn gradient_handle_at(&self, point: CanvasPoint) -> Option<GradientHandle> {
+        let (_, placement, gradient) = self.gradient_target()?;
+        let reach = GRADIENT_HIT_RADIUS / self.viewport.zoom;
+        gradient::handle_at(gradient, &placement, point, reach)
+    }
+
+    /// Start dragging a handle of the shown gradient. Returns false if no
+    /// gradient is shown.
+    pub fn start_gradient_drag(&mut self, handle: GradientHandle, _cx: &mut Context<Self>) -> bool {
+        let Some((shape_id, ..)) = self.gradient_target() else {
+            return false;
+        };
+        self.begin_gesture();
+        self.drag = Some(DragState::DraggingGradient { shape_id, handle });
+        true
+    }
+
+    /// Move the dragged handle to `point`. Stops slide along the line
+    /// between the handles, and may pass each other.
+    pub fn update_gradient_drag(&mut self, point: CanvasPoint, cx: &mut Context<Self>) {
+        let Some(DragState::DraggingGradient { shape_id, handle }) = self.drag else {
+            return;
+        };
+        let Some(placement) = self.get_shape(shape_id).map(|shape| self.placement_of(shape)) else {
+            return;
+        };
+        let Some(gradient) = self
+            .get_shape_mut(shape_id)
+            .and_then(|shape| {
+                let top = shape.top_fill()?;
+                shape.fills[top].gradient_mut()
+            })
+        else {
+            return;
+        };
+        let handle = match handle {
+            GradientHandle::Start | GradientHandle::End => {
+                let Some(position) = gradient::handle_position(&placement, point) else {
+                    return;
+                };
+                if handle == GradientHandle::Start {
+                    gradient.start = position;
+                } else {
+                    gradient.end = position;
+                }
+                handle
+            }
+            GradientHandle::Stop(index) => {
+                let Some(offset) = gradient::offset_along(gradient, &placement, point) else {
+                    return;
+                };
+                GradientHandle::Stop(gradient.move_stop(index, offset))
+            }
+        };
+        self.drag = Some(DragState::DraggingGradient { shape_id, handle });
+        cx.notify();
+    }
+
+    /// Finish dragging a gradient's handle.
+    pub fn finish_gradient_drag(&mut self, cx: &mut Context<Self>) {
+        if let Some(DragState::DraggingGradient { .. }) = self.drag.take() {
+            self.commit_gesture();
+            cx.emit(CanvasEvent::ContentChanged);
+            cx.notify();
+        }
+    }
+
+    /// Find the topmost frame that fully contains a shape's bounds.
+    ///
+    /// Returns None if no frame contains the shape.
+    fn find_containing_frame(&self, shape_id: ShapeId) -> Option<ShapeId> {
+        let shape = self.get_shape(shape_id)?;
+        let shape_min = shape.position.0;
+        let shape_max = shape.position.0 + shape.size.0;
+
+        // Find frames (in reverse z-order to get topmost first)
+        self.shapes
+            .iter()
+            .rev()
+            .filter(|s| s.kind == ShapeKind::Frame && s.id != shape_id)
+            .find(|frame| {
+                let frame_world = frame.world_position(&self.shapes);
+                let frame_min = frame_world.0;
+                let frame_max = frame_world.0 + frame.size.0;
+
+                // Check if shape is fully inside frame
+                shape_min.x >= frame_min.x
+                    && shape_min.y >= frame_min.y
+                    && shape_max.x <= frame_max.x
+                    && shape_max.y <= frame_max.y
+            })
+            .map(|f| f.id)
+    }
+
+    /// Auto-parent a shape if it's fully inside a frame.
+    ///
+    /// Called after drawing a new shape to automatically nest it
+    /// inside any containing frame.
+    fn auto_parent_if_inside_frame(&mut self, shape_id: ShapeId, cx: &mut Context<Self>) {
+        if let Some(frame_id) = self.find_containing_frame(shape_id) {
+            self.add_child(shape_id, frame_id, cx);
+        }
+    }
+
+    /// Delete selected shapes.
+    pub fn delete_selected(&mut self, cx: &mut Context<Self>) {
+        // While editing a path, Delete means its selected vertex
+        if self.delete_path_vertex(cx) {
+            return;
+        }
+        let before = self.snapshot();
+        let to_remove: Vec<_> = self.selection.iter().copied().collect();
+        for id in to_remove {
+            self.remove_shape(id, cx);
+        }
+        self.push_undo(before);
+    }
+
+    /// Duplicate selected shapes with a slight offset.
+    pub fn duplicate_selected(&mut self, cx: &mut Context<Self>) {
+        let offset = CanvasDelta::new(20.0, 20.0);
+        let to_duplicate: Vec<_> = self
+            .shapes
+            .iter()
+            .filter(|s| self.selection.contains(&s.id))
+            .cloned()
+            .collect();
+
+        if to_duplicate.is_empty() {
+            return;
+        }
+
+        let before = self.snapshot();
+
+        // Clear current selection
+        self.selection.clear();
+
+        // Create duplicates with new IDs and offset positions
+        for mut shape in to_duplicate {
+            shape.id = ShapeId::new();
+            shape.position = shape.position + offset;
+            let new_id = shape.id;
+            let index = self.shapes.len();
+            self.shapes.push(shape);
+            self.shape_index.insert(new_id, index);
+            self.selection.insert(new_id);
+            cx.emit(CanvasEvent::ShapeAdded(new_id));
+        }
+        self.push_undo(before);
+
+        cx.emit(CanvasEvent::SelectionChanged);
+        cx.emit(CanvasEvent::ContentChanged);
+        cx.notify();
+    }
+
+    /// Move selected shapes by a delta.
+    /// Shapes in autolayout frames are skipped.
+    pub fn move_selected(&mut self, delta: CanvasDelta, cx: &mut Context<Self>) {
+        let before = self.snapshot();
+
+        // Collect IDs of shapes that are in autolayout (can't move)
+        let in_layout: std::collections::HashSet<_> = self
+            .selection
+            .iter()
+            .filter(|id| self.is_in_autolayout(**id))
+            .copied()
+            .collect();
+
+        for shape in &mut self.shapes {
+            if self.selection.contains(&shape.id) && !in_layout.contains(&shape.id) {
+                shape.translate(delta);
+            }
+        }
+        // Repeated nudges read as one move
+        self.push_undo_coalesced(before, "nudge");
+        cx.emit(CanvasEvent::ContentChanged);
+        cx.notify();
+    }
+
+    /// Start drawing a new shape. With `snap`, its corners snap as they're
+    /// placed.
+    pub fn start_draw(
+        &mut self,
+        kind: ShapeKind,
+        start: CanvasPoint,
+        snap: bool,
+        cx: &mut Context<Self>,
+    ) {
+        self.begin_gesture();
+        self.start_snapping(&HashSet::new(), None);
+        let start = CanvasPoint(start.0 + self.snap_point(start, snap));
+        let mut shape = if kind == ShapeKind::Text {
+            Shape::text(start.0, Text::default())
+        } else {
+            let mut shape = Shape::new(kind, start, CanvasSize::new(0.0, 0.0));
+            let stroke = self.default_stroke.clone();
+            shape.strokes = vec![stroke.with_align(StrokeAlign::for_kind(kind))];
+            shape.fills = self.default_fill.map(Fill::new).into_iter().collect();
+            // Frames clip children by default
+            if kind == ShapeKind::Frame {
+                shape.clip_children = true;
+            }
+            shape
+        };
+        shape.name = next_name(&self.shapes, kind);
+
+        let id = shape.id;
+        let index = self.shapes.len();
+        self.shapes.push(shape);
+        self.shape_index.insert(id, index);
+        self.drag = Some(DragState::DrawingShape {
+            shape_id: id,
+            start,
+        });
+        cx.notify();
+    }
+
+    /// Update the shape being drawn. With `snap`, the dragged corner snaps.
+    pub fn update_draw(&mut self, current: CanvasPoint, snap: bool, cx: &mut Context<Self>) {
+        // Copy data from drag state to avoid borrow issues
+        let drag_info = match &self.drag {
+            Some(DragState::DrawingShape { shape_id, start }) => Some((*shape_id, *start)),
+            _ => None,
+        };
+
+        if let Some((shape_id, start)) = drag_info {
+            let current = CanvasPoint(current.0 + self.snap_point(current, snap));
+            if let Some(shape) = self.get_shape_mut(shape_id) {
+                // Calculate size and position (handle negative drag)
+                let min_x = start.x().min(current.x());
+                let min_y = start.y().min(current.y());
+                let max_x = start.x().max(current.x());
+                let max_y = start.y().max(current.y());
+                shape.position = CanvasPoint::new(min_x, min_y);
+                shape.size = CanvasSize::new(max_x - min_x, max_y - min_y);
+                cx.notify();
+            }
+        }
+    }
+
+    /// Finish drawing a shape.
+    pub fn finish_draw(&mut self, cx: &mut Context<Self>) {
+        if let Some(DragState::DrawingShape { shape_id, .. }) = self.drag.take() {
+            self.stop_snapping();
+            // Auto-parent to containing frame if applicable
+            self.auto_parent_if_inside_frame(shape_id, cx);
+
+            // Select the newly drawn shape
+            self.selection.clear();
+            self.selection.insert(shape_id);
+            // Switch back to Select tool
+            self.tool = Tool::Select;
+            cx.emit(CanvasEvent::ShapeAdded(shape_id));
+            if self.prepare_drawn_text(shape_id) {
+                // Creating and typing the text is one undo step, committed
+                // when editing finishes
+                self.start_text_edit(shape_id, cx);
+                return;
+            }
+            self.commit_gesture();
+            cx.emit(CanvasEvent::SelectionChanged);
+            cx.emit(CanvasEvent::ContentChanged);
+            cx.notify();
+        }
+    }
+
+    /// Pick the sizing for a text box the user just drew. A click makes text
+    /// that grows as typed; a drag fixes the width and wraps to it.
+    ///
+    /// Returns false if the shape isn't text.
+    fn prepare_drawn_text(&mut self, shape_id: ShapeId) -> bool {
+        let Some(shape) = self.get_shape_mut(shape_id) else {
+            return false;
+        };
+        let Some(text) = shape.text.as_mut() else {
+            return false;
+        };
+        if shape.size.width() < MIN_TEXT_BOX_WIDTH {
+            text.sizing = TextSizing::AutoWidth;
+        } else {
+            text.sizing = TextSizing::AutoHeight;
+        }
+        shape.size = CanvasSize::new(shape.size.width(), text.line_height_px());
+        true
+    }
+
+    /// Start editing a text shape in place.
+    ///
+    /// The whole edit is recorded as one undo step when it finishes. An edit
+    /// that continues a gesture (drawing a new text box) extends it.
+    pub fn start_text_edit(&mut self, shape_id: ShapeId, cx: &mut Context<Self>) {
+        if self.get_shape(shape_id).and_then(|s| s.text.as_ref()).is_none() {
+            return;
+        }
+        if self.editing_text.is_some() {
+            self.finish_text_edit(cx);
+        }
+        if self.gesture_start.is_none() {
+            self.begin_gesture();
+        }
+        self.editing_text = Some(shape_id);
+        self.fit_text_sizes();
+        self.selection.clear();
+        self.selection.insert(shape_id);
+        cx.emit(CanvasEvent::TextEditStarted(shape_id));
+        cx.emit(CanvasEvent::SelectionChanged);
+        cx.notify();
+    }
+
+    /// Replace the content of a text shape. Undo is left to the caller, or
+    /// to the surrounding text edit.
+    pub fn set_text_content(&mut self, shape_id: ShapeId, content: &str, cx: &mut Context<Self>) {
+        let Some(text) = self.get_shape_mut(shape_id).and_then(|s| s.text.as_mut()) else {
+            return;
+        };
+        if text.content != content {
+            text.content = content.to_string();
+            self.fit_text_sizes();
+            cx.emit(CanvasEvent::ContentChanged);
+            cx.notify();
+        }
+    }
+
+    /// Stop editing text. Text left empty is deleted, so an abandoned text
+    /// box leaves nothing behind, not even an undo step.
+    pub fn finish_text_edit(&mut self, cx: &mut Context<Self>) {
+        let Some(shape_id) = self.editing_text.take() else {
+            return;
+        };
+        let is_empty = self
+            .get_shape(shape_id)
+            .and_then(|s| s.text.as_ref())
+            .is_some_and(|text| text.content.trim().is_empty());
+        if is_empty {
+            self.unparent(shape_id, cx);
+            self.remove_shape(shape_id, cx);
+        }
+        self.commit_gesture();
+        cx.emit(CanvasEvent::ContentChanged);
+        cx.notify();
+    }
+
+    /// Fit text shapes to their content on their automatic axes.
+    ///
+    /// Edits fit text before they're recorded, so a text's new size lands in
+    /// the same undo step as the change that caused it. Returns true if any
+    /// shape changed size.
+    fn fit_text_sizes(&mut self) -> bool {
+        let mut resized = Vec::new();
+        for shape in &mut self.shapes {
+            let Some(text) = shape.text.as_ref() else {
+                continue;
+            };
+            let width = shape.effective_size().width();
+            let measured = TextLayout::new(text, width, &self.text_system).size();
+            let size = text.fit_size(shape.size, measured);
+            if size != shape.size {
+                shape.size = size;
+                resized.push(shape.id);
+            }
+        }
+        for id in &resized {
+            self.apply_layout_for_frame(*id);
+        }
+        !resized.is_empty()
+    }
+
+    /// Handle a pen-tool press at `point`.
+    ///
+    /// Without a path being edited, this starts drawing a new one. While
+    /// drawing, clicks append vertices (a drag pulls out smooth handles) and
+    /// clicking the first vertex closes the path. On an existing path,
+    /// vertices and handles can be dragged, a click on a segment adds a
+    /// vertex there, and a double-click on a vertex toggles it smooth.
+    /// Clicking anywhere else finishes the edit.
+    pub fn pen_down(&mut self, point: CanvasPoint, click_count: usize, cx: &mut Context<Self>) {
+        let Some(edit) = self.editing_path else {
+            self.start_path(point, cx);
+            return;
+        };
+        let Some(path) = self.path_in_canvas(edit.shape_id) else {
+            self.editing_path = None;
+            self.start_path(point, cx);
+            return;
+        };
+        let radius = PATH_HIT_RADIUS / self.viewport.zoom;
+
+        if let Some((vertex, part)) = pen::part_at(&path, edit.selected_vertex, point.0, radius) {
+            if edit.extending && vertex == 0 && part == PathPart::Vertex && path.vertices.len() > 1
+            {
+                self.update_path(edit.shape_id, |path| path.closed = true);
+                self.finish_path_edit(cx);
+                self.tool = Tool::Select;
+                return;
+            }
+            if part == PathPart::Vertex && click_count >= 2 {
+                self.update_path(edit.shape_id, |path| pen::toggle_smooth(path, vertex));
+            }
+            self.drag_path_part(edit.shape_id, vertex, part, false, cx);
+            return;
+        }
+
+        let local = self.canvas_to_path(edit.shape_id, point);
+        if edit.extending {
+            let vertex = self.update_path(edit.shape_id, |path| {
+                path.vertices.push(PathVertex::corner(local));
+                path.vertices.len() - 1
+            });
+            let part = PathPart::Handle(HandleSide::Out);
+            self.drag_path_part(edit.shape_id, vertex, part, true, cx);
+            return;
+        }
+
+        if let Some((segment, t, distance)) = path.nearest(point.0) {
+            if distance <= radius {
+                let vertex = self.update_path(edit.shape_id, |path| path.split_segment(segment, t));
+                self.drag_path_part(edit.shape_id, vertex, PathPart::Vertex, false, cx);
+                return;
+            }
+        }
+
+        self.finish_path_edit(cx);
+        self.tool = Tool::Select;
+    }
+
+    /// Start drawing a new path with its first vertex at `point`.
+    fn start_path(&mut self, point: CanvasPoint, cx: &mut Context<Self>) {
+        self.begin_gesture();
+        let mut shape = Shape::path(PathData::new(vec![PathVertex::corner(point.0)], false));
+        shape.name = next_name(&self.shapes, ShapeKind::Path);
+        shape.strokes = vec![self.default_stroke.clone().with_align(StrokeAlign::Center)];
+        let shape_id = shape.id;
+        let index = self.shapes.len();
+        self.shapes.push(shape);
+        self.shape_index.insert(shape_id, index);
+        self.selection.clear();
+        self.selection.insert(shape_id);
+        self.editing_path = Some(PathEdit {
+            shape_id,
+            selected_vertex: Some(0),
+            extending: true,
+        });
+        cx.emit(CanvasEvent::ShapeAdded(shape_id));
+        cx.emit(CanvasEvent::SelectionChanged);
+        let part = PathPart::Handle(HandleSide::Out);
+        self.drag_path_part(shape_id, 0, part, true, cx);
+    }
+
+    /// Select `vertex` and start dragging one of its parts. A `pulling`
+    /// drag draws out mirrored handles from a vertex just placed.
+    fn drag_path_part(
+        &mut self,
+        shape_id: ShapeId,
+        vertex: usize,
+        part: PathPart,
+        pulling: bool,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(edit) = self.editing_path.as_mut() {
+            edit.selected_vertex = Some(vertex);
+        }
+        self.drag = Some(DragState::DraggingPathPart {
+            shape_id,
+            vertex,
+            part,
+            pulling,
+        });
+        cx.notify();
+    }
+
+    /// Move the dragged vertex or handle to `point`.
+    pub fn update_path_drag(&mut self, point: CanvasPoint, cx: &mut Context<Self>) {
+        let Some(DragState::DraggingPathPart {
+            shape_id,
+            vertex,
+            part,
+            pulling,
+        }) = self.drag
+        else {
+            return;
+        };
+        let local = self.canvas_to_path(shape_id, point);
+        self.update_path(shape_id, |path| {
+            let Some(vertex) = path.vertices.get_mut(vertex) else {
+                return;
+            };
+            match part {
+                PathPart::Vertex => vertex.point = local,
+                PathPart::Handle(side) => {
+                    if pulling {
+                        vertex.mode = HandleMode::Mirrored;
+                    }
+                    vertex.set_handle(side, local - vertex.point);
+                }
+            }
+        });
+        cx.notify();
+    }
+
+    /// Finish dragging a path part. The edit itself continues.
+    pub fn finish_path_drag(&mut self, cx: &mut Context<Self>) {
+        if let Some(DragState::DraggingPathPart { .. }) = self.drag {
+            self.drag = None;
+            cx.emit(CanvasEvent::ContentChanged);
+            cx.notify();
+        }
+    }
+
+    /// Start editing an existing path's vertices with the pen.
+    ///
+    /// Like a text edit, the whole path edit is one undo step.
+    pub fn start_path_edit(&mut self, shape_id: ShapeId, cx: &mut Context<Self>) {
+        if self.get_shape(shape_id).and_then(|s| s.path.as_ref()).is_none() {
+            return;
+        }
+        self.finish_path_edit(cx);
+        if self.gesture_start.is_none() {
+            self.begin_gesture();
+        }
+        self.editing_path = Some(PathEdit {
+            shape_id,
+            selected_vertex: None,
+            extending: false,
+        });
+        self.tool = Tool::Pen;
+        self.selection.clear();
+        self.selection.insert(shape_id);
+        cx.emit(CanvasEvent::SelectionChanged);
+        cx.notify();
+    }
+
+    /// Stop editing a path. A path left with fewer than two vertices is
+    /// deleted, so an abandoned one leaves nothing behind.
+    pub fn finish_path_edit(&mut self, cx: &mut Context<Self>) {
+        let Some(edit) = self.editing_path.take() else {
+            return;
+        };
+        if let Some(DragState::DraggingPathPart { .. }) = self.drag {
+            self.drag = None;
+        }
+        let vertex_count = self
+            .get_shape(edit.shape_id)
+            .and_then(|s| s.path.as_ref())
+            .map_or(0, |path| path.vertices.len());
+        if vertex_count < 2 {
+            self.unparent(edit.shape_id, cx);
+            self.remove_shape(edit.shape_id, cx);
+        } else if edit.extending {
+            self.auto_parent_if_inside_frame(edit.shape_id, cx);
+        }
+        self.commit_gesture();
+        cx.emit(CanvasEvent::SelectionChanged);
+        cx.emit(CanvasEvent::ContentChanged);
+        cx.notify();
+    }
+
+    /// Delete the selected vertex of the path being edited. Returns false
+    /// if there is none.
+    fn delete_path_vertex(&mut self, cx: &mut Context<Self>) -> bool {
+        let Some(PathEdit {
+            shape_id,
+            selected_vertex: Some(vertex),
+            ..
+        }) = self.editing_path
+        else {
+            return false;
+        };
+        let remaining = self.update_path(shape_id, |path| {
+            path.remove_vertex(vertex);
+            path.vertices.len()
+        });
+        if let Some(edit) = self.editing_path.as_mut() {
+            edit.selected_vertex = None;
+        }
+        if remaining < 2 {
+            self.finish_path_edit(cx);
+            self.tool = Tool::Select;
+        }
+        cx.emit(CanvasEvent::ContentChanged);
+        cx.notify();
+        true
+    }
+
+    /// Edit a path shape's geometry, refitting its box afterwards.
+    fn update_path<R>(&mut self, shape_id: ShapeId, edit: impl FnOnce(&mut PathData) -> R) -> R
+    where
+        R: Default,
+    {
+        let Some(shape) = self.get_shape_mut(shape_id) else {
+            return R::default();
+        };
+        let Some(path) = shape.path.as_mut() else {
+            return R::default();
+        };
+        let result = edit(path);
+        shape.fit_path();
+        self.apply_layout_for_frame(shape_id);
+        result
+    }
+
+    /// A path shape's geometry in canvas space.
+    ///
+    /// World placements are computed fresh rather than cached, since path
+    /// edits move the shape's origin between frames.
+    fn path_in_canvas(&self, shape_id: ShapeId) -> Option<PathData> {
+        let shape = self.get_shape(shape_id)?;
+        shape.path_in_canvas(&shape.world_placement(&self.shapes))
+    }
+
+    /// A canvas point in a path shape's local space.
+    fn canvas_to_path(&self, shape_id: ShapeId, point: CanvasPoint) -> Vec2 {
+        self.get_shape(shape_id)
+            .map_or(point.0, |shape| shape.canvas_to_path(point, &shape.world_placement(&self.shapes)))
     }
 
     /// Start moving selected shapes.
@@ -539,24 +1752,38 @@ impl Canvas {
             .map(|s| (s.id, s.position))
             .collect();
 
+        let ids: Vec<_> = self.selection.iter().copied().collect();
+        let start_bounds = self.union_bounds(&ids);
+
+        self.begin_gesture();
+        self.start_snapping(&self.selection.clone(), None);
         self.drag = Some(DragState::MovingShapes {
             start_mouse,
             start_positions: positions,
+            start_bounds,
         });
         true
     }
 
-    /// Update shape positions during move.
-    pub fn update_move(&mut self, current_mouse: CanvasPoint, cx: &mut Context<Self>) {
+    /// Update shape positions during move. With `snap`, the box around the
+    /// shapes snaps as a whole.
+    pub fn update_move(&mut self, current_mouse: CanvasPoint, snap: bool, cx: &mut Context<Self>) {
         // Copy data to avoid borrow issues
-        let (start_mouse, positions): (CanvasPoint, Vec<_>) = match &self.drag {
-            Some(DragState::MovingShapes { start_mouse, start_positions }) => {
-                (*start_mouse, start_positions.clone())
+        let (start_mouse, positions, start_bounds): (CanvasPoint, Vec<_>, _) = match &self.drag {
+            Some(DragState::MovingShapes { start_mouse, start_positions, start_bounds }) => {
+                (*start_mouse, start_positions.clone(), *start_bounds)
             }
             _ => return,
         };
 
-        let delta = current_mouse - start_mouse;
+        let mut delta = current_mouse - start_mouse;
+        let snapped = self.snapper.as_ref().filter(|_| snap).zip(start_bounds);
+        let (offset, guides) = match snapped {
+            Some((snapper, (min, max))) => snapper.snap_box(min.0 + delta.0, max.0 + delta.0),
+            None => (Vec2::ZERO, Vec::new()),
+        };
+        delta.0 += offset;
+        self.snap_guides = guides;
 
         for (id, start_pos) in positions {
             if let Some(shape) = self.get_shape_mut(id) {
@@ -570,6 +1797,8 @@ impl Canvas {
     pub fn finish_move(&mut self, cx: &mut Context<Self>) {
         if matches!(self.drag, Some(DragState::MovingShapes { .. })) {
             self.drag = None;
+            self.stop_snapping();
+            self.commit_gesture();
             cx.emit(CanvasEvent::ContentChanged);
             cx.notify();
         }
@@ -583,8 +1812,14 @@ impl Canvas {
             return false;
         }
 
-        let Some((min, max)) = self.selection_bounds() else {
-            return false;
+        let (frame, space) = match self.selected_shape() {
+            Some(shape) => (shape.local_placement().unflipped(), self.parent_placement(shape)),
+            None => {
+                let Some((min, max)) = self.selection_bounds() else {
+                    return false;
+                };
+                (Placement::new(min, CanvasSize(max.0 - min.0)), Placement::default())
+            }
         };
 
         let shape_ids: Vec<_> = self.selection.iter().copied().collect();
@@ -595,34 +1830,53 @@ impl Canvas {
             .map(|s| (s.id, s.position, s.size))
             .collect();
 
+        self.begin_gesture();
+        self.start_snapping(&self.selection.clone(), None);
         self.drag = Some(DragState::ResizingShapes {
             handle,
             start_mouse,
-            start_bounds: (min, max),
+            frame,
+            space,
             shape_ids,
             start_shape_data,
         });
         true
     }
 
-    /// Update shape sizes during resize.
-    pub fn update_resize(&mut self, current_mouse: CanvasPoint, cx: &mut Context<Self>) {
-        let (handle, start_mouse, start_bounds, start_shape_data) = match &self.drag {
+    /// Update shape sizes during resize. With `snap`, the dragged corner
+    /// snaps, unless the box is turned and its edges don't line up with
+    /// anything.
+    pub fn update_resize(&mut self, current_mouse: CanvasPoint, snap: bool, cx: &mut Context<Self>) {
+        let (handle, start_mouse, frame, space, start_shape_data) = match &self.drag {
             Some(DragState::ResizingShapes {
                 handle,
                 start_mouse,
-                start_bounds,
+                frame,
+                space,
                 start_shape_data,
                 ..
-            }) => (*handle, *start_mouse, *start_bounds, start_shape_data.clone()),
+            }) => (*handle, *start_mouse, *frame, *space, start_shape_data.clone()),
             _ => return,
         };
 
-        // Extract raw Vec2 values for math operations
-        let (start_min, start_max) = (start_bounds.0 .0, start_bounds.1 .0);
+        // The dragged corner follows the pointer, so snapping it is moving
+        // the pointer by as much
+        let snap = snap && !frame.is_rotated() && !space.is_rotated();
+        let corner = match handle {
+            ResizeHandle::TopLeft => Vec2::ZERO,
+            ResizeHandle::TopRight => Vec2::new(frame.size.0.x, 0.0),
+            ResizeHandle::BottomLeft => Vec2::new(0.0, frame.size.0.y),
+            ResizeHandle::BottomRight => frame.size.0,
+        };
+        let corner = space.to_canvas(frame.to_canvas(corner)) + (current_mouse - start_mouse).0;
+        let current_mouse = CanvasPoint(current_mouse.0 + self.snap_point(CanvasPoint(corner), snap));
+
+        // Work in the frame's own axes, so a rotated shape resizes along its
+        // edges rather than the canvas's
+        let (start_min, start_max) = (Vec2::ZERO, frame.size.0);
         let start_size = start_max - start_min;
-        let start_mouse = start_mouse.0;
-        let current_mouse = current_mouse.0;
+        let start_mouse = frame.to_local(space.to_local(start_mouse.0));
+        let current_mouse = frame.to_local(space.to_local(current_mouse.0));
 
         // Calculate new bounds based on which handle is being dragged
         let delta = current_mouse - start_mouse;
@@ -664,34 +1918,263 @@ impl Canvas {
                 let orig_pos = orig_pos.0;
                 let orig_size = orig_size.0;
 
-                // Calculate relative position within original bounds (0 to 1)
-                let rel_pos = orig_pos - start_min;
+                // Calculate relative position within original bounds
+                let rel_pos = orig_pos - frame.position.0;
+
+                // Apply flip: if flipped, mirror the relative position
+                let rel_pos = Vec2::new(
+                    if flip_x { start_size.x - rel_pos.x - orig_size.x } else { rel_pos.x },
+                    if flip_y { start_size.y - rel_pos.y - orig_size.y } else { rel_pos.y },
+                );
+
+                // Scale position and size
+                let local_min = new_min + rel_pos * scale;
+                let size = orig_size * scale;
+                shape.position = if frame.is_rotated() {
+                    // The shape turns about its new center, which must land
+                    // where the frame's axes put it
+                    CanvasPoint(frame.to_canvas(local_min + size / 2.0) - size / 2.0)
+                } else {
+                    CanvasPoint(frame.position.0 + local_min)
+                };
+                shape.resize(CanvasSize(size));
+            }
+        }
+        cx.notify();
+    }
+
+    /// Start rotating selected shapes about the selection's center.
+    /// Returns false if nothing is selected.
+    pub fn start_rotate(&mut self, start_mouse: CanvasPoint, _cx: &mut Context<Self>) -> bool {
+        let Some(frame) = self.selection_frame() else {
+            return false;
+        };
+        let center = frame.center();
+        let start_shape_data = self
+            .shapes
+            .iter()
+            .filter(|s| self.selection.contains(&s.id))
+            .map(|s| (s.id, self.placement_of(s).center(), s.rotation))
+            .collect();
+
+        self.begin_gesture();
+        self.drag = Some(DragState::RotatingShapes {
+            center,
+            start_angle: (start_mouse.0 - center).to_angle(),
+            start_shape_data,
+        });
+        true
+    }
+
+    /// Update rotation during a rotate drag. With `snap`, the rotation moves
+    /// in steps of [`ROTATION_SNAP`] degrees.
+    pub fn update_rotate(&mut self, current_mouse: CanvasPoint, snap: bool, cx: &mut Context<Self>) {
+        let Some(DragState::RotatingShapes {
+            center,
+            start_angle,
+            start_shape_data,
+        }) = self.drag.clone()
+        else {
+            return;
+        };
+
+        let mut delta = ((current_mouse.0 - center).to_angle() - start_angle).to_degrees();
+        if snap {
+            delta = (delta / ROTATION_SNAP).round() * ROTATION_SNAP;
+        }
+        let turn = Vec2::from_angle(delta.to_radians());
+        let moves_centers = start_shape_data.len() > 1;
+
+        for (id, world_center, rotation) in start_shape_data {
+            let Some(shape) = self.get_shape(id) else {
+                continue;
+            };
+            let parent = self.parent_placement(shape);
+            // A mirrored parent turns its children the other way
+            let mirrored = parent.flip_x != parent.flip_y;
+            let rotation = normalize_degrees(rotation + if mirrored { -delta } else { delta });
+            // A lone shape turns in place; a group swings about its center
+            let position = moves_centers.then(|| {
+                let world_center = center + turn.rotate(world_center - center);
+                CanvasPoint(parent.to_local(world_center) - shape.effective_size().0 / 2.0)
+            });
+
+            if let Some(shape) = self.get_shape_mut(id) {
+                shape.rotation = rotation;
+                if let Some(position) = position {
+                    shape.position = position;
+                }
+            }
+        }
+        cx.notify();
+    }
+
+    /// Finish rotating shapes.
+    pub fn finish_rotate(&mut self, cx: &mut Context<Self>) {
+        if let Some(DragState::RotatingShapes { .. }) = self.drag.take() {
+            self.commit_gesture();
+            cx.emit(CanvasEvent::ContentChanged);
+            cx.notify();
+        }
+    }
+
+    /// Mirror selected shapes. Several shapes also swap places across the
+    /// selection's center, so the group mirrors as a whole.
+    pub fn flip_selected(&mut self, axis: FlipAxis, cx: &mut Context<Self>) {
+        let Some((min, max)) = self.selection_bounds() else {
+            return;
+        };
+        let before = self.snapshot();
+        let mid = (min.0 + max.0) / 2.0;
+        let mirror_positions = self.selection.len() > 1;
+
+        for shape in &mut self.shapes {
+            if !self.selection.contains(&shape.id) {
+                continue;
+            }
+            shape.flip(axis);
+            if mirror_positions {
+                let center = shape.local_placement().center();
+                let mirrored = match axis {
+                    FlipAxis::Horizontal => Vec2::new(2.0 * mid.x - center.x, center.y),
+                    FlipAxis::Vertical => Vec2::new(center.x, 2.0 * mid.y - center.y),
+                };
+                shape.translate(CanvasDelta(mirrored - center));
+            }
+        }
+        self.push_undo(before);
+        cx.emit(CanvasEvent::ContentChanged);
+        cx.notify();
+    }
+
+    /// The selection's box in canvas space, which resize and rotate handles
+    /// are drawn around. A single shape's box turns with it; several shapes
+    /// share their axis-aligned bounds.
+    pub fn selection_frame(&self) -> Option<Placement> {
+        if let Some(shape) = self.selected_shape() {
+            return Some(self.placement_of(shape).unflipped());
+        }
+        let (min, max) = self.selection_bounds()?;
+        Some(Placement::new(min, CanvasSize(max.0 - min.0)))
+    }
+
+    /// The selected shape, if exactly one is selected.
+    fn selected_shape(&self) -> Option<&Shape> {
+        match self.selection.len() {
+            1 => self.selection.iter().next().and_then(|id| self.get_shape(*id)),
+            _ => None,
+        }
+    }
+
+    /// The world placement of a shape's parent, or the identity for root
+    /// shapes, mapping canvas points into the space the shape's position
+    /// is in.
+    fn parent_placement(&self, shape: &Shape) -> Placement {
+        shape
+            .parent
+            .and_then(|id| self.get_shape(id))
+            .map(|parent| self.placement_of(parent))
+            .unwrap_or_default()
+    }
+
+    /// Finish resizing shapes.
+    pub fn finish_resize(&mut self, cx: &mut Context<Self>) {
+        if let Some(DragState::ResizingShapes { shape_ids, .. }) = self.drag.take() {
+            self.stop_snapping();
+            // If any resized shapes are frames with layout, reapply their layout
+            for shape_id in &shape_ids {
+                if self.get_shape(*shape_id).map(|s| s.has_layout()).unwrap_or(false) {
+                    self.apply_layout_for_frame(*shape_id);
+                }
+            }
+            self.commit_gesture();
+            cx.emit(CanvasEvent::ContentChanged);
+            cx.notify();
+        }
+    }
+
+    /// Start a marquee selection. With `additive`, shapes it takes are added
+    /// to the current selection rather than replacing it.
+    pub fn start_marquee(&mut self, start: CanvasPoint, additive: bool, cx: &mut Context<Self>) {
+        let base = if additive {
+            self.selection.iter().copied().collect()
+        } else {
+            self.clear_selection(cx);
+            Vec::new()
+        };
+        self.drag = Some(DragState::Selecting {
+            start,
+            current: start,
+            base,
+        });
+    }
+
+    /// Drag the marquee's free corner to `current`, selecting what it takes
+    /// as it goes.
+    pub fn update_marquee(&mut self, current: CanvasPoint, mode: MarqueeMode, cx: &mut Context<Self>) {
+        let Some(DragState::Selecting { start, base, .. }) = self.drag.clone() else {
+            return;
+        };
+        self.drag = Some(DragState::Selecting {
+            start,
+            current,
+            base: base.clone(),
+        });
+
+        let marquee = Marquee::new(start.0, current.0);
+        // Only shapes near the marquee can be taken, but the walk down to
+        // them passes through their ancestors
+        let reachable = self.with_ancestors(self.spatial_index.query(marquee.min, marquee.max));
+        let hits = marquee_selection(&self.shapes, marquee, mode, |shape| {
+            let pickable = shape.visible && !shape.locked;
+            (pickable && reachable.contains(&shape.id)).then(|| self.placement_of(shape))
+        });
+        let selection: HashSet<_> = base.into_iter().chain(hits).collect();
+        if selection != self.selection {
+            self.selection = selection;
+            cx.emit(CanvasEvent::SelectionChanged);
+        }
+        cx.notify();
+    }
+
+    /// Finish a marquee selection, keeping what it selected.
+    pub fn finish_marquee(&mut self, cx: &mut Context<Self>) {
+        if let Some(DragState::Selecting { .. }) = self.drag.take() {
+            cx.notify();
+        }
+    }
+
+    /// Shapes whose world bounds meet the rectangle from `min` to `max`,
+    /// in no particular order. Bounds are as of the last
+    /// [`compute_world_positions`](Self::compute_world_positions).
+    pub fn shapes_in_bounds(&self, min: CanvasPoint, max: CanvasPoint) -> Vec<ShapeId> {
+        self.spatial_index.query(min.0, max.0)
+    }
 
-                // Apply flip: if flipped, mirror the relative position
-                let rel_pos = Vec2::new(
-                    if flip_x { start_size.x - rel_pos.x - orig_size.x } else { rel_pos.x },
-                    if flip_y { start_size.y - rel_pos.y - orig_size.y } else { rel_pos.y },
-                );
+    /// Shapes to paint for a view of the rectangle from `min` to `max`:
+    /// those within it, and their ancestors, which paint them.
+    pub fn shapes_to_paint(&self, min: CanvasPoint, max: CanvasPoint) -> HashSet<ShapeId> {
+        self.with_ancestors(self.spatial_index.query(min.0, max.0))
+    }
 
-                // Scale position and size
-                shape.position = CanvasPoint(new_min + rel_pos * scale);
-                shape.size = CanvasSize(orig_size * scale);
+    /// `ids` and all their ancestors.
+    fn with_ancestors(&self, ids: Vec<ShapeId>) -> HashSet<ShapeId> {
+        let mut set = HashSet::with_capacity(ids.len());
+        for id in ids {
+            let mut next = Some(id);
+            // Stop at the first ancestor already in, whose own are too
+            while let Some(id) = next.filter(|id| set.insert(*id)) {
+                next = self.get_shape(id).and_then(|shape| shape.parent);
             }
         }
-        cx.notify();
+        set
     }
 
-    /// Finish resizing shapes.
-    pub fn finish_resize(&mut self, cx: &mut Context<Self>) {
-        if let Some(DragState::ResizingShapes { shape_ids, .. }) = self.drag.take() {
-            // If any resized shapes are frames with layout, reapply their layout
-            for shape_id in &shape_ids {
-                if self.get_shape(*shape_id).map(|s| s.has_layout()).unwrap_or(false) {
-                    self.apply_layout_for_frame(*shape_id);
-                }
-            }
-            cx.emit(CanvasEvent::ContentChanged);
-            cx.notify();
+    /// The marquee being dragged, if any.
+    pub fn marquee(&self) -> Option<Marquee> {
+        match &self.drag {
+            Some(DragState::Selecting { start, current, .. }) => Some(Marquee::new(start.0, current.0)),
+            _ => None,
         }
     }
 
@@ -704,6 +2187,7 @@ impl Canvas {
     pub fn update_pan(&mut self, current_screen_pos: ScreenPoint, cx: &mut Context<Self>) {
         if let Some(DragState::Panning { last_screen_pos }) = &mut self.drag {
             let delta = current_screen_pos.0 - last_screen_pos.0;
+            self.view_animation = None;
             self.viewport.pan(delta);
             *last_screen_pos = current_screen_pos;
             cx.notify();
@@ -717,8 +2201,16 @@ impl Canvas {
         }
     }
 
+    /// Pan the view by a delta in screen pixels.
+    pub fn pan(&mut self, delta: Vec2, cx: &mut Context<Self>) {
+        self.view_animation = None;
+        self.viewport.pan(delta);
+        cx.notify();
+    }
+
     /// Zoom at a screen point.
     pub fn zoom_at(&mut self, screen_point: Point<f32>, factor: f32, cx: &mut Context<Self>) {
+        self.view_animation = None;
         self.viewport.zoom_at(screen_point, factor);
         cx.notify();
     }
@@ -730,6 +2222,15 @@ impl Canvas {
             .unwrap_or(false)
     }
 
+    /// Check if a shape's parent is a layout frame, whether or not the shape
+    /// itself is absolutely positioned.
+    pub fn has_layout_parent(&self, shape_id: ShapeId) -> bool {
+        self.get_shape(shape_id)
+            .and_then(|s| s.parent)
+            .and_then(|parent| self.get_shape(parent))
+            .is_some_and(|parent| parent.has_layout())
+    }
+
     /// Check if any selected shapes are in autolayout.
     pub fn selection_in_autolayout(&self) -> bool {
         self.selection.iter().any(|id| self.is_in_autolayout(*id))
@@ -759,9 +2260,83 @@ impl Canvas {
         Some((CanvasPoint(min), CanvasPoint(max)))
     }
 
-    /// Apply layout for a single frame to its children.
-    /// Does nothing if the frame doesn't have layout enabled.
+    /// Reapply layout after a change to `frame_id` or its children.
+    ///
+    /// Hug sizes propagate upward and Fill sizes downward, so a change deep in
+    /// a tree of layout frames can move shapes anywhere in it. The whole tree
+    /// under the frame's top-level ancestor is laid out again.
     pub fn apply_layout_for_frame(&mut self, frame_id: ShapeId) {
+        let mut root = frame_id;
+        while let Some(parent) = self.get_shape(root).and_then(|s| s.parent) {
+            root = parent;
+        }
+        let frames = self.layout_frames_under(&[root]);
+        self.apply_layouts(&frames);
+    }
+
+    /// Lay out `frames`, which must be in parent-before-child order.
+    ///
+    /// Hug frames are sized deepest-first so each frame sees its children's
+    /// final sizes, then positions are assigned top-down so Fill and Stretch
+    /// children see their parent's final size.
+    fn apply_layouts(&mut self, frames: &[ShapeId]) {
+        for frame_id in frames.iter().rev() {
+            self.resolve_hug_size(*frame_id);
+        }
+        for frame_id in frames {
+            self.layout_children(*frame_id);
+        }
+    }
+
+    /// Layout frames in the subtrees rooted at `roots`, parents first.
+    fn layout_frames_under(&self, roots: &[ShapeId]) -> Vec<ShapeId> {
+        let mut frames = Vec::new();
+        let mut stack: Vec<ShapeId> = roots.iter().rev().copied().collect();
+        while let Some(id) = stack.pop() {
+            let Some(shape) = self.get_shape(id) else {
+                continue;
+            };
+            if shape.has_layout() {
+                frames.push(id);
+            }
+            stack.extend(shape.children.iter().rev());
+        }
+        frames
+    }
+
+    /// Layout inputs for the in-flow children among `children_ids`. Hidden
+    /// children take no room.
+    fn layout_inputs(&self, children_ids: &[ShapeId]) -> Vec<LayoutInput> {
+        children_ids
+            .iter()
+            .filter_map(|child_id| self.get_shape(*child_id))
+            .filter(|child| child.visible && !child.child_layout.absolute)
+            .map(|child| LayoutInput::from_child_layout(child.id, child.size, &child.child_layout))
+            .collect()
+    }
+
+    /// Resize a layout frame on its Hug axes to fit its children.
+    fn resolve_hug_size(&mut self, frame_id: ShapeId) {
+        let Some(frame) = self.get_shape(frame_id) else {
+            return;
+        };
+        let Some(layout) = frame.layout.as_ref() else {
+            return;
+        };
+        let sizing = &frame.child_layout;
+        if sizing.width_mode != SizingMode::Hug && sizing.height_mode != SizingMode::Hug {
+            return;
+        }
+        let inputs = self.layout_inputs(&frame.children);
+        let size = resolve_frame_size(frame.size, sizing, layout, &inputs);
+        if let Some(frame) = self.get_shape_mut(frame_id) {
+            // Hug is the frame's real size, not a layout override
+            frame.size = size;
+        }
+    }
+
+    /// Position and size the children of a single layout frame.
+    fn layout_children(&mut self, frame_id: ShapeId) {
         // Gather frame info and children order
         let (frame_size, layout, children_ids) = {
             let Some(frame) = self.get_shape(frame_id) else {
@@ -770,21 +2345,20 @@ impl Canvas {
             let Some(layout) = frame.layout.clone() else {
                 return; // No layout enabled
             };
-            (frame.size, layout, frame.children.clone())
+            // The parent's layout may have stretched this frame
+            (frame.effective_size(), layout, frame.children.clone())
         };
 
+        // Absolute children don't take part in the flow; they only follow
+        // changes to the frame's size
+        if let Some(previous_size) = self.layout_sizes.insert(frame_id, frame_size) {
+            if previous_size != frame_size {
+                self.apply_absolute_constraints(&children_ids, previous_size, frame_size);
+            }
+        }
+
         // Gather children info in the order specified by frame.children
-        let child_inputs: Vec<LayoutInput> = children_ids
-            .iter()
-            .filter_map(|child_id| {
-                self.get_shape(*child_id).map(|child| LayoutInput {
-                    id: child.id,
-                    size: child.size,
-                    width_mode: child.child_layout.width_mode,
-                    height_mode: child.child_layout.height_mode,
-                })
-            })
-            .collect();
+        let child_inputs = self.layout_inputs(&children_ids);
 
         if child_inputs.is_empty() {
             return;
@@ -809,8 +2383,71 @@ impl Canvas {
         }
     }
 
+    fn apply_absolute_constraints(
+        &mut self,
+        children_ids: &[ShapeId],
+        old_frame_size: CanvasSize,
+        new_frame_size: CanvasSize,
+    ) {
+        for child_id in children_ids {
+            let Some(child) = self.get_shape_mut(*child_id) else {
+                continue;
+            };
+            if !child.child_layout.absolute {
+                continue;
+            }
+            let (position, size) = apply_constraints(
+                child.position,
+                child.size,
+                old_frame_size,
+                new_frame_size,
+                &child.child_layout,
+            );
+            child.position = position;
+            child.resize(size);
+        }
+    }
+
+    /// Take a shape out of its parent's layout flow, or put it back.
+    ///
+    /// A shape made absolute keeps the position and size it was laid out at,
+    /// so it doesn't jump. Returns false if the shape doesn't exist or its
+    /// parent isn't a layout frame.
+    pub fn set_absolute(&mut self, shape_id: ShapeId, absolute: bool) -> bool {
+        if !self.has_layout_parent(shape_id) {
+            return false;
+        }
+        let Some(shape) = self.get_shape_mut(shape_id) else {
+            return false;
+        };
+        if shape.child_layout.absolute == absolute {
+            return true;
+        }
+        if absolute {
+            shape.position = shape.effective_position();
+            let size = shape.effective_size();
+            shape.resize(size);
+            shape.clear_computed();
+        }
+        shape.child_layout.absolute = absolute;
+        self.apply_layout_for_frame(shape_id);
+        true
+    }
+
+    /// Take the current size of every layout frame as the baseline for
+    /// constraints, after the shape list was replaced wholesale.
+    fn reset_layout_sizes(&mut self) {
+        self.layout_sizes = self
+            .shapes
+            .iter()
+            .filter(|s| s.has_layout())
+            .map(|s| (s.id, s.effective_size()))
+            .collect();
+    }
+
     /// Clear computed layout values for children when layout is disabled.
     pub fn clear_layout_for_frame(&mut self, frame_id: ShapeId) {
+        self.layout_sizes.remove(&frame_id);
         let children_ids: Vec<ShapeId> = {
             let Some(frame) = self.get_shape(frame_id) else {
                 return;
@@ -827,18 +2464,14 @@ impl Canvas {
 
     /// Apply layout for all frames that have layout enabled.
     pub fn apply_all_layouts(&mut self) {
-        // Collect frame IDs (we need to avoid borrowing issues)
-        let frame_ids: Vec<ShapeId> = self
+        let roots: Vec<ShapeId> = self
             .shapes
             .iter()
-            .filter(|s| s.has_layout())
+            .filter(|s| s.parent.is_none())
             .map(|s| s.id)
             .collect();
-
-        // Process each frame
-        for frame_id in frame_ids {
-            self.apply_layout_for_frame(frame_id);
-        }
+        let frames = self.layout_frames_under(&roots);
+        self.apply_layouts(&frames);
     }
 
     /// Rebuild the shape index from the current shapes vec.
@@ -850,17 +2483,535 @@ impl Canvas {
         }
     }
 
-    /// Load shapes from an external source, replacing all existing shapes.
-    /// Rebuilds the index automatically.
+    /// Load shapes from an external source as a single-page document,
+    /// replacing every page.
     pub fn load_shapes(&mut self, shapes: Vec<Shape>, cx: &mut Context<Self>) {
-        self.shapes = shapes;
+        self.load_pages(vec![(DEFAULT_PAGE_NAME.to_string(), shapes, Vec::new())], cx);
+    }
+
+    /// Load a document's pages, each its name, shapes and guides, replacing
+    /// every page, and open the first. An empty list loads a single empty
+    /// page.
+    pub fn load_pages(
+        &mut self,
+        pages: Vec<(String, Vec<Shape>, Vec<Guide>)>,
+        cx: &mut Context<Self>,
+    ) {
+        // Documents from before shapes had names
+        let mut pages = pages.into_iter().map(|(name, mut shapes, guides)| {
+            name_unnamed(&mut shapes);
+            (name, PageState::new(shapes, guides))
+        });
+        let (name, first) = pages
+            .next()
+            .unwrap_or_else(|| (DEFAULT_PAGE_NAME.to_string(), PageState::default()));
+        self.pages = Pages::new(name);
+        for (name, page) in pages {
+            self.pages.push(name, page);
+        }
+        self.enter_page(first, cx);
+    }
+
+    // === View ===
+
+    /// The part of the canvas shapes can be seen in, clear of the rulers,
+    /// relative to its top-left.
+    fn view_area(&self) -> Bounds<f32> {
+        let (width, height) = if self.bounds.size.width > Pixels::ZERO {
+            (self.bounds.size.width.into(), self.bounds.size.height.into())
+        } else {
+            DEFAULT_VIEW_SIZE
+        };
+        Bounds {
+            origin: gpui::point(RULER_SIZE, RULER_SIZE),
+            size: gpui::size((width - RULER_SIZE).max(1.0), (height - RULER_SIZE).max(1.0)),
+        }
+    }
+
+    /// Change the view to `viewport`, easing there over a few frames when
+    /// `animate` is set.
+    pub fn set_viewport(&mut self, viewport: Viewport, animate: bool, cx: &mut Context<Self>) {
+        if animate {
+            self.view_animation = Some(ViewAnimation::new(self.viewport.clone(), viewport));
+        } else {
+            self.view_animation = None;
+            self.viewport = viewport;
+        }
+        cx.notify();
+    }
+
+    /// Move the view along the animation under way. Returns whether it's
+    /// still under way.
+    pub fn step_view_animation(&mut self) -> bool {
+        let Some(animation) = &self.view_animation else {
+            return false;
+        };
+        let (viewport, done) = animation.at(std::time::Instant::now(), self.view_area());
+        self.viewport = viewport;
+        if done {
+            self.view_animation = None;
+        }
+        !done
+    }
+
+    /// Where the view is headed: the end of the animation under way, or
+    /// the view itself.
+    fn target_viewport(&self) -> &Viewport {
+        self.view_animation.as_ref().map_or(&self.viewport, ViewAnimation::target)
+    }
+
+    /// Zoom to fit every shown shape in the view. Returns false, leaving
+    /// the view alone, when there's nothing to show.
+    pub fn zoom_to_fit(&mut self, animate: bool, cx: &mut Context<Self>) -> bool {
+        let shown: Vec<_> = self.shapes.iter().map(|s| s.id).filter(|id| self.is_shown(*id)).collect();
+        self.zoom_to_shapes(&shown, animate, cx)
+    }
+
+    /// Zoom to fit the selection in the view. Returns false when nothing
+    /// is selected.
+    pub fn zoom_to_selection(&mut self, animate: bool, cx: &mut Context<Self>) -> bool {
+        let selected: Vec<_> = self.selection.iter().copied().collect();
+        self.zoom_to_shapes(&selected, animate, cx)
+    }
+
+    /// Zoom to fit shapes in the view, centered. Returns false when none of
+    /// them exist.
+    pub fn zoom_to_shapes(&mut self, ids: &[ShapeId], animate: bool, cx: &mut Context<Self>) -> bool {
+        let Some((min, max)) = self.union_bounds(ids) else {
+            return false;
+        };
+        let viewport = Viewport::framing(min, max, self.view_area(), ZOOM_PADDING);
+        self.set_viewport(viewport, animate, cx);
+        true
+    }
+
+    /// Pan the view just far enough to show shapes, at the zoom it's at.
+    /// Returns false when none of them exist.
+    pub fn scroll_into_view(&mut self, ids: &[ShapeId], animate: bool, cx: &mut Context<Self>) -> bool {
+        let Some((min, max)) = self.union_bounds(ids) else {
+            return false;
+        };
+        let viewport = self.target_viewport().scrolled_to(min, max, self.view_area(), ZOOM_PADDING);
+        if viewport != *self.target_viewport() {
+            self.set_viewport(viewport, animate, cx);
+        }
+        true
+    }
+
+    /// Set the zoom, where 1.0 is 100%, keeping the middle of the view
+    /// where it is.
+    pub fn set_zoom(&mut self, zoom: f32, animate: bool, cx: &mut Context<Self>) {
+        let mut viewport = self.target_viewport().clone();
+        viewport.set_zoom(zoom, self.view_area().center());
+        self.set_viewport(viewport, animate, cx);
+    }
+
+    /// Zoom in to the next zoom level, or out when `zoom_in` is false.
+    pub fn step_zoom(&mut self, zoom_in: bool, animate: bool, cx: &mut Context<Self>) {
+        let zoom = step_zoom(self.target_viewport().zoom, zoom_in);
+        self.set_zoom(zoom, animate, cx);
+    }
+
+    // === Pages ===
+
+    pub fn pages(&self) -> &Pages {
+        &self.pages
+    }
+
+    /// Shapes on page `index`, whether or not it's active.
+    pub fn page_shapes(&self, index: usize) -> Option<&[Shape]> {
+        if index == self.pages.active() {
+            Some(&self.shapes)
+        } else {
+            self.pages.stored(index).map(|page| page.shapes.as_slice())
+        }
+    }
+
+    /// Guides on page `index`, whether or not it's active.
+    pub fn page_guides(&self, index: usize) -> Option<&[Guide]> {
+        if index == self.pages.active() {
+            Some(&self.guides)
+        } else {
+            self.pages.stored(index).map(|page| page.guides.as_slice())
+        }
+    }
+
+    /// Every page's name, shapes and guides, in order, for saving.
+    pub fn page_documents(&self) -> Vec<(String, Vec<Shape>, Vec<Guide>)> {
+        (0..self.pages.len())
+            .filter_map(|index| {
+                let name = self.pages.name(index)?.to_string();
+                let shapes = self.page_shapes(index)?.to_vec();
+                Some((name, shapes, self.page_guides(index)?.to_vec()))
+            })
+            .collect()
+    }
+
+    /// Open page `index`, keeping the current page's shapes, selection, view
+    /// and history for when it's opened again. Returns false if there's no
+    /// such page or it's already open.
+    pub fn switch_page(&mut self, index: usize, cx: &mut Context<Self>) -> bool {
+        if index >= self.pages.len() || index == self.pages.active() {
+            return false;
+        }
+        self.finish_editing(cx);
+        let current = self.take_page_state();
+        let incoming = self.pages.switch(index, current);
+        self.enter_page(incoming, cx);
+        true
+    }
+
+    /// Add an empty page after the last and open it. Without a name, it's
+    /// numbered after the existing pages. Returns its index.
+    pub fn add_page(&mut self, name: Option<String>, cx: &mut Context<Self>) -> usize {
+        let name = name.unwrap_or_else(|| self.pages.next_name());
+        let index = self.pages.push(name, PageState::default());
+        self.switch_page(index, cx);
+        index
+    }
+
+    /// Returns false if there's no page `index`.
+    pub fn rename_page(&mut self, index: usize, name: impl Into<String>, cx: &mut Context<Self>) -> bool {
+        let renamed = self.pages.rename(index, name);
+        if renamed {
+            cx.emit(CanvasEvent::PagesChanged);
+            cx.notify();
+        }
+        renamed
+    }
+
+    /// Move page `from` to position `to`. Returns false if either is out of
+    /// range.
+    pub fn move_page(&mut self, from: usize, to: usize, cx: &mut Context<Self>) -> bool {
+        let moved = self.pages.move_page(from, to);
+        if moved {
+            cx.emit(CanvasEvent::PagesChanged);
+            cx.notify();
+        }
+        moved
+    }
+
+    /// Delete page `index` and everything on it. Deleting the open page opens
+    /// the one before it, or after it if it was first. Returns false if
+    /// there's no such page or it's the only one; a document always has a
+    /// page.
+    pub fn delete_page(&mut self, index: usize, cx: &mut Context<Self>) -> bool {
+        if index >= self.pages.len() || self.pages.len() == 1 {
+            return false;
+        }
+        if index == self.pages.active() {
+            let neighbour = if index == 0 { 1 } else { index - 1 };
+            self.switch_page(neighbour, cx);
+        }
+        self.pages.remove(index);
+        cx.emit(CanvasEvent::PagesChanged);
+        cx.notify();
+        true
+    }
+
+    // === Components ===
+
+    /// Bring component instances up to date with their mains. Edits reach
+    /// the shapes directly from many places, so this runs as each edit is
+    /// recorded and around each command rather than after each edit.
+    /// Returns true if any instance changed.
+    pub fn sync_components(&mut self) -> bool {
+        if !self.component_sync.sync(&mut self.shapes) {
+            return false;
+        }
         self.rebuild_index();
-        self.selection.clear();
+        // Shapes removed from a main leave its instances too
+        let index = &self.shape_index;
+        self.selection.retain(|id| index.contains_key(id));
+        if self.hovered.is_some_and(|id| !index.contains_key(&id)) {
+            self.hovered = None;
+        }
+        self.apply_all_layouts();
+        // Typography follows the main, content may not
+        self.fit_text_sizes();
+        self.compute_world_positions();
+        true
+    }
+
+    /// Make the target frames main components. Returns the frames that
+    /// became mains; shapes that aren't frames, or are already part of a
+    /// component, are left alone.
+    pub fn make_components(&mut self, ids: &[ShapeId], cx: &mut Context<Self>) -> Vec<ShapeId> {
+        let mut made = Vec::new();
+        for shape in &mut self.shapes {
+            if ids.contains(&shape.id) && shape.kind == ShapeKind::Frame && shape.component.is_none() {
+                shape.component = Some(ComponentLink::Main);
+                made.push(shape.id);
+            }
+        }
+        if !made.is_empty() {
+            cx.emit(CanvasEvent::ContentChanged);
+            cx.notify();
+        }
+        made
+    }
+
+    /// Place a new instance of the main component `main` at the root level,
+    /// its top-left at `position`, or beside the main without one. Returns
+    /// the instance's root, or None if `main` isn't a main component.
+    pub fn create_instance(
+        &mut self,
+        main: ShapeId,
+        position: Option<CanvasPoint>,
+        cx: &mut Context<Self>,
+    ) -> Option<ShapeId> {
+        let mut tree = instantiate(&self.shapes, main)?;
+        let main_shape = self.get_shape(main)?;
+        let (min, max) = main_shape.world_placement(&self.shapes).bounds();
+        tree[0].position = position.unwrap_or(CanvasPoint::new(max.x() + 40.0, min.y()));
+        tree[0].rotation = 0.0;
+        let root = tree[0].id;
+        for shape in tree {
+            self.add_shape(shape, cx);
+        }
+        self.apply_layout_for_frame(root);
+        self.compute_world_positions();
+        self.select(root, false, cx);
+        Some(root)
+    }
+
+    /// Unlink an instance from its main, keeping its shapes as they look.
+    /// Returns false if `root` isn't an instance.
+    pub fn detach_instance(&mut self, root: ShapeId, cx: &mut Context<Self>) -> bool {
+        if !detach_instance(&mut self.shapes, root) {
+            return false;
+        }
+        // The relinked copies' last sync was from their old sources
+        self.component_sync.clear();
+        self.sync_components();
+        cx.emit(CanvasEvent::ContentChanged);
+        cx.notify();
+        true
+    }
+
+    /// Drop the overrides of the target copies, so they take their fill,
+    /// stroke and text from the main again. Returns the shapes that had
+    /// overrides.
+    pub fn reset_overrides(&mut self, ids: &[ShapeId], cx: &mut Context<Self>) -> Vec<ShapeId> {
+        let mut reset = Vec::new();
+        for shape in &mut self.shapes {
+            if !ids.contains(&shape.id) {
+                continue;
+            }
+            if let Some(overrides) = shape.component.as_mut().and_then(|link| link.overrides_mut()) {
+                if !overrides.is_empty() {
+                    *overrides = Default::default();
+                    reset.push(shape.id);
+                }
+            }
+        }
+        if !reset.is_empty() {
+            // Otherwise the overridden values, unlike the last sync's, read
+            // as fresh edits
+            self.component_sync.clear();
+            self.sync_components();
+            cx.emit(CanvasEvent::ContentChanged);
+            cx.notify();
+        }
+        reset
+    }
+
+    /// Commit any in-place edit or drag, so it lands in the history of the
+    /// page it was made on.
+    fn finish_editing(&mut self, cx: &mut Context<Self>) {
+        self.finish_text_edit(cx);
+        self.finish_path_edit(cx);
+        self.drag = None;
+        self.stop_snapping();
+        self.commit_gesture();
+    }
+
+    fn take_page_state(&mut self) -> PageState {
+        PageState {
+            shapes: std::mem::take(&mut self.shapes),
+            guides: std::mem::take(&mut self.guides),
+            selection: std::mem::take(&mut self.selection),
+            viewport: std::mem::take(&mut self.viewport),
+            history: std::mem::take(&mut self.history),
+        }
+    }
+
+    /// Put a page's state on the canvas and rebuild everything derived from
+    /// its shapes.
+    fn enter_page(&mut self, page: PageState, cx: &mut Context<Self>) {
+        self.shapes = page.shapes;
+        self.guides = page.guides;
+        self.selection = page.selection;
+        self.viewport = page.viewport;
+        self.history = page.history;
+        self.component_sync.clear();
+        self.rebuild_index();
+        self.reset_layout_sizes();
+        // Until a sync records what copies were given, edits to them would
+        // be taken for their mains'
+        self.sync_components();
+        // Files hold layout settings, not their results
+        self.apply_all_layouts();
+        // Nor text measured with this machine's fonts
+        self.fit_text_sizes();
+        self.spatial_index.clear();
+        self.compute_world_positions();
+        self.hovered = None;
+        self.drag = None;
+        self.stop_snapping();
+        self.view_animation = None;
+        self.editing_text = None;
+        self.editing_path = None;
+        self.gesture_start = None;
+        cx.emit(CanvasEvent::SelectionChanged);
+        cx.emit(CanvasEvent::ContentChanged);
+        cx.emit(CanvasEvent::PagesChanged);
+        cx.notify();
+    }
+
+    /// Capture the undoable state of the canvas.
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            shapes: self.shapes.clone(),
+            selection: self.selection.clone(),
+            guides: self.guides.clone(),
+        }
+    }
+
+    /// Record `before` as an undo step if the document changed since it was
+    /// taken. Selection-only changes are not undoable on their own.
+    pub fn push_undo(&mut self, before: Snapshot) {
+        // Instances follow their mains within the edit's step
+        self.sync_components();
+        self.fit_text_sizes();
+        if before.shapes != self.shapes || before.guides != self.guides {
+            self.history.push(before);
+        }
+    }
+
+    /// Like [`Canvas::push_undo`], but merges rapid edits sharing `key`
+    /// (keystrokes in one field, repeated nudges) into a single step.
+    pub fn push_undo_coalesced(&mut self, before: Snapshot, key: &'static str) {
+        self.sync_components();
+        self.fit_text_sizes();
+        if before.shapes != self.shapes || before.guides != self.guides {
+            self.history.push_coalesced(before, key);
+        }
+    }
+
+    /// Edit shape `id` and record the edit as an undo step. Edits sharing
+    /// `coalesce`, such as keystrokes in one field, make one step. Returns
+    /// false if there's no such shape.
+    pub fn edit_shape(
+        &mut self,
+        id: ShapeId,
+        coalesce: Option<&'static str>,
+        cx: &mut Context<Self>,
+        edit: impl FnOnce(&mut Shape),
+    ) -> bool {
+        let before = self.snapshot();
+        let Some(shape) = self.get_shape_mut(id) else {
+            return false;
+        };
+        edit(shape);
+        match coalesce {
+            Some(key) => self.push_undo_coalesced(before, key),
+            None => self.push_undo(before),
+        }
+        cx.emit(CanvasEvent::ContentChanged);
+        cx.notify();
+        true
+    }
+
+    fn begin_gesture(&mut self) {
+        self.gesture_start = Some(self.snapshot());
+    }
+
+    fn commit_gesture(&mut self) {
+        if let Some(before) = self.gesture_start.take() {
+            self.push_undo(before);
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.history.can_undo()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.history.can_redo()
+    }
+
+    /// Revert the last recorded edit. Returns false if there was nothing to undo.
+    pub fn undo(&mut self, cx: &mut Context<Self>) -> bool {
+        // Undoing mid-gesture would fight the gesture for the shapes
+        if self.drag.is_some() || self.editing_text.is_some() || self.editing_path.is_some() {
+            return false;
+        }
+        match self.history.undo(self.snapshot()) {
+            Some(snapshot) => {
+                self.restore(snapshot, cx);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Reapply the last undone edit. Returns false if there was nothing to redo.
+    pub fn redo(&mut self, cx: &mut Context<Self>) -> bool {
+        if self.drag.is_some() || self.editing_text.is_some() || self.editing_path.is_some() {
+            return false;
+        }
+        match self.history.redo(self.snapshot()) {
+            Some(snapshot) => {
+                self.restore(snapshot, cx);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn restore(&mut self, snapshot: Snapshot, cx: &mut Context<Self>) {
+        self.shapes = snapshot.shapes;
+        self.selection = snapshot.selection;
+        self.guides = snapshot.guides;
+        self.component_sync.clear();
+        self.rebuild_index();
+        self.reset_layout_sizes();
+        self.sync_components();
+        if self.hovered.is_some_and(|id| !self.shape_index.contains_key(&id)) {
+            self.hovered = None;
+        }
+        cx.emit(CanvasEvent::SelectionChanged);
         cx.emit(CanvasEvent::ContentChanged);
         cx.notify();
     }
 }
 
+/// Where a guide along `axis` through `point` crosses it.
+fn guide_along(axis: GuideAxis, point: CanvasPoint) -> f32 {
+    match axis {
+        GuideAxis::Horizontal => point.y(),
+        GuideAxis::Vertical => point.x(),
+    }
+}
+
+/// Bounds a shape is indexed under: everything it paints. Strokes centered
+/// on or outside its edge reach past the box.
+fn index_bounds(shape: &Shape, placement: &Placement) -> (Vec2, Vec2) {
+    let (min, max) = placement.bounds();
+    let overhang = shape
+        .strokes
+        .iter()
+        .filter(|stroke| stroke.visible)
+        .map(|stroke| match stroke.align {
+            StrokeAlign::Inside => 0.0,
+            StrokeAlign::Center => stroke.width / 2.0,
+            StrokeAlign::Outside => stroke.width,
+        })
+        .fold(0.0, f32::max);
+    (min.0 - overhang, max.0 + overhang)
+}
+
 impl EventEmitter<CanvasEvent> for Canvas {}
 
 impl Focusable for Canvas {
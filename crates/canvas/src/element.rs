@@ -1,16 +1,40 @@
 use crate::canvas::{Canvas, DragState, ResizeHandle, Tool};
+use crate::gradient;
+use crate::marquee::MarqueeMode;
+use crate::outline;
+use crate::pen::{self, PathEdit};
+use crate::ruler;
+use crate::snap::SnapGuide;
+use crate::stroke;
+use crate::text::TextLayout;
 use glam::Vec2;
 use gpui::{
     point, px, size, transparent_black, App, BorderStyle, Bounds, ContentMask, DispatchPhase,
     Element, ElementId, Entity, Hitbox, IntoElement, MouseButton, MouseDownEvent, MouseMoveEvent,
-    MouseUpEvent, PaintQuad, Pixels, ScrollDelta, ScrollWheelEvent, Style, Window,
+    MouseUpEvent, PaintQuad, PathBuilder, Pixels, Point, ScrollDelta, ScrollWheelEvent, Style,
+    Window,
 };
-use node::{CanvasPoint, CanvasSize, ScreenPoint, Shape, ShapeId, ShapeKind};
-use std::collections::HashSet;
+use node::{
+    CanvasPoint, CanvasSize, Guide, GuideAxis, HandleSide, Placement, Paint, ScreenPoint, Shape,
+    ShapeId, ShapeKind,
+};
+use std::collections::{HashMap, HashSet};
 
 /// Size of resize handles in pixels.
 const HANDLE_SIZE: f32 = 8.0;
 
+/// Distance in pixels from the selection's top edge to the rotate knob.
+const ROTATE_KNOB_OFFSET: f32 = 20.0;
+
+/// Size of path vertex and handle markers in pixels.
+const PATH_MARKER_SIZE: f32 = 6.0;
+
+/// Grid lines closer than this many pixels aren't drawn.
+const MIN_GRID_SPACING: f32 = 8.0;
+
+/// Length in pixels of the ticks either side of a spacing guide's ends.
+const GUIDE_TICK: f32 = 3.0;
+
 /// A GPUI element that renders and handles interaction for a Canvas.
 pub struct CanvasElement {
     canvas: Entity<Canvas>,
@@ -67,8 +91,20 @@ impl Element for CanvasElement {
         bounds: Bounds<Pixels>,
         _request_layout: &mut Self::RequestLayoutState,
         window: &mut Window,
-        _cx: &mut App,
+        cx: &mut App,
     ) -> Self::PrepaintState {
+        let animating = self.canvas.update(cx, |canvas, cx| {
+            canvas.bounds = bounds;
+            let animating = canvas.step_view_animation();
+            if animating {
+                cx.notify();
+            }
+            animating
+        });
+        if animating {
+            window.request_animation_frame();
+        }
+
         let hitbox = window.insert_hitbox(bounds, gpui::HitboxBehavior::BlockMouse);
         CanvasElementState { hitbox }
     }
@@ -88,52 +124,104 @@ impl Element for CanvasElement {
         // Read canvas state for rendering
         // Layout is already applied to canvas state when children are added,
         // so we don't need to recompute it here.
-        let (shapes, selection, hovered, viewport, theme, drag, world_positions) =
+        let (shapes, selection, hovered, viewport, theme, _drag, placements) =
             self.canvas.update(cx, |canvas, _| canvas.clone_render_state());
+        let editing_text = self.canvas.read(cx).editing_text;
+        let editing_path = self.canvas.read(cx).editing_path;
+        let selection_frame = self.canvas.read(cx).selection_frame();
+        let key_shape = self.canvas.read(cx).key_shape();
+        let marquee = self.canvas.read(cx).marquee();
+        let snap_guides = self.canvas.read(cx).snap_guides().to_vec();
+        let grid = self.canvas.read(cx).snapping.grid;
+        let guides = self.canvas.read(cx).guides().to_vec();
+        let selection_bounds = self.canvas.read(cx).selection_bounds();
+        let gradient_target = self
+            .canvas
+            .read(cx)
+            .gradient_target()
+            .map(|(_, placement, gradient)| (placement, gradient.clone()));
+        // Shapes in view, from the index refreshed above, so off-screen
+        // shapes cost nothing to skip
+        let view_min = viewport.screen_to_canvas(ScreenPoint::new(0.0, 0.0));
+        let view_max = viewport.screen_to_canvas(ScreenPoint::new(
+            bounds.size.width.into(),
+            bounds.size.height.into(),
+        ));
+        let visible = self.canvas.read(cx).shapes_to_paint(view_min, view_max);
 
         // Paint background
         window.paint_quad(gpui::fill(bounds, theme.canvas_background));
 
         // Set up content mask for clipping to canvas bounds
         window.with_content_mask(Some(ContentMask { bounds }), |window| {
+            if let Some(grid) = grid {
+                paint_grid(grid, &viewport, &theme, bounds, window);
+            }
+
             // Paint only root shapes (shapes with no parent)
             // Children are painted recursively by their parent frames
             for shape in shapes.iter().filter(|s| s.parent.is_none()) {
                 paint_shape_recursive(
                     shape,
                     &shapes,
-                    &world_positions,
+                    &placements,
+                    &visible,
                     &selection,
                     hovered,
+                    editing_text,
                     &viewport,
                     &theme,
                     bounds,
+                    1.0,
                     window,
                 );
             }
 
-            // Paint multi-selection bounding box
-            if selection.len() > 1 {
-                if let Some((min, max)) =
-                    selection_bounds_from_shapes(&shapes, &selection, &viewport)
+            paint_page_guides(&guides, &viewport, &theme, bounds, window);
+
+            if let Some(frame) = selection_frame {
+                // A single shape's outline is already painted with it
+                if selection.len() > 1 {
+                    let frame_outline = outline::box_outline(&frame);
+                    if let Some(path) =
+                        outline::build(&frame_outline, &viewport, bounds.origin, PathBuilder::stroke(px(1.0)))
+                    {
+                        window.paint_path(path, theme.selection);
+                    }
+                }
+                paint_selection_handles(window, &frame, &viewport, bounds.origin, theme.selection);
+            }
+
+            if let Some((placement, gradient)) = &gradient_target {
+                gradient::paint_handles(gradient, placement, &viewport, &theme, bounds.origin, window);
+            }
+
+            if let Some(key) = key_shape.and_then(|id| placements.get(&id)) {
+                let key_outline = outline::box_outline(key);
+                if let Some(path) =
+                    outline::build(&key_outline, &viewport, bounds.origin, PathBuilder::stroke(px(2.0)))
                 {
-                    let screen_bounds = Bounds {
-                        origin: point(bounds.origin.x + px(min.x), bounds.origin.y + px(min.y)),
-                        size: size(px(max.x - min.x), px(max.y - min.y)),
-                    };
-                    window.paint_quad(gpui::outline(
-                        screen_bounds,
-                        theme.selection,
-                        BorderStyle::Solid,
-                    ));
-                    paint_selection_handles(window, screen_bounds, theme.selection);
+                    window.paint_path(path, theme.selection);
                 }
             }
 
-            // Paint drag selection rectangle if active
-            if let Some(DragState::Selecting { start: _ }) = &drag {
-                // Would need current mouse position - skip for now
+            if let Some(edit) = editing_path {
+                paint_path_edit(edit, &shapes, &placements, &viewport, &theme, bounds, window);
+            }
+
+            if let Some(marquee) = marquee {
+                let min = viewport.canvas_to_screen(CanvasPoint(marquee.min));
+                let max = viewport.canvas_to_screen(CanvasPoint(marquee.max));
+                let marquee_bounds = Bounds {
+                    origin: point(bounds.origin.x + px(min.x()), bounds.origin.y + px(min.y())),
+                    size: size(px(max.x() - min.x()), px(max.y() - min.y())),
+                };
+                window.paint_quad(gpui::fill(marquee_bounds, theme.selection.opacity(0.1)));
+                window.paint_quad(gpui::outline(marquee_bounds, theme.selection, BorderStyle::Solid));
             }
+
+            paint_guides(&snap_guides, &viewport, &theme, bounds.origin, window);
+            ruler::paint(&viewport, selection_bounds, &theme, bounds, window, cx);
         });
 
         // Register mouse event handlers
@@ -169,7 +257,7 @@ impl Element for CanvasElement {
             let hitbox = hitbox.clone();
             move |event: &MouseUpEvent, phase, window, cx| {
                 if phase == DispatchPhase::Bubble && hitbox.is_hovered(window) {
-                    handle_mouse_up(&canvas, event, cx);
+                    handle_mouse_up(&canvas, event, bounds, cx);
                 }
             }
         });
@@ -188,92 +276,84 @@ impl Element for CanvasElement {
     }
 }
 
-fn paint_selection_handles(window: &mut Window, bounds: Bounds<Pixels>, color: gpui::Hsla) {
+/// Screen positions of the selection's handles relative to the element
+/// origin: the frame's corners in [`ResizeHandle::ALL`] order, and the
+/// rotate knob above the frame's top edge.
+fn selection_handles(frame: &Placement, viewport: &crate::Viewport) -> ([Vec2; 4], Vec2) {
+    let [top_left, top_right, bottom_right, bottom_left] = frame
+        .corners()
+        .map(|corner| viewport.canvas_to_screen(CanvasPoint(corner)).0);
+    let up = (top_left - bottom_left).try_normalize().unwrap_or(Vec2::NEG_Y);
+    let knob = (top_left + top_right) / 2.0 + up * ROTATE_KNOB_OFFSET;
+    ([top_left, top_right, bottom_left, bottom_right], knob)
+}
+
+fn paint_selection_handles(
+    window: &mut Window,
+    frame: &Placement,
+    viewport: &crate::Viewport,
+    origin: Point<Pixels>,
+    color: gpui::Hsla,
+) {
+    let (corners, knob) = selection_handles(frame, viewport);
     let handle_size = px(HANDLE_SIZE);
-    let half = handle_size / 2.0;
-
-    let corners = [
-        point(bounds.origin.x - half, bounds.origin.y - half), // top-left
-        point(
-            bounds.origin.x + bounds.size.width - half,
-            bounds.origin.y - half,
-        ), // top-right
-        point(
-            bounds.origin.x - half,
-            bounds.origin.y + bounds.size.height - half,
-        ), // bottom-left
-        point(
-            bounds.origin.x + bounds.size.width - half,
-            bounds.origin.y + bounds.size.height - half,
-        ), // bottom-right
-    ];
+    let handle_at = |center: Vec2| Bounds {
+        origin: point(
+            origin.x + px(center.x) - handle_size / 2.0,
+            origin.y + px(center.y) - handle_size / 2.0,
+        ),
+        size: size(handle_size, handle_size),
+    };
+
+    let top_center = (corners[0] + corners[1]) / 2.0;
+    let mut stem = PathBuilder::stroke(px(1.0));
+    stem.move_to(point(origin.x + px(top_center.x), origin.y + px(top_center.y)));
+    stem.line_to(point(origin.x + px(knob.x), origin.y + px(knob.y)));
+    if let Ok(stem) = stem.build() {
+        window.paint_path(stem, color);
+    }
+    let knob = handle_at(knob);
+    window.paint_quad(gpui::fill(knob, gpui::white()).corner_radii(handle_size / 2.0));
+    window.paint_quad(
+        gpui::outline(knob, color, BorderStyle::Solid).corner_radii(handle_size / 2.0),
+    );
 
     for corner in corners {
-        let handle_bounds = Bounds {
-            origin: corner,
-            size: size(handle_size, handle_size),
-        };
+        let handle_bounds = handle_at(corner);
         window.paint_quad(gpui::fill(handle_bounds, gpui::white()));
         window.paint_quad(gpui::outline(handle_bounds, color, BorderStyle::Solid));
     }
 }
 
-/// Check if a screen point (relative to canvas element origin) hits a resize handle.
-/// Returns the handle if hit.
-fn hit_test_resize_handle(
-    screen_point: Vec2,
-    selection_bounds: Bounds<Pixels>,
-) -> Option<ResizeHandle> {
-    let hit_radius = HANDLE_SIZE; // Slightly larger hit area
-
-    let origin_x: f32 = selection_bounds.origin.x.into();
-    let origin_y: f32 = selection_bounds.origin.y.into();
-    let width: f32 = selection_bounds.size.width.into();
-    let height: f32 = selection_bounds.size.height.into();
-
-    let corners = [
-        (ResizeHandle::TopLeft, Vec2::new(origin_x, origin_y)),
-        (ResizeHandle::TopRight, Vec2::new(origin_x + width, origin_y)),
-        (ResizeHandle::BottomLeft, Vec2::new(origin_x, origin_y + height)),
-        (ResizeHandle::BottomRight, Vec2::new(origin_x + width, origin_y + height)),
-    ];
-
-    for (handle, center) in corners {
-        let dx = screen_point.x - center.x;
-        let dy = screen_point.y - center.y;
-        if dx.abs() <= hit_radius && dy.abs() <= hit_radius {
-            return Some(handle);
-        }
-    }
-
-    None
+/// A handle on the selection frame.
+enum FrameHandle {
+    Resize(ResizeHandle),
+    Rotate,
 }
 
-fn selection_bounds_from_shapes(
-    shapes: &[node::Shape],
-    selection: &std::collections::HashSet<node::ShapeId>,
+/// The selection handle under a screen point (relative to the canvas
+/// element origin), if any.
+fn hit_test_selection_handle(
+    screen_point: Vec2,
+    frame: &Placement,
     viewport: &crate::Viewport,
-) -> Option<(Vec2, Vec2)> {
-    let selected: Vec<_> = shapes.iter().filter(|s| selection.contains(&s.id)).collect();
-
-    if selected.is_empty() {
-        return None;
-    }
+) -> Option<FrameHandle> {
+    // Slightly larger than the handles themselves
+    let hit_radius = HANDLE_SIZE;
+    let hits = |center: Vec2| {
+        let delta = (screen_point - center).abs();
+        delta.x <= hit_radius && delta.y <= hit_radius
+    };
 
-    let mut min = Vec2::new(f32::MAX, f32::MAX);
-    let mut max = Vec2::new(f32::MIN, f32::MIN);
-
-    for shape in selected {
-        let canvas_max = CanvasPoint(shape.position.0 + shape.size.0);
-        let screen_min = viewport.canvas_to_screen(shape.position);
-        let screen_max = viewport.canvas_to_screen(canvas_max);
-        min.x = min.x.min(screen_min.x());
-        min.y = min.y.min(screen_min.y());
-        max.x = max.x.max(screen_max.x());
-        max.y = max.y.max(screen_max.y());
+    let (corners, knob) = selection_handles(frame, viewport);
+    if hits(knob) {
+        return Some(FrameHandle::Rotate);
     }
-
-    Some((min, max))
+    ResizeHandle::ALL
+        .into_iter()
+        .zip(corners)
+        .find(|(_, corner)| hits(*corner))
+        .map(|(handle, _)| FrameHandle::Resize(handle))
 }
 
 fn handle_mouse_down(
@@ -298,51 +378,100 @@ fn handle_mouse_down(
     if event.button != MouseButton::Left {
         return;
     }
+    // Control holds snapping off
+    let snap = !event.modifiers.control;
 
     canvas.update(cx, |canvas, cx| {
         let canvas_pos = canvas.viewport.screen_to_canvas(screen_pos);
 
+        // Clicking outside the text being edited ends the edit
+        canvas.finish_text_edit(cx);
+        // Path edits continue only while the pen is in hand
+        if canvas.tool != Tool::Pen {
+            canvas.finish_path_edit(cx);
+        }
+
+        // Guides are pulled out of the rulers, whatever the tool
+        if ruler::is_on_ruler(local_x, local_y) {
+            if let Some(axis) = ruler::guide_axis_at(local_x, local_y) {
+                canvas.pull_guide(axis, canvas_pos, cx);
+            }
+            return;
+        }
+
         match canvas.tool {
             Tool::Select => {
-                // First check if clicking on a resize handle (only if something is selected)
-                if !canvas.selection.is_empty() {
-                    if let Some((min, max)) = canvas.selection_bounds() {
-                        let screen_min = canvas.viewport.canvas_to_screen(min);
-                        let screen_max = canvas.viewport.canvas_to_screen(max);
-                        let selection_screen_bounds = Bounds {
-                            origin: point(px(screen_min.x()), px(screen_min.y())),
-                            size: size(px(screen_max.x() - screen_min.x()), px(screen_max.y() - screen_min.y())),
-                        };
-
-                        if let Some(handle) = hit_test_resize_handle(local_vec, selection_screen_bounds) {
+                // First check if clicking on a selection handle
+                if let Some(frame) = canvas.selection_frame() {
+                    match hit_test_selection_handle(local_vec, &frame, &canvas.viewport) {
+                        Some(FrameHandle::Resize(handle)) => {
                             canvas.start_resize(handle, canvas_pos, cx);
                             return;
                         }
+                        Some(FrameHandle::Rotate) => {
+                            canvas.start_rotate(canvas_pos, cx);
+                            return;
+                        }
+                        None => {}
                     }
                 }
 
+                if let Some(handle) = canvas.gradient_handle_at(canvas_pos) {
+                    canvas.start_gradient_drag(handle, cx);
+                    return;
+                }
+
+                if let Some(index) = canvas.guide_at(canvas_pos) {
+                    canvas.start_guide_drag(index, cx);
+                    return;
+                }
+
                 // Then check if clicking on a shape
                 if let Some(shape_id) = canvas.shape_at_point(canvas_pos) {
+                    if event.click_count >= 2 {
+                        match canvas.get_shape(shape_id).map(|s| s.kind) {
+                            Some(ShapeKind::Text) => {
+                                canvas.start_text_edit(shape_id, cx);
+                                return;
+                            }
+                            Some(ShapeKind::Path) => {
+                                canvas.start_path_edit(shape_id, cx);
+                                return;
+                            }
+                            _ => {}
+                        }
+                    }
+                    // Alt-clicking within the selection picks what it aligns to
+                    if event.modifiers.alt && canvas.selection.contains(&shape_id) {
+                        canvas.toggle_key_shape(shape_id, cx);
+                        return;
+                    }
                     let add_to_selection = event.modifiers.shift;
                     if !canvas.selection.contains(&shape_id) {
                         canvas.select(shape_id, add_to_selection, cx);
                     }
                     canvas.start_move(canvas_pos, cx);
                 } else {
-                    canvas.clear_selection(cx);
+                    canvas.start_marquee(canvas_pos, event.modifiers.shift, cx);
                 }
             }
             Tool::Pan => {
                 canvas.start_pan(screen_pos);
             }
             Tool::Rectangle => {
-                canvas.start_draw(ShapeKind::Rectangle, canvas_pos, cx);
+                canvas.start_draw(ShapeKind::Rectangle, canvas_pos, snap, cx);
             }
             Tool::Ellipse => {
-                canvas.start_draw(ShapeKind::Ellipse, canvas_pos, cx);
+                canvas.start_draw(ShapeKind::Ellipse, canvas_pos, snap, cx);
             }
             Tool::Frame => {
-                canvas.start_draw(ShapeKind::Frame, canvas_pos, cx);
+                canvas.start_draw(ShapeKind::Frame, canvas_pos, snap, cx);
+            }
+            Tool::Text => {
+                canvas.start_draw(ShapeKind::Text, canvas_pos, snap, cx);
+            }
+            Tool::Pen => {
+                canvas.pen_down(canvas_pos, event.click_count, cx);
             }
         }
     });
@@ -363,30 +492,42 @@ fn handle_mouse_move(
 
         // Clone drag state to avoid borrow issues
         let drag = canvas.drag.clone();
+        // Control holds snapping off
+        let snap = !event.modifiers.control;
 
         match drag {
             Some(DragState::MovingShapes { .. }) => {
-                canvas.update_move(canvas_pos, cx);
+                canvas.update_move(canvas_pos, snap, cx);
             }
             Some(DragState::ResizingShapes { .. }) => {
-                canvas.update_resize(canvas_pos, cx);
+                canvas.update_resize(canvas_pos, snap, cx);
             }
-            Some(DragState::DrawingShape { shape_id, start }) => {
-                // Calculate size and position (handle negative drag)
-                let min = Vec2::new(start.x().min(canvas_pos.x()), start.y().min(canvas_pos.y()));
-                let max = Vec2::new(start.x().max(canvas_pos.x()), start.y().max(canvas_pos.y()));
-
-                if let Some(shape) = canvas.get_shape_mut(shape_id) {
-                    shape.position = CanvasPoint(min);
-                    shape.size = CanvasSize(max - min);
-                }
-                cx.notify();
+            Some(DragState::RotatingShapes { .. }) => {
+                canvas.update_rotate(canvas_pos, event.modifiers.shift, cx);
+            }
+            Some(DragState::DrawingShape { .. }) => {
+                canvas.update_draw(canvas_pos, snap, cx);
             }
             Some(DragState::Panning { .. }) => {
                 canvas.update_pan(screen_pos, cx);
             }
             Some(DragState::Selecting { .. }) => {
-                // TODO: implement drag selection
+                // Alt takes only shapes entirely inside the marquee
+                let mode = if event.modifiers.alt {
+                    MarqueeMode::Contain
+                } else {
+                    MarqueeMode::Intersect
+                };
+                canvas.update_marquee(canvas_pos, mode, cx);
+            }
+            Some(DragState::DraggingPathPart { .. }) => {
+                canvas.update_path_drag(canvas_pos, cx);
+            }
+            Some(DragState::DraggingGuide { .. }) => {
+                canvas.update_guide_drag(canvas_pos, snap, cx);
+            }
+            Some(DragState::DraggingGradient { .. }) => {
+                canvas.update_gradient_drag(canvas_pos, cx);
             }
             None => {
                 // Update hover state
@@ -400,7 +541,12 @@ fn handle_mouse_move(
     });
 }
 
-fn handle_mouse_up(canvas: &Entity<Canvas>, event: &MouseUpEvent, cx: &mut App) {
+fn handle_mouse_up(
+    canvas: &Entity<Canvas>,
+    event: &MouseUpEvent,
+    bounds: Bounds<Pixels>,
+    cx: &mut App,
+) {
     // Handle middle button release for panning
     if event.button == MouseButton::Middle {
         canvas.update(cx, |canvas, _cx| {
@@ -421,6 +567,9 @@ fn handle_mouse_up(canvas: &Entity<Canvas>, event: &MouseUpEvent, cx: &mut App)
             Some(DragState::ResizingShapes { .. }) => {
                 canvas.finish_resize(cx);
             }
+            Some(DragState::RotatingShapes { .. }) => {
+                canvas.finish_rotate(cx);
+            }
             Some(DragState::DrawingShape { .. }) => {
                 canvas.finish_draw(cx);
             }
@@ -428,7 +577,19 @@ fn handle_mouse_up(canvas: &Entity<Canvas>, event: &MouseUpEvent, cx: &mut App)
                 canvas.finish_pan();
             }
             Some(DragState::Selecting { .. }) => {
-                canvas.drag = None;
+                canvas.finish_marquee(cx);
+            }
+            Some(DragState::DraggingPathPart { .. }) => {
+                canvas.finish_path_drag(cx);
+            }
+            Some(DragState::DraggingGuide { .. }) => {
+                // Dropped back on a ruler, it goes away
+                let local = event.position - bounds.origin;
+                let on_ruler = ruler::is_on_ruler(local.x.into(), local.y.into());
+                canvas.finish_guide_drag(on_ruler, cx);
+            }
+            Some(DragState::DraggingGradient { .. }) => {
+                canvas.finish_gradient_drag(cx);
             }
             None => {}
         }
@@ -459,9 +620,7 @@ fn handle_scroll(
             canvas.zoom_at(local_pos, factor, cx);
         } else {
             // Regular scroll = pan
-            let pan_delta = Vec2::new(delta_x, delta_y);
-            canvas.viewport.pan(pan_delta);
-            cx.notify();
+            canvas.pan(Vec2::new(delta_x, delta_y), cx);
         }
     });
 }
@@ -469,130 +628,180 @@ fn handle_scroll(
 /// Paint a shape and its children recursively.
 ///
 /// For frames with `clip_children` enabled, children are rendered within
-/// a content mask that clips to the frame's bounds.
+/// a content mask that clips to the frame's bounds. Masks are axis-aligned,
+/// so a rotated frame clips to the box around it.
+///
+/// `inherited_opacity` is that of the shape's ancestors. GPUI has no layers
+/// to paint a group into before fading it, so each shape is faded on its
+/// own, and overlapping children show through each other where an export
+/// would not. Blend modes are left to exports for the same reason.
 fn paint_shape_recursive(
     shape: &Shape,
     all_shapes: &[Shape],
-    world_positions: &std::collections::HashMap<ShapeId, CanvasPoint>,
+    placements: &HashMap<ShapeId, Placement>,
+    visible: &HashSet<ShapeId>,
     selection: &HashSet<ShapeId>,
     hovered: Option<ShapeId>,
+    editing_text: Option<ShapeId>,
     viewport: &crate::Viewport,
     theme: &theme::Theme,
     canvas_bounds: Bounds<Pixels>,
+    inherited_opacity: f32,
     window: &mut Window,
 ) {
-    // Get cached world position (O(1) lookup)
-    let world_pos = world_positions
+    // Skip if neither the shape nor anything under it is in view, or it's
+    // hidden along with everything under it
+    if !visible.contains(&shape.id) || !shape.visible {
+        return;
+    }
+    let opacity = inherited_opacity * shape.opacity.clamp(0.0, 1.0);
+
+    // Get cached placement (O(1) lookup)
+    let placement = placements
         .get(&shape.id)
         .copied()
-        .unwrap_or_else(|| shape.world_position(all_shapes));
-
-    // Convert to screen coordinates
-    let screen_rect = viewport.canvas_to_screen_bounds(world_pos, shape.size);
-    let screen_bounds = Bounds {
-        origin: point(
-            canvas_bounds.origin.x + px(screen_rect.origin.x),
-            canvas_bounds.origin.y + px(screen_rect.origin.y),
-        ),
-        size: size(px(screen_rect.size.width), px(screen_rect.size.height)),
+        .unwrap_or_else(|| shape.world_placement(all_shapes));
+    let origin = canvas_bounds.origin;
+    let to_window = |rect: Bounds<f32>| Bounds {
+        origin: point(origin.x + px(rect.origin.x), origin.y + px(rect.origin.y)),
+        size: size(px(rect.size.width), px(rect.size.height)),
     };
 
-    // Skip if not visible
-    if !canvas_bounds.intersects(&screen_bounds) {
-        return;
-    }
+    // The unturned box, where quads and text are painted
+    let screen_bounds = to_window(viewport.canvas_to_screen_bounds(placement.position, placement.size));
+    // Everything the shape covers once turned
+    let (min, max) = placement.bounds();
+    let covered = to_window(viewport.canvas_to_screen_bounds(min, CanvasSize(max.0 - min.0)));
+
+    // Quads can't turn, so rotated shapes and paths are painted from their
+    // outlines instead
+    let outline = (placement.is_rotated() || shape.kind == ShapeKind::Path)
+        .then(|| outline::shape_outline(shape, &placement));
 
     // Clamp corner radius to half the smaller dimension
-    let max_radius = shape.size.width().min(shape.size.height()) / 2.0;
+    let shape_size = placement.size;
+    let max_radius = shape_size.width().min(shape_size.height()) / 2.0;
     let corner_radius = px(shape.corner_radius.min(max_radius) * viewport.zoom);
 
-    // Paint fill
-    if let Some(fill) = &shape.fill {
-        match shape.kind {
-            ShapeKind::Rectangle | ShapeKind::Frame => {
-                window.paint_quad(
-                    gpui::fill(screen_bounds, fill.color).corner_radii(corner_radius),
-                );
+    // Ellipses are quads rounded all the way
+    let quad_radius = if shape.kind == ShapeKind::Ellipse {
+        let w: f32 = screen_bounds.size.width.into();
+        let h: f32 = screen_bounds.size.height.into();
+        px(w.min(h) / 2.0)
+    } else {
+        corner_radius
+    };
+
+    // Text is set in one color. The in-place editor shows it while it's
+    // being edited. GPUI can't turn glyphs, so rotated text is set upright
+    // in its box.
+    if shape.kind == ShapeKind::Text {
+        let text = shape.text.as_ref().filter(|_| editing_text != Some(shape.id));
+        if let (Some(text), Some(color)) = (text, shape.text_color()) {
+            TextLayout::new(text, shape_size.width(), window.text_system()).paint(
+                screen_bounds.origin,
+                viewport.zoom,
+                color.opacity(opacity),
+                window,
+            );
+        }
+    }
+
+    // Paint fills, bottom first, then strokes over them. Text outlines
+    // aren't supported.
+    let painted = shape.kind != ShapeKind::Text;
+    for fill in shape.fills.iter().filter(|fill| painted && fill.visible) {
+        match (&outline, &fill.paint) {
+            // Gradients are cut from the outline whether or not it's turned
+            (outline, Paint::Gradient(gradient)) => {
+                let outline = outline
+                    .clone()
+                    .unwrap_or_else(|| outline::shape_outline(shape, &placement));
+                let opacity = fill.opacity * opacity;
+                gradient::paint(gradient, opacity, &outline, &placement, viewport, origin, window);
             }
-            ShapeKind::Ellipse => {
-                let w: f32 = screen_bounds.size.width.into();
-                let h: f32 = screen_bounds.size.height.into();
-                let radius = px(w.min(h) / 2.0);
-                window.paint_quad(
-                    gpui::fill(screen_bounds, fill.color).corner_radii(radius),
-                );
+            (Some(outline), Paint::Solid(color)) => {
+                if let Some(path) = outline::build(outline, viewport, origin, PathBuilder::fill()) {
+                    window.paint_path(path, color.opacity(fill.opacity * opacity));
+                }
+            }
+            (None, Paint::Solid(color)) => {
+                let color = color.opacity(fill.opacity * opacity);
+                window.paint_quad(gpui::fill(screen_bounds, color).corner_radii(quad_radius));
             }
         }
     }
 
-    // Paint stroke
-    if let Some(stroke) = &shape.stroke {
-        let stroke_width = px(stroke.width * viewport.zoom);
-        match shape.kind {
-            ShapeKind::Rectangle | ShapeKind::Frame => {
-                window.paint_quad(PaintQuad {
-                    bounds: screen_bounds,
-                    corner_radii: corner_radius.into(),
-                    background: transparent_black().into(),
-                    border_widths: stroke_width.into(),
-                    border_color: stroke.color.into(),
-                    border_style: BorderStyle::Solid,
-                });
-            }
-            ShapeKind::Ellipse => {
-                let w: f32 = screen_bounds.size.width.into();
-                let h: f32 = screen_bounds.size.height.into();
-                let radius = px(w.min(h) / 2.0);
-                window.paint_quad(PaintQuad {
-                    bounds: screen_bounds,
-                    corner_radii: radius.into(),
-                    background: transparent_black().into(),
-                    border_widths: stroke_width.into(),
-                    border_color: stroke.color.into(),
-                    border_style: BorderStyle::Solid,
-                });
-            }
+    for stroke in shape.strokes.iter().filter(|stroke| painted && stroke.visible) {
+        if outline.is_none() && stroke.is_border() {
+            window.paint_quad(PaintQuad {
+                bounds: screen_bounds,
+                corner_radii: quad_radius.into(),
+                background: transparent_black().into(),
+                border_widths: px(stroke.width * viewport.zoom).into(),
+                border_color: stroke.color.opacity(stroke.opacity * opacity),
+                border_style: BorderStyle::Solid,
+            });
+        } else {
+            stroke::paint(stroke, opacity, shape, &placement, viewport, origin, window);
         }
     }
 
+    // Hover and selection trace a path's curve and a rotated shape's box
+    let indicator = |window: &mut Window, color: gpui::Hsla| {
+        let traced = match shape.kind {
+            ShapeKind::Path => outline.clone(),
+            _ if placement.is_rotated() => Some(outline::box_outline(&placement)),
+            _ => None,
+        };
+        match traced {
+            Some(traced) => {
+                let builder = PathBuilder::stroke(px(1.0));
+                if let Some(path) = outline::build(&traced, viewport, origin, builder) {
+                    window.paint_path(path, color);
+                }
+            }
+            None => window.paint_quad(
+                gpui::outline(screen_bounds, color, BorderStyle::Solid).corner_radii(corner_radius),
+            ),
+        }
+    };
+
     // Paint hover indicator
     if hovered == Some(shape.id) && !selection.contains(&shape.id) {
-        window.paint_quad(
-            gpui::outline(screen_bounds, theme.hover, BorderStyle::Solid)
-                .corner_radii(corner_radius),
-        );
+        indicator(window, theme.hover);
     }
 
-    // Paint selection indicator
+    // Paint selection indicator. Handles are painted once for the whole
+    // selection.
     if selection.contains(&shape.id) {
-        window.paint_quad(
-            gpui::outline(screen_bounds, theme.selection, BorderStyle::Solid)
-                .corner_radii(corner_radius),
-        );
-        paint_selection_handles(window, screen_bounds, theme.selection);
+        indicator(window, theme.selection);
     }
 
     // Paint children (for frames)
     if !shape.children.is_empty() {
         // Optionally clip children to frame bounds
         let clip_mask = if shape.clip_children {
-            Some(ContentMask { bounds: screen_bounds })
+            Some(ContentMask { bounds: covered })
         } else {
             None
         };
 
         window.with_content_mask(clip_mask, |window| {
-            for child_id in &shape.children {
+            for child_id in shape.children.iter().filter(|id| visible.contains(id)) {
                 if let Some(child) = all_shapes.iter().find(|s| s.id == *child_id) {
                     paint_shape_recursive(
                         child,
                         all_shapes,
-                        world_positions,
+                        placements,
+                        visible,
                         selection,
                         hovered,
+                        editing_text,
                         viewport,
                         theme,
                         canvas_bounds,
+                        opacity,
                         window,
                     );
                 }
@@ -601,6 +810,194 @@ fn paint_shape_recursive(
     }
 }
 
+/// Paint the pen's view of the path being edited: its curve, every vertex,
+/// and the handles around the selected vertex.
+fn paint_path_edit(
+    edit: PathEdit,
+    shapes: &[Shape],
+    placements: &HashMap<ShapeId, Placement>,
+    viewport: &crate::Viewport,
+    theme: &theme::Theme,
+    canvas_bounds: Bounds<Pixels>,
+    window: &mut Window,
+) {
+    let Some(shape) = shapes.iter().find(|s| s.id == edit.shape_id) else {
+        return;
+    };
+    let placement = placements
+        .get(&shape.id)
+        .copied()
+        .unwrap_or_else(|| shape.world_placement(shapes));
+    let Some(path) = shape.path_in_canvas(&placement) else {
+        return;
+    };
+    let origin = canvas_bounds.origin;
+    let builder = PathBuilder::stroke(px(1.0));
+    if let Some(curve) = outline::build(&path.to_bez_path(), viewport, origin, builder) {
+        window.paint_path(curve, theme.selection);
+    }
+
+    let to_screen = |point: Vec2| {
+        let screen = viewport.canvas_to_screen(CanvasPoint(point));
+        gpui::point(origin.x + px(screen.x()), origin.y + px(screen.y()))
+    };
+    let marker = |center: Point<Pixels>| Bounds {
+        origin: point(
+            center.x - px(PATH_MARKER_SIZE / 2.0),
+            center.y - px(PATH_MARKER_SIZE / 2.0),
+        ),
+        size: size(px(PATH_MARKER_SIZE), px(PATH_MARKER_SIZE)),
+    };
+
+    for index in pen::handle_vertices(&path, edit.selected_vertex) {
+        let vertex = &path.vertices[index];
+        let anchor = to_screen(vertex.point);
+        for side in [HandleSide::In, HandleSide::Out] {
+            let handle = vertex.handle(side);
+            if handle == Vec2::ZERO {
+                continue;
+            }
+            let end = to_screen(vertex.point + handle);
+            let mut line = PathBuilder::stroke(px(1.0));
+            line.move_to(anchor);
+            line.line_to(end);
+            if let Ok(line) = line.build() {
+                window.paint_path(line, theme.selection);
+            }
+            let dot = marker(end);
+            window.paint_quad(
+                gpui::fill(dot, gpui::white()).corner_radii(px(PATH_MARKER_SIZE / 2.0)),
+            );
+            window.paint_quad(
+                gpui::outline(dot, theme.selection, BorderStyle::Solid)
+                    .corner_radii(px(PATH_MARKER_SIZE / 2.0)),
+            );
+        }
+    }
+
+    for (index, vertex) in path.vertices.iter().enumerate() {
+        let square = marker(to_screen(vertex.point));
+        let fill = if edit.selected_vertex == Some(index) {
+            theme.selection
+        } else {
+            gpui::white()
+        };
+        window.paint_quad(gpui::fill(square, fill));
+        window.paint_quad(gpui::outline(square, theme.selection, BorderStyle::Solid));
+    }
+}
+
+/// Paint grid lines every `spacing` canvas units across the view, unless
+/// they'd be too close together to make out.
+fn paint_grid(
+    spacing: f32,
+    viewport: &crate::Viewport,
+    theme: &theme::Theme,
+    canvas_bounds: Bounds<Pixels>,
+    window: &mut Window,
+) {
+    let step = spacing * viewport.zoom;
+    if !step.is_finite() || step < MIN_GRID_SPACING {
+        return;
+    }
+    let width: f32 = canvas_bounds.size.width.into();
+    let height: f32 = canvas_bounds.size.height.into();
+    let first = viewport.canvas_to_screen(CanvasPoint(
+        (viewport.screen_to_canvas(ScreenPoint::new(0.0, 0.0)).0 / spacing).ceil() * spacing,
+    ));
+    let origin = canvas_bounds.origin;
+
+    let mut x = first.x();
+    while x < width {
+        let line = Bounds {
+            origin: point(origin.x + px(x), origin.y),
+            size: size(px(1.0), canvas_bounds.size.height),
+        };
+        window.paint_quad(gpui::fill(line, theme.grid));
+        x += step;
+    }
+    let mut y = first.y();
+    while y < height {
+        let line = Bounds {
+            origin: point(origin.x, origin.y + px(y)),
+            size: size(canvas_bounds.size.width, px(1.0)),
+        };
+        window.paint_quad(gpui::fill(line, theme.grid));
+        y += step;
+    }
+}
+
+/// Paint the page's guides across the view.
+fn paint_page_guides(
+    guides: &[Guide],
+    viewport: &crate::Viewport,
+    theme: &theme::Theme,
+    canvas_bounds: Bounds<Pixels>,
+    window: &mut Window,
+) {
+    let origin = canvas_bounds.origin;
+    for guide in guides {
+        let line = match guide.axis {
+            GuideAxis::Horizontal => {
+                let y = viewport.canvas_to_screen(CanvasPoint::new(0.0, guide.position)).y();
+                Bounds {
+                    origin: point(origin.x, origin.y + px(y)),
+                    size: size(canvas_bounds.size.width, px(1.0)),
+                }
+            }
+            GuideAxis::Vertical => {
+                let x = viewport.canvas_to_screen(CanvasPoint::new(guide.position, 0.0)).x();
+                Bounds {
+                    origin: point(origin.x + px(x), origin.y),
+                    size: size(px(1.0), canvas_bounds.size.height),
+                }
+            }
+        };
+        window.paint_quad(gpui::fill(line, theme.guide));
+    }
+}
+
+/// Paint what a gesture snapped to: lines where edges and centers lined
+/// up, and the equal gaps, with ticks at their ends.
+fn paint_guides(
+    guides: &[SnapGuide],
+    viewport: &crate::Viewport,
+    theme: &theme::Theme,
+    origin: Point<Pixels>,
+    window: &mut Window,
+) {
+    let to_screen = |point: Vec2| {
+        let screen = viewport.canvas_to_screen(CanvasPoint(point));
+        gpui::point(origin.x + px(screen.x()), origin.y + px(screen.y()))
+    };
+    for guide in guides {
+        let mut line = PathBuilder::stroke(px(1.0));
+        match *guide {
+            SnapGuide::Align { from, to } => {
+                line.move_to(to_screen(from));
+                line.line_to(to_screen(to));
+            }
+            SnapGuide::Spacing { from, to } => {
+                let (from, to) = (to_screen(from), to_screen(to));
+                let tick = if from.x == to.x {
+                    point(px(GUIDE_TICK), px(0.0))
+                } else {
+                    point(px(0.0), px(GUIDE_TICK))
+                };
+                line.move_to(from);
+                line.line_to(to);
+                for end in [from, to] {
+                    line.move_to(end - tick);
+                    line.line_to(end + tick);
+                }
+            }
+        }
+        if let Ok(line) = line.build() {
+            window.paint_path(line, theme.guide);
+        }
+    }
+}
+
 // Helper trait for Canvas to clone state for rendering
 trait CloneRenderState {
     fn clone_render_state(
@@ -612,7 +1009,7 @@ trait CloneRenderState {
         crate::Viewport,
         theme::Theme,
         Option<DragState>,
-        std::collections::HashMap<node::ShapeId, node::CanvasPoint>,
+        HashMap<ShapeId, Placement>,
     );
 }
 
@@ -626,7 +1023,7 @@ impl CloneRenderState for Canvas {
         crate::Viewport,
         theme::Theme,
         Option<DragState>,
-        std::collections::HashMap<node::ShapeId, node::CanvasPoint>,
+        HashMap<ShapeId, Placement>,
     ) {
         // Compute world positions before cloning
         self.compute_world_positions();
@@ -637,7 +1034,7 @@ impl CloneRenderState for Canvas {
             self.viewport.clone(),
             self.theme.clone(),
             self.drag.clone(),
-            self.world_position_cache().clone(),
+            self.placement_cache().clone(),
         )
     }
 }
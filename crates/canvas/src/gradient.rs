@@ -0,0 +1,452 @@
+//! Gradient fills on the canvas: painting them, and the handles that edit
+//! them.
+//!
+//! GPUI fills a path in one color, so a gradient is painted as bands a
+//! couple of pixels wide on screen, each cut from the shape's outline and
+//! filled with the gradient's color at its middle. Cutting happens in the
+//! box's units, where gradients are defined. Linear bands lie between two
+//! lines across the gradient and angular ones between two rays from its
+//! center, so half-planes cut them. Radial bands are rings: the outline cut
+//! by two discs, which even-odd filling turns into the ring between them
+//! once both are in one path.
+
+use crate::{outline, Viewport};
+use glam::Vec2;
+use gpui::{point, px, size, BorderStyle, Bounds, PathBuilder, Pixels, Point, Window};
+use kurbo::{Affine, BezPath, PathEl};
+use node::{CanvasPoint, Gradient, GradientKind, Placement};
+use std::f32::consts::TAU;
+use theme::Theme;
+
+/// Width of a band on screen, in pixels.
+const BAND_WIDTH: f32 = 2.0;
+
+/// Bands between a gradient's handles, however small or big it is on
+/// screen.
+const MIN_BANDS: usize = 8;
+const MAX_BANDS: usize = 256;
+
+/// Sides of the polygons that stand in for a radial gradient's circles.
+const CIRCLE_SIDES: usize = 64;
+
+/// How far flattened outlines may stray from the curves, in screen pixels.
+const FLATNESS: f64 = 0.25;
+
+/// Size of a gradient's handles and stops on screen, in pixels.
+const HANDLE_SIZE: f32 = 8.0;
+
+type Polygon = Vec<Vec2>;
+
+/// A point on a gradient that can be dragged.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GradientHandle {
+    Start,
+    End,
+    /// The stop at this index, which slides along the line between the
+    /// handles.
+    Stop(usize),
+}
+
+/// Maps the box's units, (0, 0) at its top-left to (1, 1) at its
+/// bottom-right, onto the canvas.
+fn unit_affine(placement: &Placement) -> Affine {
+    let size = placement.size.0;
+    outline::to_affine(placement) * Affine::scale_non_uniform(size.x as f64, size.y as f64)
+}
+
+/// A point on canvas in the box's units. Boxes without area have none.
+fn to_unit(placement: &Placement, point: Vec2) -> Option<Vec2> {
+    let size = placement.size.0;
+    (size.x != 0.0 && size.y != 0.0).then(|| placement.to_local(point) / size)
+}
+
+fn to_canvas(placement: &Placement, unit: Vec2) -> Vec2 {
+    placement.to_canvas(unit * placement.size.0)
+}
+
+/// Paint `outline`, a shape's outline on canvas at `placement`, filled with
+/// `gradient` faded by `opacity`.
+pub fn paint(
+    gradient: &Gradient,
+    opacity: f32,
+    outline: &BezPath,
+    placement: &Placement,
+    viewport: &Viewport,
+    origin: Point<Pixels>,
+    window: &mut Window,
+) {
+    let to_canvas = unit_affine(placement);
+    if to_canvas.determinant().abs() < f64::EPSILON {
+        return;
+    }
+    let polygons = flatten(outline, FLATNESS / viewport.zoom as f64, to_canvas.inverse());
+
+    let count = band_count(gradient, placement, viewport.zoom);
+    // Opaque bands can overlap, so antialiased edges don't let the canvas
+    // show through the seams. Translucent ones would show the overlap.
+    let opaque = opacity >= 1.0 && gradient.stops.iter().all(|stop| stop.color.a >= 1.0);
+    let overlap = if opaque { 0.5 / count as f32 } else { 0.0 };
+
+    for (offset, pieces) in bands(gradient, &polygons, count, overlap) {
+        let mut path = BezPath::new();
+        for piece in &pieces {
+            for (i, corner) in piece.iter().enumerate() {
+                let corner = to_canvas * kurbo::Point::new(corner.x as f64, corner.y as f64);
+                if i == 0 {
+                    path.move_to(corner);
+                } else {
+                    path.line_to(corner);
+                }
+            }
+            path.close_path();
+        }
+        if let Some(path) = outline::build(&path, viewport, origin, PathBuilder::fill()) {
+            window.paint_path(path, gradient.color_at(offset).opacity(opacity));
+        }
+    }
+}
+
+/// How many bands to paint between the handles for them to be about
+/// [`BAND_WIDTH`] wide on screen.
+fn band_count(gradient: &Gradient, placement: &Placement, zoom: f32) -> usize {
+    let start = to_canvas(placement, gradient.start);
+    let end = to_canvas(placement, gradient.end);
+    let length = match gradient.kind {
+        GradientKind::Linear | GradientKind::Radial => start.distance(end),
+        // Around the edge of the box
+        GradientKind::Angular => placement.size.0.length() * TAU / 2.0,
+    };
+    ((length * zoom / BAND_WIDTH).ceil() as usize).clamp(MIN_BANDS, MAX_BANDS)
+}
+
+/// `path` flattened into polygons, mapped by `transform`.
+fn flatten(path: &BezPath, tolerance: f64, transform: Affine) -> Vec<Polygon> {
+    let mut polygons = Vec::new();
+    let mut current = Vec::new();
+    let mut finish = |current: &mut Polygon| {
+        if current.len() >= 3 {
+            polygons.push(std::mem::take(current));
+        }
+        current.clear();
+    };
+    kurbo::flatten(path.iter(), tolerance, |element| match element {
+        PathEl::MoveTo(p) => {
+            finish(&mut current);
+            let p = transform * p;
+            current.push(Vec2::new(p.x as f32, p.y as f32));
+        }
+        PathEl::LineTo(p) => {
+            let p = transform * p;
+            current.push(Vec2::new(p.x as f32, p.y as f32));
+        }
+        PathEl::ClosePath => finish(&mut current),
+        // Flattening leaves only lines
+        PathEl::QuadTo(..) | PathEl::CurveTo(..) => {}
+    });
+    finish(&mut current);
+    polygons
+}
+
+/// `polygons`, in the box's units, cut into `count` bands of `gradient`
+/// between its handles, and the bands beyond them. Each band comes with
+/// the offset its color is taken at. Bands reach `overlap` into the next.
+fn bands(
+    gradient: &Gradient,
+    polygons: &[Polygon],
+    count: usize,
+    overlap: f32,
+) -> Vec<(f32, Vec<Polygon>)> {
+    let start = gradient.start;
+    let axis = gradient.end - gradient.start;
+    let step = 1.0 / count as f32;
+    let offsets = polygons.iter().flatten().map(|p| gradient.offset_at(*p));
+    let (lowest, highest) =
+        offsets.fold((f32::MAX, f32::MIN), |(lo, hi), t| (lo.min(t), hi.max(t)));
+
+    match gradient.kind {
+        GradientKind::Linear => {
+            let length_squared = axis.length_squared();
+            if length_squared == 0.0 {
+                return vec![(0.0, polygons.to_vec())];
+            }
+            // Offset t is where dot(p, axis) reaches this
+            let level = |t: f32| t * length_squared + start.dot(axis);
+            let mut intervals = vec![(None, Some(0.0), 0.0)];
+            intervals.extend((0..count).map(|k| {
+                let a = k as f32 * step;
+                (Some(a), Some(a + step), a + step / 2.0)
+            }));
+            intervals.push((Some(1.0), None, 1.0));
+
+            intervals
+                .into_iter()
+                .filter(|(a, b, _)| a.is_none_or(|a| a < highest) && b.is_none_or(|b| b > lowest))
+                .map(|(a, b, offset)| {
+                    let pieces = polygons
+                        .iter()
+                        .map(|polygon| {
+                            let mut piece = polygon.clone();
+                            if let Some(a) = a {
+                                piece = clip(&piece, axis, level(a));
+                            }
+                            if let Some(b) = b {
+                                piece = clip(&piece, -axis, -level(b + overlap));
+                            }
+                            piece
+                        })
+                        .filter(|piece| piece.len() >= 3)
+                        .collect();
+                    (offset, pieces)
+                })
+                .collect()
+        }
+        GradientKind::Radial => {
+            let radius = axis.length();
+            if radius == 0.0 {
+                return vec![(1.0, polygons.to_vec())];
+            }
+            let disc = |t: f32| -> Vec<Polygon> {
+                polygons
+                    .iter()
+                    .map(|polygon| clip_to_disc(polygon, start, t * radius))
+                    .filter(|piece| piece.len() >= 3)
+                    .collect()
+            };
+            let mut bands: Vec<_> = (0..count)
+                .map(|k| k as f32 * step)
+                .take_while(|a| *a <= highest)
+                .map(|a| {
+                    let mut pieces = disc(a + step + overlap);
+                    if a > 0.0 {
+                        pieces.extend(disc(a));
+                    }
+                    (a + step / 2.0, pieces)
+                })
+                .collect();
+            if highest > 1.0 {
+                let mut pieces = polygons.to_vec();
+                pieces.extend(disc(1.0));
+                bands.push((1.0, pieces));
+            }
+            bands
+        }
+        GradientKind::Angular => {
+            let ray = |t: f32| {
+                let angle = axis.y.atan2(axis.x) + t * TAU;
+                Vec2::new(angle.cos(), angle.sin())
+            };
+            (0..count)
+                .map(|k| {
+                    let a = k as f32 * step;
+                    // The last band ends where the first begins
+                    let b = if k + 1 < count { a + step + overlap } else { 1.0 };
+                    let (from, to) = (ray(a), ray(b));
+                    // Clockwise of `from` and anticlockwise of `to`
+                    let after = Vec2::new(-from.y, from.x);
+                    let before = Vec2::new(to.y, -to.x);
+                    let pieces = polygons
+                        .iter()
+                        .map(|polygon| {
+                            let piece = clip(polygon, after, after.dot(start));
+                            clip(&piece, before, before.dot(start))
+                        })
+                        .filter(|piece| piece.len() >= 3)
+                        .collect();
+                    (a + step / 2.0, pieces)
+                })
+                .collect()
+        }
+    }
+}
+
+/// The part of `polygon` where `dot(p, normal) >= min`.
+fn clip(polygon: &[Vec2], normal: Vec2, min: f32) -> Polygon {
+    let mut clipped = Vec::with_capacity(polygon.len() + 1);
+    for (i, &current) in polygon.iter().enumerate() {
+        let previous = polygon[(i + polygon.len() - 1) % polygon.len()];
+        let (above, above_before) = (current.dot(normal) - min, previous.dot(normal) - min);
+        if (above >= 0.0) != (above_before >= 0.0) {
+            let t = above_before / (above_before - above);
+            clipped.push(previous + (current - previous) * t);
+        }
+        if above >= 0.0 {
+            clipped.push(current);
+        }
+    }
+    clipped
+}
+
+/// The part of `polygon` within `radius` of `center`, the circle taken as a
+/// polygon around it.
+fn clip_to_disc(polygon: &[Vec2], center: Vec2, radius: f32) -> Polygon {
+    (0..CIRCLE_SIDES).fold(polygon.to_vec(), |piece, side| {
+        let angle = side as f32 * TAU / CIRCLE_SIDES as f32;
+        let inward = -Vec2::new(angle.cos(), angle.sin());
+        clip(&piece, inward, inward.dot(center) - radius)
+    })
+}
+
+/// Where a gradient's start and end handles and its stops sit on canvas,
+/// for a shape at `placement`. Stops sit along the line between the
+/// handles.
+fn handle_points(gradient: &Gradient, placement: &Placement) -> (Vec2, Vec2, Vec<Vec2>) {
+    let start = gradient.start;
+    let end = gradient.end;
+    let stops = gradient
+        .stops
+        .iter()
+        .map(|stop| to_canvas(placement, start.lerp(end, stop.offset)))
+        .collect();
+    (to_canvas(placement, start), to_canvas(placement, end), stops)
+}
+
+/// The handle or stop within `reach` of `point` on canvas. Handles come
+/// before stops that sit on them.
+pub fn handle_at(
+    gradient: &Gradient,
+    placement: &Placement,
+    point: CanvasPoint,
+    reach: f32,
+) -> Option<GradientHandle> {
+    let (start, end, stops) = handle_points(gradient, placement);
+    let near = |at: Vec2| at.distance(point.0) <= reach;
+    if near(start) {
+        return Some(GradientHandle::Start);
+    }
+    if near(end) {
+        return Some(GradientHandle::End);
+    }
+    stops
+        .iter()
+        .enumerate()
+        .filter(|(_, at)| near(**at))
+        .min_by(|(_, a), (_, b)| a.distance(point.0).total_cmp(&b.distance(point.0)))
+        .map(|(index, _)| GradientHandle::Stop(index))
+}
+
+/// `point` on canvas as a handle position in the box's units.
+pub fn handle_position(placement: &Placement, point: CanvasPoint) -> Option<Vec2> {
+    to_unit(placement, point.0)
+}
+
+/// The offset along the line between the handles nearest `point` on canvas.
+pub fn offset_along(gradient: &Gradient, placement: &Placement, point: CanvasPoint) -> Option<f32> {
+    let unit = to_unit(placement, point.0)?;
+    let axis = gradient.end - gradient.start;
+    let length_squared = axis.length_squared();
+    let offset = (unit - gradient.start).dot(axis) / length_squared;
+    (length_squared > 0.0).then(|| offset.clamp(0.0, 1.0))
+}
+
+/// Paint the line between a gradient's handles, the handles, and its stops
+/// in their colors.
+pub fn paint_handles(
+    gradient: &Gradient,
+    placement: &Placement,
+    viewport: &Viewport,
+    theme: &Theme,
+    origin: Point<Pixels>,
+    window: &mut Window,
+) {
+    let (start, end, stops) = handle_points(gradient, placement);
+    let screen = |at: Vec2| viewport.canvas_to_screen(CanvasPoint(at)).0;
+    let mut line = BezPath::new();
+    line.move_to(kurbo::Point::new(start.x as f64, start.y as f64));
+    line.line_to(kurbo::Point::new(end.x as f64, end.y as f64));
+    if let Some(path) = outline::build(&line, viewport, origin, PathBuilder::stroke(px(1.0))) {
+        window.paint_path(path, theme.selection);
+    }
+
+    let square = |at: Vec2| {
+        let at = screen(at);
+        Bounds {
+            origin: point(
+                origin.x + px(at.x - HANDLE_SIZE / 2.0),
+                origin.y + px(at.y - HANDLE_SIZE / 2.0),
+            ),
+            size: size(px(HANDLE_SIZE), px(HANDLE_SIZE)),
+        }
+    };
+    for (stop, at) in gradient.stops.iter().zip(stops) {
+        window.paint_quad(gpui::fill(square(at), stop.color));
+        window.paint_quad(gpui::outline(square(at), gpui::white(), BorderStyle::Solid));
+    }
+    for at in [start, end] {
+        let radius = px(HANDLE_SIZE / 2.0);
+        window.paint_quad(gpui::fill(square(at), gpui::white()).corner_radii(radius));
+        let border = gpui::outline(square(at), theme.selection, BorderStyle::Solid);
+        window.paint_quad(border.corner_radii(radius));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use node::GradientKind;
+
+    fn unit_square() -> Polygon {
+        vec![Vec2::ZERO, Vec2::new(1.0, 0.0), Vec2::ONE, Vec2::new(0.0, 1.0)]
+    }
+
+    fn area(polygon: &[Vec2]) -> f32 {
+        let twice: f32 = polygon
+            .iter()
+            .zip(polygon.iter().cycle().skip(1))
+            .map(|(a, b)| a.x * b.y - b.x * a.y)
+            .sum();
+        twice.abs() / 2.0
+    }
+
+    fn gradient(kind: GradientKind) -> Gradient {
+        Gradient::new(kind, gpui::black(), gpui::white())
+    }
+
+    #[test]
+    fn clipping_keeps_one_side_of_a_line() {
+        let left = clip(&unit_square(), Vec2::new(-1.0, 0.0), -0.25);
+        assert!((area(&left) - 0.25).abs() < 1e-6);
+        assert!(clip(&unit_square(), Vec2::X, 2.0).is_empty());
+    }
+
+    #[test]
+    fn linear_bands_tile_the_shape() {
+        let bands = bands(&gradient(GradientKind::Linear), &[unit_square()], 8, 0.0);
+        // The handles span the square, so nothing lies beyond them
+        assert_eq!(bands.len(), 8);
+        let total: f32 = bands.iter().flat_map(|(_, pieces)| pieces).map(|p| area(p)).sum();
+        assert!((total - 1.0).abs() < 1e-5);
+        assert_eq!(bands[0].0, 1.0 / 16.0);
+    }
+
+    #[test]
+    fn radial_bands_are_rings_and_the_rest_beyond() {
+        let bands = bands(&gradient(GradientKind::Radial), &[unit_square()], 8, 0.0);
+        // Eight rings out to the handle, then the corners past it
+        assert_eq!(bands.len(), 9);
+        let (offset, beyond) = bands.last().unwrap();
+        assert_eq!(*offset, 1.0);
+        assert_eq!(beyond.len(), 2);
+        assert_eq!(bands[0].1.len(), 1);
+    }
+
+    #[test]
+    fn angular_bands_go_around_the_center() {
+        let bands = bands(&gradient(GradientKind::Angular), &[unit_square()], 8, 0.0);
+        assert_eq!(bands.len(), 8);
+        let total: f32 = bands.iter().flat_map(|(_, pieces)| pieces).map(|p| area(p)).sum();
+        assert!((total - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn stops_are_picked_along_the_line() {
+        let size = node::CanvasSize::new(100.0, 50.0);
+        let placement = Placement::new(CanvasPoint::new(10.0, 10.0), size);
+        let mut gradient = gradient(GradientKind::Linear);
+        gradient.add_stop(0.5);
+        let at = |x, y| handle_at(&gradient, &placement, CanvasPoint::new(x, y), 4.0);
+        assert_eq!(at(10.0, 35.0), Some(GradientHandle::Start));
+        assert_eq!(at(61.0, 36.0), Some(GradientHandle::Stop(1)));
+        assert_eq!(at(60.0, 50.0), None);
+        assert_eq!(offset_along(&gradient, &placement, CanvasPoint::new(35.0, 0.0)), Some(0.25));
+    }
+}
@@ -0,0 +1,233 @@
+//! Undo/redo history for canvas document state.
+//!
+//! History stores whole-document snapshots rather than inverse operations.
+//! Shapes are small and documents are flat, so cloning the shape list is
+//! cheap compared to keeping an inverse for every mutation path (commands,
+//! gestures, panel edits) in sync as the model grows.
+
+use node::{Guide, Shape, ShapeId};
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+/// Edits sharing a coalesce key within this window collapse into one entry.
+const DEFAULT_GROUP_INTERVAL: Duration = Duration::from_millis(500);
+const MAX_HISTORY_LEN: usize = 200;
+
+/// The undoable portion of canvas state.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Snapshot {
+    pub shapes: Vec<Shape>,
+    pub selection: HashSet<ShapeId>,
+    pub guides: Vec<Guide>,
+}
+
+#[derive(Clone, Debug)]
+struct HistoryEntry {
+    snapshot: Snapshot,
+    coalesce_key: Option<&'static str>,
+    timestamp: Instant,
+}
+
+/// Undo and redo stacks of document snapshots.
+///
+/// Each entry holds the state *before* an edit. Undo swaps the current state
+/// for the top of the undo stack and pushes the current state onto the redo
+/// stack; redo does the reverse.
+#[derive(Debug)]
+pub struct History {
+    undo_stack: Vec<HistoryEntry>,
+    redo_stack: Vec<Snapshot>,
+    group_interval: Duration,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl History {
+    pub fn new() -> Self {
+        Self {
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            group_interval: DEFAULT_GROUP_INTERVAL,
+        }
+    }
+
+    /// Record the state before an edit.
+    pub fn push(&mut self, before: Snapshot) {
+        self.push_entry(before, None);
+    }
+
+    /// Record the state before an edit, merging with the previous entry if it
+    /// has the same key and was recorded within the group interval.
+    ///
+    /// Used for streams of small edits (typing into a field, nudging with the
+    /// arrow keys) that the user thinks of as one change.
+    pub fn push_coalesced(&mut self, before: Snapshot, key: &'static str) {
+        let now = Instant::now();
+        if let Some(last) = self.undo_stack.last_mut() {
+            if last.coalesce_key == Some(key)
+                && now.duration_since(last.timestamp) < self.group_interval
+            {
+                // Keep the older snapshot, extend the window.
+                last.timestamp = now;
+                self.redo_stack.clear();
+                return;
+            }
+        }
+        self.push_entry(before, Some(key));
+    }
+
+    fn push_entry(&mut self, before: Snapshot, coalesce_key: Option<&'static str>) {
+        self.undo_stack.push(HistoryEntry {
+            snapshot: before,
+            coalesce_key,
+            timestamp: Instant::now(),
+        });
+        if self.undo_stack.len() > MAX_HISTORY_LEN {
+            self.undo_stack.remove(0);
+        }
+        self.redo_stack.clear();
+    }
+
+    /// Pop the previous state, storing `current` for redo.
+    pub fn undo(&mut self, current: Snapshot) -> Option<Snapshot> {
+        let entry = self.undo_stack.pop()?;
+        self.redo_stack.push(current);
+        Some(entry.snapshot)
+    }
+
+    /// Pop the next state, storing `current` for undo.
+    pub fn redo(&mut self, current: Snapshot) -> Option<Snapshot> {
+        let next = self.redo_stack.pop()?;
+        self.undo_stack.push(HistoryEntry {
+            snapshot: current,
+            coalesce_key: None,
+            timestamp: Instant::now(),
+        });
+        Some(next)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::Vec2;
+
+    fn snapshot_with(count: usize) -> Snapshot {
+        Snapshot {
+            shapes: (0..count)
+                .map(|i| Shape::rectangle(Vec2::new(i as f32 * 10.0, 0.0), Vec2::new(10.0, 10.0)))
+                .collect(),
+            selection: HashSet::new(),
+            guides: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn undo_restores_previous_snapshot() {
+        let mut history = History::new();
+        let before = snapshot_with(1);
+        let after = snapshot_with(2);
+
+        history.push(before.clone());
+        let restored = history.undo(after.clone()).unwrap();
+
+        assert_eq!(restored, before);
+        assert!(!history.can_undo());
+        assert!(history.can_redo());
+    }
+
+    #[test]
+    fn redo_reapplies_undone_snapshot() {
+        let mut history = History::new();
+        let before = snapshot_with(1);
+        let after = snapshot_with(2);
+
+        history.push(before.clone());
+        let restored = history.undo(after.clone()).unwrap();
+        let reapplied = history.redo(restored).unwrap();
+
+        assert_eq!(reapplied, after);
+        assert!(history.can_undo());
+        assert!(!history.can_redo());
+    }
+
+    #[test]
+    fn push_clears_redo_stack() {
+        let mut history = History::new();
+        history.push(snapshot_with(0));
+        history.undo(snapshot_with(1));
+        assert!(history.can_redo());
+
+        history.push(snapshot_with(0));
+        assert!(!history.can_redo());
+    }
+
+    #[test]
+    fn empty_history_returns_none() {
+        let mut history = History::new();
+        assert!(history.undo(snapshot_with(0)).is_none());
+        assert!(history.redo(snapshot_with(0)).is_none());
+    }
+
+    #[test]
+    fn coalesced_pushes_with_same_key_merge() {
+        let mut history = History::new();
+        let first = snapshot_with(1);
+
+        history.push_coalesced(first.clone(), "x");
+        history.push_coalesced(snapshot_with(2), "x");
+        history.push_coalesced(snapshot_with(3), "x");
+
+        // One entry, holding the state from before the first edit
+        let restored = history.undo(snapshot_with(4)).unwrap();
+        assert_eq!(restored, first);
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn coalesced_pushes_with_different_keys_stay_separate() {
+        let mut history = History::new();
+        history.push_coalesced(snapshot_with(1), "x");
+        history.push_coalesced(snapshot_with(2), "y");
+
+        history.undo(snapshot_with(3));
+        assert!(history.can_undo());
+    }
+
+    #[test]
+    fn coalescing_expires_after_group_interval() {
+        let mut history = History::new();
+        history.group_interval = Duration::ZERO;
+        history.push_coalesced(snapshot_with(1), "x");
+        history.push_coalesced(snapshot_with(2), "x");
+
+        history.undo(snapshot_with(3));
+        assert!(history.can_undo());
+    }
+
+    #[test]
+    fn history_is_capped() {
+        let mut history = History::new();
+        for _ in 0..MAX_HISTORY_LEN + 10 {
+            history.push(Snapshot::default());
+        }
+        assert_eq!(history.undo_stack.len(), MAX_HISTORY_LEN);
+    }
+}
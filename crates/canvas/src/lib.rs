@@ -2,12 +2,32 @@
 //!
 //! Provides a flat canvas with basic shape rendering and interaction.
 
+mod arrange;
 mod canvas;
 mod element;
+mod gradient;
+mod history;
+mod marquee;
+mod outline;
+mod pages;
+mod pen;
+mod ruler;
+mod snap;
+mod spatial;
+mod stroke;
+mod text;
 mod viewport;
 
+pub use arrange::{arrange, Arrange};
 pub use canvas::{Canvas, CanvasEvent, DragState, ResizeHandle, Tool};
 pub use element::CanvasElement;
+pub use gradient::GradientHandle;
+pub use history::{History, Snapshot};
+pub use marquee::{Marquee, MarqueeMode};
+pub use pages::{PageState, Pages, DEFAULT_PAGE_NAME};
+pub use pen::{PathEdit, PathPart};
+pub use ruler::RULER_SIZE;
+pub use snap::{SnapGuide, SnapSettings, Snapper};
 // Re-export coordinate types from node for convenience
 pub use node::{CanvasDelta, CanvasPoint, CanvasSize, LocalPoint, ScreenPoint};
-pub use viewport::Viewport;
+pub use viewport::{Viewport, MAX_ZOOM, MIN_ZOOM};
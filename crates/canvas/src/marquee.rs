@@ -0,0 +1,206 @@
+//! Marquee (drag-rectangle) selection.
+//!
+//! A marquee picks the outermost shapes it reaches: a hit frame is selected
+//! whole, and only a frame the marquee misses is searched for children. So
+//! dragging across a card selects the card, while dragging inside a large
+//! frame that extends past the marquee selects the shapes within it.
+
+use glam::Vec2;
+use node::{Placement, Shape, ShapeId};
+
+/// Which shapes a marquee takes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MarqueeMode {
+    /// Shapes the rectangle touches.
+    #[default]
+    Intersect,
+    /// Only shapes entirely inside the rectangle.
+    Contain,
+}
+
+/// A marquee rectangle in canvas space, normalized so `min <= max`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Marquee {
+    pub min: Vec2,
+    pub max: Vec2,
+}
+
+impl Marquee {
+    /// The rectangle spanned by two corners dragged in any direction.
+    pub fn new(a: Vec2, b: Vec2) -> Self {
+        Self {
+            min: a.min(b),
+            max: a.max(b),
+        }
+    }
+
+    pub fn size(&self) -> Vec2 {
+        self.max - self.min
+    }
+
+    /// Whether `placement`'s box counts as taken in `mode`.
+    pub fn hits(&self, placement: &Placement, mode: MarqueeMode) -> bool {
+        let corners = placement.corners();
+        match mode {
+            MarqueeMode::Contain => corners
+                .iter()
+                .all(|c| c.cmpge(self.min).all() && c.cmple(self.max).all()),
+            MarqueeMode::Intersect => self.overlaps(&corners),
+        }
+    }
+
+    /// Separating axis test between the rectangle and a convex quad. The
+    /// rectangle's own axes are covered by comparing bounds; the quad's two
+    /// edge directions are the only others that can separate them.
+    fn overlaps(&self, quad: &[Vec2; 4]) -> bool {
+        let quad_min = quad.iter().fold(Vec2::MAX, |m, c| m.min(*c));
+        let quad_max = quad.iter().fold(Vec2::MIN, |m, c| m.max(*c));
+        if quad_min.cmpgt(self.max).any() || quad_max.cmplt(self.min).any() {
+            return false;
+        }
+
+        let rect = [
+            self.min,
+            Vec2::new(self.max.x, self.min.y),
+            self.max,
+            Vec2::new(self.min.x, self.max.y),
+        ];
+        let project = |points: &[Vec2; 4], axis: Vec2| {
+            points.iter().fold((f32::MAX, f32::MIN), |(lo, hi), p| {
+                let d = p.dot(axis);
+                (lo.min(d), hi.max(d))
+            })
+        };
+        [quad[1] - quad[0], quad[3] - quad[0]].into_iter().all(|edge| {
+            let axis = edge.perp();
+            let (rect_lo, rect_hi) = project(&rect, axis);
+            let (quad_lo, quad_hi) = project(quad, axis);
+            rect_lo <= quad_hi && quad_lo <= rect_hi
+        })
+    }
+}
+
+/// Shapes taken by `marquee`, outermost first.
+///
+/// `placement` gives each shape's world placement, or None for a shape the
+/// marquee can't reach, which is skipped along with its children. Children
+/// of a missed frame are searched in turn, except under a clipping frame in
+/// intersect mode: whatever of them shows lies within the frame, so they
+/// can't be touched where the frame isn't.
+pub fn marquee_selection(
+    shapes: &[Shape],
+    marquee: Marquee,
+    mode: MarqueeMode,
+    placement: impl Fn(&Shape) -> Option<Placement>,
+) -> Vec<ShapeId> {
+    fn visit(
+        shape: &Shape,
+        shapes: &[Shape],
+        marquee: Marquee,
+        mode: MarqueeMode,
+        placement: &dyn Fn(&Shape) -> Option<Placement>,
+        hits: &mut Vec<ShapeId>,
+    ) {
+        let Some(shape_placement) = placement(shape) else {
+            return;
+        };
+        if marquee.hits(&shape_placement, mode) {
+            hits.push(shape.id);
+            return;
+        }
+        if shape.clip_children && mode == MarqueeMode::Intersect {
+            return;
+        }
+        for child in shape
+            .children
+            .iter()
+            .filter_map(|id| shapes.iter().find(|s| s.id == *id))
+        {
+            visit(child, shapes, marquee, mode, placement, hits);
+        }
+    }
+
+    let mut hits = Vec::new();
+    for root in shapes.iter().filter(|s| s.parent.is_none()) {
+        visit(root, shapes, marquee, mode, &placement, &mut hits);
+    }
+    hits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use node::CanvasPoint;
+
+    fn rect(x: f32, y: f32, w: f32, h: f32) -> Shape {
+        Shape::rectangle(Vec2::new(x, y), Vec2::new(w, h))
+    }
+
+    /// A 200×200 frame at the origin with a 20×20 rectangle at (50, 50),
+    /// frame first.
+    fn frame_with_child(clip: bool) -> Vec<Shape> {
+        let mut child = rect(50.0, 50.0, 20.0, 20.0);
+        let frame = Shape::frame(Vec2::ZERO, Vec2::new(200.0, 200.0))
+            .with_clip_children(clip)
+            .with_child(&mut child);
+        vec![frame, child]
+    }
+
+    fn select(shapes: &[Shape], a: Vec2, b: Vec2, mode: MarqueeMode) -> Vec<ShapeId> {
+        marquee_selection(shapes, Marquee::new(a, b), mode, |s| Some(s.world_placement(shapes)))
+    }
+
+    // === Modes ===
+
+    #[test]
+    fn marquee_normalizes_drag_direction() {
+        let marquee = Marquee::new(Vec2::new(10.0, 0.0), Vec2::new(0.0, 10.0));
+        assert_eq!(marquee.min, Vec2::ZERO);
+        assert_eq!(marquee.size(), Vec2::splat(10.0));
+    }
+
+    #[test]
+    fn intersect_takes_touched_shapes_and_contain_only_enclosed() {
+        let shapes = vec![rect(0.0, 0.0, 10.0, 10.0), rect(8.0, 0.0, 10.0, 10.0)];
+        let (a, b) = (Vec2::new(-1.0, -1.0), Vec2::new(11.0, 11.0));
+        assert_eq!(select(&shapes, a, b, MarqueeMode::Intersect).len(), 2);
+        assert_eq!(select(&shapes, a, b, MarqueeMode::Contain), vec![shapes[0].id]);
+    }
+
+    #[test]
+    fn intersect_follows_rotated_edges() {
+        // A diamond whose bounding box reaches the marquee but whose edges don't
+        let mut diamond = rect(0.0, 0.0, 100.0, 100.0);
+        diamond.rotation = 45.0;
+        let shapes = vec![diamond];
+        let missed = select(&shapes, Vec2::splat(-20.0), Vec2::splat(-5.0), MarqueeMode::Intersect);
+        assert!(missed.is_empty());
+        let hit = select(&shapes, Vec2::splat(10.0), Vec2::splat(25.0), MarqueeMode::Intersect);
+        assert_eq!(hit, vec![shapes[0].id]);
+    }
+
+    // === Frames ===
+
+    #[test]
+    fn hit_frame_is_taken_without_its_children() {
+        let shapes = frame_with_child(false);
+        let hits = select(&shapes, Vec2::splat(-10.0), Vec2::splat(60.0), MarqueeMode::Intersect);
+        assert_eq!(hits, vec![shapes[0].id]);
+    }
+
+    #[test]
+    fn children_of_a_missed_frame_are_searched() {
+        let shapes = frame_with_child(false);
+        let hits = select(&shapes, Vec2::splat(40.0), Vec2::splat(80.0), MarqueeMode::Contain);
+        assert_eq!(hits, vec![shapes[1].id]);
+    }
+
+    #[test]
+    fn clipped_children_outside_a_missed_frame_are_not_touched() {
+        let mut shapes = frame_with_child(true);
+        // The child pokes out of its clipping frame, where it's hidden
+        shapes[1].position = CanvasPoint::new(190.0, 50.0);
+        let hits = select(&shapes, Vec2::new(205.0, 0.0), Vec2::new(300.0, 100.0), MarqueeMode::Intersect);
+        assert!(hits.is_empty());
+    }
+}
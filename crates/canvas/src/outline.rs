@@ -0,0 +1,103 @@
+//! Outlines of shapes that quads can't draw.
+//!
+//! GPUI quads are axis-aligned, so rotated shapes and paths are built as
+//! kurbo paths in canvas space and converted to GPUI paths for painting.
+
+use crate::Viewport;
+use gpui::{point, px, PathBuilder, Pixels, Point};
+use kurbo::{Affine, BezPath, PathEl, Shape as _};
+use node::{Placement, Shape, ShapeKind};
+
+/// Tolerance for flattening ellipses into Béziers, in canvas units.
+const TOLERANCE: f64 = 0.1;
+
+/// A shape's outline in canvas space at `placement`.
+pub fn shape_outline(shape: &Shape, placement: &Placement) -> BezPath {
+    inset_outline(shape, placement, 0.0)
+}
+
+/// A shape's outline moved `inset` into its box, or out of it if negative,
+/// with rounded corners kept concentric. Paths aren't moved.
+pub fn inset_outline(shape: &Shape, placement: &Placement, inset: f32) -> BezPath {
+    let size = placement.size.0;
+    let local = match shape.kind {
+        ShapeKind::Path => {
+            let Some(path) = shape.path_in_canvas(placement) else {
+                return BezPath::new();
+            };
+            return path.to_bez_path();
+        }
+        ShapeKind::Ellipse => {
+            kurbo::Ellipse::from_rect(inset_rect(size, inset)).to_path(TOLERANCE)
+        }
+        ShapeKind::Rectangle | ShapeKind::Frame | ShapeKind::Text => {
+            let radius = shape.corner_radius.min(size.x.min(size.y) / 2.0).max(0.0);
+            // Square corners stay square
+            let radius = if radius > 0.0 { (radius - inset).max(0.0) } else { 0.0 };
+            inset_rect(size, inset).to_rounded_rect(radius as f64).to_path(TOLERANCE)
+        }
+    };
+    to_affine(placement) * local
+}
+
+/// The placement's box in canvas space, without its corner radius.
+pub fn box_outline(placement: &Placement) -> BezPath {
+    let mut path = BezPath::new();
+    for (i, corner) in placement.corners().into_iter().enumerate() {
+        let corner = kurbo::Point::new(corner.x as f64, corner.y as f64);
+        if i == 0 {
+            path.move_to(corner);
+        } else {
+            path.line_to(corner);
+        }
+    }
+    path.close_path();
+    path
+}
+
+/// `path` on screen, built into `builder`, or None if there's nothing to
+/// paint. `origin` is the canvas element's origin in the window.
+pub fn build(
+    path: &BezPath,
+    viewport: &Viewport,
+    origin: Point<Pixels>,
+    mut builder: PathBuilder,
+) -> Option<gpui::Path<Pixels>> {
+    let to_screen = |p: kurbo::Point| {
+        let screen = viewport.canvas_to_screen(node::CanvasPoint::new(p.x as f32, p.y as f32));
+        point(origin.x + px(screen.x()), origin.y + px(screen.y()))
+    };
+    let mut has_segments = false;
+    for element in path.elements() {
+        match *element {
+            PathEl::MoveTo(p) => builder.move_to(to_screen(p)),
+            PathEl::LineTo(p) => builder.line_to(to_screen(p)),
+            PathEl::QuadTo(c, p) => builder.curve_to(to_screen(p), to_screen(c)),
+            PathEl::CurveTo(c1, c2, p) => {
+                builder.cubic_bezier_to(to_screen(p), to_screen(c1), to_screen(c2))
+            }
+            PathEl::ClosePath => builder.close(),
+        }
+        has_segments |= !matches!(element, PathEl::MoveTo(_));
+    }
+    if !has_segments {
+        return None;
+    }
+    builder.build().ok()
+}
+
+/// The box moved in by `inset`, but never past its middle.
+fn inset_rect(size: glam::Vec2, inset: f32) -> kurbo::Rect {
+    let x = inset.min(size.x / 2.0) as f64;
+    let y = inset.min(size.y / 2.0) as f64;
+    kurbo::Rect::new(x, y, size.x as f64 - x, size.y as f64 - y)
+}
+
+/// The placement's transform from its box to the canvas, for kurbo.
+pub fn to_affine(placement: &Placement) -> Affine {
+    let affine = placement.affine();
+    let [a, b] = affine.matrix2.x_axis.to_array();
+    let [c, d] = affine.matrix2.y_axis.to_array();
+    let [e, f] = affine.translation.to_array();
+    Affine::new([a, b, c, d, e, f].map(f64::from))
+}
@@ -0,0 +1,251 @@
+//! Pages of a document.
+//!
+//! A document is a list of pages, each its own canvas of shapes. The canvas
+//! edits one page at a time: the active page's shapes, selection, view and
+//! undo history live on the canvas itself, where every tool and command
+//! already works on them. Every other page keeps its state here until it's
+//! turned to, so switching pages moves state rather than copying it, and
+//! undo never reaches across pages.
+
+use crate::{History, Viewport};
+use node::{Guide, Shape, ShapeId};
+use std::collections::HashSet;
+
+/// Name of the page a new document starts with.
+pub const DEFAULT_PAGE_NAME: &str = "Page 1";
+
+/// A page's state while another page is on the canvas.
+#[derive(Debug, Default)]
+pub struct PageState {
+    pub shapes: Vec<Shape>,
+    pub guides: Vec<Guide>,
+    pub selection: HashSet<ShapeId>,
+    pub viewport: Viewport,
+    pub history: History,
+}
+
+impl PageState {
+    /// A page holding `shapes` and `guides`, at the default view with no
+    /// history.
+    pub fn new(shapes: Vec<Shape>, guides: Vec<Guide>) -> Self {
+        Self {
+            shapes,
+            guides,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Page {
+    name: String,
+    /// None for the active page, whose state is on the canvas.
+    stored: Option<PageState>,
+}
+
+/// The document's pages in order, and which one is active.
+#[derive(Debug)]
+pub struct Pages {
+    pages: Vec<Page>,
+    active: usize,
+}
+
+impl Pages {
+    /// A single active page.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            pages: vec![Page {
+                name: name.into(),
+                stored: None,
+            }],
+            active: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.pages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pages.is_empty()
+    }
+
+    /// Index of the page on the canvas.
+    pub fn active(&self) -> usize {
+        self.active
+    }
+
+    pub fn name(&self, index: usize) -> Option<&str> {
+        self.pages.get(index).map(|page| page.name.as_str())
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.pages.iter().map(|page| page.name.as_str())
+    }
+
+    /// Index of the first page called `name`.
+    pub fn find(&self, name: &str) -> Option<usize> {
+        self.pages.iter().position(|page| page.name == name)
+    }
+
+    /// The state of a page that isn't active. None for the active page,
+    /// whose state is on the canvas, or an index out of range.
+    pub fn stored(&self, index: usize) -> Option<&PageState> {
+        self.pages.get(index)?.stored.as_ref()
+    }
+
+    /// "Page N" for the lowest N from the page count up that no page uses.
+    pub fn next_name(&self) -> String {
+        (self.len() + 1..)
+            .map(|n| format!("Page {}", n))
+            .find(|name| self.find(name).is_none())
+            .expect("page numbers are unbounded")
+    }
+
+    /// Add an inactive page at the end. Returns its index.
+    pub fn push(&mut self, name: impl Into<String>, state: PageState) -> usize {
+        self.pages.push(Page {
+            name: name.into(),
+            stored: Some(state),
+        });
+        self.pages.len() - 1
+    }
+
+    /// Make page `index` active. `current` is the state of the page leaving
+    /// the canvas; the returned state is the one to put on it.
+    ///
+    /// Panics if `index` is out of range or already active.
+    pub fn switch(&mut self, index: usize, current: PageState) -> PageState {
+        assert_ne!(index, self.active, "page {} is already active", index);
+        let incoming = self.pages[index].stored.take().expect("inactive pages are stored");
+        self.pages[self.active].stored = Some(current);
+        self.active = index;
+        incoming
+    }
+
+    /// Returns false if `index` is out of range.
+    pub fn rename(&mut self, index: usize, name: impl Into<String>) -> bool {
+        match self.pages.get_mut(index) {
+            Some(page) => {
+                page.name = name.into();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Move page `from` to position `to`, shifting the pages between. The
+    /// active page stays active wherever it ends up. Returns false if
+    /// either index is out of range.
+    pub fn move_page(&mut self, from: usize, to: usize) -> bool {
+        if from >= self.len() || to >= self.len() {
+            return false;
+        }
+        let page = self.pages.remove(from);
+        self.pages.insert(to, page);
+        self.active = if self.active == from {
+            to
+        } else if from < self.active && self.active <= to {
+            self.active - 1
+        } else if to <= self.active && self.active < from {
+            self.active + 1
+        } else {
+            self.active
+        };
+        true
+    }
+
+    /// Remove an inactive page, returning its state. None if `index` is
+    /// active or out of range.
+    pub fn remove(&mut self, index: usize) -> Option<PageState> {
+        self.pages.get(index)?.stored.as_ref()?;
+        let page = self.pages.remove(index);
+        if index < self.active {
+            self.active -= 1;
+        }
+        page.stored
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::Vec2;
+
+    fn page_with_shape() -> PageState {
+        PageState::new(vec![Shape::rectangle(Vec2::ZERO, Vec2::ONE)], Vec::new())
+    }
+
+    fn names(pages: &Pages) -> Vec<&str> {
+        pages.names().collect()
+    }
+
+    // === Switching ===
+
+    #[test]
+    fn switching_trades_state_with_the_canvas() {
+        let mut pages = Pages::new("One");
+        let two = pages.push("Two", page_with_shape());
+        let mut viewport = Viewport::new();
+        viewport.zoom = 2.0;
+        let current = PageState {
+            viewport,
+            ..Default::default()
+        };
+
+        let incoming = pages.switch(two, current);
+        assert_eq!(incoming.shapes.len(), 1);
+        assert_eq!(pages.active(), two);
+        assert!(pages.stored(two).is_none());
+        assert_eq!(pages.stored(0).unwrap().viewport.zoom, 2.0);
+
+        let back = pages.switch(0, incoming);
+        assert_eq!(back.viewport.zoom, 2.0);
+        assert_eq!(pages.stored(two).unwrap().shapes.len(), 1);
+    }
+
+    // === Editing ===
+
+    #[test]
+    fn moving_pages_keeps_the_active_page_active() {
+        let mut pages = Pages::new("A");
+        pages.push("B", PageState::default());
+        pages.push("C", PageState::default());
+
+        assert!(pages.move_page(0, 2));
+        assert_eq!(names(&pages), ["B", "C", "A"]);
+        assert_eq!(pages.active(), 2);
+
+        assert!(pages.move_page(1, 0));
+        assert_eq!(names(&pages), ["C", "B", "A"]);
+        assert_eq!(pages.active(), 2);
+
+        assert!(pages.move_page(2, 0));
+        assert_eq!(pages.active(), 0);
+        assert!(!pages.move_page(0, 3));
+    }
+
+    #[test]
+    fn only_inactive_pages_are_removed() {
+        let mut pages = Pages::new("A");
+        pages.push("B", PageState::default());
+        pages.switch(1, PageState::default());
+
+        assert!(pages.remove(1).is_none());
+        assert!(pages.remove(0).is_some());
+        assert_eq!(names(&pages), ["B"]);
+        assert_eq!(pages.active(), 0);
+    }
+
+    #[test]
+    fn new_pages_are_numbered_past_existing_names() {
+        let mut pages = Pages::new(DEFAULT_PAGE_NAME);
+        assert_eq!(pages.next_name(), "Page 2");
+        pages.push("Page 2", PageState::default());
+        pages.rename(0, "Cover");
+        assert_eq!(pages.next_name(), "Page 3");
+        assert!(pages.rename(1, "Page 3"));
+        assert_eq!(pages.next_name(), "Page 4");
+        assert!(!pages.rename(5, "Missing"));
+    }
+}
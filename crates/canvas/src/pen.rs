@@ -0,0 +1,194 @@
+//! Pen tool state and hit testing for path editing.
+//!
+//! The pen draws a path by clicking vertices (dragging pulls out smooth
+//! handles) and edits an existing path's vertices and handles in place.
+//! Hit testing works on the path in canvas space with a radius in canvas
+//! units, so callers scale screen tolerances by the zoom.
+
+use glam::Vec2;
+use node::{HandleSide, PathData, PathVertex, ShapeId};
+
+/// A path whose vertices the pen is drawing or editing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PathEdit {
+    pub shape_id: ShapeId,
+    /// The vertex last clicked. Its handles and its neighbours' are shown,
+    /// and Delete removes it.
+    pub selected_vertex: Option<usize>,
+    /// Whether clicks on empty canvas append vertices to the end of the
+    /// path, as while drawing a new one.
+    pub extending: bool,
+}
+
+/// A draggable part of a path.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PathPart {
+    Vertex,
+    Handle(HandleSide),
+}
+
+/// Vertices whose handles are shown: the selected vertex and its
+/// neighbours, which share its segments.
+pub fn handle_vertices(path: &PathData, selected: Option<usize>) -> Vec<usize> {
+    let Some(selected) = selected.filter(|&i| i < path.vertices.len()) else {
+        return Vec::new();
+    };
+    let len = path.vertices.len();
+    let mut vertices = vec![selected];
+    if selected > 0 || path.closed {
+        vertices.push((selected + len - 1) % len);
+    }
+    if selected + 1 < len || path.closed {
+        vertices.push((selected + 1) % len);
+    }
+    vertices.sort_unstable();
+    vertices.dedup();
+    vertices
+}
+
+/// The part of `path` within `radius` of `point`, if any.
+///
+/// Shown handles win over vertices, since a short handle can sit on top of
+/// its vertex and would otherwise be impossible to grab. Zero handles are
+/// not parts; they're the vertex itself.
+pub fn part_at(
+    path: &PathData,
+    selected: Option<usize>,
+    point: Vec2,
+    radius: f32,
+) -> Option<(usize, PathPart)> {
+    for index in handle_vertices(path, selected) {
+        let vertex = &path.vertices[index];
+        for side in [HandleSide::In, HandleSide::Out] {
+            let handle = vertex.handle(side);
+            if handle != Vec2::ZERO && (vertex.point + handle).distance(point) <= radius {
+                return Some((index, PathPart::Handle(side)));
+            }
+        }
+    }
+    path.vertices
+        .iter()
+        .position(|vertex| vertex.point.distance(point) <= radius)
+        .map(|index| (index, PathPart::Vertex))
+}
+
+/// Turn a corner vertex smooth, or a smooth one into a corner.
+///
+/// New handles follow the line between the vertex's neighbours, a sixth of
+/// its length each way, as in a Catmull-Rom spline through them.
+pub fn toggle_smooth(path: &mut PathData, index: usize) {
+    let len = path.vertices.len();
+    let Some(vertex) = path.vertices.get(index) else {
+        return;
+    };
+    let point = vertex.point;
+    if !vertex.is_corner() {
+        path.vertices[index] = PathVertex::corner(point);
+        return;
+    }
+    let wrap = path.closed && len > 2;
+    let prev = match index {
+        0 if wrap => Some(len - 1),
+        0 => None,
+        _ => Some(index - 1),
+    };
+    let next = if index + 1 < len {
+        Some(index + 1)
+    } else {
+        wrap.then_some(0)
+    };
+    let point_at = |i: Option<usize>| i.map(|i| path.vertices[i].point);
+    let (prev, next) = (point_at(prev), point_at(next));
+    let handle = match (prev, next) {
+        (Some(prev), Some(next)) => (next - prev) / 6.0,
+        (None, Some(next)) => (next - point) / 3.0,
+        (Some(prev), None) => (point - prev) / 3.0,
+        (None, None) => return,
+    };
+    path.vertices[index] = PathVertex::smooth(point, handle);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(closed: bool) -> PathData {
+        PathData::new(
+            vec![
+                PathVertex::corner(Vec2::new(0.0, 0.0)),
+                PathVertex::corner(Vec2::new(100.0, 0.0)),
+                PathVertex::corner(Vec2::new(100.0, 100.0)),
+                PathVertex::corner(Vec2::new(0.0, 100.0)),
+            ],
+            closed,
+        )
+    }
+
+    // === Shown handles ===
+
+    #[test]
+    fn no_handles_without_a_selected_vertex() {
+        assert!(handle_vertices(&square(true), None).is_empty());
+    }
+
+    #[test]
+    fn closed_path_neighbours_wrap_around() {
+        assert_eq!(handle_vertices(&square(true), Some(0)), vec![0, 1, 3]);
+    }
+
+    #[test]
+    fn open_path_ends_have_one_neighbour() {
+        assert_eq!(handle_vertices(&square(false), Some(0)), vec![0, 1]);
+        assert_eq!(handle_vertices(&square(false), Some(3)), vec![2, 3]);
+    }
+
+    // === Hit testing ===
+
+    #[test]
+    fn hits_vertex_within_radius() {
+        let path = square(true);
+        assert_eq!(
+            part_at(&path, None, Vec2::new(98.0, 2.0), 4.0),
+            Some((1, PathPart::Vertex))
+        );
+        assert_eq!(part_at(&path, None, Vec2::new(50.0, 50.0), 4.0), None);
+    }
+
+    #[test]
+    fn shown_handle_wins_over_its_vertex() {
+        let mut path = square(true);
+        path.vertices[1] = PathVertex::smooth(Vec2::new(100.0, 0.0), Vec2::new(3.0, 0.0));
+        assert_eq!(
+            part_at(&path, Some(1), Vec2::new(103.0, 0.0), 4.0),
+            Some((1, PathPart::Handle(HandleSide::Out)))
+        );
+    }
+
+    #[test]
+    fn hidden_handles_are_not_hit() {
+        let mut path = square(true);
+        path.vertices[2] = PathVertex::smooth(Vec2::new(100.0, 100.0), Vec2::new(0.0, 30.0));
+        // Vertex 2 isn't selected or next to the selection
+        assert_eq!(part_at(&path, Some(0), Vec2::new(100.0, 130.0), 4.0), None);
+    }
+
+    // === Smoothing ===
+
+    #[test]
+    fn smoothing_a_corner_aligns_handles_with_its_neighbours() {
+        let mut path = square(false);
+        toggle_smooth(&mut path, 1);
+        let vertex = path.vertices[1];
+        assert_eq!(vertex.handle_out, Vec2::new(100.0, 100.0) / 6.0);
+        assert_eq!(vertex.handle_in, -vertex.handle_out);
+    }
+
+    #[test]
+    fn smoothing_twice_restores_the_corner() {
+        let mut path = square(true);
+        toggle_smooth(&mut path, 0);
+        assert!(!path.vertices[0].is_corner());
+        toggle_smooth(&mut path, 0);
+        assert_eq!(path.vertices[0], PathVertex::corner(Vec2::ZERO));
+    }
+}
@@ -0,0 +1,176 @@
+//! Rulers along the canvas's top and left edges.
+//!
+//! Rulers are painted over the canvas rather than beside it, so the view
+//! doesn't shift when they're shown. Ticks fall on round canvas units: the
+//! labeled ones are 1, 2 or 5 times a power of ten apart, whichever keeps
+//! labels readable at the zoom, with ten smaller ticks between each pair.
+
+use crate::Viewport;
+use gpui::{point, px, size, App, Bounds, Pixels, SharedString, TextRun, Window};
+use node::{CanvasPoint, GuideAxis, ScreenPoint};
+use theme::Theme;
+
+/// Thickness of each ruler in pixels.
+pub const RULER_SIZE: f32 = 20.0;
+
+/// Labeled ticks are at least this many pixels apart.
+const MIN_LABEL_SPACING: f32 = 50.0;
+
+/// Font size of tick labels in pixels.
+const LABEL_SIZE: f32 = 9.0;
+
+/// Lengths in pixels of labeled and unlabeled ticks.
+const MAJOR_TICK: f32 = 8.0;
+const MINOR_TICK: f32 = 4.0;
+
+/// Canvas units between labeled ticks at `zoom`.
+pub fn tick_step(zoom: f32) -> f32 {
+    let min = MIN_LABEL_SPACING / zoom.max(f32::EPSILON);
+    let power = 10f32.powf(min.log10().floor());
+    [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .map(|factor| factor * power)
+        .find(|step| *step >= min)
+        .unwrap_or(10.0 * power)
+}
+
+/// Ticks from `start` to `end` in canvas units, a tenth of `step` apart:
+/// each one's position and whether it's labeled.
+pub fn ticks(start: f32, end: f32, step: f32) -> impl Iterator<Item = (f32, bool)> {
+    let minor = step / 10.0;
+    let first = (start / minor).ceil() as i64;
+    let last = (end / minor).floor() as i64;
+    (first..=last).map(move |n| (n as f32 * minor, n % 10 == 0))
+}
+
+/// A tick's label, with as many decimals as the step needs.
+pub fn label(value: f32, step: f32) -> String {
+    // Adding zero turns -0 into 0
+    let value = value + 0.0;
+    if step >= 1.0 {
+        format!("{}", value.round() as i64)
+    } else {
+        let decimals = (-step.log10()).ceil() as usize;
+        format!("{:.*}", decimals, value)
+    }
+}
+
+/// The ruler at a point relative to the canvas's top-left, as the axis of
+/// the guides it gives. None off the rulers and in the corner where they
+/// meet.
+pub fn guide_axis_at(x: f32, y: f32) -> Option<GuideAxis> {
+    match (x < RULER_SIZE, y < RULER_SIZE) {
+        (false, true) => Some(GuideAxis::Horizontal),
+        (true, false) => Some(GuideAxis::Vertical),
+        _ => None,
+    }
+}
+
+/// Whether a point relative to the canvas's top-left is on either ruler.
+pub fn is_on_ruler(x: f32, y: f32) -> bool {
+    x < RULER_SIZE || y < RULER_SIZE
+}
+
+/// Paint both rulers over the canvas at `bounds`, with `highlight`, the
+/// selection's extent, marked along each.
+pub fn paint(
+    viewport: &Viewport,
+    highlight: Option<(CanvasPoint, CanvasPoint)>,
+    theme: &Theme,
+    bounds: Bounds<Pixels>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let origin = bounds.origin;
+    let (width, height): (f32, f32) = (bounds.size.width.into(), bounds.size.height.into());
+    let strip = |x: f32, y: f32, w: f32, h: f32| Bounds {
+        origin: point(origin.x + px(x), origin.y + px(y)),
+        size: size(px(w), px(h)),
+    };
+
+    window.paint_quad(gpui::fill(strip(0.0, 0.0, width, RULER_SIZE), theme.ui_background));
+    window.paint_quad(gpui::fill(strip(0.0, 0.0, RULER_SIZE, height), theme.ui_background));
+
+    if let Some((min, max)) = highlight {
+        let min = viewport.canvas_to_screen(min);
+        let max = viewport.canvas_to_screen(max);
+        let color = theme.selection.opacity(0.2);
+        window.paint_quad(gpui::fill(strip(min.x(), 0.0, max.x() - min.x(), RULER_SIZE), color));
+        window.paint_quad(gpui::fill(strip(0.0, min.y(), RULER_SIZE, max.y() - min.y()), color));
+    }
+
+    let step = tick_step(viewport.zoom);
+    let view_min = viewport.screen_to_canvas(ScreenPoint::new(0.0, 0.0));
+    let view_max = viewport.screen_to_canvas(ScreenPoint::new(width, height));
+    let text_style = window.text_style();
+    let run = |text: &str| TextRun {
+        len: text.len(),
+        font: text_style.font(),
+        color: theme.ui_text_muted,
+        background_color: None,
+        underline: None,
+        strikethrough: None,
+    };
+    let label_size = px(LABEL_SIZE);
+
+    for (value, major) in ticks(view_min.x(), view_max.x(), step) {
+        let x = viewport.canvas_to_screen(CanvasPoint::new(value, 0.0)).x();
+        let length = if major { MAJOR_TICK } else { MINOR_TICK };
+        window.paint_quad(gpui::fill(strip(x, RULER_SIZE - length, 1.0, length), theme.ui_border));
+        if major {
+            let text: SharedString = label(value, step).into();
+            let line = window.text_system().shape_line(text.clone(), label_size, &[run(&text)], None);
+            let _ = line.paint(point(origin.x + px(x + 2.0), origin.y + px(1.0)), label_size, window, cx);
+        }
+    }
+
+    for (value, major) in ticks(view_min.y(), view_max.y(), step) {
+        let y = viewport.canvas_to_screen(CanvasPoint::new(0.0, value)).y();
+        let length = if major { MAJOR_TICK } else { MINOR_TICK };
+        window.paint_quad(gpui::fill(strip(RULER_SIZE - length, y, length, 1.0), theme.ui_border));
+        if major {
+            // Stacked a character to a line, to fit the ruler's width
+            for (i, character) in label(value, step).chars().enumerate() {
+                let text: SharedString = character.to_string().into();
+                let line = window.text_system().shape_line(text.clone(), label_size, &[run(&text)], None);
+                let top = y + 2.0 + i as f32 * LABEL_SIZE;
+                let _ = line.paint(point(origin.x + px(2.0), origin.y + px(top)), label_size, window, cx);
+            }
+        }
+    }
+
+    // Edges between the rulers and the canvas, and the corner they share
+    window.paint_quad(gpui::fill(strip(0.0, RULER_SIZE - 1.0, width, 1.0), theme.ui_border));
+    window.paint_quad(gpui::fill(strip(RULER_SIZE - 1.0, 0.0, 1.0, height), theme.ui_border));
+    window.paint_quad(gpui::fill(strip(0.0, 0.0, RULER_SIZE, RULER_SIZE), theme.ui_background));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps_keep_labels_apart() {
+        assert_eq!(tick_step(1.0), 50.0);
+        assert_eq!(tick_step(2.0), 50.0);
+        assert_eq!(tick_step(0.3), 200.0);
+        assert_eq!(tick_step(0.1), 500.0);
+        assert_eq!(tick_step(10.0), 5.0);
+    }
+
+    #[test]
+    fn every_tenth_tick_is_labeled() {
+        let ticks: Vec<_> = ticks(-12.0, 12.0, 50.0).collect();
+        assert_eq!(ticks.first(), Some(&(-10.0, false)));
+        assert_eq!(ticks.len(), 5);
+        assert!(ticks.contains(&(0.0, true)));
+    }
+
+    #[test]
+    fn labels_have_the_decimals_the_step_needs() {
+        assert_eq!(label(150.0, 50.0), "150");
+        assert_eq!(label(-0.0, 50.0), "0");
+        assert_eq!(label(-1.5, 0.5), "-1.5");
+        assert_eq!(label(0.25, 0.05), "0.25");
+    }
+}
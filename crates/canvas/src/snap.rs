@@ -0,0 +1,362 @@
+//! Snapping shapes as they're moved, resized and drawn.
+//!
+//! Each axis snaps on its own. The page's guides and the edges and centers
+//! of other shapes, the parent frame among them, come first, then even
+//! spacing between neighbors, then the grid, then whole pixels. Snapping to
+//! shapes leaves guides behind to show what lined up.
+
+use glam::Vec2;
+use node::{Guide, GuideAxis};
+use serde::{Deserialize, Serialize};
+
+/// Below this, a guide's end counts as touching an edge.
+const EPSILON: f32 = 0.01;
+
+/// What moved, resized and drawn shapes snap to.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SnapSettings {
+    /// Off turns every kind of snapping off.
+    pub enabled: bool,
+    /// Edges, centers and spacing of other shapes and the parent frame.
+    pub to_shapes: bool,
+    /// The page's guides.
+    pub to_guides: bool,
+    /// Grid spacing in canvas units. The grid is drawn when set.
+    pub grid: Option<f32>,
+    /// Whole canvas units, when nothing else snaps.
+    pub to_pixels: bool,
+    /// How near, in screen pixels, an edge has to come to snap.
+    pub threshold: f32,
+}
+
+impl Default for SnapSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            to_shapes: true,
+            to_guides: true,
+            grid: None,
+            to_pixels: true,
+            threshold: 6.0,
+        }
+    }
+}
+
+/// A line showing what a shape snapped to, in canvas space.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SnapGuide {
+    /// Edges or centers lined up along it.
+    Align { from: Vec2, to: Vec2 },
+    /// One of several equal gaps, spanning it.
+    Spacing { from: Vec2, to: Vec2 },
+}
+
+/// A box as min and max corners.
+type Rect = (Vec2, Vec2);
+
+/// Snaps one gesture. The shapes it can snap to are gathered when the
+/// gesture starts.
+#[derive(Clone, Debug)]
+pub struct Snapper {
+    settings: SnapSettings,
+    /// Boxes around the shapes that stay put.
+    targets: Vec<Rect>,
+    /// Where the page's guides cross each axis.
+    guides: [Vec<f32>; 2],
+    /// The settings' threshold in canvas units.
+    threshold: f32,
+}
+
+/// A snap along one axis: how far to shift, and whether it was to a shape.
+struct AxisSnap {
+    offset: f32,
+    to_shape: bool,
+    spacing: Vec<SnapGuide>,
+}
+
+impl Snapper {
+    pub fn new(settings: SnapSettings, targets: Vec<Rect>, zoom: f32) -> Self {
+        Self {
+            threshold: settings.threshold / zoom.max(f32::EPSILON),
+            settings,
+            targets,
+            guides: Default::default(),
+        }
+    }
+
+    /// Snap to the page's guides too.
+    pub fn with_guides(mut self, guides: &[Guide]) -> Self {
+        for guide in guides {
+            let axis = match guide.axis {
+                GuideAxis::Vertical => 0,
+                GuideAxis::Horizontal => 1,
+            };
+            self.guides[axis].push(guide.position);
+        }
+        self
+    }
+
+    /// How far to shift a box that's been moved to `min..max`, and the
+    /// guides to show for it.
+    pub fn snap_box(&self, min: Vec2, max: Vec2) -> (Vec2, Vec<SnapGuide>) {
+        self.snap((min, max), true)
+    }
+
+    /// How far to shift a dragged point, such as the corner of a shape
+    /// being drawn or resized, and the guides to show for it.
+    pub fn snap_point(&self, point: Vec2) -> (Vec2, Vec<SnapGuide>) {
+        self.snap((point, point), false)
+    }
+
+    fn snap(&self, rect: Rect, spaced: bool) -> (Vec2, Vec<SnapGuide>) {
+        if !self.settings.enabled {
+            return (Vec2::ZERO, Vec::new());
+        }
+        let x = self.snap_axis(rect, 0, spaced);
+        let y = self.snap_axis(rect, 1, spaced);
+        let offset = Vec2::new(x.offset, y.offset);
+
+        let snapped = (rect.0 + offset, rect.1 + offset);
+        let mut guides = Vec::new();
+        for (axis, snap) in [(0, &x), (1, &y)] {
+            if snap.to_shape {
+                guides.extend(self.align_guides(snapped, axis));
+            }
+            guides.extend(snap.spacing.iter().copied());
+        }
+        (offset, guides)
+    }
+
+    fn snap_axis(&self, rect: Rect, axis: usize, spaced: bool) -> AxisSnap {
+        let features = lines(rect, axis);
+        let guides = self.guides[axis].iter().copied().filter(|_| self.settings.to_guides);
+        let shapes = self
+            .targets
+            .iter()
+            .filter(|_| self.settings.to_shapes)
+            .flat_map(|&target| lines(target, axis));
+        let nearest = guides
+            .chain(shapes)
+            .flat_map(|line| features.iter().map(move |feature| line - feature))
+            .filter(|offset| offset.abs() <= self.threshold)
+            .min_by(|a, b| a.abs().total_cmp(&b.abs()));
+        if let Some(offset) = nearest {
+            return AxisSnap { offset, to_shape: true, spacing: Vec::new() };
+        }
+        if spaced && self.settings.to_shapes {
+            if let Some((offset, spacing)) = self.snap_spacing(rect, axis) {
+                return AxisSnap { offset, to_shape: false, spacing };
+            }
+        }
+
+        let start = rect.0[axis];
+        let offset = match self.settings.grid.filter(|grid| *grid > 0.0) {
+            Some(grid) => (start / grid).round() * grid - start,
+            None if self.settings.to_pixels => start.round() - start,
+            None => 0.0,
+        };
+        AxisSnap { offset, to_shape: false, spacing: Vec::new() }
+    }
+
+    /// Snap a box to even gaps with its neighbors along `axis`: centered
+    /// between the nearest on either side, or as far from one as that one
+    /// is from the next.
+    fn snap_spacing(&self, rect: Rect, axis: usize) -> Option<(f32, Vec<SnapGuide>)> {
+        let before = self.neighbor(rect, axis, false);
+        let after = self.neighbor(rect, axis, true);
+        let size = rect.1[axis] - rect.0[axis];
+        let mut candidates: Vec<(f32, Vec<(Rect, Rect)>)> = Vec::new();
+
+        if let (Some(before), Some(after)) = (before, after) {
+            let start = (before.1[axis] + after.0[axis] - size) / 2.0;
+            candidates.push((start - rect.0[axis], vec![(before, rect), (rect, after)]));
+        }
+        if let Some(before) = before {
+            if let Some(further) = self.neighbor(before, axis, false) {
+                let gap = before.0[axis] - further.1[axis];
+                let start = before.1[axis] + gap;
+                candidates.push((start - rect.0[axis], vec![(further, before), (before, rect)]));
+            }
+        }
+        if let Some(after) = after {
+            if let Some(further) = self.neighbor(after, axis, true) {
+                let gap = further.0[axis] - after.1[axis];
+                let start = after.0[axis] - gap - size;
+                candidates.push((start - rect.0[axis], vec![(rect, after), (after, further)]));
+            }
+        }
+
+        let (offset, pairs) = candidates
+            .into_iter()
+            .filter(|(offset, _)| offset.abs() <= self.threshold)
+            .min_by(|a, b| a.0.abs().total_cmp(&b.0.abs()))?;
+        let shift = |r: Rect| if r == rect { shifted(r, axis, offset) } else { r };
+        let guides = pairs
+            .into_iter()
+            .map(|(a, b)| gap_guide(shift(a), shift(b), axis))
+            .collect();
+        Some((offset, guides))
+    }
+
+    /// The nearest target wholly before or after `rect` along `axis` that
+    /// overlaps it across the axis.
+    fn neighbor(&self, rect: Rect, axis: usize, after: bool) -> Option<Rect> {
+        let cross = 1 - axis;
+        self.targets
+            .iter()
+            .copied()
+            .filter(|t| t.0[cross] < rect.1[cross] && t.1[cross] > rect.0[cross])
+            .filter(|t| if after { t.0[axis] >= rect.1[axis] } else { t.1[axis] <= rect.0[axis] })
+            .min_by(|a, b| {
+                let distance = |t: &Rect| {
+                    if after { t.0[axis] - rect.1[axis] } else { rect.0[axis] - t.1[axis] }
+                };
+                distance(a).total_cmp(&distance(b))
+            })
+    }
+
+    /// Lines through every target edge or center that `rect` lines up with
+    /// along `axis`, spanning both boxes.
+    fn align_guides(&self, rect: Rect, axis: usize) -> Vec<SnapGuide> {
+        let cross = 1 - axis;
+        let features = lines(rect, axis);
+        let mut guides = Vec::new();
+        for &target in &self.targets {
+            for line in lines(target, axis) {
+                if features.iter().any(|feature| (feature - line).abs() < EPSILON) {
+                    let from = rect.0[cross].min(target.0[cross]);
+                    let to = rect.1[cross].max(target.1[cross]);
+                    let point = |along: f32| {
+                        let mut point = Vec2::ZERO;
+                        point[axis] = line;
+                        point[cross] = along;
+                        point
+                    };
+                    guides.push(SnapGuide::Align { from: point(from), to: point(to) });
+                }
+            }
+        }
+        guides
+    }
+}
+
+/// A box's edges and center along an axis.
+fn lines(rect: Rect, axis: usize) -> [f32; 3] {
+    let (min, max) = (rect.0[axis], rect.1[axis]);
+    [min, (min + max) / 2.0, max]
+}
+
+fn shifted(rect: Rect, axis: usize, offset: f32) -> Rect {
+    let mut shift = Vec2::ZERO;
+    shift[axis] = offset;
+    (rect.0 + shift, rect.1 + shift)
+}
+
+/// The gap between two boxes, `a` before `b` along `axis`, drawn midway
+/// across where they overlap.
+fn gap_guide(a: Rect, b: Rect, axis: usize) -> SnapGuide {
+    let cross = 1 - axis;
+    let middle = (a.0[cross].max(b.0[cross]) + a.1[cross].min(b.1[cross])) / 2.0;
+    let mut from = Vec2::ZERO;
+    from[axis] = a.1[axis];
+    from[cross] = middle;
+    let mut to = from;
+    to[axis] = b.0[axis];
+    SnapGuide::Spacing { from, to }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: f32, y: f32, w: f32, h: f32) -> Rect {
+        (Vec2::new(x, y), Vec2::new(x + w, y + h))
+    }
+
+    fn snapper(targets: Vec<Rect>) -> Snapper {
+        Snapper::new(SnapSettings::default(), targets, 1.0)
+    }
+
+    #[test]
+    fn edges_snap_to_nearby_edges_and_centers() {
+        let snapper = snapper(vec![rect(0.0, 0.0, 100.0, 100.0)]);
+        let (min, max) = rect(103.0, 47.0, 20.0, 10.0);
+        let (offset, guides) = snapper.snap_box(min, max);
+        // Left edge to the right edge, center to the center
+        assert_eq!(offset, Vec2::new(-3.0, -2.0));
+        let align = |from: (f32, f32), to: (f32, f32)| SnapGuide::Align {
+            from: Vec2::new(from.0, from.1),
+            to: Vec2::new(to.0, to.1),
+        };
+        assert!(guides.contains(&align((100.0, 0.0), (100.0, 100.0))));
+        assert!(guides.contains(&align((0.0, 50.0), (120.0, 50.0))));
+    }
+
+    #[test]
+    fn nothing_near_falls_back_to_the_grid_then_pixels() {
+        let (offset, guides) = snapper(Vec::new()).snap_point(Vec2::new(10.25, 7.75));
+        assert_eq!(offset, Vec2::new(-0.25, 0.25));
+        assert!(guides.is_empty());
+
+        let settings = SnapSettings { grid: Some(8.0), ..Default::default() };
+        let snapper = Snapper::new(settings, Vec::new(), 1.0);
+        let (offset, _) = snapper.snap_point(Vec2::new(10.0, 13.0));
+        assert_eq!(offset, Vec2::new(-2.0, 3.0));
+    }
+
+    #[test]
+    fn the_threshold_is_in_screen_pixels() {
+        let targets = vec![rect(0.0, 0.0, 10.0, 10.0)];
+        let settings = SnapSettings { to_pixels: false, ..Default::default() };
+        let zoomed_in = Snapper::new(settings, targets.clone(), 4.0);
+        assert_eq!(zoomed_in.snap_point(Vec2::new(13.0, 30.0)).0, Vec2::ZERO);
+        let zoomed_out = Snapper::new(settings, targets, 0.5);
+        assert_eq!(zoomed_out.snap_point(Vec2::new(13.0, 30.0)).0, Vec2::new(-3.0, 0.0));
+    }
+
+    #[test]
+    fn boxes_snap_to_even_gaps() {
+        let snapper = snapper(vec![rect(0.0, 0.0, 10.0, 10.0), rect(100.0, 0.0, 10.0, 10.0)]);
+        // Only neighbors across from each other count
+        let (min, max) = rect(52.0, 200.0, 10.0, 10.0);
+        assert_eq!(snapper.snap_box(min, max).0.x, 0.0);
+
+        // Centered between the two, 40 from each
+        let (min, max) = rect(52.0, 2.0, 10.0, 6.0);
+        let (offset, guides) = snapper.snap_box(min, max);
+        assert_eq!(offset.x, -2.0);
+        let spacing = |from: f32, to: f32| SnapGuide::Spacing {
+            from: Vec2::new(from, 5.0),
+            to: Vec2::new(to, 5.0),
+        };
+        assert!(guides.contains(&spacing(10.0, 50.0)));
+        assert!(guides.contains(&spacing(60.0, 100.0)));
+
+        // Continuing a row: 90 from the last, as it is from the one before
+        let (min, max) = rect(198.0, 2.0, 10.0, 6.0);
+        assert_eq!(snapper.snap_box(min, max).0.x, 2.0);
+    }
+
+    #[test]
+    fn guides_snap_the_axis_they_cross() {
+        let snapper = snapper(Vec::new()).with_guides(&[
+            Guide::new(GuideAxis::Vertical, 40.0),
+            Guide::new(GuideAxis::Horizontal, 8.0),
+        ]);
+        let (offset, guides) = snapper.snap_point(Vec2::new(37.0, 43.0));
+        // Nothing crosses y near 43, so it only rounds
+        assert_eq!(offset, Vec2::new(3.0, 0.0));
+        assert!(guides.is_empty());
+
+        let (min, max) = rect(0.0, 0.0, 10.0, 10.0);
+        assert_eq!(snapper.snap_box(min, max).0, Vec2::new(0.0, -2.0));
+    }
+
+    #[test]
+    fn disabled_settings_leave_things_be() {
+        let settings = SnapSettings { enabled: false, ..Default::default() };
+        let snapper = Snapper::new(settings, vec![rect(0.0, 0.0, 10.0, 10.0)], 1.0);
+        assert_eq!(snapper.snap_point(Vec2::new(11.5, 0.5)), (Vec2::ZERO, Vec::new()));
+    }
+}
@@ -0,0 +1,232 @@
+//! Spatial index of shape bounds.
+//!
+//! Hit testing, marquee selection and culling ask which shapes lie near a
+//! point or rectangle. The index answers from a quadtree of axis-aligned
+//! world bounds, so those questions cost roughly the number of shapes
+//! nearby rather than the number on the canvas. Answers are candidates by
+//! bounds; callers still test exact geometry.
+//!
+//! The quadtree works in whole canvas units, so regions are rounded outward
+//! and results filtered against the exact bounds kept alongside.
+
+use glam::Vec2;
+use node::ShapeId;
+use quadtree_rs::{area::AreaBuilder, point::Point, Quadtree};
+use std::collections::{HashMap, HashSet};
+
+/// The tree spans 2^DEPTH canvas units on each axis, centered on the origin.
+/// Shapes beyond it are kept in a list scanned on every query.
+const DEPTH: usize = 24;
+
+/// Axis-aligned bounds as (min, max).
+type Bounds = (Vec2, Vec2);
+
+struct Entry {
+    bounds: Bounds,
+    /// Handle in the tree, or None for shapes outside it.
+    handle: Option<u64>,
+}
+
+pub struct SpatialIndex {
+    tree: Quadtree<i32, ShapeId>,
+    entries: HashMap<ShapeId, Entry>,
+    outside: HashSet<ShapeId>,
+}
+
+impl Default for SpatialIndex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SpatialIndex {
+    pub fn new() -> Self {
+        let half = 1 << (DEPTH - 1);
+        Self {
+            tree: Quadtree::new_with_anchor(Point { x: -half, y: -half }, DEPTH),
+            entries: HashMap::new(),
+            outside: HashSet::new(),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.tree.reset();
+        self.entries.clear();
+        self.outside.clear();
+    }
+
+    /// Index a shape at `bounds`, replacing where it was. Unchanged bounds
+    /// leave the tree alone, so refreshing every shape each frame only
+    /// touches the ones that moved.
+    pub fn update(&mut self, id: ShapeId, min: Vec2, max: Vec2) {
+        if self.entries.get(&id).is_some_and(|entry| entry.bounds == (min, max)) {
+            return;
+        }
+        self.remove(id);
+        let handle = region(min, max).and_then(|area| self.tree.insert(area, id));
+        if handle.is_none() {
+            self.outside.insert(id);
+        }
+        self.entries.insert(
+            id,
+            Entry {
+                bounds: (min, max),
+                handle,
+            },
+        );
+    }
+
+    pub fn remove(&mut self, id: ShapeId) {
+        if let Some(entry) = self.entries.remove(&id) {
+            match entry.handle {
+                Some(handle) => {
+                    self.tree.delete_by_handle(handle);
+                }
+                None => {
+                    self.outside.remove(&id);
+                }
+            }
+        }
+    }
+
+    /// Drop shapes for which `keep` is false, such as ones no longer on
+    /// the canvas.
+    pub fn retain(&mut self, keep: impl Fn(ShapeId) -> bool) {
+        let gone: Vec<_> = self.entries.keys().copied().filter(|id| !keep(*id)).collect();
+        for id in gone {
+            self.remove(id);
+        }
+    }
+
+    /// Shapes whose bounds meet the rectangle from `min` to `max`, edges
+    /// included, in no particular order.
+    pub fn query(&self, min: Vec2, max: Vec2) -> Vec<ShapeId> {
+        let overlaps = |id: &ShapeId| {
+            self.entries.get(id).is_some_and(|entry| {
+                let (lo, hi) = entry.bounds;
+                lo.cmple(max).all() && hi.cmpge(min).all()
+            })
+        };
+        let in_tree = region(min, max)
+            .map(|area| self.tree.query(area).map(|entry| *entry.value_ref()).collect::<Vec<_>>())
+            .unwrap_or_default();
+        in_tree
+            .into_iter()
+            .chain(self.outside.iter().copied())
+            .filter(overlaps)
+            .collect()
+    }
+}
+
+/// The whole-unit region covering `min` to `max`. Rounding outward and
+/// padding by a unit keeps shapes that only touch an edge, which the tree
+/// wouldn't count as overlapping.
+fn region(min: Vec2, max: Vec2) -> Option<quadtree_rs::area::Area<i32>> {
+    if !(min.is_finite() && max.is_finite()) {
+        return None;
+    }
+    let lo = min.floor() - 1.0;
+    let hi = max.ceil() + 1.0;
+    let limit = (1 << (DEPTH - 1)) as f32;
+    if lo.min_element() < -limit || hi.max_element() > limit {
+        return None;
+    }
+    let size = (hi - lo).max(Vec2::ONE);
+    AreaBuilder::default()
+        .anchor(Point {
+            x: lo.x as i32,
+            y: lo.y as i32,
+        })
+        .dimensions((size.x as i32, size.y as i32))
+        .build()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at_point(index: &SpatialIndex, point: Vec2) -> Vec<ShapeId> {
+        index.query(point, point)
+    }
+
+    fn sorted(mut ids: Vec<ShapeId>) -> Vec<ShapeId> {
+        ids.sort_by_key(|id| id.to_uuid_string());
+        ids
+    }
+
+    // === Queries ===
+
+    #[test]
+    fn query_includes_touching_edges() {
+        let mut index = SpatialIndex::new();
+        let id = ShapeId::new();
+        index.update(id, Vec2::new(0.5, 0.5), Vec2::new(10.5, 10.5));
+        assert_eq!(at_point(&index, Vec2::new(10.5, 3.0)), vec![id]);
+        assert!(at_point(&index, Vec2::new(10.6, 3.0)).is_empty());
+    }
+
+    #[test]
+    fn update_moves_and_remove_forgets() {
+        let mut index = SpatialIndex::new();
+        let id = ShapeId::new();
+        index.update(id, Vec2::ZERO, Vec2::splat(10.0));
+        index.update(id, Vec2::splat(100.0), Vec2::splat(110.0));
+        assert!(at_point(&index, Vec2::splat(5.0)).is_empty());
+        assert_eq!(at_point(&index, Vec2::splat(105.0)), vec![id]);
+        index.remove(id);
+        assert!(index.entries.is_empty());
+        assert!(at_point(&index, Vec2::splat(105.0)).is_empty());
+    }
+
+    #[test]
+    fn shapes_beyond_the_tree_are_still_found() {
+        let mut index = SpatialIndex::new();
+        let far = ShapeId::new();
+        index.update(far, Vec2::splat(1.0e8), Vec2::splat(1.0e8 + 10.0));
+        assert_eq!(at_point(&index, Vec2::splat(1.0e8 + 5.0)), vec![far]);
+        index.retain(|_| false);
+        assert!(at_point(&index, Vec2::splat(1.0e8 + 5.0)).is_empty());
+    }
+
+    // === Scale ===
+
+    #[test]
+    fn ten_thousand_shapes_match_a_linear_scan() {
+        let mut index = SpatialIndex::new();
+        let mut shapes = Vec::new();
+        for i in 0..10_000 {
+            let (col, row) = ((i % 100) as f32, (i / 100) as f32);
+            // Overlapping boxes of varying size, so queries meet several
+            let min = Vec2::new(col * 30.0, row * 30.0);
+            let max = min + Vec2::new(20.0 + (i % 7) as f32 * 5.0, 20.0 + (i % 5) as f32 * 6.0);
+            let id = ShapeId::new();
+            index.update(id, min, max);
+            shapes.push((id, min, max));
+        }
+        assert_eq!(index.entries.len(), 10_000);
+
+        let scan = |lo: Vec2, hi: Vec2| {
+            sorted(
+                shapes
+                    .iter()
+                    .filter(|(_, min, max)| min.cmple(hi).all() && max.cmpge(lo).all())
+                    .map(|(id, _, _)| *id)
+                    .collect(),
+            )
+        };
+
+        for i in 0..1_000 {
+            let point = Vec2::new((i * 37 % 3000) as f32 + 0.5, (i * 53 % 3000) as f32 + 0.5);
+            assert_eq!(sorted(at_point(&index, point)), scan(point, point));
+        }
+        for i in 0..100 {
+            let min = Vec2::new((i * 71 % 2800) as f32, (i * 29 % 2800) as f32);
+            let max = min + Vec2::new(150.0, 90.0);
+            let hits = sorted(index.query(min, max));
+            assert_eq!(hits, scan(min, max));
+            // A viewport-sized query touches a sliver of the canvas
+            assert!(hits.len() < 100);
+        }
+    }
+}
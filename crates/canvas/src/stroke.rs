@@ -0,0 +1,166 @@
+//! Strokes that quads can't draw as borders.
+//!
+//! A stroke is painted by filling the area it covers, which kurbo finds by
+//! stroking a centre line: the shape's edge, moved into or out of the box
+//! by half the stroke's width for strokes inside or outside it. Paths can't
+//! be moved that way, so a closed path's inside or outside stroke is the
+//! band between its edge and one side of a stroke twice as wide. Open
+//! paths have no inside, and dashed strokes on paths would need their
+//! dashes cut from such a band, so both stay centered on the curve.
+
+use crate::{outline, Viewport};
+use gpui::{FillOptions, PathBuilder, PathStyle, Pixels, Point, Window};
+use kurbo::{BezPath, Cap, Join, PathEl, Shape as _, StrokeOpts};
+use node::{Placement, Shape, ShapeKind, Stroke, StrokeAlign, StrokeCap, StrokeJoin};
+
+/// How far flattened curves may stray, in screen pixels.
+const FLATNESS: f64 = 0.25;
+
+/// Paint `stroke` on `shape`, which is on canvas at `placement`, faded by
+/// `opacity` on top of the stroke's own.
+pub fn paint(
+    stroke: &Stroke,
+    opacity: f32,
+    shape: &Shape,
+    placement: &Placement,
+    viewport: &Viewport,
+    origin: Point<Pixels>,
+    window: &mut Window,
+) {
+    let area = stroke_area(stroke, shape, placement, FLATNESS / viewport.zoom as f64);
+    // Where a stroke crosses itself, at joins or over a closed shape's two
+    // sides, even-odd filling would leave holes
+    let builder = PathBuilder::fill().with_style(PathStyle::Fill(FillOptions::non_zero()));
+    if let Some(path) = outline::build(&area, viewport, origin, builder) {
+        window.paint_path(path, stroke.color.opacity(stroke.opacity * opacity));
+    }
+}
+
+/// What `stroke` covers on canvas, to be filled non-zero.
+fn stroke_area(stroke: &Stroke, shape: &Shape, placement: &Placement, tolerance: f64) -> BezPath {
+    let inset = match stroke.align {
+        StrokeAlign::Inside => stroke.width / 2.0,
+        StrokeAlign::Center => 0.0,
+        StrokeAlign::Outside => -stroke.width / 2.0,
+    };
+    let centre = match shape.kind {
+        ShapeKind::Path => {
+            let edge = outline::shape_outline(shape, placement);
+            if inset != 0.0 && stroke.dash_pattern().is_none() {
+                if let Some(band) = path_band(&edge, stroke, tolerance) {
+                    return band;
+                }
+            }
+            edge
+        }
+        _ => outline::inset_outline(shape, placement, inset),
+    };
+    kurbo::stroke(centre.iter(), &style(stroke), &StrokeOpts::default(), tolerance)
+}
+
+/// The band `stroke` covers inside or outside `edge`, a closed path of one
+/// piece, or None if it isn't one.
+fn path_band(edge: &BezPath, stroke: &Stroke, tolerance: f64) -> Option<BezPath> {
+    let elements = edge.elements();
+    let pieces = elements.iter().filter(|el| matches!(el, PathEl::MoveTo(_))).count();
+    if pieces != 1 || elements.last() != Some(&PathEl::ClosePath) {
+        return None;
+    }
+    let mut wide = style(stroke);
+    wide.width *= 2.0;
+    let mut sides = split(&kurbo::stroke(edge.iter(), &wide, &StrokeOpts::default(), tolerance));
+    sides.sort_by(|a, b| a.area().abs().total_cmp(&b.area().abs()));
+    let side = match stroke.align {
+        StrokeAlign::Inside => sides.first()?,
+        StrokeAlign::Outside => sides.last()?,
+        StrokeAlign::Center => return None,
+    };
+    // Wound against the edge, the far side's inside is left out. Offsetting
+    // leaves small loops at corners, but they lie within the band.
+    let side = if (side.area() > 0.0) == (edge.area() > 0.0) {
+        side.reverse_subpaths()
+    } else {
+        side.clone()
+    };
+    let mut band = edge.clone();
+    band.extend(side);
+    Some(band)
+}
+
+/// `path`'s subpaths, each on its own.
+fn split(path: &BezPath) -> Vec<BezPath> {
+    let mut pieces: Vec<BezPath> = Vec::new();
+    for element in path.elements() {
+        match (element, pieces.last_mut()) {
+            (PathEl::MoveTo(_), _) | (_, None) => pieces.push(BezPath::from_vec(vec![*element])),
+            (_, Some(piece)) => piece.push(*element),
+        }
+    }
+    pieces
+}
+
+fn style(stroke: &Stroke) -> kurbo::Stroke {
+    let cap = match stroke.cap {
+        StrokeCap::Butt => Cap::Butt,
+        StrokeCap::Round => Cap::Round,
+        StrokeCap::Square => Cap::Square,
+    };
+    let join = match stroke.join {
+        StrokeJoin::Miter => Join::Miter,
+        StrokeJoin::Round => Join::Round,
+        StrokeJoin::Bevel => Join::Bevel,
+    };
+    let style = kurbo::Stroke::new(stroke.width as f64).with_caps(cap).with_join(join);
+    match stroke.dash_pattern() {
+        Some(dash) => style.with_dashes(0.0, dash.iter().map(|&length| length as f64)),
+        None => style,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::Vec2;
+    use kurbo::Rect;
+
+    const TOLERANCE: f64 = 0.1;
+
+    fn square() -> BezPath {
+        Rect::new(0.0, 0.0, 100.0, 100.0).to_path(TOLERANCE)
+    }
+
+    fn stroke(align: StrokeAlign) -> Stroke {
+        Stroke::new(gpui::black(), 10.0).with_align(align)
+    }
+
+    fn close(a: Rect, b: Rect) -> bool {
+        [a.x0 - b.x0, a.y0 - b.y0, a.x1 - b.x1, a.y1 - b.y1].iter().all(|d| d.abs() < 1e-6)
+    }
+
+    #[test]
+    fn path_bands_lie_on_their_side_of_the_edge() {
+        let inside = path_band(&square(), &stroke(StrokeAlign::Inside), TOLERANCE).unwrap();
+        assert!(close(inside.bounding_box(), square().bounding_box()));
+
+        let outside = path_band(&square(), &stroke(StrokeAlign::Outside), TOLERANCE).unwrap();
+        assert!(close(outside.bounding_box(), Rect::new(-10.0, -10.0, 110.0, 110.0)));
+
+        let mut open = BezPath::new();
+        open.move_to((0.0, 0.0));
+        open.line_to((100.0, 0.0));
+        assert!(path_band(&open, &stroke(StrokeAlign::Inside), TOLERANCE).is_none());
+    }
+
+    #[test]
+    fn box_strokes_follow_their_alignment() {
+        let shape = Shape::rectangle(Vec2::ZERO, Vec2::new(100.0, 100.0));
+        let placement = shape.world_placement(std::slice::from_ref(&shape));
+        let area = |align| stroke_area(&stroke(align), &shape, &placement, TOLERANCE);
+
+        assert!(close(area(StrokeAlign::Inside).bounding_box(), square().bounding_box()));
+        let center = area(StrokeAlign::Center).bounding_box();
+        assert!(close(center, Rect::new(-5.0, -5.0, 105.0, 105.0)));
+        let outside = area(StrokeAlign::Outside).bounding_box();
+        assert!(close(outside, Rect::new(-10.0, -10.0, 110.0, 110.0)));
+    }
+}
@@ -0,0 +1,248 @@
+//! Layout and painting for text shapes.
+//!
+//! gpui shapes glyphs but has no letter spacing, and its wrapping can't
+//! account for it. Each paragraph is shaped as one unwrapped line instead,
+//! and spacing and line breaks are applied here. Layout is in canvas units;
+//! painting scales it by the zoom.
+
+use gpui::{
+    font, point, px, FontId, FontWeight, GlyphId, Hsla, Pixels, Point, TextRun, Window, WindowTextSystem,
+};
+use node::{CanvasSize, Text, TextAlign};
+use std::ops::Range;
+
+#[derive(Clone, Debug)]
+struct PlacedGlyph {
+    font_id: FontId,
+    id: GlyphId,
+    /// Offset from the start of the line.
+    x: f32,
+    is_emoji: bool,
+}
+
+#[derive(Clone, Debug, Default)]
+struct TextLine {
+    glyphs: Vec<PlacedGlyph>,
+    width: f32,
+}
+
+/// Text broken into lines and positioned within its box.
+#[derive(Clone, Debug)]
+pub struct TextLayout {
+    lines: Vec<TextLine>,
+    font_size: f32,
+    line_height: f32,
+    /// Offset of each line's baseline from the top of its line box.
+    baseline: f32,
+    box_width: f32,
+    align: TextAlign,
+}
+
+impl TextLayout {
+    /// Lay out `text` in a box `box_width` wide, wrapping lines if its
+    /// sizing mode calls for it.
+    pub fn new(text: &Text, box_width: f32, text_system: &WindowTextSystem) -> Self {
+        let font_size = px(text.font_size);
+        let mut run_font = font(text.font_family.clone());
+        run_font.weight = FontWeight(text.font_weight as f32);
+        let font_id = text_system.resolve_font(&run_font);
+
+        let ascent: f32 = text_system.ascent(font_id, font_size).into();
+        let descent: f32 = text_system.descent(font_id, font_size).into();
+        let line_height = text.line_height_px();
+        // Center the font's extent in the line box, as CSS does
+        let baseline = (line_height - (ascent + descent)) / 2.0 + ascent;
+
+        let wrap_width = text.wrap_width(box_width);
+        let mut lines = Vec::new();
+        for paragraph in text.content.split('\n') {
+            let run = TextRun {
+                len: paragraph.len(),
+                font: run_font.clone(),
+                color: gpui::black(),
+                background_color: None,
+                underline: None,
+                strikethrough: None,
+            };
+            let shaped = text_system.layout_line(paragraph, font_size, &[run], None);
+
+            let glyphs: Vec<(FontId, &gpui::ShapedGlyph)> = shaped
+                .runs
+                .iter()
+                .flat_map(|run| run.glyphs.iter().map(move |glyph| (run.font_id, glyph)))
+                .collect();
+            let line_width: f32 = shaped.width.into();
+            let advances: Vec<f32> = glyphs
+                .iter()
+                .enumerate()
+                .map(|(i, (_, glyph))| {
+                    let next_x: f32 = glyphs
+                        .get(i + 1)
+                        .map(|(_, next)| next.position.x.into())
+                        .unwrap_or(line_width);
+                    let x: f32 = glyph.position.x.into();
+                    next_x - x + text.letter_spacing
+                })
+                .collect();
+            let whitespace: Vec<bool> = glyphs
+                .iter()
+                .map(|(_, glyph)| {
+                    paragraph[glyph.index..]
+                        .chars()
+                        .next()
+                        .is_some_and(char::is_whitespace)
+                })
+                .collect();
+
+            for range in break_lines(&advances, &whitespace, wrap_width) {
+                let mut line = TextLine::default();
+                for i in range {
+                    let (font_id, glyph) = glyphs[i];
+                    line.glyphs.push(PlacedGlyph {
+                        font_id,
+                        id: glyph.id,
+                        x: line.width,
+                        is_emoji: glyph.is_emoji,
+                    });
+                    line.width += advances[i];
+                }
+                lines.push(line);
+            }
+        }
+
+        Self {
+            lines,
+            font_size: text.font_size,
+            line_height,
+            baseline,
+            box_width,
+            align: text.align,
+        }
+    }
+
+    /// The size the laid out text covers.
+    pub fn size(&self) -> CanvasSize {
+        let width = self.lines.iter().map(|line| line.width).fold(0.0, f32::max);
+        CanvasSize::new(width, self.lines.len() as f32 * self.line_height)
+    }
+
+    /// Paint the text with its box's top-left corner at `origin` on screen.
+    pub fn paint(&self, origin: Point<Pixels>, zoom: f32, color: Hsla, window: &mut Window) {
+        let font_size = px(self.font_size * zoom);
+        for (row, line) in self.lines.iter().enumerate() {
+            let indent = match self.align {
+                TextAlign::Left => 0.0,
+                TextAlign::Center => (self.box_width - line.width) / 2.0,
+                TextAlign::Right => self.box_width - line.width,
+            };
+            let baseline = row as f32 * self.line_height + self.baseline;
+            for glyph in &line.glyphs {
+                let glyph_origin = point(
+                    origin.x + px((indent + glyph.x) * zoom),
+                    origin.y + px(baseline * zoom),
+                );
+                // A glyph missing from the atlas is skipped, not fatal
+                let _ = if glyph.is_emoji {
+                    window.paint_emoji(glyph_origin, glyph.font_id, glyph.id, font_size)
+                } else {
+                    window.paint_glyph(glyph_origin, glyph.font_id, glyph.id, font_size, color)
+                };
+            }
+        }
+    }
+}
+
+/// Break a paragraph's glyphs into lines no wider than `wrap_width`.
+///
+/// `advances` are glyph advances including letter spacing. Lines break
+/// after whitespace where possible and between any two glyphs otherwise.
+/// The whitespace a line breaks at is dropped, so it doesn't count toward
+/// the line's width. Always returns at least one line.
+fn break_lines(advances: &[f32], whitespace: &[bool], wrap_width: Option<f32>) -> Vec<Range<usize>> {
+    let mut lines = Vec::new();
+    let mut start = 0;
+    let mut width = 0.0;
+    let mut last_break = None;
+
+    for i in 0..advances.len() {
+        if whitespace[i] {
+            width += advances[i];
+            last_break = Some(i + 1);
+            continue;
+        }
+        if let Some(wrap_width) = wrap_width {
+            if i > start && width + advances[i] > wrap_width {
+                let next_start = match last_break {
+                    Some(b) if b > start => b,
+                    _ => i,
+                };
+                let mut end = next_start;
+                while end > start && whitespace[end - 1] {
+                    end -= 1;
+                }
+                lines.push(start..end);
+                start = next_start;
+                width = advances[start..i].iter().sum();
+                last_break = None;
+            }
+        }
+        width += advances[i];
+    }
+
+    lines.push(start..advances.len());
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn glyphs(text: &str) -> (Vec<f32>, Vec<bool>) {
+        let advances = text.chars().map(|_| 10.0).collect();
+        let whitespace = text.chars().map(char::is_whitespace).collect();
+        (advances, whitespace)
+    }
+
+    // === Line breaking ===
+
+    #[test]
+    fn unwrapped_text_is_one_line() {
+        let (advances, whitespace) = glyphs("hello world");
+        assert_eq!(break_lines(&advances, &whitespace, None), vec![0..11]);
+    }
+
+    #[test]
+    fn empty_paragraph_is_one_empty_line() {
+        assert_eq!(break_lines(&[], &[], Some(100.0)), vec![0..0]);
+    }
+
+    #[test]
+    fn wraps_after_whitespace_and_drops_it() {
+        let (advances, whitespace) = glyphs("hello world");
+        assert_eq!(break_lines(&advances, &whitespace, Some(80.0)), vec![0..5, 6..11]);
+    }
+
+    #[test]
+    fn line_may_fill_wrap_width_exactly() {
+        let (advances, whitespace) = glyphs("hello world");
+        assert_eq!(break_lines(&advances, &whitespace, Some(110.0)), vec![0..11]);
+    }
+
+    #[test]
+    fn long_word_breaks_between_glyphs() {
+        let (advances, whitespace) = glyphs("abcdefgh");
+        assert_eq!(break_lines(&advances, &whitespace, Some(30.0)), vec![0..3, 3..6, 6..8]);
+    }
+
+    #[test]
+    fn every_line_keeps_at_least_one_glyph() {
+        let (advances, whitespace) = glyphs("abc");
+        assert_eq!(break_lines(&advances, &whitespace, Some(5.0)), vec![0..1, 1..2, 2..3]);
+    }
+
+    #[test]
+    fn wraps_at_the_last_break_opportunity() {
+        let (advances, whitespace) = glyphs("a b c d");
+        assert_eq!(break_lines(&advances, &whitespace, Some(40.0)), vec![0..3, 4..7]);
+    }
+}
@@ -1,9 +1,21 @@
 use glam::Vec2;
 use gpui::{Bounds, Point, Size};
 use node::{CanvasPoint, CanvasSize, ScreenPoint};
+use std::time::{Duration, Instant};
+
+/// Zoom limits: 2% to 6400%.
+pub const MIN_ZOOM: f32 = 0.02;
+pub const MAX_ZOOM: f32 = 64.0;
+
+/// Zooms that zooming in and out step between.
+const ZOOM_LEVELS: [f32; 13] =
+    [0.02, 0.05, 0.1, 0.25, 0.5, 1.0, 2.0, 4.0, 8.0, 16.0, 32.0, 48.0, 64.0];
+
+/// How long an animated change of view takes.
+const ANIMATION_DURATION: Duration = Duration::from_millis(250);
 
 /// Camera/viewport state for the canvas.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Viewport {
     /// Pan offset in canvas coordinates
     pub offset: Vec2,
@@ -71,8 +83,13 @@ impl Viewport {
 
     /// Zoom the viewport, keeping a screen point fixed.
     pub fn zoom_at(&mut self, screen_point: Point<f32>, factor: f32) {
+        self.set_zoom(self.zoom * factor, screen_point);
+    }
+
+    /// Set the zoom, within the limits, keeping a screen point fixed.
+    pub fn set_zoom(&mut self, zoom: f32, screen_point: Point<f32>) {
         let old_zoom = self.zoom;
-        self.zoom = (self.zoom * factor).clamp(0.1, 10.0);
+        self.zoom = clamp_zoom(zoom);
 
         // Adjust offset to keep the point under cursor fixed
         if self.zoom != old_zoom {
@@ -86,4 +103,167 @@ impl Viewport {
         self.offset = Vec2::ZERO;
         self.zoom = 1.0;
     }
+
+    /// The view that fits the box from `min` to `max` in `area` of the
+    /// screen, `padding` in from its edges, and centers it there.
+    pub fn framing(min: CanvasPoint, max: CanvasPoint, area: Bounds<f32>, padding: f32) -> Self {
+        // Boxes with no width or height, like a straight line, fit by the
+        // side they have
+        let content = (max.0 - min.0).max(Vec2::splat(f32::EPSILON));
+        let room = Vec2::new(area.size.width, area.size.height) - Vec2::splat(2.0 * padding);
+        let fit = room.max(Vec2::ONE) / content;
+        let zoom = clamp_zoom(fit.x.min(fit.y));
+        Self::centered_on((min.0 + max.0) / 2.0, zoom, area)
+    }
+
+    /// This view panned just far enough to show the box from `min` to
+    /// `max` in `area` of the screen, `padding` in from its edges. A box
+    /// too big to show whole is shown from its top-left.
+    pub fn scrolled_to(&self, min: CanvasPoint, max: CanvasPoint, area: Bounds<f32>, padding: f32) -> Self {
+        let margin = Vec2::splat(padding / self.zoom);
+        let area_min = Vec2::new(area.origin.x, area.origin.y);
+        let area_max = area_min + Vec2::new(area.size.width, area.size.height);
+        let view_min = self.screen_to_canvas(ScreenPoint(area_min)).0 + margin;
+        let view_max = self.screen_to_canvas(ScreenPoint(area_max)).0 - margin;
+        let scroll = |min: f32, max: f32, view_min: f32, view_max: f32| {
+            if min < view_min || max - min > view_max - view_min {
+                view_min - min
+            } else if max > view_max {
+                view_max - max
+            } else {
+                0.0
+            }
+        };
+        let shift = Vec2::new(
+            scroll(min.x(), max.x(), view_min.x, view_max.x),
+            scroll(min.y(), max.y(), view_min.y, view_max.y),
+        );
+        Self { offset: self.offset + shift, zoom: self.zoom }
+    }
+
+    /// The view at zoom `zoom` with `center` in the middle of `area`.
+    fn centered_on(center: Vec2, zoom: f32, area: Bounds<f32>) -> Self {
+        let area_center = area.center();
+        Self {
+            offset: Vec2::new(area_center.x, area_center.y) / zoom - center,
+            zoom,
+        }
+    }
+
+    /// The view `t` of the way from `self` to `to`, both seen in `area`.
+    ///
+    /// Zoom changes by equal factors rather than equal amounts, so zooming
+    /// from 10% to 1000% doesn't spend most of the way above 500%, and the
+    /// point in the middle of the area moves in a straight line.
+    pub fn interpolate(&self, to: &Viewport, t: f32, area: Bounds<f32>) -> Self {
+        let area_center = area.center();
+        let screen_center = ScreenPoint::new(area_center.x, area_center.y);
+        let from_center = self.screen_to_canvas(screen_center).0;
+        let to_center = to.screen_to_canvas(screen_center).0;
+        let zoom = self.zoom * (to.zoom / self.zoom).powf(t);
+        Self::centered_on(from_center.lerp(to_center, t), zoom, area)
+    }
+}
+
+/// `zoom` within the zoom limits.
+pub fn clamp_zoom(zoom: f32) -> f32 {
+    zoom.clamp(MIN_ZOOM, MAX_ZOOM)
+}
+
+/// The next zoom level up from `zoom`, or down when `zoom_in` is false.
+pub fn step_zoom(zoom: f32, zoom_in: bool) -> f32 {
+    // Zooms within a hair of a level count as at it
+    let next = if zoom_in {
+        ZOOM_LEVELS.iter().find(|level| **level > zoom * 1.01)
+    } else {
+        ZOOM_LEVELS.iter().rev().find(|level| **level < zoom / 1.01)
+    };
+    next.copied().unwrap_or(clamp_zoom(zoom))
+}
+
+/// An animated change of view, eased to a stop.
+#[derive(Clone, Debug)]
+pub struct ViewAnimation {
+    from: Viewport,
+    to: Viewport,
+    start: Instant,
+}
+
+impl ViewAnimation {
+    pub fn new(from: Viewport, to: Viewport) -> Self {
+        Self { from, to, start: Instant::now() }
+    }
+
+    /// Where the view ends up.
+    pub fn target(&self) -> &Viewport {
+        &self.to
+    }
+
+    /// The view at `now` in `area`, and whether the animation is done.
+    pub fn at(&self, now: Instant, area: Bounds<f32>) -> (Viewport, bool) {
+        let progress = now.saturating_duration_since(self.start).as_secs_f32()
+            / ANIMATION_DURATION.as_secs_f32();
+        if progress >= 1.0 {
+            return (self.to.clone(), true);
+        }
+        let eased = 1.0 - (1.0 - progress).powi(3);
+        (self.from.interpolate(&self.to, eased, area), false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{point, size};
+
+    fn area() -> Bounds<f32> {
+        Bounds { origin: point(20.0, 20.0), size: size(400.0, 300.0) }
+    }
+
+    fn close(a: Vec2, b: Vec2) -> bool {
+        (a - b).length() < 1e-3
+    }
+
+    #[test]
+    fn framing_fits_the_tighter_side_and_centers() {
+        let view = Viewport::framing(CanvasPoint::new(0.0, 0.0), CanvasPoint::new(100.0, 50.0), area(), 20.0);
+        // 360 across for 100 wide fits tighter than 260 down for 50 tall
+        assert_eq!(view.zoom, 3.6);
+        let center = view.canvas_to_screen(CanvasPoint::new(50.0, 25.0)).0;
+        assert!(close(center, Vec2::new(220.0, 170.0)));
+        let tiny = Viewport::framing(CanvasPoint::new(5.0, 5.0), CanvasPoint::new(5.0, 5.0), area(), 20.0);
+        assert_eq!(tiny.zoom, MAX_ZOOM);
+    }
+
+    #[test]
+    fn scrolling_moves_only_as_far_as_needed() {
+        let view = Viewport::new();
+        // Already in view
+        let shown = view.scrolled_to(CanvasPoint::new(50.0, 50.0), CanvasPoint::new(80.0, 80.0), area(), 10.0);
+        assert_eq!(shown, view);
+        // Off to the right and above
+        let moved = view.scrolled_to(CanvasPoint::new(500.0, -40.0), CanvasPoint::new(520.0, -20.0), area(), 10.0);
+        assert!(close(moved.canvas_to_screen(CanvasPoint::new(520.0, -40.0)).0, Vec2::new(410.0, 30.0)));
+        assert_eq!(moved.zoom, 1.0);
+    }
+
+    #[test]
+    fn interpolation_zooms_geometrically() {
+        let from = Viewport::new();
+        let to = Viewport { offset: Vec2::new(-100.0, 0.0), zoom: 4.0 };
+        let halfway = from.interpolate(&to, 0.5, area());
+        assert!((halfway.zoom - 2.0).abs() < 1e-5);
+        assert_eq!(from.interpolate(&to, 0.0, area()), from);
+        assert!(close(from.interpolate(&to, 1.0, area()).offset, to.offset));
+    }
+
+    #[test]
+    fn zoom_steps_between_levels() {
+        assert_eq!(step_zoom(1.0, true), 2.0);
+        assert_eq!(step_zoom(1.0, false), 0.5);
+        assert_eq!(step_zoom(0.7, true), 1.0);
+        assert_eq!(step_zoom(0.7, false), 0.5);
+        assert_eq!(step_zoom(MAX_ZOOM, true), MAX_ZOOM);
+        assert_eq!(step_zoom(MIN_ZOOM, false), MIN_ZOOM);
+    }
 }
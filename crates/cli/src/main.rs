@@ -1,13 +1,16 @@
 //! Luna CLI - Command-line interface for interacting with Luna instances.
 //!
-//! Connect to a running Luna process and send commands/queries via JSON.
+//! Connect to a running Luna process and send commands/queries via JSON,
+//! or export saved projects without one.
 
 use anyhow::{Context, Result};
 use api::{Command, Query};
 use clap::{Parser, Subcommand};
+use interchange::{export_png, export_svg, Document, Project};
+use node::ShapeId;
 use std::io::{BufRead, BufReader, Write};
 use std::os::unix::net::UnixStream;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Luna CLI - interact with running Luna instances
 #[derive(Parser)]
@@ -54,6 +57,54 @@ enum Commands {
 
     /// Get shape count
     Count,
+
+    /// Undo the last change
+    Undo,
+
+    /// Redo the last undone change
+    Redo,
+
+    /// Export a saved project to SVG (no running instance needed)
+    Export {
+        /// Path to the .luna project folder
+        project: PathBuf,
+
+        /// Write the SVG to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Page to export (default: the first page)
+        #[arg(long)]
+        page: Option<String>,
+
+        /// Export only this shape or frame, by full or short ID.
+        /// Repeat for several; by default the whole page is exported.
+        #[arg(long = "shape", value_name = "ID")]
+        shapes: Vec<String>,
+    },
+
+    /// Render a saved project to PNG (no running instance or GPU needed)
+    Render {
+        /// Path to the .luna project folder
+        project: PathBuf,
+
+        /// PNG file to write
+        #[arg(short, long)]
+        output: PathBuf,
+
+        /// Pixels per canvas unit
+        #[arg(long, default_value_t = 1.0)]
+        scale: f32,
+
+        /// Page to render (default: the first page)
+        #[arg(long)]
+        page: Option<String>,
+
+        /// Render only this shape or frame, by full or short ID.
+        /// Repeat for several; by default the whole page is rendered.
+        #[arg(long = "shape", value_name = "ID")]
+        shapes: Vec<String>,
+    },
 }
 
 fn main() -> Result<()> {
@@ -67,6 +118,21 @@ fn main() -> Result<()> {
         Commands::Shapes => query_shapes(cli.socket),
         Commands::Selection => query_selection(cli.socket),
         Commands::Count => query_count(cli.socket),
+        Commands::Undo => run_command(cli.socket, Command::Undo),
+        Commands::Redo => run_command(cli.socket, Command::Redo),
+        Commands::Export {
+            project,
+            output,
+            page,
+            shapes,
+        } => export_project(&project, output, page.as_deref(), &shapes),
+        Commands::Render {
+            project,
+            output,
+            scale,
+            page,
+            shapes,
+        } => render_project(&project, &output, scale, page.as_deref(), &shapes),
     }
 }
 
@@ -167,6 +233,14 @@ fn connect_interactive(socket: Option<PathBuf>, pid: Option<u32>) -> Result<()>
                 let query = Query::GetShapeCount;
                 send_to_stream(&mut writer, &mut reader, &serde_json::to_string(&query)?)?;
             }
+            "undo" => {
+                let command = Command::Undo;
+                send_to_stream(&mut writer, &mut reader, &serde_json::to_string(&command)?)?;
+            }
+            "redo" => {
+                let command = Command::Redo;
+                send_to_stream(&mut writer, &mut reader, &serde_json::to_string(&command)?)?;
+            }
             _ => {
                 // Try to parse as command or query
                 send_to_stream(&mut writer, &mut reader, input)?;
@@ -185,6 +259,8 @@ fn print_help() {
     println!("  shapes      - Get all shapes on canvas");
     println!("  selection   - Get current selection");
     println!("  count       - Get shape count");
+    println!("  undo        - Undo the last change");
+    println!("  redo        - Redo the last undone change");
     println!("  help, ?     - Show this help");
     println!("  quit, exit  - Exit interactive mode");
     println!();
@@ -252,6 +328,12 @@ fn send_query(socket: Option<PathBuf>, json: &str) -> Result<()> {
     Ok(())
 }
 
+/// Send a built-in command.
+fn run_command(socket: Option<PathBuf>, command: Command) -> Result<()> {
+    let json = serde_json::to_string(&command)?;
+    send_command(socket, &json)
+}
+
 /// Query all shapes.
 fn query_shapes(socket: Option<PathBuf>) -> Result<()> {
     let query = Query::GetAllShapes;
@@ -273,6 +355,62 @@ fn query_count(socket: Option<PathBuf>) -> Result<()> {
     send_query(socket, &json)
 }
 
+/// Export a page of a saved project, or some of its shapes, to SVG.
+fn export_project(path: &Path, output: Option<PathBuf>, page: Option<&str>, shapes: &[String]) -> Result<()> {
+    let (doc, ids) = load_for_export(path, page, shapes)?;
+    let svg = export_svg(&doc.shapes, &ids);
+    match output {
+        Some(output) => std::fs::write(&output, svg)
+            .with_context(|| format!("Failed to write {}", output.display()))?,
+        None => print!("{}", svg),
+    }
+    Ok(())
+}
+
+/// Render a page of a saved project, or some of its shapes, to PNG.
+fn render_project(path: &Path, output: &Path, scale: f32, page: Option<&str>, shapes: &[String]) -> Result<()> {
+    let (doc, ids) = load_for_export(path, page, shapes)?;
+    let png = export_png(&doc.shapes, &ids, scale)?;
+    std::fs::write(output, png).with_context(|| format!("Failed to write {}", output.display()))?;
+    Ok(())
+}
+
+/// Load a project page along with the shapes to export from it: those
+/// named by `shapes`, or every top-level shape if none are.
+fn load_for_export(path: &Path, page: Option<&str>, shapes: &[String]) -> Result<(Document, Vec<ShapeId>)> {
+    let project = Project::load(path).with_context(|| format!("Failed to load {}", path.display()))?;
+    let doc = match page {
+        Some(name) => project
+            .pages
+            .into_iter()
+            .find(|(page_name, _)| page_name == name)
+            .map(|(_, doc)| doc)
+            .with_context(|| format!("No page named '{}'", name))?,
+        None => project
+            .pages
+            .into_iter()
+            .next()
+            .map(|(_, doc)| doc)
+            .context("Project has no pages")?,
+    };
+
+    let ids = if shapes.is_empty() {
+        doc.shapes.iter().filter(|s| s.parent.is_none()).map(|s| s.id).collect()
+    } else {
+        shapes
+            .iter()
+            .map(|wanted| {
+                doc.shapes
+                    .iter()
+                    .find(|s| s.id.to_uuid_string() == *wanted || s.id.to_string() == *wanted)
+                    .map(|s| s.id)
+                    .with_context(|| format!("No shape with ID '{}'", wanted))
+            })
+            .collect::<Result<Vec<_>>>()?
+    };
+    Ok((doc, ids))
+}
+
 /// Resolve which socket to connect to.
 fn resolve_socket(explicit: Option<PathBuf>, pid: Option<u32>) -> Result<PathBuf> {
     // If explicit socket provided, use it
@@ -14,23 +14,68 @@
 //!
 //! ```kdl
 //! document version="0.1" {
-//!   rect "abc12345" x=100 y=100 width=150 height=100 {
+//!   rect "abc12345" name="Card" x=100 y=100 width=150 height=100 {
 //!     fill h=0.5 s=0.8 l=0.5 a=1.0
 //!     stroke width=2 h=0 s=0 l=0 a=1
 //!     radius 8
 //!   }
 //!   ellipse "def67890" x=300 y=150 width=120 height=120 {
+//!     fill gradient="radial" start-x=0.5 start-y=0.5 end-x=1.0 end-y=0.5 {
+//!       stop offset=0.0 h=0.6 s=0.8 l=0.6 a=1.0
+//!       stop offset=1.0 h=0.6 s=0.8 l=0.3 a=1.0
+//!     }
 //!     stroke width=2 h=0 s=0 l=0 a=1
 //!   }
+//!   frame "1c2d3e4f" x=500 y=100 width=200 height=120 {
+//!     layout direction="column" gap=8 main-axis="center" padding-top=16 padding-bottom=16
+//!     rect "5a6b7c8d" x=0 y=16 width=200 height=40 {
+//!       sizing width="fill" height="fixed"
+//!     }
+//!   }
+//!   text "0a1b2c3d" x=100 y=300 width=120 height=19.2 {
+//!     fill h=0 s=0 l=0 a=1
+//!     content "Hello" align="left" sizing="auto-width"
+//!     font "Inter" size=16.0 weight=400 line-height=1.2 letter-spacing=0.0
+//!   }
+//!   path "4e5f6a7b" x=300 y=300 width=100 height=50 closed=#true {
+//!     stroke width=2 h=0 s=0 l=0 a=1
+//!     vertex x=0 y=50
+//!     vertex x=50 y=0 in-x=-30 in-y=0 out-x=30 out-y=0 mode="mirrored"
+//!     vertex x=100 y=50
+//!   }
+//!   frame "6c7d8e9f" x=0 y=500 width=100 height=40 {
+//!     component
+//!     text "7d8e9fa0" x=8 y=8 width=60 height=19.2 { ... }
+//!   }
+//!   frame "8e9fa0b1" x=140 y=500 width=100 height=40 {
+//!     instance "6c7d8e9f"
+//!     text "9fa0b1c2" x=8 y=8 width=60 height=19.2 {
+//!       copy "7d8e9fa0" override-text=#true
+//!       ...
+//!     }
+//!   }
+//!   guide axis="vertical" position=240.0
 //! }
 //! ```
 
+mod png;
 mod project;
+mod svg;
+mod svg_import;
 
+pub use png::{export_png, render_pixmap};
 pub use project::Project;
+pub use svg::export_svg;
+pub use svg_import::{import_svg, SvgImport};
 
 use kdl::{KdlDocument, KdlEntry, KdlNode};
-use node::{Fill, Shape, ShapeId, ShapeKind, Stroke};
+use glam::Vec2;
+use node::{
+    BlendMode, ChildLayout, ComponentLink, Constraint, CrossAxisAlignment, Fill, FrameLayout, Gradient, GradientKind, GradientStop, Guide, GuideAxis, HandleMode,
+    LayoutDirection, LineAlignment, MainAxisAlignment, Overrides, PathData, PathVertex, Shape, ShapeId,
+    Paint, ShapeKind, SizingMode, Stroke, StrokeAlign, StrokeCap, StrokeJoin, Text, TextAlign,
+    TextSizing,
+};
 
 pub const FORMAT_VERSION: &str = "0.1";
 
@@ -41,6 +86,7 @@ pub enum InterchangeError {
     InvalidStructure(String),
     MissingField(String),
     InvalidValue(String),
+    Render(String),
 }
 
 impl std::fmt::Display for InterchangeError {
@@ -50,6 +96,7 @@ impl std::fmt::Display for InterchangeError {
             Self::InvalidStructure(msg) => write!(f, "Invalid structure: {}", msg),
             Self::MissingField(msg) => write!(f, "Missing field: {}", msg),
             Self::InvalidValue(msg) => write!(f, "Invalid value: {}", msg),
+            Self::Render(msg) => write!(f, "Render error: {}", msg),
         }
     }
 }
@@ -61,6 +108,7 @@ impl std::error::Error for InterchangeError {}
 pub struct Document {
     pub version: String,
     pub shapes: Vec<Shape>,
+    pub guides: Vec<Guide>,
 }
 
 impl Document {
@@ -68,6 +116,7 @@ impl Document {
         Self {
             version: FORMAT_VERSION.to_string(),
             shapes,
+            guides: Vec::new(),
         }
     }
 
@@ -84,6 +133,12 @@ impl Document {
         for shape in self.shapes.iter().filter(|s| s.parent.is_none()) {
             children.nodes_mut().push(shape_to_kdl(shape, &self.shapes));
         }
+        for guide in &self.guides {
+            let mut node = KdlNode::new("guide");
+            node.push(KdlEntry::new_prop("axis", guide_axis_name(guide.axis)));
+            node.push(KdlEntry::new_prop("position", guide.position as f64));
+            children.nodes_mut().push(node);
+        }
 
         doc.nodes_mut().push(doc_node);
         doc.to_string()
@@ -109,13 +164,24 @@ impl Document {
 
         // Parse shapes from children (recursively flattens nested frames)
         let mut shapes = Vec::new();
+        let mut guides = Vec::new();
         if let Some(children) = doc_node.children() {
             for node in children.nodes() {
-                parse_shape_recursive(node, None, &mut shapes)?;
+                if node.name().value() == "guide" {
+                    guides.push(parse_guide(node)?);
+                } else {
+                    parse_shape_recursive(node, None, &mut shapes)?;
+                }
             }
         }
 
-        Ok(Self { version, shapes })
+        Ok(Self { version, shapes, guides })
+    }
+
+    /// Export the whole document as an SVG string.
+    pub fn to_svg(&self) -> String {
+        let roots: Vec<ShapeId> = self.shapes.iter().filter(|s| s.parent.is_none()).map(|s| s.id).collect();
+        export_svg(&self.shapes, &roots)
     }
 }
 
@@ -125,46 +191,76 @@ fn shape_to_kdl(shape: &Shape, all_shapes: &[Shape]) -> KdlNode {
         ShapeKind::Rectangle => "rect",
         ShapeKind::Ellipse => "ellipse",
         ShapeKind::Frame => "frame",
+        ShapeKind::Text => "text",
+        ShapeKind::Path => "path",
     };
 
     let mut node = KdlNode::new(type_name);
 
     // ID as first argument (full UUID for round-trip fidelity)
     node.push(KdlEntry::new(shape.id.to_uuid_string()));
+    if !shape.name.is_empty() {
+        node.push(KdlEntry::new_prop("name", shape.name.clone()));
+    }
+    if !shape.visible {
+        node.push(KdlEntry::new_prop("hidden", true));
+    }
+    if shape.locked {
+        node.push(KdlEntry::new_prop("locked", true));
+    }
+
+    // Position and size as properties. Shapes in a layout are written where
+    // the layout put them, so readers without a layout engine see the same
+    // picture; loading lays them out again anyway.
+    let position = shape.effective_position();
+    let size = shape.effective_size();
+    node.push(KdlEntry::new_prop("x", position.x() as f64));
+    node.push(KdlEntry::new_prop("y", position.y() as f64));
+    node.push(KdlEntry::new_prop("width", size.width() as f64));
+    node.push(KdlEntry::new_prop("height", size.height() as f64));
 
-    // Position and size as properties
-    node.push(KdlEntry::new_prop("x", shape.position.x() as f64));
-    node.push(KdlEntry::new_prop("y", shape.position.y() as f64));
-    node.push(KdlEntry::new_prop("width", shape.size.width() as f64));
-    node.push(KdlEntry::new_prop("height", shape.size.height() as f64));
+    // Transforms only when set, so unturned shapes stay terse
+    if shape.rotation != 0.0 {
+        node.push(KdlEntry::new_prop("rotation", shape.rotation as f64));
+    }
+    if shape.flip_x {
+        node.push(KdlEntry::new_prop("flip-x", true));
+    }
+    if shape.flip_y {
+        node.push(KdlEntry::new_prop("flip-y", true));
+    }
+    if shape.opacity != 1.0 {
+        node.push(KdlEntry::new_prop("opacity", shape.opacity as f64));
+    }
+    if shape.blend_mode != BlendMode::Normal {
+        node.push(KdlEntry::new_prop("blend", blend_mode_name(shape.blend_mode)));
+    }
 
     // Frame-specific: clip property
     if shape.kind == ShapeKind::Frame && shape.clip_children {
         node.push(KdlEntry::new_prop("clip", true));
     }
 
+    if shape.path.as_ref().is_some_and(|path| path.closed) {
+        node.push(KdlEntry::new_prop("closed", true));
+    }
+
     // Children block for styles and nested shapes
     let mut has_children = false;
     let children = node.children_mut().get_or_insert_with(KdlDocument::new);
 
-    if let Some(fill) = &shape.fill {
-        let mut fill_node = KdlNode::new("fill");
-        fill_node.push(KdlEntry::new_prop("h", fill.color.h as f64));
-        fill_node.push(KdlEntry::new_prop("s", fill.color.s as f64));
-        fill_node.push(KdlEntry::new_prop("l", fill.color.l as f64));
-        fill_node.push(KdlEntry::new_prop("a", fill.color.a as f64));
-        children.nodes_mut().push(fill_node);
+    if let Some(link) = &shape.component {
+        children.nodes_mut().push(component_to_kdl(link));
         has_children = true;
     }
 
-    if let Some(stroke) = &shape.stroke {
-        let mut stroke_node = KdlNode::new("stroke");
-        stroke_node.push(KdlEntry::new_prop("width", stroke.width as f64));
-        stroke_node.push(KdlEntry::new_prop("h", stroke.color.h as f64));
-        stroke_node.push(KdlEntry::new_prop("s", stroke.color.s as f64));
-        stroke_node.push(KdlEntry::new_prop("l", stroke.color.l as f64));
-        stroke_node.push(KdlEntry::new_prop("a", stroke.color.a as f64));
-        children.nodes_mut().push(stroke_node);
+    for fill in &shape.fills {
+        children.nodes_mut().push(fill_to_kdl(fill));
+        has_children = true;
+    }
+
+    for stroke in &shape.strokes {
+        children.nodes_mut().push(stroke_to_kdl(stroke, shape.kind));
         has_children = true;
     }
 
@@ -175,6 +271,40 @@ fn shape_to_kdl(shape: &Shape, all_shapes: &[Shape]) -> KdlNode {
         has_children = true;
     }
 
+    if let Some(layout) = &shape.layout {
+        children.nodes_mut().push(layout_to_kdl(layout));
+        has_children = true;
+    }
+
+    if shape.child_layout != ChildLayout::default() {
+        children.nodes_mut().push(sizing_to_kdl(&shape.child_layout));
+        has_children = true;
+    }
+
+    if let Some(text) = &shape.text {
+        let mut content_node = KdlNode::new("content");
+        content_node.push(KdlEntry::new(text.content.clone()));
+        content_node.push(KdlEntry::new_prop("align", text_align_name(text.align)));
+        content_node.push(KdlEntry::new_prop("sizing", text_sizing_name(text.sizing)));
+        children.nodes_mut().push(content_node);
+
+        let mut font_node = KdlNode::new("font");
+        font_node.push(KdlEntry::new(text.font_family.clone()));
+        font_node.push(KdlEntry::new_prop("size", text.font_size as f64));
+        font_node.push(KdlEntry::new_prop("weight", text.font_weight as i128));
+        font_node.push(KdlEntry::new_prop("line-height", text.line_height as f64));
+        font_node.push(KdlEntry::new_prop("letter-spacing", text.letter_spacing as f64));
+        children.nodes_mut().push(font_node);
+        has_children = true;
+    }
+
+    if let Some(path) = &shape.path {
+        for vertex in &path.vertices {
+            children.nodes_mut().push(vertex_to_kdl(vertex));
+            has_children = true;
+        }
+    }
+
     // Recursively serialize child shapes (for frames)
     for child_id in &shape.children {
         if let Some(child) = all_shapes.iter().find(|s| s.id == *child_id) {
@@ -202,6 +332,8 @@ fn parse_shape_recursive(
         "rect" => ShapeKind::Rectangle,
         "ellipse" => ShapeKind::Ellipse,
         "frame" => ShapeKind::Frame,
+        "text" => ShapeKind::Text,
+        "path" => ShapeKind::Path,
         other => {
             return Err(InterchangeError::InvalidValue(format!(
                 "Unknown shape type: {}",
@@ -237,30 +369,44 @@ fn parse_shape_recursive(
         node::CanvasSize::new(width, height),
     );
     shape.id = id;
+    if let Some(name) = node.get("name").and_then(|v| v.as_string()) {
+        shape.name = name.to_string();
+    }
+    shape.visible = !node.get("hidden").and_then(|v| v.as_bool()).unwrap_or(false);
+    shape.locked = node.get("locked").and_then(|v| v.as_bool()).unwrap_or(false);
     shape.parent = parent_id;
     shape.clip_children = clip_children;
+    shape.rotation = get_f32_prop(node, "rotation").unwrap_or(0.0);
+    shape.flip_x = node.get("flip-x").and_then(|v| v.as_bool()).unwrap_or(false);
+    shape.flip_y = node.get("flip-y").and_then(|v| v.as_bool()).unwrap_or(false);
+    shape.opacity = get_f32_prop(node, "opacity").unwrap_or(1.0).clamp(0.0, 1.0);
+    if let Some(name) = node.get("blend").and_then(|v| v.as_string()) {
+        shape.blend_mode = parse_blend_mode(name)?;
+    }
+    if kind == ShapeKind::Text {
+        shape.text = Some(Text::default());
+    }
+    if kind == ShapeKind::Path {
+        let closed = node.get("closed").and_then(|v| v.as_bool()).unwrap_or(false);
+        shape.path = Some(PathData::new(Vec::new(), closed));
+    }
 
     // Collect child shape IDs (we'll parse them after adding this shape)
     let mut child_ids = Vec::new();
 
-    // Parse children (fill, stroke, radius, and nested shapes)
+    // A shape has the fills and strokes written for it, in order
+    shape.fills.clear();
+    shape.strokes.clear();
+
+    // Parse children (fills, strokes, radius, and nested shapes)
     if let Some(children) = node.children() {
         for child in children.nodes() {
             match child.name().value() {
                 "fill" => {
-                    let h = get_f32_prop(child, "h").unwrap_or(0.0);
-                    let s = get_f32_prop(child, "s").unwrap_or(0.0);
-                    let l = get_f32_prop(child, "l").unwrap_or(0.0);
-                    let a = get_f32_prop(child, "a").unwrap_or(1.0);
-                    shape.fill = Some(Fill::new(gpui::Hsla { h, s, l, a }));
+                    shape.fills.push(parse_fill(child)?);
                 }
                 "stroke" => {
-                    let width = get_f32_prop(child, "width").unwrap_or(1.0);
-                    let h = get_f32_prop(child, "h").unwrap_or(0.0);
-                    let s = get_f32_prop(child, "s").unwrap_or(0.0);
-                    let l = get_f32_prop(child, "l").unwrap_or(0.0);
-                    let a = get_f32_prop(child, "a").unwrap_or(1.0);
-                    shape.stroke = Some(Stroke::new(gpui::Hsla { h, s, l, a }, width));
+                    shape.strokes.push(parse_stroke(child, kind)?);
                 }
                 "radius" => {
                     if let Some(entry) = child.entries().first() {
@@ -269,8 +415,56 @@ fn parse_shape_recursive(
                         }
                     }
                 }
-                // Nested shapes (rect, ellipse, frame)
-                "rect" | "ellipse" | "frame" => {
+                "content" => {
+                    if let Some(text) = shape.text.as_mut() {
+                        if let Some(content) = get_string_arg(child) {
+                            text.content = content.to_string();
+                        }
+                        if let Some(align) = child.get("align").and_then(|v| v.as_string()) {
+                            text.align = parse_text_align(align)?;
+                        }
+                        if let Some(sizing) = child.get("sizing").and_then(|v| v.as_string()) {
+                            text.sizing = parse_text_sizing(sizing)?;
+                        }
+                    }
+                }
+                "font" => {
+                    if let Some(text) = shape.text.as_mut() {
+                        if let Some(family) = get_string_arg(child) {
+                            text.font_family = family.to_string();
+                        }
+                        if let Some(size) = get_f32_prop(child, "size") {
+                            text.font_size = size;
+                        }
+                        if let Some(weight) = child.get("weight").and_then(|v| v.as_integer()) {
+                            text.font_weight = u16::try_from(weight).map_err(|_| {
+                                InterchangeError::InvalidValue(format!("Font weight: {}", weight))
+                            })?;
+                        }
+                        if let Some(line_height) = get_f32_prop(child, "line-height") {
+                            text.line_height = line_height;
+                        }
+                        if let Some(spacing) = get_f32_prop(child, "letter-spacing") {
+                            text.letter_spacing = spacing;
+                        }
+                    }
+                }
+                "component" | "instance" | "copy" => {
+                    shape.component = Some(parse_component(child)?);
+                }
+                "layout" => {
+                    shape.layout = Some(parse_layout(child)?);
+                }
+                "sizing" => {
+                    shape.child_layout = parse_sizing(child)?;
+                }
+                "vertex" => {
+                    if let Some(path) = shape.path.as_mut() {
+                        path.vertices.push(parse_vertex(child)?);
+                    }
+                }
+                // Nested shapes (rect, ellipse, frame, text, path)
+                "rect" | "ellipse" | "frame" | "text" | "path" => {
                     // We'll parse these after adding the parent shape
                     // Just note we have child nodes to process
                 }
@@ -286,7 +480,7 @@ fn parse_shape_recursive(
     if let Some(children) = node.children() {
         for child in children.nodes() {
             match child.name().value() {
-                "rect" | "ellipse" | "frame" => {
+                "rect" | "ellipse" | "frame" | "text" | "path" => {
                     let child_id = parse_shape_recursive(child, Some(id), shapes)?;
                     child_ids.push(child_id);
                 }
@@ -305,16 +499,691 @@ fn parse_shape_recursive(
     Ok(id)
 }
 
+/// A path vertex, in the shape's local space. Zero handles and the default
+/// mode are left out.
+fn vertex_to_kdl(vertex: &PathVertex) -> KdlNode {
+    let mut node = KdlNode::new("vertex");
+    node.push(KdlEntry::new_prop("x", vertex.point.x as f64));
+    node.push(KdlEntry::new_prop("y", vertex.point.y as f64));
+    if vertex.handle_in != Vec2::ZERO {
+        node.push(KdlEntry::new_prop("in-x", vertex.handle_in.x as f64));
+        node.push(KdlEntry::new_prop("in-y", vertex.handle_in.y as f64));
+    }
+    if vertex.handle_out != Vec2::ZERO {
+        node.push(KdlEntry::new_prop("out-x", vertex.handle_out.x as f64));
+        node.push(KdlEntry::new_prop("out-y", vertex.handle_out.y as f64));
+    }
+    if vertex.mode != HandleMode::Free {
+        node.push(KdlEntry::new_prop("mode", handle_mode_name(vertex.mode)));
+    }
+    node
+}
+
+fn parse_vertex(node: &KdlNode) -> Result<PathVertex, InterchangeError> {
+    let prop_pair = |x: &str, y: &str| {
+        Vec2::new(
+            get_f32_prop(node, x).unwrap_or(0.0),
+            get_f32_prop(node, y).unwrap_or(0.0),
+        )
+    };
+    let mode = match node.get("mode").and_then(|v| v.as_string()) {
+        Some(name) => parse_handle_mode(name)?,
+        None => HandleMode::Free,
+    };
+    Ok(PathVertex {
+        point: prop_pair("x", "y"),
+        handle_in: prop_pair("in-x", "in-y"),
+        handle_out: prop_pair("out-x", "out-y"),
+        mode,
+    })
+}
+
+/// A frame's autolayout. Everything but the direction is written only when
+/// it differs from the default, and wrapping settings only when wrapping.
+fn layout_to_kdl(layout: &FrameLayout) -> KdlNode {
+    let defaults = FrameLayout::default();
+    let mut node = KdlNode::new("layout");
+    node.push(KdlEntry::new_prop("direction", layout_direction_name(layout.direction)));
+    if layout.gap != 0.0 {
+        node.push(KdlEntry::new_prop("gap", layout.gap as f64));
+    }
+    if layout.main_axis_alignment != defaults.main_axis_alignment {
+        node.push(KdlEntry::new_prop("main-axis", main_axis_name(layout.main_axis_alignment)));
+    }
+    if layout.cross_axis_alignment != defaults.cross_axis_alignment {
+        node.push(KdlEntry::new_prop("cross-axis", cross_axis_name(layout.cross_axis_alignment)));
+    }
+    let padding = layout.padding;
+    for (name, value) in [
+        ("padding-top", padding.top),
+        ("padding-right", padding.right),
+        ("padding-bottom", padding.bottom),
+        ("padding-left", padding.left),
+    ] {
+        if value != 0.0 {
+            node.push(KdlEntry::new_prop(name, value as f64));
+        }
+    }
+    if layout.wrap {
+        node.push(KdlEntry::new_prop("wrap", true));
+        if layout.cross_gap != 0.0 {
+            node.push(KdlEntry::new_prop("cross-gap", layout.cross_gap as f64));
+        }
+        if layout.line_alignment != defaults.line_alignment {
+            node.push(KdlEntry::new_prop("line-alignment", line_alignment_name(layout.line_alignment)));
+        }
+    }
+    node
+}
+
+fn parse_layout(node: &KdlNode) -> Result<FrameLayout, InterchangeError> {
+    let mut layout = FrameLayout::default();
+    if let Some(name) = node.get("direction").and_then(|v| v.as_string()) {
+        layout.direction = parse_layout_direction(name)?;
+    }
+    if let Some(name) = node.get("main-axis").and_then(|v| v.as_string()) {
+        layout.main_axis_alignment = parse_main_axis(name)?;
+    }
+    if let Some(name) = node.get("cross-axis").and_then(|v| v.as_string()) {
+        layout.cross_axis_alignment = parse_cross_axis(name)?;
+    }
+    if let Some(name) = node.get("line-alignment").and_then(|v| v.as_string()) {
+        layout.line_alignment = parse_line_alignment(name)?;
+    }
+    layout.gap = get_f32_prop(node, "gap").unwrap_or(0.0);
+    layout.padding.top = get_f32_prop(node, "padding-top").unwrap_or(0.0);
+    layout.padding.right = get_f32_prop(node, "padding-right").unwrap_or(0.0);
+    layout.padding.bottom = get_f32_prop(node, "padding-bottom").unwrap_or(0.0);
+    layout.padding.left = get_f32_prop(node, "padding-left").unwrap_or(0.0);
+    layout.wrap = node.get("wrap").and_then(|v| v.as_bool()).unwrap_or(false);
+    layout.cross_gap = get_f32_prop(node, "cross-gap").unwrap_or(0.0);
+    Ok(layout)
+}
+
+/// A fill: a color, or a gradient with its handles and a `stop` child for
+/// each of its stops.
+fn fill_to_kdl(fill: &Fill) -> KdlNode {
+    let mut node = KdlNode::new("fill");
+    match &fill.paint {
+        Paint::Solid(color) => push_color(&mut node, *color),
+        Paint::Gradient(gradient) => push_gradient(&mut node, gradient),
+    }
+    push_layer(&mut node, fill.visible, fill.opacity);
+    node
+}
+
+fn push_gradient(node: &mut KdlNode, gradient: &Gradient) {
+    node.push(KdlEntry::new_prop("gradient", gradient_kind_name(gradient.kind)));
+    for (name, value) in [
+        ("start-x", gradient.start.x),
+        ("start-y", gradient.start.y),
+        ("end-x", gradient.end.x),
+        ("end-y", gradient.end.y),
+    ] {
+        node.push(KdlEntry::new_prop(name, value as f64));
+    }
+    let stops = node.children_mut().get_or_insert_with(KdlDocument::new);
+    for stop in &gradient.stops {
+        let mut stop_node = KdlNode::new("stop");
+        stop_node.push(KdlEntry::new_prop("offset", stop.offset as f64));
+        push_color(&mut stop_node, stop.color);
+        stops.nodes_mut().push(stop_node);
+    }
+}
+
+fn parse_fill(node: &KdlNode) -> Result<Fill, InterchangeError> {
+    let paint = match node.get("gradient").and_then(|v| v.as_string()) {
+        Some(kind) => Paint::Gradient(parse_gradient(node, kind)?),
+        None => Paint::Solid(parse_color(node)),
+    };
+    let mut fill = Fill::from(paint);
+    (fill.visible, fill.opacity) = parse_layer(node);
+    Ok(fill)
+}
+
+fn parse_gradient(node: &KdlNode, kind: &str) -> Result<Gradient, InterchangeError> {
+    let mut gradient = Gradient::new(parse_gradient_kind(kind)?, gpui::black(), gpui::white());
+    let point = |x, y, default: Vec2| {
+        Vec2::new(
+            get_f32_prop(node, x).unwrap_or(default.x),
+            get_f32_prop(node, y).unwrap_or(default.y),
+        )
+    };
+    gradient.start = point("start-x", "start-y", gradient.start);
+    gradient.end = point("end-x", "end-y", gradient.end);
+    gradient.stops = node
+        .children()
+        .into_iter()
+        .flat_map(|children| children.nodes())
+        .filter(|child| child.name().value() == "stop")
+        .map(|stop| GradientStop::new(get_f32_prop(stop, "offset").unwrap_or(0.0), parse_color(stop)))
+        .collect();
+    if gradient.stops.is_empty() {
+        return Err(InterchangeError::MissingField("gradient stops".into()));
+    }
+    gradient.sort_stops();
+    Ok(gradient)
+}
+
+/// A stroke: its width and color, and how it's drawn where that isn't the
+/// default. Alignment is written when it isn't the default for `kind`.
+fn stroke_to_kdl(stroke: &Stroke, kind: ShapeKind) -> KdlNode {
+    let mut node = KdlNode::new("stroke");
+    node.push(KdlEntry::new_prop("width", stroke.width as f64));
+    push_color(&mut node, stroke.color);
+    push_layer(&mut node, stroke.visible, stroke.opacity);
+    if stroke.align != StrokeAlign::for_kind(kind) {
+        node.push(KdlEntry::new_prop("align", stroke_align_name(stroke.align)));
+    }
+    if stroke.cap != StrokeCap::default() {
+        node.push(KdlEntry::new_prop("cap", stroke_cap_name(stroke.cap)));
+    }
+    if stroke.join != StrokeJoin::default() {
+        node.push(KdlEntry::new_prop("join", stroke_join_name(stroke.join)));
+    }
+    if !stroke.dash.is_empty() {
+        let mut dash = KdlNode::new("dash");
+        for length in &stroke.dash {
+            dash.push(KdlEntry::new(*length as f64));
+        }
+        node.children_mut().get_or_insert_with(KdlDocument::new).nodes_mut().push(dash);
+    }
+    node
+}
+
+/// Files from before strokes could be aligned have none written, and are
+/// read with their kind's default, which is how they were drawn.
+fn parse_stroke(node: &KdlNode, kind: ShapeKind) -> Result<Stroke, InterchangeError> {
+    let width = get_f32_prop(node, "width").unwrap_or(1.0);
+    let mut stroke = Stroke::new(parse_color(node), width).with_align(StrokeAlign::for_kind(kind));
+    (stroke.visible, stroke.opacity) = parse_layer(node);
+    if let Some(name) = node.get("align").and_then(|v| v.as_string()) {
+        stroke.align = parse_stroke_align(name)?;
+    }
+    if let Some(name) = node.get("cap").and_then(|v| v.as_string()) {
+        stroke.cap = parse_stroke_cap(name)?;
+    }
+    if let Some(name) = node.get("join").and_then(|v| v.as_string()) {
+        stroke.join = parse_stroke_join(name)?;
+    }
+    stroke.dash = node
+        .children()
+        .into_iter()
+        .flat_map(|children| children.nodes())
+        .filter(|child| child.name().value() == "dash")
+        .flat_map(|dash| dash.entries())
+        .filter(|entry| entry.name().is_none())
+        .filter_map(|entry| entry.value().as_float())
+        .map(|length| length as f32)
+        .collect();
+    Ok(stroke)
+}
+
+/// Whether a fill or stroke is shown and how opaque, written only when
+/// it's hidden or translucent.
+fn push_layer(node: &mut KdlNode, visible: bool, opacity: f32) {
+    if !visible {
+        node.push(KdlEntry::new_prop("visible", false));
+    }
+    if opacity != 1.0 {
+        node.push(KdlEntry::new_prop("opacity", opacity as f64));
+    }
+}
+
+fn parse_layer(node: &KdlNode) -> (bool, f32) {
+    let visible = node.get("visible").and_then(|v| v.as_bool()).unwrap_or(true);
+    (visible, get_f32_prop(node, "opacity").unwrap_or(1.0))
+}
+
+fn push_color(node: &mut KdlNode, color: gpui::Hsla) {
+    node.push(KdlEntry::new_prop("h", color.h as f64));
+    node.push(KdlEntry::new_prop("s", color.s as f64));
+    node.push(KdlEntry::new_prop("l", color.l as f64));
+    node.push(KdlEntry::new_prop("a", color.a as f64));
+}
+
+fn parse_color(node: &KdlNode) -> gpui::Hsla {
+    gpui::Hsla {
+        h: get_f32_prop(node, "h").unwrap_or(0.0),
+        s: get_f32_prop(node, "s").unwrap_or(0.0),
+        l: get_f32_prop(node, "l").unwrap_or(0.0),
+        a: get_f32_prop(node, "a").unwrap_or(1.0),
+    }
+}
+
+/// How a shape is sized and placed inside its parent's layout. Limits and
+/// constraints are written only when set.
+fn sizing_to_kdl(sizing: &ChildLayout) -> KdlNode {
+    let mut node = KdlNode::new("sizing");
+    node.push(KdlEntry::new_prop("width", sizing_mode_name(sizing.width_mode)));
+    node.push(KdlEntry::new_prop("height", sizing_mode_name(sizing.height_mode)));
+    for (name, limit) in [
+        ("min-width", sizing.min_width),
+        ("max-width", sizing.max_width),
+        ("min-height", sizing.min_height),
+        ("max-height", sizing.max_height),
+    ] {
+        if let Some(limit) = limit {
+            node.push(KdlEntry::new_prop(name, limit as f64));
+        }
+    }
+    if sizing.absolute {
+        node.push(KdlEntry::new_prop("absolute", true));
+    }
+    if sizing.horizontal_constraint != Constraint::default() {
+        node.push(KdlEntry::new_prop("horizontal", constraint_name(sizing.horizontal_constraint)));
+    }
+    if sizing.vertical_constraint != Constraint::default() {
+        node.push(KdlEntry::new_prop("vertical", constraint_name(sizing.vertical_constraint)));
+    }
+    node
+}
+
+fn parse_sizing(node: &KdlNode) -> Result<ChildLayout, InterchangeError> {
+    let mut sizing = ChildLayout::default();
+    if let Some(name) = node.get("width").and_then(|v| v.as_string()) {
+        sizing.width_mode = parse_sizing_mode(name)?;
+    }
+    if let Some(name) = node.get("height").and_then(|v| v.as_string()) {
+        sizing.height_mode = parse_sizing_mode(name)?;
+    }
+    if let Some(name) = node.get("horizontal").and_then(|v| v.as_string()) {
+        sizing.horizontal_constraint = parse_constraint(name)?;
+    }
+    if let Some(name) = node.get("vertical").and_then(|v| v.as_string()) {
+        sizing.vertical_constraint = parse_constraint(name)?;
+    }
+    sizing.min_width = get_f32_prop(node, "min-width");
+    sizing.max_width = get_f32_prop(node, "max-width");
+    sizing.min_height = get_f32_prop(node, "min-height");
+    sizing.max_height = get_f32_prop(node, "max-height");
+    sizing.absolute = node.get("absolute").and_then(|v| v.as_bool()).unwrap_or(false);
+    Ok(sizing)
+}
+
+/// A shape's part in a component: `component` on a main, `instance` with
+/// the main's id on an instance's root, `copy` with the copied shape's id
+/// inside an instance. Overrides are written only when set.
+fn component_to_kdl(link: &ComponentLink) -> KdlNode {
+    let (mut node, source) = match link {
+        ComponentLink::Main => return KdlNode::new("component"),
+        ComponentLink::Instance { main, .. } => (KdlNode::new("instance"), main),
+        ComponentLink::Copy { source, .. } => (KdlNode::new("copy"), source),
+    };
+    node.push(KdlEntry::new(source.to_uuid_string()));
+    let overrides = link.overrides();
+    for (name, set) in [
+        ("override-fill", overrides.fill),
+        ("override-stroke", overrides.stroke),
+        ("override-text", overrides.text),
+    ] {
+        if set {
+            node.push(KdlEntry::new_prop(name, true));
+        }
+    }
+    node
+}
+
+fn parse_component(node: &KdlNode) -> Result<ComponentLink, InterchangeError> {
+    let name = node.name().value();
+    if name == "component" {
+        return Ok(ComponentLink::Main);
+    }
+    let source = get_string_arg(node)
+        .map(ShapeId::from_str)
+        .ok_or_else(|| InterchangeError::MissingField(format!("{} source id", name)))?;
+    let flag = |prop| node.get(prop).and_then(|v| v.as_bool()).unwrap_or(false);
+    let overrides = Overrides {
+        fill: flag("override-fill"),
+        stroke: flag("override-stroke"),
+        text: flag("override-text"),
+    };
+    Ok(match name {
+        "instance" => ComponentLink::Instance { main: source, overrides },
+        _ => ComponentLink::Copy { source, overrides },
+    })
+}
+
+fn parse_guide(node: &KdlNode) -> Result<Guide, InterchangeError> {
+    let axis = node
+        .get("axis")
+        .and_then(|v| v.as_string())
+        .ok_or_else(|| InterchangeError::MissingField("guide axis".into()))?;
+    let position = get_f32_prop(node, "position")
+        .ok_or_else(|| InterchangeError::MissingField("guide position".into()))?;
+    Ok(Guide::new(parse_guide_axis(axis)?, position))
+}
+
 fn get_f32_prop(node: &KdlNode, name: &str) -> Option<f32> {
     node.get(name)
         .and_then(|v| v.as_float())
         .map(|v| v as f32)
 }
 
+/// The first positional string argument of a node.
+fn get_string_arg(node: &KdlNode) -> Option<&str> {
+    node.entries()
+        .iter()
+        .find(|e| e.name().is_none())
+        .and_then(|e| e.value().as_string())
+}
+
+fn guide_axis_name(axis: GuideAxis) -> &'static str {
+    match axis {
+        GuideAxis::Horizontal => "horizontal",
+        GuideAxis::Vertical => "vertical",
+    }
+}
+
+fn parse_guide_axis(name: &str) -> Result<GuideAxis, InterchangeError> {
+    match name {
+        "horizontal" => Ok(GuideAxis::Horizontal),
+        "vertical" => Ok(GuideAxis::Vertical),
+        other => Err(InterchangeError::InvalidValue(format!(
+            "Unknown guide axis: {}",
+            other
+        ))),
+    }
+}
+
+fn text_align_name(align: TextAlign) -> &'static str {
+    match align {
+        TextAlign::Left => "left",
+        TextAlign::Center => "center",
+        TextAlign::Right => "right",
+    }
+}
+
+fn parse_text_align(name: &str) -> Result<TextAlign, InterchangeError> {
+    match name {
+        "left" => Ok(TextAlign::Left),
+        "center" => Ok(TextAlign::Center),
+        "right" => Ok(TextAlign::Right),
+        other => Err(InterchangeError::InvalidValue(format!(
+            "Unknown text align: {}",
+            other
+        ))),
+    }
+}
+
+fn text_sizing_name(sizing: TextSizing) -> &'static str {
+    match sizing {
+        TextSizing::AutoWidth => "auto-width",
+        TextSizing::AutoHeight => "auto-height",
+        TextSizing::Fixed => "fixed",
+    }
+}
+
+fn parse_text_sizing(name: &str) -> Result<TextSizing, InterchangeError> {
+    match name {
+        "auto-width" => Ok(TextSizing::AutoWidth),
+        "auto-height" => Ok(TextSizing::AutoHeight),
+        "fixed" => Ok(TextSizing::Fixed),
+        other => Err(InterchangeError::InvalidValue(format!(
+            "Unknown text sizing: {}",
+            other
+        ))),
+    }
+}
+
+fn handle_mode_name(mode: HandleMode) -> &'static str {
+    match mode {
+        HandleMode::Free => "free",
+        HandleMode::Aligned => "aligned",
+        HandleMode::Mirrored => "mirrored",
+    }
+}
+
+fn parse_handle_mode(name: &str) -> Result<HandleMode, InterchangeError> {
+    match name {
+        "free" => Ok(HandleMode::Free),
+        "aligned" => Ok(HandleMode::Aligned),
+        "mirrored" => Ok(HandleMode::Mirrored),
+        other => Err(InterchangeError::InvalidValue(format!(
+            "Unknown handle mode: {}",
+            other
+        ))),
+    }
+}
+
+fn layout_direction_name(direction: LayoutDirection) -> &'static str {
+    match direction {
+        LayoutDirection::Row => "row",
+        LayoutDirection::Column => "column",
+    }
+}
+
+fn parse_layout_direction(name: &str) -> Result<LayoutDirection, InterchangeError> {
+    match name {
+        "row" => Ok(LayoutDirection::Row),
+        "column" => Ok(LayoutDirection::Column),
+        other => Err(InterchangeError::InvalidValue(format!(
+            "Unknown layout direction: {}",
+            other
+        ))),
+    }
+}
+
+fn main_axis_name(alignment: MainAxisAlignment) -> &'static str {
+    match alignment {
+        MainAxisAlignment::Start => "start",
+        MainAxisAlignment::Center => "center",
+        MainAxisAlignment::End => "end",
+        MainAxisAlignment::SpaceBetween => "space-between",
+    }
+}
+
+fn parse_main_axis(name: &str) -> Result<MainAxisAlignment, InterchangeError> {
+    match name {
+        "start" => Ok(MainAxisAlignment::Start),
+        "center" => Ok(MainAxisAlignment::Center),
+        "end" => Ok(MainAxisAlignment::End),
+        "space-between" => Ok(MainAxisAlignment::SpaceBetween),
+        other => Err(InterchangeError::InvalidValue(format!(
+            "Unknown main axis alignment: {}",
+            other
+        ))),
+    }
+}
+
+fn cross_axis_name(alignment: CrossAxisAlignment) -> &'static str {
+    match alignment {
+        CrossAxisAlignment::Start => "start",
+        CrossAxisAlignment::Center => "center",
+        CrossAxisAlignment::End => "end",
+        CrossAxisAlignment::Stretch => "stretch",
+    }
+}
+
+fn parse_cross_axis(name: &str) -> Result<CrossAxisAlignment, InterchangeError> {
+    match name {
+        "start" => Ok(CrossAxisAlignment::Start),
+        "center" => Ok(CrossAxisAlignment::Center),
+        "end" => Ok(CrossAxisAlignment::End),
+        "stretch" => Ok(CrossAxisAlignment::Stretch),
+        other => Err(InterchangeError::InvalidValue(format!(
+            "Unknown cross axis alignment: {}",
+            other
+        ))),
+    }
+}
+
+fn line_alignment_name(alignment: LineAlignment) -> &'static str {
+    match alignment {
+        LineAlignment::Start => "start",
+        LineAlignment::Center => "center",
+        LineAlignment::End => "end",
+        LineAlignment::SpaceBetween => "space-between",
+    }
+}
+
+fn parse_line_alignment(name: &str) -> Result<LineAlignment, InterchangeError> {
+    match name {
+        "start" => Ok(LineAlignment::Start),
+        "center" => Ok(LineAlignment::Center),
+        "end" => Ok(LineAlignment::End),
+        "space-between" => Ok(LineAlignment::SpaceBetween),
+        other => Err(InterchangeError::InvalidValue(format!(
+            "Unknown line alignment: {}",
+            other
+        ))),
+    }
+}
+
+fn gradient_kind_name(kind: GradientKind) -> &'static str {
+    match kind {
+        GradientKind::Linear => "linear",
+        GradientKind::Radial => "radial",
+        GradientKind::Angular => "angular",
+    }
+}
+
+fn parse_gradient_kind(name: &str) -> Result<GradientKind, InterchangeError> {
+    match name {
+        "linear" => Ok(GradientKind::Linear),
+        "radial" => Ok(GradientKind::Radial),
+        "angular" => Ok(GradientKind::Angular),
+        other => Err(InterchangeError::InvalidValue(format!(
+            "Unknown gradient: {}",
+            other
+        ))),
+    }
+}
+
+fn stroke_align_name(align: StrokeAlign) -> &'static str {
+    match align {
+        StrokeAlign::Inside => "inside",
+        StrokeAlign::Center => "center",
+        StrokeAlign::Outside => "outside",
+    }
+}
+
+fn parse_stroke_align(name: &str) -> Result<StrokeAlign, InterchangeError> {
+    match name {
+        "inside" => Ok(StrokeAlign::Inside),
+        "center" => Ok(StrokeAlign::Center),
+        "outside" => Ok(StrokeAlign::Outside),
+        other => Err(InterchangeError::InvalidValue(format!(
+            "Unknown stroke alignment: {}",
+            other
+        ))),
+    }
+}
+
+fn stroke_cap_name(cap: StrokeCap) -> &'static str {
+    match cap {
+        StrokeCap::Butt => "butt",
+        StrokeCap::Round => "round",
+        StrokeCap::Square => "square",
+    }
+}
+
+fn parse_stroke_cap(name: &str) -> Result<StrokeCap, InterchangeError> {
+    match name {
+        "butt" => Ok(StrokeCap::Butt),
+        "round" => Ok(StrokeCap::Round),
+        "square" => Ok(StrokeCap::Square),
+        other => Err(InterchangeError::InvalidValue(format!(
+            "Unknown stroke cap: {}",
+            other
+        ))),
+    }
+}
+
+fn stroke_join_name(join: StrokeJoin) -> &'static str {
+    match join {
+        StrokeJoin::Miter => "miter",
+        StrokeJoin::Round => "round",
+        StrokeJoin::Bevel => "bevel",
+    }
+}
+
+fn parse_stroke_join(name: &str) -> Result<StrokeJoin, InterchangeError> {
+    match name {
+        "miter" => Ok(StrokeJoin::Miter),
+        "round" => Ok(StrokeJoin::Round),
+        "bevel" => Ok(StrokeJoin::Bevel),
+        other => Err(InterchangeError::InvalidValue(format!(
+            "Unknown stroke join: {}",
+            other
+        ))),
+    }
+}
+
+/// Also the name CSS gives the mode, for SVG export.
+pub(crate) fn blend_mode_name(mode: BlendMode) -> &'static str {
+    match mode {
+        BlendMode::Normal => "normal",
+        BlendMode::Multiply => "multiply",
+        BlendMode::Screen => "screen",
+        BlendMode::Overlay => "overlay",
+        BlendMode::Darken => "darken",
+        BlendMode::Lighten => "lighten",
+        BlendMode::ColorDodge => "color-dodge",
+        BlendMode::ColorBurn => "color-burn",
+        BlendMode::HardLight => "hard-light",
+        BlendMode::SoftLight => "soft-light",
+        BlendMode::Difference => "difference",
+        BlendMode::Exclusion => "exclusion",
+        BlendMode::Hue => "hue",
+        BlendMode::Saturation => "saturation",
+        BlendMode::Color => "color",
+        BlendMode::Luminosity => "luminosity",
+    }
+}
+
+fn parse_blend_mode(name: &str) -> Result<BlendMode, InterchangeError> {
+    BlendMode::ALL
+        .into_iter()
+        .find(|mode| blend_mode_name(*mode) == name)
+        .ok_or_else(|| InterchangeError::InvalidValue(format!("Unknown blend mode: {}", name)))
+}
+
+fn sizing_mode_name(mode: SizingMode) -> &'static str {
+    match mode {
+        SizingMode::Fixed => "fixed",
+        SizingMode::Fill => "fill",
+        SizingMode::Hug => "hug",
+    }
+}
+
+fn parse_sizing_mode(name: &str) -> Result<SizingMode, InterchangeError> {
+    match name {
+        "fixed" => Ok(SizingMode::Fixed),
+        "fill" => Ok(SizingMode::Fill),
+        "hug" => Ok(SizingMode::Hug),
+        other => Err(InterchangeError::InvalidValue(format!(
+            "Unknown sizing mode: {}",
+            other
+        ))),
+    }
+}
+
+fn constraint_name(constraint: Constraint) -> &'static str {
+    match constraint {
+        Constraint::Start => "start",
+        Constraint::End => "end",
+        Constraint::Center => "center",
+        Constraint::Stretch => "stretch",
+    }
+}
+
+fn parse_constraint(name: &str) -> Result<Constraint, InterchangeError> {
+    match name {
+        "start" => Ok(Constraint::Start),
+        "end" => Ok(Constraint::End),
+        "center" => Ok(Constraint::Center),
+        "stretch" => Ok(Constraint::Stretch),
+        other => Err(InterchangeError::InvalidValue(format!(
+            "Unknown constraint: {}",
+            other
+        ))),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use glam::Vec2;
 
     #[test]
     fn test_roundtrip() {
@@ -349,4 +1218,261 @@ mod tests {
         assert_eq!(parsed.shapes[0].kind, ShapeKind::Rectangle);
         assert_eq!(parsed.shapes[1].kind, ShapeKind::Ellipse);
     }
+
+    #[test]
+    fn test_text_roundtrip() {
+        let text = Text::new("Hello\nworld")
+            .with_font_family("Berkeley Mono")
+            .with_font_weight(700)
+            .with_font_size(24.0)
+            .with_align(TextAlign::Center)
+            .with_sizing(TextSizing::AutoHeight);
+        let shape = Shape::text(Vec2::new(10.0, 20.0), text.clone());
+
+        let kdl = Document::new(vec![shape]).to_kdl();
+        let parsed = Document::from_kdl(&kdl).expect("Failed to parse");
+
+        assert_eq!(parsed.shapes.len(), 1);
+        assert_eq!(parsed.shapes[0].kind, ShapeKind::Text);
+        assert_eq!(parsed.shapes[0].text, Some(text));
+        assert_eq!(parsed.shapes[0].fills.len(), 1);
+    }
+
+    #[test]
+    fn test_path_roundtrip() {
+        let path = PathData::new(
+            vec![
+                PathVertex::corner(Vec2::new(0.0, 50.0)),
+                PathVertex::smooth(Vec2::new(50.0, 0.0), Vec2::new(30.0, 0.0)),
+                PathVertex::corner(Vec2::new(100.0, 50.0)),
+            ],
+            true,
+        );
+        let shape = Shape::path(path).with_fill(gpui::Hsla {
+            h: 0.0,
+            s: 0.0,
+            l: 0.5,
+            a: 1.0,
+        });
+
+        let kdl = Document::new(vec![shape.clone()]).to_kdl();
+        let parsed = Document::from_kdl(&kdl).expect("Failed to parse");
+
+        assert_eq!(parsed.shapes.len(), 1);
+        assert_eq!(parsed.shapes[0].kind, ShapeKind::Path);
+        assert_eq!(parsed.shapes[0].path, shape.path);
+        assert_eq!(parsed.shapes[0].size, shape.size);
+    }
+
+    #[test]
+    fn test_gradient_roundtrip() {
+        let mut gradient = Gradient::new(GradientKind::Angular, gpui::red(), gpui::blue());
+        gradient.end = Vec2::new(0.5, 0.0);
+        gradient.stops.insert(1, GradientStop::new(0.25, gpui::green()));
+        let mut shape = Shape::ellipse(Vec2::ZERO, Vec2::new(80.0, 40.0));
+        shape.fills = vec![Fill::from(Paint::Gradient(gradient))];
+
+        let kdl = Document::new(vec![shape.clone()]).to_kdl();
+        assert_eq!(kdl.matches("stop offset=").count(), 3);
+
+        let parsed = Document::from_kdl(&kdl).expect("Failed to parse");
+        assert_eq!(parsed.shapes[0].fills, shape.fills);
+    }
+
+    #[test]
+    fn test_fill_and_stroke_stacks_roundtrip() {
+        let mut shape = Shape::rectangle(Vec2::ZERO, Vec2::new(100.0, 50.0)).with_fill(gpui::red());
+        let mut hidden = Fill::new(gpui::blue());
+        hidden.visible = false;
+        hidden.opacity = 0.5;
+        shape.fills.push(hidden);
+        let mut dashed = Stroke::new(gpui::green(), 3.0).with_align(StrokeAlign::Outside);
+        dashed.dash = vec![4.0, 2.0];
+        dashed.cap = StrokeCap::Round;
+        dashed.join = StrokeJoin::Bevel;
+        shape.strokes.push(dashed);
+        let path = Shape::path(PathData::new(vec![PathVertex::corner(Vec2::ZERO)], false));
+
+        let kdl = Document::new(vec![shape.clone(), path]).to_kdl();
+        assert_eq!(kdl.matches("fill ").count(), 2);
+        assert_eq!(kdl.matches("visible=").count(), 1);
+        // Strokes lying where their kind's do by default don't say so
+        assert_eq!(kdl.matches("align=").count(), 1);
+
+        let parsed = Document::from_kdl(&kdl).expect("Failed to parse");
+        assert_eq!(parsed.shapes[0].fills, shape.fills);
+        assert_eq!(parsed.shapes[0].strokes, shape.strokes);
+        assert_eq!(parsed.shapes[1].strokes[0].align, StrokeAlign::Center);
+    }
+
+    #[test]
+    fn test_transform_roundtrip() {
+        let mut turned = Shape::rectangle(Vec2::new(10.0, 20.0), Vec2::new(100.0, 50.0));
+        turned.rotation = -30.0;
+        turned.flip_x = true;
+        let plain = Shape::rectangle(Vec2::ZERO, Vec2::new(10.0, 10.0));
+
+        let kdl = Document::new(vec![turned.clone(), plain]).to_kdl();
+        assert_eq!(kdl.matches("rotation=").count(), 1);
+        assert!(!kdl.contains("flip-y"));
+
+        let parsed = Document::from_kdl(&kdl).expect("Failed to parse");
+        assert_eq!(parsed.shapes[0].rotation, -30.0);
+        assert!(parsed.shapes[0].flip_x);
+        assert!(!parsed.shapes[0].flip_y);
+        assert_eq!(parsed.shapes[1].rotation, 0.0);
+    }
+
+    #[test]
+    fn test_opacity_and_blend_mode_roundtrip() {
+        let mut faded = Shape::rectangle(Vec2::ZERO, Vec2::new(10.0, 10.0));
+        faded.opacity = 0.25;
+        faded.blend_mode = BlendMode::ColorBurn;
+        let plain = Shape::rectangle(Vec2::ZERO, Vec2::new(10.0, 10.0));
+
+        let kdl = Document::new(vec![faded, plain]).to_kdl();
+        assert_eq!(kdl.matches("blend=").count(), 1);
+
+        let parsed = Document::from_kdl(&kdl).expect("Failed to parse");
+        assert_eq!(parsed.shapes[0].opacity, 0.25);
+        assert_eq!(parsed.shapes[0].blend_mode, BlendMode::ColorBurn);
+        assert_eq!(parsed.shapes[1].opacity, 1.0);
+        assert_eq!(parsed.shapes[1].blend_mode, BlendMode::Normal);
+
+        assert!(Document::from_kdl(&kdl.replace("color-burn", "glow")).is_err());
+    }
+
+    #[test]
+    fn test_layout_roundtrip() {
+        let layout = FrameLayout::column()
+            .with_gap(8.0)
+            .with_main_axis(MainAxisAlignment::SpaceBetween)
+            .with_cross_axis(CrossAxisAlignment::Stretch)
+            .with_wrap(4.0)
+            .with_line_alignment(LineAlignment::Center);
+        let layout = FrameLayout {
+            padding: node::Padding::new(16.0, 8.0, 0.0, 8.0),
+            ..layout
+        };
+        let mut frame = Shape::frame(Vec2::ZERO, Vec2::new(200.0, 300.0)).with_layout(layout.clone());
+        frame.child_layout = ChildLayout::fixed().with_height(SizingMode::Hug);
+        let mut fill = Shape::rectangle(Vec2::ZERO, Vec2::new(50.0, 50.0)).with_child_layout(
+            ChildLayout::fill_main()
+                .with_width_limits(Some(20.0), None)
+                .with_height(SizingMode::Hug),
+        );
+        let mut pinned = Shape::rectangle(Vec2::new(150.0, 10.0), Vec2::new(40.0, 40.0))
+            .with_child_layout(ChildLayout::default().absolute(Constraint::End, Constraint::Stretch));
+        for child in [&mut fill, &mut pinned] {
+            child.parent = Some(frame.id);
+            frame.children.push(child.id);
+        }
+
+        let shapes = vec![frame, fill, pinned];
+        let kdl = Document::new(shapes.clone()).to_kdl();
+        let parsed = Document::from_kdl(&kdl).expect("Failed to parse");
+
+        assert_eq!(parsed.shapes.len(), 3);
+        for (parsed, original) in parsed.shapes.iter().zip(&shapes) {
+            assert_eq!(parsed.layout, original.layout);
+            assert_eq!(parsed.child_layout, original.child_layout);
+        }
+    }
+
+    #[test]
+    fn test_layout_defaults_stay_terse() {
+        let frame = Shape::frame(Vec2::ZERO, Vec2::new(100.0, 100.0)).with_layout(FrameLayout::row());
+        let kdl = Document::new(vec![frame]).to_kdl();
+        assert!(kdl.contains("layout direction=row"));
+        assert!(!kdl.contains("gap") && !kdl.contains("padding"));
+        assert!(!kdl.contains("sizing"));
+    }
+
+    #[test]
+    fn test_laid_out_shapes_are_written_where_they_show() {
+        let mut shape = Shape::rectangle(Vec2::ZERO, Vec2::new(50.0, 50.0));
+        shape.computed_position = Some(node::CanvasPoint::new(16.0, 24.0));
+        shape.computed_size = Some(node::CanvasSize::new(168.0, 50.0));
+
+        let kdl = Document::new(vec![shape]).to_kdl();
+        let parsed = Document::from_kdl(&kdl).expect("Failed to parse");
+        assert_eq!(parsed.shapes[0].position, node::CanvasPoint::new(16.0, 24.0));
+        assert_eq!(parsed.shapes[0].size, node::CanvasSize::new(168.0, 50.0));
+    }
+
+    #[test]
+    fn test_unknown_layout_values_are_rejected() {
+        let kdl = r#"document version="0.1" {
+            frame "00000000-0000-0000-0000-000000000001" x=0 y=0 width=10 height=10 {
+                layout direction="diagonal"
+            }
+        }"#;
+        assert!(matches!(Document::from_kdl(kdl), Err(InterchangeError::InvalidValue(_))));
+    }
+
+    #[test]
+    fn test_names_roundtrip() {
+        let mut named = Shape::rectangle(Vec2::ZERO, Vec2::ONE);
+        named.name = "Card \"hero\"".to_string();
+        let unnamed = Shape::rectangle(Vec2::ZERO, Vec2::ONE);
+
+        let kdl = Document::new(vec![named, unnamed]).to_kdl();
+        assert_eq!(kdl.matches("name=").count(), 1);
+        let parsed = Document::from_kdl(&kdl).expect("Failed to parse");
+        assert_eq!(parsed.shapes[0].name, "Card \"hero\"");
+        assert_eq!(parsed.shapes[1].name, "");
+    }
+
+    #[test]
+    fn test_hidden_and_locked_roundtrip() {
+        let mut hidden = Shape::rectangle(Vec2::ZERO, Vec2::ONE);
+        hidden.visible = false;
+        let mut locked = Shape::rectangle(Vec2::ZERO, Vec2::ONE);
+        locked.locked = true;
+        let plain = Shape::rectangle(Vec2::ZERO, Vec2::ONE);
+
+        let kdl = Document::new(vec![hidden, locked, plain]).to_kdl();
+        assert_eq!(kdl.matches("hidden=#true").count(), 1);
+        assert_eq!(kdl.matches("locked=#true").count(), 1);
+        let parsed = Document::from_kdl(&kdl).expect("Failed to parse");
+        let flags: Vec<_> = parsed.shapes.iter().map(|s| (s.visible, s.locked)).collect();
+        assert_eq!(flags, [(false, false), (true, true), (true, false)]);
+    }
+
+    #[test]
+    fn test_component_roundtrip() {
+        let mut main = Shape::frame(Vec2::ZERO, Vec2::new(100.0, 40.0));
+        main.component = Some(ComponentLink::Main);
+        let mut label = Shape::text(Vec2::new(8.0, 8.0), Text::new("Button"));
+        label.parent = Some(main.id);
+        main.children.push(label.id);
+        let mut shapes = vec![main.clone(), label.clone()];
+        let mut instance = node::instantiate(&shapes, main.id).unwrap();
+        if let Some(ComponentLink::Copy { overrides, .. }) = &mut instance[1].component {
+            overrides.text = true;
+        }
+        shapes.extend(instance);
+
+        let kdl = Document::new(shapes.clone()).to_kdl();
+        assert!(kdl.contains("override-text=#true"));
+        assert!(!kdl.contains("override-fill"));
+        let parsed = Document::from_kdl(&kdl).expect("Failed to parse");
+        for (parsed, original) in parsed.shapes.iter().zip(&shapes) {
+            assert_eq!(parsed.id, original.id);
+            assert_eq!(parsed.component, original.component);
+        }
+    }
+
+    #[test]
+    fn test_guides_roundtrip() {
+        let guides = vec![
+            Guide::new(GuideAxis::Vertical, 240.0),
+            Guide::new(GuideAxis::Horizontal, -12.5),
+        ];
+        let shapes = vec![Shape::rectangle(Vec2::ZERO, Vec2::ONE)];
+        let doc = Document { guides: guides.clone(), ..Document::new(shapes) };
+        let parsed = Document::from_kdl(&doc.to_kdl()).expect("Failed to parse");
+        assert_eq!(parsed.shapes.len(), 1);
+        assert_eq!(parsed.guides, guides);
+    }
 }
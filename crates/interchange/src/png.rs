@@ -0,0 +1,148 @@
+//! PNG export.
+//!
+//! Rasterizes on the CPU, without a window or GPU: shapes are exported to
+//! SVG and drawn with resvg, so PNGs match SVG exports exactly. Text uses
+//! the fonts installed on the system, loaded once per process.
+
+use crate::{export_svg, InterchangeError};
+use node::{Shape, ShapeId};
+use resvg::tiny_skia::{Pixmap, Transform};
+use resvg::usvg::{self, fontdb};
+use std::sync::{Arc, OnceLock};
+
+/// Render `ids` and their children at `scale` pixels per canvas unit, as
+/// [`export_svg`] would draw them. The background is transparent.
+pub fn render_pixmap(shapes: &[Shape], ids: &[ShapeId], scale: f32) -> Result<Pixmap, InterchangeError> {
+    if !(scale.is_finite() && scale > 0.0) {
+        return Err(InterchangeError::InvalidValue(format!("Scale: {}", scale)));
+    }
+    if !shapes.iter().any(|shape| ids.contains(&shape.id)) {
+        return Err(InterchangeError::Render("Nothing to render".into()));
+    }
+    let svg = export_svg(shapes, ids);
+    let options = usvg::Options {
+        fontdb: system_fonts(),
+        ..Default::default()
+    };
+    let tree = usvg::Tree::from_str(&svg, &options).map_err(|e| InterchangeError::Render(e.to_string()))?;
+
+    let size = tree.size();
+    let width = (size.width() * scale).ceil() as u32;
+    let height = (size.height() * scale).ceil() as u32;
+    let mut pixmap = Pixmap::new(width, height)
+        .ok_or_else(|| InterchangeError::Render(format!("Image too large: {}x{}", width, height)))?;
+    resvg::render(&tree, Transform::from_scale(scale, scale), &mut pixmap.as_mut());
+    Ok(pixmap)
+}
+
+/// Render `ids` and their children to PNG bytes at `scale` pixels per
+/// canvas unit.
+pub fn export_png(shapes: &[Shape], ids: &[ShapeId], scale: f32) -> Result<Vec<u8>, InterchangeError> {
+    render_pixmap(shapes, ids, scale)?
+        .encode_png()
+        .map_err(|e| InterchangeError::Render(format!("Failed to encode PNG: {}", e)))
+}
+
+fn system_fonts() -> Arc<fontdb::Database> {
+    static FONTS: OnceLock<Arc<fontdb::Database>> = OnceLock::new();
+    FONTS
+        .get_or_init(|| {
+            let mut fonts = fontdb::Database::new();
+            fonts.load_system_fonts();
+            Arc::new(fonts)
+        })
+        .clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::Vec2;
+    use gpui::Hsla;
+
+    /// `shape` filled with a fully saturated hue and no stroke.
+    fn filled(shape: Shape, hue: f32) -> Shape {
+        let mut shape = shape.with_fill(Hsla { h: hue, s: 1.0, l: 0.5, a: 1.0 });
+        shape.strokes.clear();
+        shape
+    }
+
+    /// The pixel at (x, y) as RGBA bytes.
+    fn pixel(pixmap: &Pixmap, x: u32, y: u32) -> [u8; 4] {
+        let p = pixmap.pixel(x, y).expect("pixel in bounds").demultiply();
+        [p.red(), p.green(), p.blue(), p.alpha()]
+    }
+
+    // === Rendering ===
+
+    #[test]
+    fn renders_shapes_at_scale() {
+        let red = filled(Shape::rectangle(Vec2::new(10.0, 10.0), Vec2::new(20.0, 10.0)), 0.0);
+        let blue = filled(Shape::ellipse(Vec2::new(40.0, 10.0), Vec2::new(10.0, 10.0)), 2.0 / 3.0);
+        let shapes = vec![red, blue];
+        let ids: Vec<_> = shapes.iter().map(|s| s.id).collect();
+
+        let pixmap = render_pixmap(&shapes, &ids, 2.0).unwrap();
+        assert_eq!((pixmap.width(), pixmap.height()), (80, 20));
+        assert_eq!(pixel(&pixmap, 10, 10), [255, 0, 0, 255]);
+        assert_eq!(pixel(&pixmap, 70, 10), [0, 0, 255, 255]);
+        // Outside the ellipse, in the corner of its box
+        assert_eq!(pixel(&pixmap, 61, 1)[3], 0);
+    }
+
+    #[test]
+    fn clipping_frame_hides_overflow() {
+        let mut frame = Shape::frame(Vec2::ZERO, Vec2::new(10.0, 10.0)).with_clip_children(true);
+        frame.fills.clear();
+        frame.strokes.clear();
+        let mut child = filled(Shape::rectangle(Vec2::new(5.0, 0.0), Vec2::new(10.0, 10.0)), 0.0);
+        child.parent = Some(frame.id);
+        frame.children.push(child.id);
+        let shapes = vec![frame, child];
+
+        let pixmap = render_pixmap(&shapes, &[shapes[0].id], 1.0).unwrap();
+        assert_eq!((pixmap.width(), pixmap.height()), (10, 10));
+        assert_eq!(pixel(&pixmap, 7, 5), [255, 0, 0, 255]);
+        assert_eq!(pixel(&pixmap, 2, 5)[3], 0);
+    }
+
+    #[test]
+    fn groups_fade_and_blend_as_one() {
+        let mut frame = Shape::frame(Vec2::ZERO, Vec2::new(20.0, 10.0));
+        frame.fills.clear();
+        frame.strokes.clear();
+        frame.opacity = 0.5;
+        let mut yellow = filled(Shape::rectangle(Vec2::ZERO, Vec2::new(15.0, 10.0)), 1.0 / 6.0);
+        let mut magenta = filled(Shape::rectangle(Vec2::new(5.0, 0.0), Vec2::new(15.0, 10.0)), 5.0 / 6.0);
+        magenta.blend_mode = node::BlendMode::Multiply;
+        for child in [&mut yellow, &mut magenta] {
+            child.parent = Some(frame.id);
+            frame.children.push(child.id);
+        }
+        let shapes = vec![frame, yellow, magenta];
+
+        let pixmap = render_pixmap(&shapes, &[shapes[0].id], 1.0).unwrap();
+        // Where they overlap, multiplied to red and faded once, not twice
+        let [r, g, b, a] = pixel(&pixmap, 10, 5);
+        assert_eq!((r, g, b), (255, 0, 0));
+        assert!(a.abs_diff(128) <= 1);
+        assert!(pixel(&pixmap, 2, 5)[3].abs_diff(128) <= 1);
+    }
+
+    #[test]
+    fn png_bytes_carry_the_signature() {
+        let shape = filled(Shape::rectangle(Vec2::ZERO, Vec2::new(4.0, 4.0)), 0.0);
+        let png = export_png(std::slice::from_ref(&shape), &[shape.id], 1.0).unwrap();
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+    }
+
+    #[test]
+    fn empty_export_and_bad_scale_are_errors() {
+        let shape = Shape::rectangle(Vec2::ZERO, Vec2::new(4.0, 4.0));
+        assert!(matches!(render_pixmap(&[], &[], 1.0), Err(InterchangeError::Render(_))));
+        assert!(matches!(
+            render_pixmap(std::slice::from_ref(&shape), &[shape.id], 0.0),
+            Err(InterchangeError::InvalidValue(_))
+        ));
+    }
+}
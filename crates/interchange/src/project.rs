@@ -15,6 +15,11 @@
 //!     └── canvas.kdl
 //! ```
 //!
+//! Page files are named after their pages, reduced to characters that are
+//! safe in any file system, with a number appended where two pages reduce
+//! to the same name. The manifest maps each page name to its file, so the
+//! file name is only for humans browsing the folder.
+//!
 //! # Manifest format
 //!
 //! ```kdl
@@ -28,6 +33,7 @@
 
 use crate::{Document, InterchangeError, FORMAT_VERSION};
 use kdl::{KdlDocument, KdlEntry, KdlNode};
+use std::collections::HashSet;
 use std::path::Path;
 
 /// A Luna project (folder-based package).
@@ -60,6 +66,15 @@ impl Project {
         }
     }
 
+    /// Create a project from named pages, in order.
+    pub fn from_pages(name: impl Into<String>, pages: Vec<(String, Document)>) -> Self {
+        Self {
+            name: name.into(),
+            version: FORMAT_VERSION.to_string(),
+            pages,
+        }
+    }
+
     /// Get the default/first page.
     pub fn default_page(&self) -> Option<&Document> {
         self.pages.first().map(|(_, doc)| doc)
@@ -89,21 +104,56 @@ impl Project {
         std::fs::create_dir_all(&pages_dir)
             .map_err(|e| InterchangeError::Parse(format!("Failed to create pages directory: {}", e)))?;
 
+        let files = self.page_files();
+
         // Write manifest
-        let manifest = self.to_manifest_kdl();
+        let manifest = self.to_manifest_kdl(&files);
         std::fs::write(path.join("manifest.kdl"), manifest)
             .map_err(|e| InterchangeError::Parse(format!("Failed to write manifest: {}", e)))?;
 
         // Write each page
-        for (name, doc) in &self.pages {
-            let page_path = pages_dir.join(format!("{}.kdl", name));
-            std::fs::write(&page_path, doc.to_kdl())
+        for ((name, doc), file) in self.pages.iter().zip(&files) {
+            std::fs::write(pages_dir.join(file), doc.to_kdl())
                 .map_err(|e| InterchangeError::Parse(format!("Failed to write page {}: {}", name, e)))?;
         }
 
+        // Pages deleted or renamed since the last save would otherwise linger
+        let entries = std::fs::read_dir(&pages_dir)
+            .map_err(|e| InterchangeError::Parse(format!("Failed to read pages directory: {}", e)))?;
+        for entry in entries.flatten() {
+            let file = entry.file_name();
+            let is_stale = file.to_str().is_some_and(|file| {
+                file.ends_with(".kdl") && !files.iter().any(|written| written == file)
+            });
+            if is_stale {
+                std::fs::remove_file(entry.path())
+                    .map_err(|e| InterchangeError::Parse(format!("Failed to remove old page: {}", e)))?;
+            }
+        }
+
         Ok(())
     }
 
+    /// File name, within `pages/`, for each page in order.
+    fn page_files(&self) -> Vec<String> {
+        let mut used = HashSet::new();
+        self.pages
+            .iter()
+            .map(|(name, _)| {
+                let stem = file_stem(name);
+                let file = (1..)
+                    .map(|n| match n {
+                        1 => format!("{}.kdl", stem),
+                        n => format!("{}-{}.kdl", stem, n),
+                    })
+                    .find(|file| !used.contains(file))
+                    .expect("page file numbers are unbounded");
+                used.insert(file.clone());
+                file
+            })
+            .collect()
+    }
+
     /// Load a project from a .luna folder.
     pub fn load(path: impl AsRef<Path>) -> Result<Self, InterchangeError> {
         let path = path.as_ref();
@@ -183,7 +233,7 @@ impl Project {
     }
 
     /// Generate manifest KDL.
-    fn to_manifest_kdl(&self) -> String {
+    fn to_manifest_kdl(&self, files: &[String]) -> String {
         let mut doc = KdlDocument::new();
 
         let mut project_node = KdlNode::new("project");
@@ -200,10 +250,10 @@ impl Project {
         let mut pages_node = KdlNode::new("pages");
         let pages_children = pages_node.children_mut().get_or_insert_with(KdlDocument::new);
 
-        for (name, _) in &self.pages {
+        for ((name, _), file) in self.pages.iter().zip(files) {
             let mut page_node = KdlNode::new("page");
             page_node.push(KdlEntry::new(name.clone()));
-            page_node.push(KdlEntry::new_prop("file", format!("pages/{}.kdl", name)));
+            page_node.push(KdlEntry::new_prop("file", format!("pages/{}", file)));
             pages_children.nodes_mut().push(page_node);
         }
 
@@ -214,6 +264,24 @@ impl Project {
     }
 }
 
+/// A page name reduced to lowercase letters, digits, `-` and `_`.
+fn file_stem(name: &str) -> String {
+    let mut stem = String::new();
+    for c in name.trim().chars() {
+        if c.is_ascii_alphanumeric() || c == '_' {
+            stem.push(c.to_ascii_lowercase());
+        } else if !stem.is_empty() && !stem.ends_with('-') {
+            stem.push('-');
+        }
+    }
+    let stem = stem.trim_end_matches('-');
+    if stem.is_empty() {
+        "page".to_string()
+    } else {
+        stem.to_string()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -267,4 +335,46 @@ mod tests {
         // Clean up
         let _ = std::fs::remove_dir_all(&temp_dir);
     }
+
+    #[test]
+    fn test_project_pages_roundtrip() {
+        let temp_dir = std::env::temp_dir().join("luna_test_project_pages");
+        let _ = std::fs::remove_dir_all(&temp_dir);
+        let project_path = temp_dir.join("pages.luna");
+
+        let rect = Shape::rectangle(Vec2::ZERO, Vec2::new(10.0, 10.0));
+        let ellipse = Shape::ellipse(Vec2::ZERO, Vec2::new(20.0, 20.0));
+        let project = Project::from_pages(
+            "Pages",
+            vec![
+                ("Cover".to_string(), Document::new(vec![rect])),
+                ("Sign in / up".to_string(), Document::new(vec![])),
+                ("cover".to_string(), Document::new(vec![ellipse])),
+                ("".to_string(), Document::new(vec![])),
+            ],
+        );
+        project.save(&project_path).expect("Failed to save");
+
+        for file in ["cover.kdl", "sign-in-up.kdl", "cover-2.kdl", "page.kdl"] {
+            assert!(project_path.join("pages").join(file).exists(), "missing {}", file);
+        }
+
+        let loaded = Project::load(&project_path).expect("Failed to load");
+        let names: Vec<&str> = loaded.pages.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["Cover", "Sign in / up", "cover", ""]);
+        assert_eq!(loaded.pages[0].1.shapes[0].kind, node::ShapeKind::Rectangle);
+        assert_eq!(loaded.pages[2].1.shapes[0].kind, node::ShapeKind::Ellipse);
+
+        // Saving fewer pages leaves no files behind for the ones removed
+        let mut trimmed = loaded;
+        trimmed.pages.truncate(1);
+        trimmed.save(&project_path).expect("Failed to save");
+        let files: Vec<_> = std::fs::read_dir(project_path.join("pages"))
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(files, ["cover.kdl"]);
+
+        let _ = std::fs::remove_dir_all(&temp_dir);
+    }
 }
@@ -0,0 +1,642 @@
+//! SVG export.
+//!
+//! Writes shapes to a standalone SVG document: a whole page, a selection,
+//! or a single frame with everything inside it. The SVG mirrors the shape
+//! tree, one group per shape with children, each transformed into its
+//! parent's box, so frames stay recognisable when the file is opened in
+//! another editor.
+//!
+//! A shape with one fill and one stroke is one element; one with more
+//! stacks an element for each, in paint order. Strokes on rectangles,
+//! frames and ellipses are moved in or out of the box to lie where the
+//! canvas draws them. SVG only strokes paths centered, so path strokes are
+//! written that way. Text is set one line per paragraph, since wrapping
+//! needs the canvas's font system. SVG has no angular gradients, so fills
+//! with one are filled with its first color.
+//!
+//! A shape's opacity and blend mode go on its group, or on its one element
+//! when it has neither children nor a stack, so it's faded and blended as a
+//! whole rather than layer by layer.
+
+use glam::Vec2;
+use gpui::Hsla;
+use crate::blend_mode_name;
+use node::{
+    BlendMode, Fill, GradientKind, Placement, Shape, ShapeId, ShapeKind, Stroke, StrokeAlign, StrokeCap,
+    StrokeJoin, TextAlign,
+};
+use std::collections::HashSet;
+use std::fmt::Write;
+
+/// Export `ids` and their children as an SVG document.
+///
+/// Shapes keep their paint order, and a shape inside another exported shape
+/// is only drawn once, as part of its ancestor. The view box fits what the
+/// exported shapes draw. Unknown ids and hidden shapes are left out.
+pub fn export_svg(shapes: &[Shape], ids: &[ShapeId]) -> String {
+    let wanted: HashSet<ShapeId> = ids.iter().copied().collect();
+    let mut roots = Vec::new();
+    for root in shapes.iter().filter(|s| s.parent.is_none()) {
+        collect_roots(root, shapes, &wanted, &mut roots);
+    }
+
+    let mut writer = SvgWriter {
+        shapes,
+        body: String::new(),
+        defs: String::new(),
+        clips: 0,
+        gradients: 0,
+    };
+    let mut bounds: Option<(Vec2, Vec2)> = None;
+    for root in &roots {
+        let placement = root.world_placement(shapes);
+        writer.write_shape(root, &placement, 1);
+        extend_bounds(root, &placement, shapes, &mut bounds);
+    }
+
+    let (min, max) = bounds.unwrap_or((Vec2::ZERO, Vec2::ZERO));
+    let size = max - min;
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"{} {} {} {}\">\n",
+        num(size.x),
+        num(size.y),
+        num(min.x),
+        num(min.y),
+        num(size.x),
+        num(size.y),
+    );
+    if !writer.defs.is_empty() {
+        svg.push_str("  <defs>\n");
+        svg.push_str(&writer.defs);
+        svg.push_str("  </defs>\n");
+    }
+    svg.push_str(&writer.body);
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// The outermost shapes under `shape`, itself included, that are in `wanted`.
+fn collect_roots<'a>(
+    shape: &'a Shape,
+    shapes: &'a [Shape],
+    wanted: &HashSet<ShapeId>,
+    roots: &mut Vec<&'a Shape>,
+) {
+    if !shape.visible {
+        return;
+    }
+    if wanted.contains(&shape.id) {
+        roots.push(shape);
+        return;
+    }
+    for child in children(shape, shapes) {
+        collect_roots(child, shapes, wanted, roots);
+    }
+}
+
+/// The visible children of `shape`, in paint order.
+fn children<'a>(shape: &'a Shape, shapes: &'a [Shape]) -> impl Iterator<Item = &'a Shape> {
+    shape
+        .children
+        .iter()
+        .filter_map(|id| shapes.iter().find(|s| s.id == *id))
+        .filter(|child| child.visible)
+}
+
+/// Grow `bounds` by what `shape` draws at `placement`. Children of a
+/// clipping frame can't draw outside it, so they're skipped.
+fn extend_bounds(shape: &Shape, placement: &Placement, shapes: &[Shape], bounds: &mut Option<(Vec2, Vec2)>) {
+    let (min, max) = placement.bounds();
+    // Strokes centered on or outside the edge fall outside the box
+    let outset = shape
+        .strokes
+        .iter()
+        .filter(|stroke| stroke.visible)
+        .map(|stroke| stroke.width / 2.0 - stroke_inset(shape, stroke))
+        .fold(0.0, f32::max);
+    let (min, max) = (min.0 - outset, max.0 + outset);
+    *bounds = Some(match *bounds {
+        Some((lo, hi)) => (lo.min(min), hi.max(max)),
+        None => (min, max),
+    });
+    if shape.clip_children {
+        return;
+    }
+    for child in children(shape, shapes) {
+        extend_bounds(child, &placement.place_child(&child.local_placement()), shapes, bounds);
+    }
+}
+
+struct SvgWriter<'a> {
+    shapes: &'a [Shape],
+    body: String,
+    defs: String,
+    /// Clip paths written so far, for unique ids.
+    clips: usize,
+    /// Gradients written so far, likewise.
+    gradients: usize,
+}
+
+impl SvgWriter<'_> {
+    /// Write `shape` at `placement`, in the coordinates of the enclosing
+    /// group. Children follow inside a group in the shape's own box.
+    fn write_shape(&mut self, shape: &Shape, placement: &Placement, depth: usize) {
+        let transform = transform_attr(placement);
+        let indent = "  ".repeat(depth);
+        let effect = effect_attrs(shape);
+        let children: Vec<&Shape> = children(shape, self.shapes).collect();
+
+        if children.is_empty() && (effect.is_empty() || !is_stacked(shape)) {
+            for element in self.geometry(shape, &format!("{transform}{effect}")) {
+                let _ = writeln!(self.body, "{indent}{element}");
+            }
+            return;
+        }
+
+        let _ = writeln!(self.body, "{indent}<g{transform}{effect}>");
+        for element in self.geometry(shape, "") {
+            let _ = writeln!(self.body, "{indent}  {element}");
+        }
+        let mut child_depth = depth + 1;
+        let clip = shape.clip_children && !children.is_empty();
+        if clip {
+            self.clips += 1;
+            let id = format!("clip-{}", self.clips);
+            let _ = writeln!(
+                self.defs,
+                "    <clipPath id=\"{id}\">{}</clipPath>",
+                rect_element(shape.effective_size().0, 0.0, shape.corner_radius, "")
+            );
+            let _ = writeln!(self.body, "{indent}  <g clip-path=\"url(#{id})\">");
+            child_depth += 1;
+        }
+        for child in children {
+            self.write_shape(child, &child.local_placement(), child_depth);
+        }
+        if clip {
+            let _ = writeln!(self.body, "{indent}  </g>");
+        }
+        let _ = writeln!(self.body, "{indent}</g>");
+    }
+
+    /// The elements drawing `shape` itself in its box, bottom first.
+    fn geometry(&mut self, shape: &Shape, transform: &str) -> Vec<String> {
+        if shape.kind == ShapeKind::Text {
+            return text_element(shape, transform).into_iter().collect();
+        }
+        let size = shape.effective_size().0;
+        let fills: Vec<&Fill> = shape.fills.iter().filter(|fill| fill.visible).collect();
+        let strokes: Vec<&Stroke> = shape.strokes.iter().filter(|stroke| stroke.visible).collect();
+        match (fills.as_slice(), strokes.as_slice()) {
+            ([], []) => return Vec::new(),
+            // One element can take a fill and a stroke
+            (fill @ ([] | [_]), stroke @ ([] | [_])) => {
+                let mut paint = match fill.first() {
+                    Some(fill) => self.fill_attrs(fill, size),
+                    None => " fill=\"none\"".to_string(),
+                };
+                let mut inset = 0.0;
+                if let Some(stroke) = stroke.first() {
+                    paint.push_str(&stroke_attrs(stroke));
+                    inset = stroke_inset(shape, stroke);
+                }
+                let attrs = format!("{transform}{paint}");
+                return outline_element(shape, inset, &attrs).into_iter().collect();
+            }
+            _ => {}
+        }
+        let mut elements = Vec::new();
+        for fill in fills {
+            let paint = self.fill_attrs(fill, size);
+            elements.extend(outline_element(shape, 0.0, &format!("{transform}{paint}")));
+        }
+        for stroke in strokes {
+            let paint = format!("{transform} fill=\"none\"{}", stroke_attrs(stroke));
+            elements.extend(outline_element(shape, stroke_inset(shape, stroke), &paint));
+        }
+        elements
+    }
+
+    /// The fill attributes for a box of `size`. Gradients are written to
+    /// the defs in the box's units, scaled up to its size.
+    fn fill_attrs(&mut self, fill: &Fill, size: Vec2) -> String {
+        let gradient = match fill.gradient() {
+            Some(gradient) if gradient.kind != GradientKind::Angular => gradient,
+            _ => return color_attrs("fill", fill.color().opacity(fill.opacity)),
+        };
+        self.gradients += 1;
+        let id = format!("gradient-{}", self.gradients);
+        let (start, end) = (gradient.start, gradient.end);
+        let (element, attrs) = if gradient.kind == GradientKind::Linear {
+            let attrs = format!(
+                "x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"",
+                num(start.x),
+                num(start.y),
+                num(end.x),
+                num(end.y)
+            );
+            ("linearGradient", attrs)
+        } else {
+            let attrs = format!(
+                "cx=\"{}\" cy=\"{}\" r=\"{}\"",
+                num(start.x),
+                num(start.y),
+                num(start.distance(end))
+            );
+            ("radialGradient", attrs)
+        };
+        let _ = writeln!(
+            self.defs,
+            "    <{element} id=\"{id}\" {attrs} gradientUnits=\"userSpaceOnUse\" \
+             gradientTransform=\"scale({} {})\">",
+            num(size.x),
+            num(size.y)
+        );
+        for stop in &gradient.stops {
+            let (color, opacity) = hex(stop.color);
+            let _ = write!(self.defs, "      <stop offset=\"{}\" stop-color=\"{color}\"", num(stop.offset));
+            if opacity < 1.0 {
+                let _ = write!(self.defs, " stop-opacity=\"{}\"", num(opacity));
+            }
+            self.defs.push_str("/>\n");
+        }
+        let _ = writeln!(self.defs, "    </{element}>");
+        let mut attrs = format!(" fill=\"url(#{id})\"");
+        if fill.opacity < 1.0 {
+            let _ = write!(attrs, " fill-opacity=\"{}\"", num(fill.opacity));
+        }
+        attrs
+    }
+}
+
+/// Whether `shape` is drawn as more than one element; see
+/// [`SvgWriter::geometry`].
+fn is_stacked(shape: &Shape) -> bool {
+    let fills = shape.fills.iter().filter(|fill| fill.visible).count();
+    let strokes = shape.strokes.iter().filter(|stroke| stroke.visible).count();
+    shape.kind != ShapeKind::Text && (fills > 1 || strokes > 1)
+}
+
+/// The shape's opacity and blend mode, which apply to whatever element or
+/// group they're put on as one.
+fn effect_attrs(shape: &Shape) -> String {
+    let mut attrs = String::new();
+    if shape.opacity < 1.0 {
+        let _ = write!(attrs, " opacity=\"{}\"", num(shape.opacity.max(0.0)));
+    }
+    if shape.blend_mode != BlendMode::Normal {
+        let _ = write!(attrs, " style=\"mix-blend-mode:{}\"", blend_mode_name(shape.blend_mode));
+    }
+    attrs
+}
+
+/// The element tracing `shape`'s edge with `attrs`, moved `inset` into its
+/// box, or None for a path without points.
+fn outline_element(shape: &Shape, inset: f32, attrs: &str) -> Option<String> {
+    let size = shape.effective_size().0;
+    match shape.kind {
+        ShapeKind::Ellipse => Some(ellipse_element(size, inset, attrs)),
+        ShapeKind::Path => {
+            let d = shape.sized_path()?.to_bez_path().to_svg();
+            Some(format!("<path{attrs} d=\"{d}\"/>"))
+        }
+        ShapeKind::Rectangle | ShapeKind::Frame | ShapeKind::Text => {
+            Some(rect_element(size, inset, shape.corner_radius, attrs))
+        }
+    }
+}
+
+/// How far into the box a stroke's centre line is moved for it to lie
+/// where the canvas draws it.
+fn stroke_inset(shape: &Shape, stroke: &Stroke) -> f32 {
+    match (shape.kind, stroke.align) {
+        (ShapeKind::Path, _) | (_, StrokeAlign::Center) => 0.0,
+        (_, StrokeAlign::Inside) => stroke.width / 2.0,
+        (_, StrokeAlign::Outside) => -stroke.width / 2.0,
+    }
+}
+
+/// A stroke's attributes, leaving out SVG's defaults.
+fn stroke_attrs(stroke: &Stroke) -> String {
+    let mut attrs = color_attrs("stroke", stroke.color.opacity(stroke.opacity));
+    let _ = write!(attrs, " stroke-width=\"{}\"", num(stroke.width));
+    if let Some(dash) = stroke.dash_pattern() {
+        let dash: Vec<String> = dash.iter().map(|length| num(*length)).collect();
+        let _ = write!(attrs, " stroke-dasharray=\"{}\"", dash.join(" "));
+    }
+    let cap = match stroke.cap {
+        StrokeCap::Butt => None,
+        StrokeCap::Round => Some("round"),
+        StrokeCap::Square => Some("square"),
+    };
+    if let Some(cap) = cap {
+        let _ = write!(attrs, " stroke-linecap=\"{cap}\"");
+    }
+    let join = match stroke.join {
+        StrokeJoin::Miter => None,
+        StrokeJoin::Round => Some("round"),
+        StrokeJoin::Bevel => Some("bevel"),
+    };
+    if let Some(join) = join {
+        let _ = write!(attrs, " stroke-linejoin=\"{join}\"");
+    }
+    attrs
+}
+
+/// A ` transform` attribute moving box-local coordinates into place, or
+/// nothing for a box at the origin.
+fn transform_attr(placement: &Placement) -> String {
+    if !placement.is_turned() {
+        let position = placement.position.0;
+        if position == Vec2::ZERO {
+            return String::new();
+        }
+        return format!(" transform=\"translate({} {})\"", num(position.x), num(position.y));
+    }
+    let affine = placement.affine();
+    let (x, y, t) = (affine.matrix2.x_axis, affine.matrix2.y_axis, affine.translation);
+    format!(
+        " transform=\"matrix({} {} {} {} {} {})\"",
+        num(x.x),
+        num(x.y),
+        num(y.x),
+        num(y.y),
+        num(t.x),
+        num(t.y)
+    )
+}
+
+/// A rect filling a box of `size`, inset by `inset` on every side so a
+/// stroke of twice that width lands inside the box, or outset by a
+/// negative one so it lands outside.
+fn rect_element(size: Vec2, inset: f32, radius: f32, attrs: &str) -> String {
+    let inner = (size - Vec2::splat(inset * 2.0)).max(Vec2::ZERO);
+    let mut rect = format!("<rect{attrs}");
+    if inset != 0.0 {
+        let _ = write!(rect, " x=\"{}\" y=\"{}\"", num(inset), num(inset));
+    }
+    let _ = write!(rect, " width=\"{}\" height=\"{}\"", num(inner.x), num(inner.y));
+    // Square corners stay square
+    let radius = if radius > 0.0 { (radius - inset).max(0.0) } else { 0.0 };
+    if radius > 0.0 {
+        let _ = write!(rect, " rx=\"{}\"", num(radius));
+    }
+    rect.push_str("/>");
+    rect
+}
+
+/// An ellipse filling a box of `size`, inset like [`rect_element`].
+fn ellipse_element(size: Vec2, inset: f32, attrs: &str) -> String {
+    let center = size / 2.0;
+    let radii = (center - Vec2::splat(inset)).max(Vec2::ZERO);
+    format!(
+        "<ellipse{attrs} cx=\"{}\" cy=\"{}\" rx=\"{}\" ry=\"{}\"/>",
+        num(center.x),
+        num(center.y),
+        num(radii.x),
+        num(radii.y)
+    )
+}
+
+/// `name="#rrggbb"`, with a `name-opacity` for translucent colors.
+fn color_attrs(name: &str, color: Hsla) -> String {
+    let (hex, opacity) = hex(color);
+    let mut attrs = format!(" {name}=\"{hex}\"");
+    if opacity < 1.0 {
+        let _ = write!(attrs, " {name}-opacity=\"{}\"", num(opacity));
+    }
+    attrs
+}
+
+/// `#rrggbb` and the opacity of a color.
+fn hex(color: Hsla) -> (String, f32) {
+    let rgba = color.to_rgb();
+    let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+    let hex = format!("#{:02x}{:02x}{:02x}", channel(rgba.r), channel(rgba.g), channel(rgba.b));
+    (hex, rgba.a)
+}
+
+/// A text element with one line per paragraph. Each line is centered in
+/// its line box, as the canvas sets it.
+fn text_element(shape: &Shape, transform: &str) -> Option<String> {
+    let text = shape.text.as_ref()?;
+    if text.content.is_empty() {
+        return None;
+    }
+    let color = shape.text_color()?;
+    let width = shape.effective_size().width();
+    let (anchor, x) = match text.align {
+        TextAlign::Left => ("start", 0.0),
+        TextAlign::Center => ("middle", width / 2.0),
+        TextAlign::Right => ("end", width),
+    };
+
+    let mut element = format!(
+        "<text{transform} font-family=\"{}\" font-size=\"{}\"",
+        escape(&text.font_family),
+        num(text.font_size)
+    );
+    if text.font_weight != 400 {
+        let _ = write!(element, " font-weight=\"{}\"", text.font_weight);
+    }
+    if text.letter_spacing != 0.0 {
+        let _ = write!(element, " letter-spacing=\"{}\"", num(text.letter_spacing));
+    }
+    if anchor != "start" {
+        let _ = write!(element, " text-anchor=\"{anchor}\"");
+    }
+    element.push_str(" dominant-baseline=\"central\"");
+    element.push_str(&color_attrs("fill", color));
+    element.push('>');
+
+    let line_height = text.font_size * text.line_height;
+    for (index, line) in text.content.split('\n').enumerate() {
+        let y = (index as f32 + 0.5) * line_height;
+        let _ = write!(element, "<tspan x=\"{}\" y=\"{}\">{}</tspan>", num(x), num(y), escape(line));
+    }
+    element.push_str("</text>");
+    Some(element)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// A number as short as it can be written, without float noise such as
+/// `0.30000001` or `-0`.
+fn num(value: f32) -> String {
+    let rounded = (value * 1000.0).round() / 1000.0;
+    if rounded == 0.0 {
+        return "0".to_string();
+    }
+    rounded.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use node::{Gradient, Paint, Text};
+
+    fn red() -> Hsla {
+        Hsla { h: 0.0, s: 1.0, l: 0.5, a: 1.0 }
+    }
+
+    /// A frame at (100, 50) and an ellipse poking out of its right edge,
+    /// frame first.
+    fn frame_with_child(clip: bool) -> Vec<Shape> {
+        let mut child = Shape::ellipse(Vec2::new(150.0, 20.0), Vec2::new(100.0, 40.0)).with_fill(red());
+        let frame = Shape::frame(Vec2::new(100.0, 50.0), Vec2::new(200.0, 100.0))
+            .with_fill(red())
+            .with_clip_children(clip)
+            .with_child(&mut child);
+        vec![frame, child]
+    }
+
+    // === Shapes ===
+
+    #[test]
+    fn rect_keeps_its_stroke_and_corners_inside_the_box() {
+        let rect = Shape::rectangle(Vec2::new(10.0, 20.0), Vec2::new(100.0, 50.0))
+            .with_stroke(Hsla { h: 0.0, s: 0.0, l: 0.0, a: 0.5 }, 4.0)
+            .with_corner_radius(8.0);
+        let svg = export_svg(std::slice::from_ref(&rect), &[rect.id]);
+        assert!(svg.contains("viewBox=\"10 20 100 50\""));
+        assert!(svg.contains(
+            "<rect transform=\"translate(10 20)\" fill=\"none\" stroke=\"#000000\" stroke-opacity=\"0.5\" \
+             stroke-width=\"4\" x=\"2\" y=\"2\" width=\"96\" height=\"46\" rx=\"6\"/>"
+        ));
+    }
+
+    #[test]
+    fn stacked_fills_and_strokes_get_an_element_each() {
+        let mut rect = Shape::rectangle(Vec2::ZERO, Vec2::new(100.0, 50.0)).with_fill(red());
+        let mut faded = Fill::new(gpui::white());
+        faded.opacity = 0.5;
+        rect.fills.push(faded);
+        let stroke = &mut rect.strokes[0];
+        stroke.align = StrokeAlign::Outside;
+        stroke.dash = vec![4.0, 2.0];
+        stroke.cap = StrokeCap::Round;
+        let svg = export_svg(std::slice::from_ref(&rect), &[rect.id]);
+        assert!(svg.contains("viewBox=\"-2 -2 104 54\""));
+        assert!(svg.contains("<rect fill=\"#ff0000\" width=\"100\" height=\"50\"/>"));
+        assert!(svg.contains("<rect fill=\"#ffffff\" fill-opacity=\"0.5\" width=\"100\""));
+        assert!(svg.contains(
+            "<rect fill=\"none\" stroke=\"#000000\" stroke-width=\"2\" stroke-dasharray=\"4 2\" \
+             stroke-linecap=\"round\" x=\"-1\" y=\"-1\" width=\"102\" height=\"52\"/>"
+        ));
+    }
+
+    #[test]
+    fn opacity_and_blending_apply_to_the_whole_shape() {
+        let mut shapes = frame_with_child(false);
+        shapes[0].opacity = 0.5;
+        shapes[1].blend_mode = BlendMode::Multiply;
+        let svg = export_svg(&shapes, &[shapes[0].id]);
+        assert!(svg.contains("<g transform=\"translate(100 50)\" opacity=\"0.5\">"));
+        assert!(svg.contains("<ellipse transform=\"translate(150 20)\" style=\"mix-blend-mode:multiply\""));
+
+        // A stack is faded as one, not fill by fill
+        let mut rect = Shape::rectangle(Vec2::ZERO, Vec2::new(10.0, 10.0)).with_fill(red());
+        rect.fills.push(Fill::new(gpui::white()));
+        rect.opacity = 0.25;
+        let svg = export_svg(std::slice::from_ref(&rect), &[rect.id]);
+        assert!(svg.contains("<g opacity=\"0.25\">\n    <rect fill=\"#ff0000\""));
+    }
+
+    #[test]
+    fn rotated_shape_is_placed_with_a_matrix() {
+        let mut rect = Shape::rectangle(Vec2::ZERO, Vec2::new(100.0, 100.0)).with_fill(red());
+        rect.rotation = 90.0;
+        let svg = export_svg(std::slice::from_ref(&rect), &[rect.id]);
+        assert!(svg.contains("transform=\"matrix(0 1 -1 0 100 0)\""));
+        assert!(svg.contains("fill=\"#ff0000\""));
+    }
+
+    #[test]
+    fn gradients_are_defined_in_the_box_units() {
+        let mut rect = Shape::rectangle(Vec2::ZERO, Vec2::new(100.0, 50.0));
+        let mut gradient = Gradient::new(GradientKind::Radial, red(), Hsla { a: 0.5, ..red() });
+        rect.fills = vec![Fill::from(Paint::Gradient(gradient.clone()))];
+        let svg = export_svg(std::slice::from_ref(&rect), &[rect.id]);
+        assert!(svg.contains(
+            "<radialGradient id=\"gradient-1\" cx=\"0.5\" cy=\"0.5\" r=\"0.5\" \
+             gradientUnits=\"userSpaceOnUse\" gradientTransform=\"scale(100 50)\">"
+        ));
+        assert!(svg.contains("<stop offset=\"1\" stop-color=\"#ff0000\" stop-opacity=\"0.5\"/>"));
+        assert!(svg.contains("<rect fill=\"url(#gradient-1)\""));
+
+        gradient.kind = GradientKind::Angular;
+        rect.fills = vec![Fill::from(Paint::Gradient(gradient))];
+        let svg = export_svg(std::slice::from_ref(&rect), &[rect.id]);
+        assert!(!svg.contains("<defs>"));
+        assert!(svg.contains("fill=\"#ff0000\""));
+    }
+
+    #[test]
+    fn text_is_escaped_and_split_into_lines() {
+        let mut text = Shape::text(Vec2::ZERO, Text::new("a < b\nc"));
+        text.fills = vec![Fill::new(red())];
+        let svg = export_svg(std::slice::from_ref(&text), &[text.id]);
+        assert!(svg.contains("<tspan x=\"0\" y=\"9.6\">a &lt; b</tspan><tspan x=\"0\" y=\"28.8\">c</tspan>"));
+    }
+
+    // === Frames ===
+
+    #[test]
+    fn frame_children_are_nested_in_its_box() {
+        let shapes = frame_with_child(false);
+        let svg = export_svg(&shapes, &[shapes[0].id]);
+        assert!(svg.contains("<g transform=\"translate(100 50)\">"));
+        assert!(svg.contains("<ellipse transform=\"translate(150 20)\""));
+        // The child pokes out of the unclipped frame, and the view box follows
+        assert!(svg.contains("viewBox=\"100 50 250 100\""));
+        assert!(!svg.contains("clipPath"));
+    }
+
+    #[test]
+    fn clipping_frame_clips_children_to_its_box() {
+        let shapes = frame_with_child(true);
+        let svg = export_svg(&shapes, &[shapes[0].id]);
+        assert!(svg.contains("<clipPath id=\"clip-1\"><rect width=\"200\" height=\"100\"/></clipPath>"));
+        assert!(svg.contains("<g clip-path=\"url(#clip-1)\">"));
+        assert!(svg.contains("viewBox=\"100 50 200 100\""));
+    }
+
+    // === Selections ===
+
+    #[test]
+    fn selected_child_is_exported_in_place_and_only_once() {
+        let shapes = frame_with_child(false);
+        let child_only = export_svg(&shapes, &[shapes[1].id]);
+        assert!(child_only.contains("viewBox=\"250 70 100 40\""));
+        assert!(child_only.contains("<ellipse transform=\"translate(250 70)\""));
+
+        let both = export_svg(&shapes, &[shapes[1].id, shapes[0].id]);
+        assert_eq!(both.matches("<ellipse").count(), 1);
+    }
+
+    #[test]
+    fn nothing_selected_exports_an_empty_document() {
+        let shapes = frame_with_child(false);
+        let svg = export_svg(&shapes, &[]);
+        assert!(svg.contains("viewBox=\"0 0 0 0\""));
+        assert!(!svg.contains("<g"));
+    }
+
+    #[test]
+    fn hidden_shapes_are_left_out() {
+        let mut shapes = frame_with_child(false);
+        shapes[1].visible = false;
+        let svg = export_svg(&shapes, &[shapes[0].id, shapes[1].id]);
+        assert!(!svg.contains("<ellipse"));
+        assert!(svg.contains("viewBox=\"100 50 200 100\""));
+
+        shapes[0].visible = false;
+        let svg = export_svg(&shapes, &[shapes[0].id]);
+        assert!(svg.contains("viewBox=\"0 0 0 0\""));
+    }
+}
@@ -0,0 +1,1029 @@
+//! SVG import.
+//!
+//! Maps the basic SVG vocabulary onto shapes: groups and nested `svg`
+//! elements become frames fitted to their content, `rect`, `circle` and
+//! `ellipse` become rectangles and ellipses, and `path`, `line`, `polyline`
+//! and `polygon` become paths. Fills, strokes and transforms carry over.
+//! Anything else (text, gradients, stylesheets, filters and so on) is
+//! skipped and reported in the import's warnings rather than lost quietly.
+//!
+//! Transforms are baked in. A box keeps its rotation and flip and takes any
+//! scale into its size; a skewed box has no equivalent and becomes a path.
+//! SVG centers strokes on the outline while the canvas draws them inside
+//! rectangles and ellipses, so those boxes grow by half the stroke on each
+//! side to keep their outer edge in place.
+
+use crate::InterchangeError;
+use glam::Vec2;
+use gpui::{Hsla, Rgba};
+use kurbo::{Affine, BezPath, Ellipse, PathEl, Point, Rect, RoundedRect, Shape as _};
+use node::{normalize_degrees, CanvasPoint, CanvasSize, Fill, PathData, PathVertex, Shape, ShapeKind, Stroke, StrokeAlign};
+use resvg::usvg::roxmltree::{self, Node};
+use std::str::FromStr;
+use svgtypes::{Align, AspectRatio, Length, LengthUnit, Paint, PointsParser, ViewBox};
+
+const SVG_NAMESPACE: &str = "http://www.w3.org/2000/svg";
+
+/// Tolerance when flattening curves that kurbo approximates, such as
+/// rounded corners of skewed rectangles, in SVG units.
+const CURVE_ACCURACY: f64 = 0.01;
+
+/// Elements that only define things for others to reference. They draw
+/// nothing themselves, and references to them are reported where used.
+const DEFINITIONS: &[&str] = &[
+    "clipPath",
+    "defs",
+    "desc",
+    "filter",
+    "linearGradient",
+    "marker",
+    "mask",
+    "metadata",
+    "pattern",
+    "radialGradient",
+    "symbol",
+    "title",
+];
+
+/// Shapes read from an SVG document.
+#[derive(Clone, Debug)]
+pub struct SvgImport {
+    /// Parents before their children. The first shape is a frame at the
+    /// origin, the size of the document's viewport, holding the rest.
+    pub shapes: Vec<Shape>,
+    /// What the document used that couldn't be imported, each listed once.
+    pub warnings: Vec<String>,
+}
+
+/// Import an SVG document as a tree of shapes.
+pub fn import_svg(source: &str) -> Result<SvgImport, InterchangeError> {
+    let document = roxmltree::Document::parse(source).map_err(|e| InterchangeError::Parse(e.to_string()))?;
+    let root = document.root_element();
+    if !(is_svg(root) && root.tag_name().name() == "svg") {
+        return Err(InterchangeError::InvalidStructure(
+            "Expected an <svg> root element".into(),
+        ));
+    }
+
+    let mut importer = Importer {
+        warnings: Vec::new(),
+        viewport: Vec2::splat(100.0),
+    };
+    let view_box = importer.view_box(root);
+    let size = importer.viewport_size(root, view_box);
+    importer.viewport = match (view_box, size) {
+        (Some(view_box), _) => Vec2::new(view_box.w as f32, view_box.h as f32),
+        (None, Some(size)) => size,
+        (None, None) => importer.viewport,
+    };
+
+    let transform = match (view_box, size) {
+        (Some(view_box), Some(size)) => view_box_transform(view_box, size, importer.aspect_ratio(root)),
+        _ => Affine::IDENTITY,
+    };
+    let context = importer.context(root, &Context::new(transform));
+    let children = importer.children(root, &context);
+
+    let mut frame = match size {
+        Some(size) => Item::frame(Shape::frame(Vec2::ZERO, size), children),
+        // Without a viewport the content decides the frame, and nothing
+        // lies outside it to clip
+        None if !children.is_empty() => Item::fitted(children),
+        None => Item::frame(Shape::frame(Vec2::ZERO, Vec2::ZERO), children),
+    };
+    frame.shape.clip_children = size.is_some();
+
+    let mut shapes = Vec::new();
+    frame.flatten(None, &mut shapes);
+    Ok(SvgImport {
+        shapes,
+        warnings: importer.warnings,
+    })
+}
+
+fn is_svg(node: Node) -> bool {
+    // Hand-written files often leave out the namespace
+    matches!(node.tag_name().namespace(), None | Some(SVG_NAMESPACE))
+}
+
+/// A shape in the document's root space, with the shapes it holds.
+struct Item {
+    shape: Shape,
+    children: Vec<Item>,
+}
+
+impl Item {
+    fn leaf(shape: Shape) -> Self {
+        Self {
+            shape,
+            children: Vec::new(),
+        }
+    }
+
+    /// `shape` as an unfilled, unstroked frame holding `children`.
+    fn frame(mut shape: Shape, children: Vec<Item>) -> Self {
+        shape.fills.clear();
+        shape.strokes.clear();
+        Self { shape, children }
+    }
+
+    /// A frame just large enough for `children`, which mustn't be empty.
+    /// It doesn't clip, as a group's content can't overflow it.
+    fn fitted(children: Vec<Item>) -> Self {
+        let (min, max) = children
+            .iter()
+            .map(|child| child.shape.local_placement().bounds())
+            .fold((Vec2::MAX, Vec2::MIN), |(min, max), (lo, hi)| {
+                (min.min(lo.0), max.max(hi.0))
+            });
+        let frame = Shape::frame(min, max - min).with_clip_children(false);
+        Self::frame(frame, children)
+    }
+
+    /// Append this item and everything under it to `shapes`, moving each
+    /// shape into its parent's space. `parent` is the parent's id and its
+    /// position in root space.
+    fn flatten(self, parent: Option<(node::ShapeId, Vec2)>, shapes: &mut Vec<Shape>) {
+        let Item { mut shape, children } = self;
+        let origin = shape.position.0;
+        if let Some((id, parent_origin)) = parent {
+            shape.parent = Some(id);
+            shape.position = CanvasPoint(origin - parent_origin);
+        }
+        shape.children = children.iter().map(|child| child.shape.id).collect();
+        let id = shape.id;
+        shapes.push(shape);
+        for child in children {
+            child.flatten(Some((id, origin)), shapes);
+        }
+    }
+}
+
+/// Inherited state while walking the document.
+#[derive(Clone, Copy)]
+struct Context {
+    /// Maps the element's user space to root space.
+    transform: Affine,
+    fill: Option<Rgba>,
+    fill_opacity: f32,
+    stroke: Option<Rgba>,
+    stroke_opacity: f32,
+    /// In the element's user space.
+    stroke_width: f64,
+    /// The element's opacity times its ancestors'. Shapes have no opacity
+    /// of their own, so it's folded into fill and stroke alpha.
+    opacity: f32,
+    /// The `color` property, which `currentColor` refers to.
+    color: Rgba,
+}
+
+impl Context {
+    /// SVG's initial values: a black fill and no stroke.
+    fn new(transform: Affine) -> Self {
+        let black = Rgba { r: 0.0, g: 0.0, b: 0.0, a: 1.0 };
+        Self {
+            transform,
+            fill: Some(black),
+            fill_opacity: 1.0,
+            stroke: None,
+            stroke_opacity: 1.0,
+            stroke_width: 1.0,
+            opacity: 1.0,
+            color: black,
+        }
+    }
+
+    fn fill(&self) -> Option<Fill> {
+        self.fill.map(|color| Fill::new(hsla(color, self.fill_opacity * self.opacity)))
+    }
+
+    fn stroke(&self) -> Option<Stroke> {
+        // Strokes scale with the geometry; a non-uniform scale is averaged
+        let width = self.stroke_width * self.transform.determinant().abs().sqrt();
+        self.stroke.filter(|_| width > 0.0).map(|color| {
+            Stroke::new(hsla(color, self.stroke_opacity * self.opacity), width as f32)
+        })
+    }
+}
+
+fn hsla(color: Rgba, opacity: f32) -> Hsla {
+    Hsla::from(Rgba {
+        a: color.a * opacity.clamp(0.0, 1.0),
+        ..color
+    })
+}
+
+/// The axis a percentage length is relative to.
+#[derive(Clone, Copy)]
+enum Axis {
+    X,
+    Y,
+    /// The viewport's normalized diagonal, for radii and stroke widths.
+    Diagonal,
+}
+
+struct Importer {
+    warnings: Vec<String>,
+    /// The size percentages refer to, in root user space.
+    viewport: Vec2,
+}
+
+impl Importer {
+    fn warn(&mut self, message: String) {
+        if !self.warnings.contains(&message) {
+            self.warnings.push(message);
+        }
+    }
+
+    fn children(&mut self, node: Node, context: &Context) -> Vec<Item> {
+        node.children().flat_map(|child| self.element(child, context)).collect()
+    }
+
+    /// The items drawn by `node`: none for elements that draw nothing,
+    /// several for a path with more than one subpath.
+    fn element(&mut self, node: Node, parent: &Context) -> Vec<Item> {
+        // Elements outside the SVG namespace are editor metadata
+        if !node.is_element() || !is_svg(node) {
+            return Vec::new();
+        }
+        let name = node.tag_name().name();
+        if DEFINITIONS.contains(&name) {
+            return Vec::new();
+        }
+        if name == "style" {
+            self.warn("Stylesheets aren't supported; only inline styles were applied".into());
+            return Vec::new();
+        }
+        if property(node, "display") == Some("none")
+            || matches!(property(node, "visibility"), Some("hidden" | "collapse"))
+        {
+            return Vec::new();
+        }
+
+        let context = self.context(node, parent);
+        let mut items = match name {
+            "g" | "a" => self.group(node, &context),
+            "svg" => self.nested_svg(node, &context),
+            "rect" => self.rect(node, &context),
+            "circle" | "ellipse" => self.ellipse(node, &context),
+            "line" | "polyline" | "polygon" | "path" => self.path(node, &context),
+            _ => {
+                self.warn(format!("<{}> elements aren't supported and were skipped", name));
+                Vec::new()
+            }
+        };
+        // Design tools export layer names as ids
+        if let Some(id) = node.attribute("id") {
+            for item in &mut items {
+                item.shape.name = id.to_string();
+            }
+        }
+        items
+    }
+
+    /// `parent` updated with what `node` sets or overrides.
+    fn context(&mut self, node: Node, parent: &Context) -> Context {
+        let mut context = *parent;
+        if let Some(value) = node.attribute("transform") {
+            match svgtypes::Transform::from_str(value) {
+                Ok(t) => context.transform = parent.transform * Affine::new([t.a, t.b, t.c, t.d, t.e, t.f]),
+                Err(_) => self.invalid("transform", value),
+            }
+        }
+        if let Some(value) = property(node, "color") {
+            match svgtypes::Color::from_str(value) {
+                Ok(color) => context.color = rgba(color),
+                Err(_) => self.invalid("color", value),
+            }
+        }
+        if let Some(value) = property(node, "fill") {
+            context.fill = self.paint("fill", value, parent.fill, context.color);
+        }
+        if let Some(value) = property(node, "stroke") {
+            context.stroke = self.paint("stroke", value, parent.stroke, context.color);
+        }
+        if let Some(value) = property(node, "stroke-width") {
+            if let Some(width) = self.length(value, Axis::Diagonal) {
+                context.stroke_width = width.max(0.0);
+            }
+        }
+        for (name, target) in [
+            ("fill-opacity", &mut context.fill_opacity),
+            ("stroke-opacity", &mut context.stroke_opacity),
+        ] {
+            if let Some(value) = property(node, name) {
+                if let Some(opacity) = self.opacity(name, value) {
+                    *target = opacity;
+                }
+            }
+        }
+        if let Some(value) = property(node, "opacity") {
+            if let Some(opacity) = self.opacity("opacity", value) {
+                context.opacity *= opacity;
+            }
+        }
+
+        for name in ["clip-path", "mask", "filter"] {
+            if property(node, name).is_some_and(|value| value != "none") {
+                self.warn(format!("`{}` isn't supported and was ignored", name));
+            }
+        }
+        if property(node, "stroke-dasharray").is_some_and(|value| value != "none") {
+            self.warn("Dashed strokes aren't supported and were drawn solid".into());
+        }
+        context
+    }
+
+    /// A fill or stroke color, or None for no paint.
+    fn paint(&mut self, name: &str, value: &str, inherited: Option<Rgba>, current: Rgba) -> Option<Rgba> {
+        match Paint::from_str(value) {
+            Ok(Paint::None) => None,
+            Ok(Paint::Inherit) => inherited,
+            Ok(Paint::CurrentColor) => Some(current),
+            Ok(Paint::Color(color)) => Some(rgba(color)),
+            Ok(Paint::FuncIRI(_, fallback)) => {
+                self.warn(format!("Gradient and pattern {}s aren't supported", name));
+                match fallback {
+                    Some(svgtypes::PaintFallback::Color(color)) => Some(rgba(color)),
+                    Some(svgtypes::PaintFallback::CurrentColor) => Some(current),
+                    Some(svgtypes::PaintFallback::None) | None => None,
+                }
+            }
+            Ok(Paint::ContextFill | Paint::ContextStroke) => {
+                self.warn(format!("Context {}s aren't supported", name));
+                None
+            }
+            Err(_) => {
+                self.invalid(name, value);
+                inherited
+            }
+        }
+    }
+
+    fn opacity(&mut self, name: &str, value: &str) -> Option<f32> {
+        match Length::from_str(value) {
+            Ok(Length {
+                number,
+                unit: LengthUnit::None,
+            }) => Some(number.clamp(0.0, 1.0) as f32),
+            Ok(Length {
+                number,
+                unit: LengthUnit::Percent,
+            }) => Some((number / 100.0).clamp(0.0, 1.0) as f32),
+            _ => {
+                self.invalid(name, value);
+                None
+            }
+        }
+    }
+
+    /// A length in user units. Em and ex assume a 16px font.
+    fn length(&mut self, value: &str, axis: Axis) -> Option<f64> {
+        let Ok(length) = Length::from_str(value) else {
+            self.invalid("length", value);
+            return None;
+        };
+        let unit = match length.unit {
+            LengthUnit::None | LengthUnit::Px => 1.0,
+            LengthUnit::Em => 16.0,
+            LengthUnit::Ex => 8.0,
+            LengthUnit::In => 96.0,
+            LengthUnit::Cm => 96.0 / 2.54,
+            LengthUnit::Mm => 96.0 / 25.4,
+            LengthUnit::Pt => 4.0 / 3.0,
+            LengthUnit::Pc => 16.0,
+            LengthUnit::Percent => {
+                let reference = match axis {
+                    Axis::X => self.viewport.x,
+                    Axis::Y => self.viewport.y,
+                    Axis::Diagonal => (self.viewport.length_squared() / 2.0).sqrt(),
+                };
+                reference as f64 / 100.0
+            }
+        };
+        Some(length.number * unit)
+    }
+
+    /// The length in attribute `name`, or `default` when it's missing.
+    fn attribute_length(&mut self, node: Node, name: &str, axis: Axis, default: f64) -> f64 {
+        node.attribute(name)
+            .and_then(|value| self.length(value, axis))
+            .unwrap_or(default)
+    }
+
+    fn invalid(&mut self, name: &str, value: &str) {
+        self.warn(format!("Ignored invalid {} `{}`", name, value));
+    }
+
+    fn view_box(&mut self, node: Node) -> Option<ViewBox> {
+        let value = node.attribute("viewBox")?;
+        match ViewBox::from_str(value) {
+            Ok(view_box) if view_box.w > 0.0 && view_box.h > 0.0 => Some(view_box),
+            _ => {
+                self.invalid("viewBox", value);
+                None
+            }
+        }
+    }
+
+    fn aspect_ratio(&mut self, node: Node) -> AspectRatio {
+        let Some(value) = node.attribute("preserveAspectRatio") else {
+            return AspectRatio::default();
+        };
+        AspectRatio::from_str(value).unwrap_or_else(|_| {
+            self.invalid("preserveAspectRatio", value);
+            AspectRatio::default()
+        })
+    }
+
+    /// The root's width and height. A missing side follows the view box's
+    /// aspect ratio; with neither side nor view box there is no viewport.
+    fn viewport_size(&mut self, node: Node, view_box: Option<ViewBox>) -> Option<Vec2> {
+        // Percentages refer to whatever embeds the document, which isn't known
+        let mut side = |name: &str| {
+            let value = node.attribute(name)?;
+            match Length::from_str(value) {
+                Ok(length) if length.unit == LengthUnit::Percent => None,
+                _ => self.length(value, Axis::X).filter(|side| *side > 0.0),
+            }
+        };
+        let (width, height) = (side("width"), side("height"));
+        let (width, height) = match (width, height, view_box) {
+            (Some(width), Some(height), _) => (width, height),
+            (Some(width), None, Some(vb)) => (width, width * vb.h / vb.w),
+            (None, Some(height), Some(vb)) => (height * vb.w / vb.h, height),
+            (None, None, Some(vb)) => (vb.w, vb.h),
+            _ => return None,
+        };
+        Some(Vec2::new(width as f32, height as f32))
+    }
+
+    fn group(&mut self, node: Node, context: &Context) -> Vec<Item> {
+        let children = self.children(node, context);
+        if children.is_empty() {
+            return Vec::new();
+        }
+        let own_opacity = property(node, "opacity").and_then(|value| value.parse::<f32>().ok());
+        if children.len() > 1 && own_opacity.is_some_and(|opacity| opacity < 1.0) {
+            self.warn("Group opacity was applied to each shape in the group".into());
+        }
+        vec![Item::fitted(children)]
+    }
+
+    /// A nested `svg` is a group with its own viewport. Its content isn't
+    /// clipped to that viewport.
+    fn nested_svg(&mut self, node: Node, context: &Context) -> Vec<Item> {
+        let x = self.attribute_length(node, "x", Axis::X, 0.0);
+        let y = self.attribute_length(node, "y", Axis::Y, 0.0);
+        let width = self.attribute_length(node, "width", Axis::X, self.viewport.x as f64);
+        let height = self.attribute_length(node, "height", Axis::Y, self.viewport.y as f64);
+        let mut context = *context;
+        context.transform *= Affine::translate((x, y));
+        if let Some(view_box) = self.view_box(node) {
+            let size = Vec2::new(width as f32, height as f32);
+            context.transform *= view_box_transform(view_box, size, self.aspect_ratio(node));
+        }
+        self.group(node, &context)
+    }
+
+    fn rect(&mut self, node: Node, context: &Context) -> Vec<Item> {
+        let x = self.attribute_length(node, "x", Axis::X, 0.0);
+        let y = self.attribute_length(node, "y", Axis::Y, 0.0);
+        let width = self.attribute_length(node, "width", Axis::X, 0.0);
+        let height = self.attribute_length(node, "height", Axis::Y, 0.0);
+        if width <= 0.0 || height <= 0.0 {
+            return Vec::new();
+        }
+        // A missing radius takes the other's value
+        let rx = node.attribute("rx").and_then(|value| self.length(value, Axis::X));
+        let ry = node.attribute("ry").and_then(|value| self.length(value, Axis::Y));
+        let (rx, ry) = match (rx, ry) {
+            (Some(rx), Some(ry)) => (rx, ry),
+            (Some(r), None) | (None, Some(r)) => (r, r),
+            (None, None) => (0.0, 0.0),
+        };
+        let radius = Vec2::new(
+            rx.clamp(0.0, width / 2.0) as f32,
+            ry.clamp(0.0, height / 2.0) as f32,
+        );
+        self.boxed(ShapeKind::Rectangle, Rect::new(x, y, x + width, y + height), radius, context)
+    }
+
+    fn ellipse(&mut self, node: Node, context: &Context) -> Vec<Item> {
+        let cx = self.attribute_length(node, "cx", Axis::X, 0.0);
+        let cy = self.attribute_length(node, "cy", Axis::Y, 0.0);
+        let (rx, ry) = if node.tag_name().name() == "circle" {
+            let r = self.attribute_length(node, "r", Axis::Diagonal, 0.0);
+            (r, r)
+        } else {
+            (
+                self.attribute_length(node, "rx", Axis::X, 0.0),
+                self.attribute_length(node, "ry", Axis::Y, 0.0),
+            )
+        };
+        if rx <= 0.0 || ry <= 0.0 {
+            return Vec::new();
+        }
+        let rect = Rect::new(cx - rx, cy - ry, cx + rx, cy + ry);
+        self.boxed(ShapeKind::Ellipse, rect, Vec2::ZERO, context)
+    }
+
+    /// A rectangle or ellipse spanning `rect` in user space, with corner
+    /// radii `radius`. Falls back to a path under a skewing transform.
+    fn boxed(&mut self, kind: ShapeKind, rect: Rect, radius: Vec2, context: &Context) -> Vec<Item> {
+        let Some(turn) = Turn::of(context.transform) else {
+            let outline = match kind {
+                ShapeKind::Ellipse => Ellipse::from_rect(rect).to_path(CURVE_ACCURACY),
+                _ if radius == Vec2::ZERO => rect.to_path(CURVE_ACCURACY),
+                _ => RoundedRect::from_rect(rect, radius.min_element() as f64).to_path(CURVE_ACCURACY),
+            };
+            return self.outline(outline, false, context);
+        };
+
+        let stroke = context.stroke();
+        let inset = stroke.as_ref().map_or(0.0, |stroke| stroke.width);
+        let center = context.transform * rect.center();
+        let size = Vec2::new(rect.width() as f32, rect.height() as f32) * turn.scale + inset;
+        let mut shape = Shape::new(
+            kind,
+            CanvasPoint(Vec2::new(center.x as f32, center.y as f32) - size / 2.0),
+            CanvasSize(size),
+        );
+        shape.rotation = turn.rotation;
+        shape.flip_x = turn.flip_x;
+        shape.flip_y = turn.flip_y;
+        shape.fills = context.fill().into_iter().collect();
+        shape.strokes = stroke.into_iter().collect();
+
+        let radius = radius * turn.scale;
+        if radius.max_element() > 0.0 {
+            if (radius.x - radius.y).abs() > radius.max_element() * 0.01 {
+                self.warn("Elliptical corners were rounded to circular ones".into());
+            }
+            shape.corner_radius = radius.min_element() + inset / 2.0;
+        }
+        vec![Item::leaf(shape)]
+    }
+
+    fn path(&mut self, node: Node, context: &Context) -> Vec<Item> {
+        let name = node.tag_name().name();
+        let mut context = *context;
+        let outline = match name {
+            "path" => {
+                let data = node.attribute("d").unwrap_or_default();
+                match BezPath::from_svg(data) {
+                    Ok(path) => path,
+                    Err(_) => {
+                        self.invalid("path data", data);
+                        return Vec::new();
+                    }
+                }
+            }
+            "line" => {
+                // Lines enclose nothing to fill
+                context.fill = None;
+                let mut path = BezPath::new();
+                path.move_to((
+                    self.attribute_length(node, "x1", Axis::X, 0.0),
+                    self.attribute_length(node, "y1", Axis::Y, 0.0),
+                ));
+                path.line_to((
+                    self.attribute_length(node, "x2", Axis::X, 0.0),
+                    self.attribute_length(node, "y2", Axis::Y, 0.0),
+                ));
+                path
+            }
+            _ => {
+                let mut path = BezPath::new();
+                for (i, (x, y)) in PointsParser::from(node.attribute("points").unwrap_or_default()).enumerate() {
+                    if i == 0 {
+                        path.move_to((x, y));
+                    } else {
+                        path.line_to((x, y));
+                    }
+                }
+                if name == "polygon" {
+                    path.close_path();
+                }
+                path
+            }
+        };
+        self.outline(outline, true, &context)
+    }
+
+    /// Path shapes for `outline` in user space, one per subpath. Filled
+    /// subpaths can't cut holes in each other once apart, which is reported
+    /// if `report_holes` is set.
+    fn outline(&mut self, mut outline: BezPath, report_holes: bool, context: &Context) -> Vec<Item> {
+        outline.apply_affine(context.transform);
+        let subpaths = split_subpaths(&outline);
+        if report_holes && context.fill.is_some() && subpaths.iter().filter(|path| path.closed).count() > 1 {
+            self.warn("Compound paths were split into one shape per subpath, which fills any holes".into());
+        }
+        subpaths
+            .into_iter()
+            .map(|path| {
+                let mut shape = Shape::path(path);
+                shape.fills = context.fill().into_iter().collect();
+                // SVG strokes are centered on the path
+                let stroke = context.stroke().map(|stroke| stroke.with_align(StrokeAlign::Center));
+                shape.strokes = stroke.into_iter().collect();
+                Item::leaf(shape)
+            })
+            .collect()
+    }
+}
+
+/// An inline `style` declaration for `name`, or else its presentation
+/// attribute. `inherit` counts as unset, which leaves the inherited value.
+fn property<'a>(node: Node<'a, '_>, name: &str) -> Option<&'a str> {
+    let inline = node.attribute("style").and_then(|style| {
+        simplecss::DeclarationTokenizer::from(style)
+            .filter(|declaration| declaration.name == name)
+            .last()
+            .map(|declaration| declaration.value)
+    });
+    inline
+        .or_else(|| node.attribute(name))
+        .map(str::trim)
+        .filter(|value| *value != "inherit")
+}
+
+fn rgba(color: svgtypes::Color) -> Rgba {
+    Rgba {
+        r: color.red as f32 / 255.0,
+        g: color.green as f32 / 255.0,
+        b: color.blue as f32 / 255.0,
+        a: color.alpha as f32 / 255.0,
+    }
+}
+
+/// Maps a view box onto a viewport of `size`, as `aspect` asks.
+fn view_box_transform(view_box: ViewBox, size: Vec2, aspect: AspectRatio) -> Affine {
+    let scale = (size.x as f64 / view_box.w, size.y as f64 / view_box.h);
+    let origin = Affine::translate((-view_box.x, -view_box.y));
+    if aspect.align == Align::None {
+        return Affine::scale_non_uniform(scale.0, scale.1) * origin;
+    }
+    let uniform = if aspect.slice {
+        scale.0.max(scale.1)
+    } else {
+        scale.0.min(scale.1)
+    };
+    let (ax, ay) = match aspect.align {
+        Align::XMinYMin => (0.0, 0.0),
+        Align::XMidYMin => (0.5, 0.0),
+        Align::XMaxYMin => (1.0, 0.0),
+        Align::XMinYMid => (0.0, 0.5),
+        Align::None | Align::XMidYMid => (0.5, 0.5),
+        Align::XMaxYMid => (1.0, 0.5),
+        Align::XMinYMax => (0.0, 1.0),
+        Align::XMidYMax => (0.5, 1.0),
+        Align::XMaxYMax => (1.0, 1.0),
+    };
+    let offset = (
+        (size.x as f64 - view_box.w * uniform) * ax,
+        (size.y as f64 - view_box.h * uniform) * ay,
+    );
+    Affine::translate(offset) * Affine::scale(uniform) * origin
+}
+
+/// A transform's linear part as a shape's box can hold it: a scale taken
+/// into the size, then a rotation and flip.
+struct Turn {
+    scale: Vec2,
+    rotation: f32,
+    flip_x: bool,
+    flip_y: bool,
+}
+
+impl Turn {
+    /// None if the transform skews or collapses an axis.
+    fn of(transform: Affine) -> Option<Self> {
+        let [a, b, c, d, _, _] = transform.as_coeffs();
+        let (sx, sy) = (a.hypot(b), c.hypot(d));
+        if sx == 0.0 || sy == 0.0 || ((a * c + b * d) / (sx * sy)).abs() > 1e-6 {
+            return None;
+        }
+        let rotation = b.atan2(a).to_degrees() as f32;
+        let mirrored = a * d - b * c < 0.0;
+        // A mirror is a flip on either axis plus a half turn; pick the one
+        // that leaves the smaller rotation
+        let (rotation, flip_x, flip_y) = if mirrored && rotation.abs() > 90.0 {
+            (normalize_degrees(rotation + 180.0), true, false)
+        } else {
+            (normalize_degrees(rotation), false, mirrored)
+        };
+        Some(Self {
+            scale: Vec2::new(sx as f32, sy as f32),
+            rotation,
+            flip_x,
+            flip_y,
+        })
+    }
+}
+
+/// Each subpath of `outline` as a path, dropping lone points. Quadratic
+/// segments are raised to cubics.
+fn split_subpaths(outline: &BezPath) -> Vec<PathData> {
+    let mut paths = Vec::new();
+    let mut vertices: Vec<PathVertex> = Vec::new();
+    // Where a subpath begins, and where drawing resumes after a close
+    let mut start = Vec2::ZERO;
+    for element in outline.elements() {
+        match *element {
+            PathEl::MoveTo(p) => {
+                finish_subpath(&mut vertices, false, &mut paths);
+                start = vec2(p);
+                vertices.push(PathVertex::corner(start));
+            }
+            PathEl::LineTo(p) => {
+                if vertices.is_empty() {
+                    vertices.push(PathVertex::corner(start));
+                }
+                vertices.push(PathVertex::corner(vec2(p)));
+            }
+            PathEl::QuadTo(control, p) => {
+                let from = vertices.last().map_or(start, |vertex| vertex.point);
+                let (control, to) = (vec2(control), vec2(p));
+                let c1 = from + (control - from) * (2.0 / 3.0);
+                let c2 = to + (control - to) * (2.0 / 3.0);
+                curve_to(&mut vertices, start, c1, c2, to);
+            }
+            PathEl::CurveTo(c1, c2, p) => curve_to(&mut vertices, start, vec2(c1), vec2(c2), vec2(p)),
+            PathEl::ClosePath => finish_subpath(&mut vertices, true, &mut paths),
+        }
+    }
+    finish_subpath(&mut vertices, false, &mut paths);
+    paths
+}
+
+fn curve_to(vertices: &mut Vec<PathVertex>, start: Vec2, c1: Vec2, c2: Vec2, to: Vec2) {
+    if vertices.is_empty() {
+        vertices.push(PathVertex::corner(start));
+    }
+    let last = vertices.last_mut().expect("vertex pushed above");
+    last.handle_out = c1 - last.point;
+    vertices.push(PathVertex {
+        handle_in: c2 - to,
+        ..PathVertex::corner(to)
+    });
+}
+
+fn finish_subpath(vertices: &mut Vec<PathVertex>, closed: bool, paths: &mut Vec<PathData>) {
+    let mut vertices = std::mem::take(vertices);
+    // A closed subpath usually ends back on its first point; the closing
+    // segment stands in for that last vertex
+    if closed && vertices.len() > 2 {
+        let (first, last) = (vertices[0], vertices[vertices.len() - 1]);
+        if first.point.distance(last.point) < 1e-4 {
+            vertices.pop();
+            vertices[0].handle_in = last.handle_in;
+        }
+    }
+    if vertices.len() > 1 {
+        paths.push(PathData::new(vertices, closed));
+    }
+}
+
+fn vec2(point: Point) -> Vec2 {
+    Vec2::new(point.x as f32, point.y as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export_svg;
+
+    fn import(body: &str) -> SvgImport {
+        let source = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"100\" height=\"100\">{}</svg>",
+            body
+        );
+        import_svg(&source).unwrap()
+    }
+
+    /// The imported shapes below the viewport frame.
+    fn content(import: &SvgImport) -> &[Shape] {
+        &import.shapes[1..]
+    }
+
+    /// A color as RGBA bytes, which survive the trip through HSL.
+    fn bytes(color: Hsla) -> [u8; 4] {
+        let c = color.to_rgb();
+        [c.r, c.g, c.b, c.a].map(|v| (v * 255.0).round() as u8)
+    }
+
+    fn assert_near(a: Vec2, b: Vec2) {
+        assert!(a.distance(b) < 1e-3, "{a} != {b}");
+    }
+
+    // === Shapes ===
+
+    #[test]
+    fn basic_shapes_map_to_rectangles_and_ellipses() {
+        let import = import(
+            "<rect x=\"10\" y=\"20\" width=\"30\" height=\"40\" rx=\"5\" fill=\"#ff0000\"/>\
+             <circle cx=\"50\" cy=\"50\" r=\"10\" fill=\"none\" stroke=\"blue\" stroke-width=\"2\"/>",
+        );
+        assert!(import.warnings.is_empty());
+        let viewport = &import.shapes[0];
+        assert_eq!(viewport.kind, ShapeKind::Frame);
+        assert_eq!(viewport.size, CanvasSize::new(100.0, 100.0));
+        assert!(viewport.clip_children && viewport.fills.is_empty() && viewport.strokes.is_empty());
+
+        let [rect, circle] = content(&import) else {
+            panic!("expected two shapes");
+        };
+        assert_eq!(rect.kind, ShapeKind::Rectangle);
+        assert_eq!(rect.parent, Some(viewport.id));
+        assert_eq!((rect.position, rect.size), (CanvasPoint::new(10.0, 20.0), CanvasSize::new(30.0, 40.0)));
+        assert_eq!(rect.corner_radius, 5.0);
+        assert_eq!(bytes(rect.fills[0].color()), [255, 0, 0, 255]);
+        assert!(rect.strokes.is_empty());
+
+        // Grown by half the stroke, which the canvas draws inside the box
+        assert_eq!(circle.kind, ShapeKind::Ellipse);
+        assert_eq!((circle.position, circle.size), (CanvasPoint::new(39.0, 39.0), CanvasSize::new(22.0, 22.0)));
+        assert!(circle.fills.is_empty());
+        assert_eq!(circle.strokes[0].width, 2.0);
+    }
+
+    #[test]
+    fn groups_become_frames_around_their_content() {
+        let import = import(
+            "<g transform=\"translate(10 10)\" fill=\"green\">\
+               <rect x=\"0\" y=\"0\" width=\"10\" height=\"10\"/>\
+               <rect x=\"20\" y=\"5\" width=\"10\" height=\"10\" fill=\"blue\"/>\
+             </g>\
+             <g/>",
+        );
+        let [group, first, second] = content(&import) else {
+            panic!("expected a group of two; the empty group is dropped");
+        };
+        assert_eq!(group.kind, ShapeKind::Frame);
+        assert_eq!((group.position, group.size), (CanvasPoint::new(10.0, 10.0), CanvasSize::new(30.0, 15.0)));
+        assert!(!group.clip_children);
+        assert_eq!(group.children, vec![first.id, second.id]);
+        assert_eq!(first.position, CanvasPoint::new(0.0, 0.0));
+        assert_eq!(second.position, CanvasPoint::new(20.0, 5.0));
+        assert_ne!(first.fills, second.fills);
+        assert_eq!(bytes(first.fills[0].color()), [0, 128, 0, 255]);
+    }
+
+    #[test]
+    fn ids_become_names() {
+        let import = import(
+            "<g id=\"Icon\"><rect id=\"Background\" width=\"10\" height=\"10\"/></g>\
+             <circle r=\"5\"/>",
+        );
+        let [group, rect, circle] = content(&import) else {
+            panic!("expected a group and two shapes");
+        };
+        assert_eq!(group.name, "Icon");
+        assert_eq!(rect.name, "Background");
+        assert!(circle.name.is_empty());
+    }
+
+    #[test]
+    fn rotation_and_scale_are_kept_but_skew_becomes_a_path() {
+        let import = import(
+            "<rect width=\"10\" height=\"20\" transform=\"translate(50 50) rotate(30) scale(2 3)\"/>\
+             <rect width=\"10\" height=\"10\" transform=\"scale(-1 1)\"/>\
+             <rect width=\"10\" height=\"10\" transform=\"skewX(20)\"/>",
+        );
+        let [turned, flipped, skewed] = content(&import) else {
+            panic!("expected three shapes");
+        };
+        assert_eq!(turned.kind, ShapeKind::Rectangle);
+        assert!((turned.rotation - 30.0).abs() < 1e-4);
+        assert_near(turned.size.0, Vec2::new(20.0, 60.0));
+        // The box's top-left corner lands where the SVG put the rect's
+        let corner = turned.local_placement().to_canvas(Vec2::ZERO);
+        assert_near(corner, Vec2::new(50.0, 50.0));
+
+        assert!(flipped.flip_x && !flipped.flip_y);
+        assert_eq!(flipped.rotation, 0.0);
+        assert_near(flipped.position.0, Vec2::new(-10.0, 0.0));
+
+        assert_eq!(skewed.kind, ShapeKind::Path);
+        assert_eq!(skewed.path.as_ref().unwrap().vertices.len(), 4);
+    }
+
+    #[test]
+    fn view_box_scales_content_into_the_viewport() {
+        let import = import_svg(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"48\" viewBox=\"0 0 24 12\">\
+               <rect x=\"2\" y=\"2\" width=\"4\" height=\"4\" stroke=\"red\"/>\
+             </svg>",
+        )
+        .unwrap();
+        assert_eq!(import.shapes[0].size, CanvasSize::new(48.0, 24.0));
+        let rect = &import.shapes[1];
+        assert_eq!((rect.position, rect.size), (CanvasPoint::new(3.0, 3.0), CanvasSize::new(10.0, 10.0)));
+        assert_eq!(rect.strokes[0].width, 2.0);
+    }
+
+    // === Paths ===
+
+    #[test]
+    fn paths_keep_curves_and_closing() {
+        let import = import(
+            "<path d=\"M 0 0 C 10 0 20 10 20 20 L 0 20 Z\"/>\
+             <polyline points=\"0,0 10,10 20,0\" fill=\"none\" stroke=\"black\"/>",
+        );
+        let [curve, polyline] = content(&import) else {
+            panic!("expected two paths");
+        };
+        let path = curve.path.as_ref().unwrap();
+        assert!(path.closed);
+        assert_eq!(path.vertices.len(), 3);
+        assert_eq!(path.vertices[0].handle_out, Vec2::new(10.0, 0.0));
+        assert_eq!(path.vertices[1].handle_in, Vec2::new(0.0, -10.0));
+        assert_eq!((curve.position, curve.size), (CanvasPoint::new(0.0, 0.0), CanvasSize::new(20.0, 20.0)));
+
+        let path = polyline.path.as_ref().unwrap();
+        assert!(!path.closed);
+        assert_eq!(path.vertices.len(), 3);
+    }
+
+    #[test]
+    fn compound_paths_split_and_report_filled_holes() {
+        let import = import("<path d=\"M0 0 H30 V30 H0 Z M10 10 H20 V20 H10 Z\"/>");
+        assert_eq!(content(&import).len(), 2);
+        assert_eq!(import.warnings.len(), 1);
+    }
+
+    // === Styles ===
+
+    #[test]
+    fn inline_styles_override_attributes_and_inherit() {
+        let import = import(
+            "<g style=\"fill: none; stroke: currentColor; stroke-width: 4\" color=\"#00ff00\" opacity=\"0.5\">\
+               <rect width=\"10\" height=\"10\" fill=\"red\" style=\"stroke-opacity: 50%\"/>\
+             </g>",
+        );
+        let rect = content(&import)
+            .iter()
+            .find(|shape| shape.kind == ShapeKind::Rectangle)
+            .unwrap();
+        assert_eq!(bytes(rect.fills[0].color()), [255, 0, 0, 128]);
+        let stroke = &rect.strokes[0];
+        assert_eq!(stroke.width, 4.0);
+        assert_eq!(bytes(stroke.color), [0, 255, 0, 64]);
+    }
+
+    #[test]
+    fn unsupported_features_are_reported() {
+        let import = import(
+            "<defs><linearGradient id=\"g\"/></defs>\
+             <style>rect { fill: red }</style>\
+             <text>Hello</text>\
+             <rect width=\"10\" height=\"10\" fill=\"url(#g) blue\" filter=\"url(#f)\"/>\
+             <rect width=\"10\" height=\"10\" display=\"none\"/>",
+        );
+        assert_eq!(import.warnings.len(), 4, "{:?}", import.warnings);
+        assert!(import.warnings.iter().any(|w| w.contains("<text>")));
+        let [rect] = content(&import) else {
+            panic!("expected only the visible rect");
+        };
+        assert_eq!(bytes(rect.fills[0].color()), [0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn malformed_documents_are_errors() {
+        assert!(matches!(import_svg("<svg"), Err(InterchangeError::Parse(_))));
+        assert!(matches!(import_svg("<html/>"), Err(InterchangeError::InvalidStructure(_))));
+    }
+
+    // === Round trip ===
+
+    #[test]
+    fn exported_shapes_import_where_they_were() {
+        let mut rect = Shape::rectangle(Vec2::new(10.0, 10.0), Vec2::new(40.0, 20.0))
+            .with_stroke(gpui::black(), 4.0)
+            .with_corner_radius(6.0);
+        rect.rotation = 45.0;
+        let ellipse = Shape::ellipse(Vec2::new(60.0, 0.0), Vec2::new(30.0, 30.0)).with_fill(gpui::white());
+        let shapes = vec![rect, ellipse];
+        let ids: Vec<_> = shapes.iter().map(|shape| shape.id).collect();
+
+        // The exported view box starts at the top-left of what's drawn
+        let origin = shapes
+            .iter()
+            .map(|shape| shape.local_placement().bounds().0 .0)
+            .fold(Vec2::MAX, Vec2::min);
+        let import = import_svg(&export_svg(&shapes, &ids)).unwrap();
+        for (original, imported) in shapes.iter().zip(content(&import)) {
+            assert_eq!(imported.kind, original.kind);
+            let placement = imported.local_placement();
+            // Exported numbers are rounded to thousandths
+            assert!(placement.position.0.distance(original.position.0 - origin) < 0.01);
+            assert!(placement.size.0.distance(original.size.0) < 0.01);
+            assert!((placement.rotation - original.rotation).abs() < 1e-3);
+            let width = |shape: &Shape| shape.strokes.first().map_or(0.0, |stroke| stroke.width);
+            assert!((width(imported) - width(original)).abs() < 0.01);
+            assert!((imported.corner_radius - original.corner_radius).abs() < 0.01);
+        }
+    }
+}
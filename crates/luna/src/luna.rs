@@ -2,41 +2,95 @@
 //!
 //! A streamlined version of Luna focused on basic shape drawing and manipulation.
 
-use api::DebugServer;
+use api::{
+    execute_command_in_context, Command, CommandResult, DebugServer, ExportFormat, PageRef, Target,
+};
 use assets::Assets;
 use canvas::{Canvas, CanvasElement, CanvasEvent, Tool};
 use glam::Vec2;
 use gpui::{
-    actions, div, point, prelude::*, px, App, Application, Entity, FocusHandle, Focusable,
-    IntoElement, KeyBinding, Menu, MenuItem, ParentElement, PathPromptOptions, Styled, Subscription,
+    actions, div, point, prelude::*, px, App, Application, Entity, ExternalPaths, FocusHandle,
+    Focusable, IntoElement, KeyBinding, Menu, MenuItem, NoAction, ParentElement, PathPromptOptions, PromptLevel, Styled, Subscription,
     TitlebarOptions, Window, WindowBackgroundAppearance, WindowOptions,
 };
 use interchange::{Document, Project};
-use node::Shape;
-use std::path::PathBuf;
+use node::{Alignment, CanvasPoint, DistributeAxis, FlipAxis, Shape, DEFAULT_TIDY_SPACING};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use theme::Theme;
-use ui::{bind_input_keys, LayerList, PropertiesPanel, ToolRail};
+use ui::{
+    bind_input_keys, LayerList, PageList, PropertiesPanel, TextEditor, ToolRail, ZoomControl,
+    INPUT_CONTEXT,
+};
 
 mod assets;
 
 actions!(
     luna,
     [
+        AlignBottom,
+        AlignCenter,
+        AlignLeft,
+        AlignMiddle,
+        AlignRight,
+        AlignTop,
+        BringForward,
+        BringToFront,
         Cancel,
+        CreateComponent,
+        CreateInstance,
         Delete,
+        DeletePage,
+        DetachInstance,
+        DistributeHorizontal,
+        DistributeVertical,
         Duplicate,
         EllipseTool,
+        ExportPng,
+        ExportSvg,
+        FlipHorizontal,
+        FlipVertical,
         FrameTool,
         HandTool,
+        HideSelection,
+        MovePageDown,
+        MovePageUp,
         NewFile,
+        NewPage,
+        Opacity10,
+        Opacity20,
+        Opacity30,
+        Opacity40,
+        Opacity50,
+        Opacity60,
+        Opacity70,
+        Opacity80,
+        Opacity90,
+        Opacity100,
         OpenProject,
+        Paste,
+        PenTool,
         Quit,
         RectangleTool,
+        Redo,
+        RenameLayer,
+        RenamePage,
+        ResetOverrides,
         SaveProject,
         SaveProjectAs,
         SelectAll,
         SelectionTool,
+        SendBackward,
+        SendToBack,
+        TextTool,
+        TidyUp,
+        ToggleLock,
+        Undo,
+        ZoomIn,
+        ZoomOut,
+        ZoomToActualSize,
+        ZoomToFit,
+        ZoomToSelection,
     ]
 );
 
@@ -44,8 +98,11 @@ actions!(
 struct Luna {
     canvas: Entity<Canvas>,
     tool_rail: Entity<ToolRail>,
+    page_list: Entity<PageList>,
     layer_list: Entity<LayerList>,
     properties: Entity<PropertiesPanel>,
+    text_editor: Entity<TextEditor>,
+    zoom_control: Entity<ZoomControl>,
     focus_handle: FocusHandle,
     theme: Theme,
     debug_server: Option<Arc<DebugServer>>,
@@ -55,13 +112,18 @@ struct Luna {
 }
 
 impl Luna {
-    pub fn new(_window: &mut Window, cx: &mut Context<Self>) -> Self {
+    pub fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
         let theme = Theme::light();
         let focus_handle = cx.focus_handle();
-        let canvas = cx.new(|cx| Canvas::new(theme.clone(), cx));
+        let text_system = window.text_system().clone();
+        let canvas = cx.new(|cx| Canvas::new(theme.clone(), text_system, cx));
         let tool_rail = cx.new(|_| ToolRail::new(canvas.clone(), theme.clone()));
-        let layer_list = cx.new(|_| LayerList::new(canvas.clone(), theme.clone()));
+        let page_list = cx.new(|cx| PageList::new(canvas.clone(), theme.clone(), cx));
+        let layer_list = cx.new(|cx| LayerList::new(canvas.clone(), theme.clone(), cx));
         let properties = cx.new(|cx| PropertiesPanel::new(canvas.clone(), theme.clone(), cx));
+        let text_editor =
+            cx.new(|cx| TextEditor::new(canvas.clone(), theme.clone(), window, cx));
+        let zoom_control = cx.new(|cx| ZoomControl::new(canvas.clone(), theme.clone(), cx));
 
         // Add some example shapes
         canvas.update(cx, |canvas, cx| {
@@ -109,8 +171,11 @@ impl Luna {
         Luna {
             canvas,
             tool_rail,
+            page_list,
             layer_list,
             properties,
+            text_editor,
+            zoom_control,
             focus_handle,
             theme,
             debug_server,
@@ -138,6 +203,12 @@ impl Luna {
             CanvasEvent::ContentChanged => {
                 // Content changed
             }
+            CanvasEvent::TextEditStarted(id) => {
+                eprintln!("Text edit started: {:?}", id);
+            }
+            CanvasEvent::PagesChanged => {
+                // Pages changed
+            }
         }
     }
 
@@ -196,6 +267,20 @@ impl Luna {
         cx.notify();
     }
 
+    fn activate_text_tool(&mut self, _: &TextTool, _window: &mut Window, cx: &mut Context<Self>) {
+        self.canvas.update(cx, |canvas, _| {
+            canvas.tool = Tool::Text;
+        });
+        cx.notify();
+    }
+
+    fn activate_pen_tool(&mut self, _: &PenTool, _window: &mut Window, cx: &mut Context<Self>) {
+        self.canvas.update(cx, |canvas, _| {
+            canvas.tool = Tool::Pen;
+        });
+        cx.notify();
+    }
+
     fn delete_selected(&mut self, _: &Delete, _window: &mut Window, cx: &mut Context<Self>) {
         self.canvas.update(cx, |canvas, cx| {
             canvas.delete_selected(cx);
@@ -208,9 +293,176 @@ impl Luna {
         });
     }
 
+    fn flip_horizontal(&mut self, _: &FlipHorizontal, _window: &mut Window, cx: &mut Context<Self>) {
+        self.canvas.update(cx, |canvas, cx| {
+            canvas.flip_selected(FlipAxis::Horizontal, cx);
+        });
+    }
+
+    fn flip_vertical(&mut self, _: &FlipVertical, _window: &mut Window, cx: &mut Context<Self>) {
+        self.canvas.update(cx, |canvas, cx| {
+            canvas.flip_selected(FlipAxis::Vertical, cx);
+        });
+    }
+
+    fn rename_layer(&mut self, _: &RenameLayer, window: &mut Window, cx: &mut Context<Self>) {
+        let selection = &self.canvas.read(cx).selection;
+        if let (1, Some(&id)) = (selection.len(), selection.iter().next()) {
+            self.layer_list.update(cx, |layer_list, cx| {
+                layer_list.rename_shape(id, window, cx);
+            });
+        }
+    }
+
+    fn hide_selection(&mut self, _: &HideSelection, _window: &mut Window, cx: &mut Context<Self>) {
+        self.run_command(Command::SetVisible { target: Target::Selection, visible: false }, cx);
+    }
+
+    /// Lock the selection, or unlock it if it's all locked already.
+    fn toggle_lock(&mut self, _: &ToggleLock, _window: &mut Window, cx: &mut Context<Self>) {
+        let canvas = self.canvas.read(cx);
+        let all_locked = canvas
+            .selection
+            .iter()
+            .all(|&id| canvas.get_shape(id).is_some_and(|shape| shape.locked));
+        self.run_command(Command::SetLocked { target: Target::Selection, locked: !all_locked }, cx);
+    }
+
+    fn bring_forward(&mut self, _: &BringForward, _window: &mut Window, cx: &mut Context<Self>) {
+        self.run_command(Command::BringForward { target: Target::Selection }, cx);
+    }
+
+    fn send_backward(&mut self, _: &SendBackward, _window: &mut Window, cx: &mut Context<Self>) {
+        self.run_command(Command::SendBackward { target: Target::Selection }, cx);
+    }
+
+    fn bring_to_front(&mut self, _: &BringToFront, _window: &mut Window, cx: &mut Context<Self>) {
+        self.run_command(Command::BringToFront { target: Target::Selection }, cx);
+    }
+
+    fn send_to_back(&mut self, _: &SendToBack, _window: &mut Window, cx: &mut Context<Self>) {
+        self.run_command(Command::SendToBack { target: Target::Selection }, cx);
+    }
+
+    fn align(&mut self, alignment: Alignment, cx: &mut Context<Self>) {
+        let relative_to = self.canvas.read(cx).align_reference();
+        self.run_command(Command::Align { target: Target::Selection, alignment, relative_to }, cx);
+    }
+
+    fn align_left(&mut self, _: &AlignLeft, _window: &mut Window, cx: &mut Context<Self>) {
+        self.align(Alignment::Left, cx);
+    }
+
+    fn align_center(&mut self, _: &AlignCenter, _window: &mut Window, cx: &mut Context<Self>) {
+        self.align(Alignment::Center, cx);
+    }
+
+    fn align_right(&mut self, _: &AlignRight, _window: &mut Window, cx: &mut Context<Self>) {
+        self.align(Alignment::Right, cx);
+    }
+
+    fn align_top(&mut self, _: &AlignTop, _window: &mut Window, cx: &mut Context<Self>) {
+        self.align(Alignment::Top, cx);
+    }
+
+    fn align_middle(&mut self, _: &AlignMiddle, _window: &mut Window, cx: &mut Context<Self>) {
+        self.align(Alignment::Middle, cx);
+    }
+
+    fn align_bottom(&mut self, _: &AlignBottom, _window: &mut Window, cx: &mut Context<Self>) {
+        self.align(Alignment::Bottom, cx);
+    }
+
+    fn distribute_horizontal(
+        &mut self,
+        _: &DistributeHorizontal,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let axis = DistributeAxis::Horizontal;
+        self.run_command(Command::Distribute { target: Target::Selection, axis }, cx);
+    }
+
+    fn distribute_vertical(
+        &mut self,
+        _: &DistributeVertical,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let axis = DistributeAxis::Vertical;
+        self.run_command(Command::Distribute { target: Target::Selection, axis }, cx);
+    }
+
+    fn tidy_up(&mut self, _: &TidyUp, _window: &mut Window, cx: &mut Context<Self>) {
+        self.run_command(Command::Tidy { target: Target::Selection, spacing: DEFAULT_TIDY_SPACING }, cx);
+    }
+
+    fn set_opacity(&mut self, opacity: f32, cx: &mut Context<Self>) {
+        self.run_command(Command::SetOpacity { target: Target::Selection, opacity }, cx);
+    }
+
+    fn create_component(&mut self, _: &CreateComponent, _window: &mut Window, cx: &mut Context<Self>) {
+        self.run_command(Command::CreateComponent { target: Target::Selection }, cx);
+    }
+
+    fn create_instance(&mut self, _: &CreateInstance, _window: &mut Window, cx: &mut Context<Self>) {
+        let canvas = self.canvas.read(cx);
+        let main = canvas
+            .selection
+            .iter()
+            .copied()
+            .find(|&id| canvas.get_shape(id).is_some_and(|shape| shape.is_main_component()));
+        if let Some(component) = main {
+            self.run_command(Command::CreateInstance { component, position: None }, cx);
+        }
+    }
+
+    fn detach_instance(&mut self, _: &DetachInstance, _window: &mut Window, cx: &mut Context<Self>) {
+        self.run_command(Command::DetachInstance { target: Target::Selection }, cx);
+    }
+
+    fn reset_overrides(&mut self, _: &ResetOverrides, _window: &mut Window, cx: &mut Context<Self>) {
+        self.run_command(Command::ResetOverrides { target: Target::Selection }, cx);
+    }
+
+    fn undo(&mut self, _: &Undo, _window: &mut Window, cx: &mut Context<Self>) {
+        self.canvas.update(cx, |canvas, cx| {
+            canvas.undo(cx);
+        });
+    }
+
+    fn redo(&mut self, _: &Redo, _window: &mut Window, cx: &mut Context<Self>) {
+        self.canvas.update(cx, |canvas, cx| {
+            canvas.redo(cx);
+        });
+    }
+
+    fn zoom_in(&mut self, _: &ZoomIn, _window: &mut Window, cx: &mut Context<Self>) {
+        self.canvas.update(cx, |canvas, cx| canvas.step_zoom(true, true, cx));
+    }
+
+    fn zoom_out(&mut self, _: &ZoomOut, _window: &mut Window, cx: &mut Context<Self>) {
+        self.canvas.update(cx, |canvas, cx| canvas.step_zoom(false, true, cx));
+    }
+
+    fn zoom_to_actual_size(&mut self, _: &ZoomToActualSize, _window: &mut Window, cx: &mut Context<Self>) {
+        self.canvas.update(cx, |canvas, cx| canvas.set_zoom(1.0, true, cx));
+    }
+
+    fn zoom_to_fit(&mut self, _: &ZoomToFit, _window: &mut Window, cx: &mut Context<Self>) {
+        self.canvas.update(cx, |canvas, cx| canvas.zoom_to_fit(true, cx));
+    }
+
+    fn zoom_to_selection(&mut self, _: &ZoomToSelection, _window: &mut Window, cx: &mut Context<Self>) {
+        self.canvas.update(cx, |canvas, cx| canvas.zoom_to_selection(true, cx));
+    }
+
     fn handle_cancel(&mut self, _: &Cancel, _window: &mut Window, cx: &mut Context<Self>) {
         self.canvas.update(cx, |canvas, cx| {
-            if canvas.tool != Tool::Select {
+            if canvas.editing_path.is_some() {
+                canvas.finish_path_edit(cx);
+                canvas.tool = Tool::Select;
+            } else if canvas.tool != Tool::Select {
                 canvas.tool = Tool::Select;
             } else {
                 canvas.clear_selection(cx);
@@ -219,6 +471,67 @@ impl Luna {
         cx.notify();
     }
 
+    fn new_page(&mut self, _: &NewPage, _window: &mut Window, cx: &mut Context<Self>) {
+        self.run_command(Command::AddPage { name: None }, cx);
+    }
+
+    fn rename_page(&mut self, _: &RenamePage, window: &mut Window, cx: &mut Context<Self>) {
+        self.page_list.update(cx, |page_list, cx| {
+            page_list.rename_active_page(window, cx);
+        });
+    }
+
+    /// Delete the open page once the user confirms. The page's shapes and
+    /// history go with it, so there's no undoing this.
+    fn delete_page(&mut self, _: &DeletePage, window: &mut Window, cx: &mut Context<Self>) {
+        let pages = self.canvas.read(cx).pages();
+        // The last page stays, so there's nothing to confirm
+        if pages.len() == 1 {
+            return;
+        }
+        let active = pages.active();
+        let message = format!("Delete \"{}\"?", pages.name(active).unwrap_or_default());
+        let answer = window.prompt(
+            PromptLevel::Warning,
+            &message,
+            Some("The page and everything on it will be deleted. This can't be undone."),
+            &["Delete", "Cancel"],
+            cx,
+        );
+        cx.spawn(async move |this, cx| {
+            if answer.await? == 0 {
+                this.update(cx, |this, cx| {
+                    this.run_command(Command::DeletePage { page: PageRef::Index(active) }, cx);
+                })?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn move_page_up(&mut self, _: &MovePageUp, _window: &mut Window, cx: &mut Context<Self>) {
+        let active = self.canvas.read(cx).pages().active();
+        if active > 0 {
+            let page = PageRef::Index(active);
+            self.run_command(Command::MovePage { page, index: active - 1 }, cx);
+        }
+    }
+
+    fn move_page_down(&mut self, _: &MovePageDown, _window: &mut Window, cx: &mut Context<Self>) {
+        let pages = self.canvas.read(cx).pages();
+        let active = pages.active();
+        if active + 1 < pages.len() {
+            let page = PageRef::Index(active);
+            self.run_command(Command::MovePage { page, index: active + 1 }, cx);
+        }
+    }
+
+    fn run_command(&mut self, command: Command, cx: &mut Context<Self>) {
+        if let CommandResult::Error { message } = execute_command_in_context(&self.canvas, command, cx) {
+            eprintln!("{}", message);
+        }
+    }
+
     fn new_file(&mut self, _: &NewFile, _window: &mut Window, cx: &mut Context<Self>) {
         self.canvas.update(cx, |canvas, cx| {
             canvas.load_shapes(Vec::new(), cx);
@@ -259,9 +572,14 @@ impl Luna {
     }
 
     fn save_to_path(&self, path: PathBuf, cx: &mut Context<Self>) {
-        let shapes: Vec<Shape> = self.canvas.read(cx).shapes.clone();
-        let doc = Document::new(shapes);
-        let project = Project::from_document("Untitled", doc);
+        let pages = self
+            .canvas
+            .read(cx)
+            .page_documents()
+            .into_iter()
+            .map(|(name, shapes, guides)| (name, Document { guides, ..Document::new(shapes) }))
+            .collect();
+        let project = Project::from_pages("Untitled", pages);
 
         if let Err(e) = project.save(&path) {
             eprintln!("Failed to save project: {}", e);
@@ -270,6 +588,67 @@ impl Luna {
         }
     }
 
+    fn export_svg(&mut self, _: &ExportSvg, _window: &mut Window, cx: &mut Context<Self>) {
+        self.prompt_export("untitled.svg", ExportFormat::Svg, 1.0, cx);
+    }
+
+    fn export_png(&mut self, _: &ExportPng, window: &mut Window, cx: &mut Context<Self>) {
+        // At the display's density, so the image looks as it does on screen
+        self.prompt_export("untitled.png", ExportFormat::Png, window.scale_factor(), cx);
+    }
+
+    fn prompt_export(
+        &mut self,
+        file_name: &'static str,
+        format: ExportFormat,
+        scale: f32,
+        cx: &mut Context<Self>,
+    ) {
+        cx.spawn(async move |this, cx| {
+            let path = cx
+                .update(|cx| {
+                    cx.prompt_for_new_path(
+                        &std::env::current_dir().unwrap_or_default(),
+                        Some(file_name),
+                    )
+                })?
+                .await??;
+
+            if let Some(path) = path {
+                this.update(cx, |this, cx| {
+                    this.export_to_path(path, format, scale, cx);
+                })?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Export the selection, or the whole page when nothing is selected.
+    fn export_to_path(
+        &self,
+        path: PathBuf,
+        format: ExportFormat,
+        scale: f32,
+        cx: &mut Context<Self>,
+    ) {
+        let target = if self.canvas.read(cx).selection.is_empty() {
+            Target::All
+        } else {
+            Target::Selection
+        };
+        let command = Command::Export {
+            target,
+            path: path.clone(),
+            format: Some(format),
+            scale,
+        };
+        match execute_command_in_context(&self.canvas, command, cx) {
+            CommandResult::Error { message } => eprintln!("Failed to export: {}", message),
+            CommandResult::Success { .. } => eprintln!("Exported to {}", path.display()),
+        }
+    }
+
     fn open_project(&mut self, _: &OpenProject, _window: &mut Window, cx: &mut Context<Self>) {
         cx.spawn(async move |this, cx| {
             let paths = cx
@@ -278,7 +657,7 @@ impl Luna {
                         files: true,
                         directories: true,
                         multiple: false,
-                        prompt: Some("Open Luna Project".into()),
+                        prompt: Some("Open Luna Project or SVG".into()),
                     })
                 })?
                 .await??;
@@ -296,21 +675,78 @@ impl Luna {
     }
 
     fn load_from_path(&mut self, path: PathBuf, cx: &mut Context<Self>) {
+        // An SVG joins the open document rather than replacing it
+        if is_svg_file(&path) {
+            let center = self.canvas.read(cx).view_center();
+            self.import_svg_file(&path, center, cx);
+            return;
+        }
         match Project::load(&path) {
             Ok(project) => {
-                if let Some(doc) = project.default_page() {
-                    self.canvas.update(cx, |canvas, cx| {
-                        canvas.load_shapes(doc.shapes.clone(), cx);
-                    });
-                    self.project_path = Some(path.clone());
-                    eprintln!("Loaded project from {}", path.display());
-                }
+                let pages = project
+                    .pages
+                    .into_iter()
+                    .map(|(name, doc)| (name, doc.shapes, doc.guides))
+                    .collect();
+                self.canvas.update(cx, |canvas, cx| {
+                    canvas.load_pages(pages, cx);
+                });
+                self.project_path = Some(path.clone());
+                eprintln!("Loaded project from {}", path.display());
             }
             Err(e) => {
                 eprintln!("Failed to load project: {}", e);
             }
         }
     }
+
+    /// Paste SVG markup from the clipboard into the middle of the view.
+    fn paste(&mut self, _: &Paste, _window: &mut Window, cx: &mut Context<Self>) {
+        let Some(text) = cx.read_from_clipboard().and_then(|item| item.text()) else {
+            return;
+        };
+        let markup = text.trim_start();
+        if markup.starts_with("<svg") || markup.starts_with("<?xml") {
+            let center = self.canvas.read(cx).view_center();
+            self.import_svg(text, center, cx);
+        }
+    }
+
+    /// Import SVG files dropped on the canvas where they were dropped.
+    fn drop_paths(&mut self, paths: &ExternalPaths, window: &mut Window, cx: &mut Context<Self>) {
+        let at = self.canvas.read(cx).window_to_canvas(window.mouse_position());
+        for path in paths.paths() {
+            if is_svg_file(path) {
+                self.import_svg_file(path, at, cx);
+            } else {
+                eprintln!("Can't import {}: only SVG files can be dropped", path.display());
+            }
+        }
+    }
+
+    fn import_svg_file(&mut self, path: &Path, center: CanvasPoint, cx: &mut Context<Self>) {
+        match std::fs::read_to_string(path) {
+            Ok(svg) => self.import_svg(svg, center, cx),
+            Err(e) => eprintln!("Failed to read {}: {}", path.display(), e),
+        }
+    }
+
+    /// Import an SVG document centered on `center`, listing anything in it
+    /// that couldn't be imported.
+    fn import_svg(&mut self, svg: String, center: CanvasPoint, cx: &mut Context<Self>) {
+        let command = Command::ImportSvg {
+            svg,
+            center: Some(center.0),
+        };
+        match execute_command_in_context(&self.canvas, command, cx) {
+            CommandResult::Error { message } => eprintln!("Failed to import SVG: {}", message),
+            CommandResult::Success { warnings, .. } => {
+                for warning in warnings {
+                    eprintln!("SVG import: {}", warning);
+                }
+            }
+        }
+    }
 }
 
 impl Render for Luna {
@@ -343,32 +779,96 @@ impl Render for Luna {
             .on_action(cx.listener(Self::activate_rectangle_tool))
             .on_action(cx.listener(Self::activate_ellipse_tool))
             .on_action(cx.listener(Self::activate_frame_tool))
+            .on_action(cx.listener(Self::activate_text_tool))
+            .on_action(cx.listener(Self::activate_pen_tool))
             .on_action(cx.listener(Self::delete_selected))
             .on_action(cx.listener(Self::duplicate_selected))
+            .on_action(cx.listener(Self::flip_horizontal))
+            .on_action(cx.listener(Self::flip_vertical))
+            .on_action(cx.listener(Self::rename_layer))
+            .on_action(cx.listener(Self::hide_selection))
+            .on_action(cx.listener(Self::toggle_lock))
+            .on_action(cx.listener(Self::bring_forward))
+            .on_action(cx.listener(Self::send_backward))
+            .on_action(cx.listener(Self::bring_to_front))
+            .on_action(cx.listener(Self::send_to_back))
+            .on_action(cx.listener(Self::align_left))
+            .on_action(cx.listener(Self::align_center))
+            .on_action(cx.listener(Self::align_right))
+            .on_action(cx.listener(Self::align_top))
+            .on_action(cx.listener(Self::align_middle))
+            .on_action(cx.listener(Self::align_bottom))
+            .on_action(cx.listener(Self::distribute_horizontal))
+            .on_action(cx.listener(Self::distribute_vertical))
+            .on_action(cx.listener(Self::tidy_up))
+            .on_action(cx.listener(|this, _: &Opacity10, _, cx| this.set_opacity(0.1, cx)))
+            .on_action(cx.listener(|this, _: &Opacity20, _, cx| this.set_opacity(0.2, cx)))
+            .on_action(cx.listener(|this, _: &Opacity30, _, cx| this.set_opacity(0.3, cx)))
+            .on_action(cx.listener(|this, _: &Opacity40, _, cx| this.set_opacity(0.4, cx)))
+            .on_action(cx.listener(|this, _: &Opacity50, _, cx| this.set_opacity(0.5, cx)))
+            .on_action(cx.listener(|this, _: &Opacity60, _, cx| this.set_opacity(0.6, cx)))
+            .on_action(cx.listener(|this, _: &Opacity70, _, cx| this.set_opacity(0.7, cx)))
+            .on_action(cx.listener(|this, _: &Opacity80, _, cx| this.set_opacity(0.8, cx)))
+            .on_action(cx.listener(|this, _: &Opacity90, _, cx| this.set_opacity(0.9, cx)))
+            .on_action(cx.listener(|this, _: &Opacity100, _, cx| this.set_opacity(1.0, cx)))
+            .on_action(cx.listener(Self::create_component))
+            .on_action(cx.listener(Self::create_instance))
+            .on_action(cx.listener(Self::detach_instance))
+            .on_action(cx.listener(Self::reset_overrides))
+            .on_action(cx.listener(Self::undo))
+            .on_action(cx.listener(Self::redo))
+            .on_action(cx.listener(Self::zoom_in))
+            .on_action(cx.listener(Self::zoom_out))
+            .on_action(cx.listener(Self::zoom_to_actual_size))
+            .on_action(cx.listener(Self::zoom_to_fit))
+            .on_action(cx.listener(Self::zoom_to_selection))
             .on_action(cx.listener(Self::handle_cancel))
+            .on_action(cx.listener(Self::new_page))
+            .on_action(cx.listener(Self::rename_page))
+            .on_action(cx.listener(Self::delete_page))
+            .on_action(cx.listener(Self::move_page_up))
+            .on_action(cx.listener(Self::move_page_down))
             .on_action(cx.listener(Self::new_file))
             .on_action(cx.listener(Self::save_project))
             .on_action(cx.listener(Self::save_project_as))
             .on_action(cx.listener(Self::open_project))
+            .on_action(cx.listener(Self::export_svg))
+            .on_action(cx.listener(Self::export_png))
+            .on_action(cx.listener(Self::paste))
             // Far left: Tool rail
             .child(
                 div()
                     .pt(px(32.0)) // Space for traffic lights
                     .child(self.tool_rail.clone()),
             )
-            // Left: Layer list
+            // Left: Pages above layers
             .child(
                 div()
+                    .flex()
+                    .flex_col()
+                    .gap(px(8.0))
+                    .h_full()
                     .p(px(8.0))
                     .pt(px(32.0)) // Space for traffic lights
-                    .child(self.layer_list.clone()),
+                    .child(self.page_list.clone())
+                    .child(div().flex_1().min_h_0().child(self.layer_list.clone())),
             )
             // Center: Canvas (takes remaining space)
             .child(
                 div()
                     .flex_1()
                     .h_full()
-                    .child(CanvasElement::new(self.canvas.clone())),
+                    .relative()
+                    .on_drop(cx.listener(Self::drop_paths))
+                    .child(CanvasElement::new(self.canvas.clone()))
+                    .child(self.text_editor.clone())
+                    .child(
+                        div()
+                            .absolute()
+                            .right(px(8.0))
+                            .bottom(px(8.0))
+                            .child(self.zoom_control.clone()),
+                    ),
             )
             // Right: Properties panel
             .child(
@@ -386,6 +886,10 @@ impl Focusable for Luna {
     }
 }
 
+fn is_svg_file(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("svg"))
+}
+
 fn init_keymap(cx: &mut App) {
     cx.bind_keys([
         KeyBinding::new("h", HandTool, None),
@@ -393,16 +897,70 @@ fn init_keymap(cx: &mut App) {
         KeyBinding::new("r", RectangleTool, None),
         KeyBinding::new("o", EllipseTool, None),
         KeyBinding::new("f", FrameTool, None),
+        KeyBinding::new("t", TextTool, None),
+        KeyBinding::new("p", PenTool, None),
         KeyBinding::new("escape", Cancel, None),
         KeyBinding::new("cmd-n", NewFile, None),
         KeyBinding::new("cmd-s", SaveProject, None),
         KeyBinding::new("cmd-shift-s", SaveProjectAs, None),
         KeyBinding::new("cmd-o", OpenProject, None),
+        KeyBinding::new("cmd-shift-e", ExportSvg, None),
+        KeyBinding::new("cmd-v", Paste, None),
         KeyBinding::new("cmd-d", Duplicate, None),
+        KeyBinding::new("shift-h", FlipHorizontal, None),
+        KeyBinding::new("shift-v", FlipVertical, None),
+        KeyBinding::new("cmd-r", RenameLayer, None),
+        KeyBinding::new("cmd-shift-h", HideSelection, None),
+        KeyBinding::new("cmd-shift-l", ToggleLock, None),
+        KeyBinding::new("cmd-]", BringForward, None),
+        KeyBinding::new("cmd-[", SendBackward, None),
+        KeyBinding::new("cmd-alt-]", BringToFront, None),
+        KeyBinding::new("cmd-alt-[", SendToBack, None),
+        KeyBinding::new("alt-a", AlignLeft, None),
+        KeyBinding::new("alt-h", AlignCenter, None),
+        KeyBinding::new("alt-d", AlignRight, None),
+        KeyBinding::new("alt-w", AlignTop, None),
+        KeyBinding::new("alt-v", AlignMiddle, None),
+        KeyBinding::new("alt-s", AlignBottom, None),
+        KeyBinding::new("ctrl-alt-h", DistributeHorizontal, None),
+        KeyBinding::new("ctrl-alt-v", DistributeVertical, None),
+        KeyBinding::new("ctrl-alt-t", TidyUp, None),
+        KeyBinding::new("1", Opacity10, None),
+        KeyBinding::new("2", Opacity20, None),
+        KeyBinding::new("3", Opacity30, None),
+        KeyBinding::new("4", Opacity40, None),
+        KeyBinding::new("5", Opacity50, None),
+        KeyBinding::new("6", Opacity60, None),
+        KeyBinding::new("7", Opacity70, None),
+        KeyBinding::new("8", Opacity80, None),
+        KeyBinding::new("9", Opacity90, None),
+        KeyBinding::new("0", Opacity100, None),
+        KeyBinding::new("cmd-alt-k", CreateComponent, None),
+        KeyBinding::new("cmd-alt-b", DetachInstance, None),
+        // Focused text inputs bind these in the "Input" context, which wins
+        KeyBinding::new("cmd-z", Undo, None),
+        KeyBinding::new("cmd-shift-z", Redo, None),
+        KeyBinding::new("cmd-=", ZoomIn, None),
+        KeyBinding::new("cmd--", ZoomOut, None),
+        KeyBinding::new("cmd-0", ZoomToActualSize, None),
+        KeyBinding::new("shift-1", ZoomToFit, None),
+        KeyBinding::new("shift-2", ZoomToSelection, None),
         KeyBinding::new("cmd-q", Quit, None),
         KeyBinding::new("delete", Delete, None),
         KeyBinding::new("backspace", Delete, None),
     ]);
+    // Bindings without a context fire wherever focus is, so keys typed into
+    // a field would switch tools or edit the selection instead
+    cx.bind_keys(
+        [
+            "h", "v", "r", "o", "f", "t", "p", // Tools
+            "shift-h", "shift-v", // Flips
+            "alt-a", "alt-h", "alt-d", "alt-w", "alt-v", "alt-s", // Alignment
+            "1", "2", "3", "4", "5", "6", "7", "8", "9", "0", // Opacity
+            "shift-1", "shift-2", // Zoom
+        ]
+        .map(|key| KeyBinding::new(key, NoAction, Some(INPUT_CONTEXT))),
+    );
 }
 
 fn main() {
@@ -426,13 +984,73 @@ fn main() {
                     MenuItem::separator(),
                     MenuItem::action("Save", SaveProject),
                     MenuItem::action("Save As...", SaveProjectAs),
+                    MenuItem::separator(),
+                    MenuItem::action("Export SVG...", ExportSvg),
+                    MenuItem::action("Export PNG...", ExportPng),
                 ],
             },
             Menu {
                 name: "Edit".into(),
                 items: vec![
+                    MenuItem::action("Undo", Undo),
+                    MenuItem::action("Redo", Redo),
+                    MenuItem::separator(),
+                    MenuItem::action("Paste", Paste),
                     MenuItem::action("Duplicate", Duplicate),
                     MenuItem::action("Delete", Delete),
+                    MenuItem::action("Rename", RenameLayer),
+                    MenuItem::action("Hide", HideSelection),
+                    MenuItem::action("Lock/Unlock", ToggleLock),
+                    MenuItem::separator(),
+                    MenuItem::action("Flip Horizontal", FlipHorizontal),
+                    MenuItem::action("Flip Vertical", FlipVertical),
+                    MenuItem::separator(),
+                    MenuItem::action("Create Component", CreateComponent),
+                    MenuItem::action("Create Instance", CreateInstance),
+                    MenuItem::action("Detach Instance", DetachInstance),
+                    MenuItem::action("Reset Overrides", ResetOverrides),
+                ],
+            },
+            Menu {
+                name: "Arrange".into(),
+                items: vec![
+                    MenuItem::action("Bring Forward", BringForward),
+                    MenuItem::action("Send Backward", SendBackward),
+                    MenuItem::action("Bring to Front", BringToFront),
+                    MenuItem::action("Send to Back", SendToBack),
+                    MenuItem::separator(),
+                    MenuItem::action("Align Left", AlignLeft),
+                    MenuItem::action("Align Center", AlignCenter),
+                    MenuItem::action("Align Right", AlignRight),
+                    MenuItem::action("Align Top", AlignTop),
+                    MenuItem::action("Align Middle", AlignMiddle),
+                    MenuItem::action("Align Bottom", AlignBottom),
+                    MenuItem::separator(),
+                    MenuItem::action("Distribute Horizontally", DistributeHorizontal),
+                    MenuItem::action("Distribute Vertically", DistributeVertical),
+                    MenuItem::action("Tidy Up", TidyUp),
+                ],
+            },
+            Menu {
+                name: "Pages".into(),
+                items: vec![
+                    MenuItem::action("New Page", NewPage),
+                    MenuItem::action("Rename Page", RenamePage),
+                    MenuItem::action("Delete Page", DeletePage),
+                    MenuItem::separator(),
+                    MenuItem::action("Move Page Up", MovePageUp),
+                    MenuItem::action("Move Page Down", MovePageDown),
+                ],
+            },
+            Menu {
+                name: "View".into(),
+                items: vec![
+                    MenuItem::action("Zoom In", ZoomIn),
+                    MenuItem::action("Zoom Out", ZoomOut),
+                    MenuItem::action("Zoom to 100%", ZoomToActualSize),
+                    MenuItem::separator(),
+                    MenuItem::action("Zoom to Fit", ZoomToFit),
+                    MenuItem::action("Zoom to Selection", ZoomToSelection),
                 ],
             },
             Menu {
@@ -443,6 +1061,8 @@ fn main() {
                     MenuItem::action("Rectangle (R)", RectangleTool),
                     MenuItem::action("Ellipse (O)", EllipseTool),
                     MenuItem::action("Frame (F)", FrameTool),
+                    MenuItem::action("Text (T)", TextTool),
+                    MenuItem::action("Pen (P)", PenTool),
                 ],
             },
         ]);
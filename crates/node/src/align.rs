@@ -0,0 +1,197 @@
+//! Lining shapes up and spacing them out.
+//!
+//! Shapes are handled by the box around them as they're turned, given as
+//! its min and max corners. Each function returns how far each box moves,
+//! in the order the boxes were given.
+
+use crate::{CanvasDelta, CanvasPoint, ShapeId};
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+/// Space left between shapes tidied up without a spacing of their own.
+pub const DEFAULT_TIDY_SPACING: f32 = 20.0;
+
+/// A box around a shape: its min and max corners.
+pub type Bounds = (CanvasPoint, CanvasPoint);
+
+/// Which edges or centers to line up.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Alignment {
+    Left,
+    /// Horizontal centers.
+    Center,
+    Right,
+    Top,
+    /// Vertical centers.
+    Middle,
+    Bottom,
+}
+
+/// What shapes are lined up against.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlignTo {
+    /// The box around all of them.
+    #[default]
+    Selection,
+    /// Each shape's own parent frame.
+    Parent,
+    /// A key shape, which stays put.
+    Shape(ShapeId),
+}
+
+/// The direction to even out the spacing in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DistributeAxis {
+    Horizontal,
+    Vertical,
+}
+
+/// Line every box up with `target`.
+pub fn align(boxes: &[Bounds], target: Bounds, alignment: Alignment) -> Vec<CanvasDelta> {
+    let (target_min, target_max) = (target.0 .0, target.1 .0);
+    let target_center = (target_min + target_max) / 2.0;
+    boxes
+        .iter()
+        .map(|&(min, max)| {
+            let (min, max) = (min.0, max.0);
+            let center = (min + max) / 2.0;
+            let delta = match alignment {
+                Alignment::Left => Vec2::new(target_min.x - min.x, 0.0),
+                Alignment::Center => Vec2::new(target_center.x - center.x, 0.0),
+                Alignment::Right => Vec2::new(target_max.x - max.x, 0.0),
+                Alignment::Top => Vec2::new(0.0, target_min.y - min.y),
+                Alignment::Middle => Vec2::new(0.0, target_center.y - center.y),
+                Alignment::Bottom => Vec2::new(0.0, target_max.y - max.y),
+            };
+            CanvasDelta(delta)
+        })
+        .collect()
+}
+
+/// Even out the gaps between boxes along an axis. The outermost boxes stay
+/// put and the rest keep their order, so fewer than three boxes don't move.
+pub fn distribute(boxes: &[Bounds], axis: DistributeAxis) -> Vec<CanvasDelta> {
+    let along = |v: Vec2| match axis {
+        DistributeAxis::Horizontal => v.x,
+        DistributeAxis::Vertical => v.y,
+    };
+    let mut deltas = vec![CanvasDelta::new(0.0, 0.0); boxes.len()];
+    if boxes.len() < 3 {
+        return deltas;
+    }
+
+    let mut order: Vec<usize> = (0..boxes.len()).collect();
+    order.sort_by(|&a, &b| along(boxes[a].0 .0).total_cmp(&along(boxes[b].0 .0)));
+    let start = along(boxes[order[0]].0 .0);
+    let end = order.iter().map(|&i| along(boxes[i].1 .0)).fold(f32::MIN, f32::max);
+    let total: f32 = boxes.iter().map(|(min, max)| along(max.0 - min.0)).sum();
+    let gap = (end - start - total) / (boxes.len() - 1) as f32;
+
+    let mut next = start;
+    for &i in &order {
+        let (min, max) = (boxes[i].0 .0, boxes[i].1 .0);
+        let shift = next - along(min);
+        deltas[i] = match axis {
+            DistributeAxis::Horizontal => CanvasDelta::new(shift, 0.0),
+            DistributeAxis::Vertical => CanvasDelta::new(0.0, shift),
+        };
+        next += along(max - min) + gap;
+    }
+    deltas
+}
+
+/// Arrange boxes into a grid as close to square as they allow, in reading
+/// order, from the top-left of the box around them. Columns are as wide as
+/// their widest box and rows as tall as their tallest, `spacing` apart.
+pub fn tidy(boxes: &[Bounds], spacing: f32) -> Vec<CanvasDelta> {
+    let mut deltas = vec![CanvasDelta::new(0.0, 0.0); boxes.len()];
+    if boxes.is_empty() {
+        return deltas;
+    }
+    let columns = (boxes.len() as f32).sqrt().ceil() as usize;
+    let size = |i: usize| boxes[i].1 .0 - boxes[i].0 .0;
+    let center = |i: usize| (boxes[i].0 .0 + boxes[i].1 .0) / 2.0;
+
+    // Rows by height, then each row left to right
+    let mut order: Vec<usize> = (0..boxes.len()).collect();
+    order.sort_by(|&a, &b| center(a).y.total_cmp(&center(b).y));
+    let mut rows: Vec<Vec<usize>> = order.chunks(columns).map(<[usize]>::to_vec).collect();
+    for row in &mut rows {
+        row.sort_by(|&a, &b| center(a).x.total_cmp(&center(b).x));
+    }
+
+    let mut widths = vec![0.0f32; columns];
+    let mut heights = vec![0.0f32; rows.len()];
+    for (r, row) in rows.iter().enumerate() {
+        for (c, &i) in row.iter().enumerate() {
+            widths[c] = widths[c].max(size(i).x);
+            heights[r] = heights[r].max(size(i).y);
+        }
+    }
+
+    let origin = boxes.iter().fold(Vec2::splat(f32::MAX), |origin, (min, _)| origin.min(min.0));
+    let mut y = origin.y;
+    for (r, row) in rows.iter().enumerate() {
+        let mut x = origin.x;
+        for (c, &i) in row.iter().enumerate() {
+            deltas[i] = CanvasDelta(Vec2::new(x, y) - boxes[i].0 .0);
+            x += widths[c] + spacing;
+        }
+        y += heights[r] + spacing;
+    }
+    deltas
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: f32, y: f32, w: f32, h: f32) -> Bounds {
+        (CanvasPoint::new(x, y), CanvasPoint::new(x + w, y + h))
+    }
+
+    fn moved(boxes: &[Bounds], deltas: &[CanvasDelta]) -> Vec<(f32, f32)> {
+        boxes.iter().zip(deltas).map(|((min, _), d)| (min.x() + d.0.x, min.y() + d.0.y)).collect()
+    }
+
+    #[test]
+    fn align_lines_up_edges_and_centers() {
+        let boxes = [rect(0.0, 0.0, 10.0, 10.0), rect(30.0, 50.0, 20.0, 30.0)];
+        let target = rect(0.0, 0.0, 50.0, 80.0);
+        let left = align(&boxes, target, Alignment::Left);
+        assert_eq!(moved(&boxes, &left), [(0.0, 0.0), (0.0, 50.0)]);
+        let center = align(&boxes, target, Alignment::Center);
+        assert_eq!(moved(&boxes, &center), [(20.0, 0.0), (15.0, 50.0)]);
+        let bottom = align(&boxes, target, Alignment::Bottom);
+        assert_eq!(moved(&boxes, &bottom), [(0.0, 70.0), (30.0, 50.0)]);
+    }
+
+    #[test]
+    fn distribute_evens_gaps_between_the_outermost() {
+        let boxes = [
+            rect(100.0, 0.0, 20.0, 10.0),
+            rect(0.0, 5.0, 10.0, 10.0),
+            rect(30.0, 0.0, 40.0, 10.0),
+        ];
+        let deltas = distribute(&boxes, DistributeAxis::Horizontal);
+        // 120 wide with 70 of boxes leaves two gaps of 25
+        assert_eq!(moved(&boxes, &deltas), [(100.0, 0.0), (0.0, 5.0), (35.0, 0.0)]);
+        let two = distribute(&boxes[..2], DistributeAxis::Vertical);
+        assert_eq!(moved(&boxes[..2], &two), [(100.0, 0.0), (0.0, 5.0)]);
+    }
+
+    #[test]
+    fn tidy_fills_rows_in_reading_order() {
+        let boxes = [
+            rect(50.0, 40.0, 10.0, 10.0),
+            rect(0.0, 0.0, 20.0, 10.0),
+            rect(90.0, 5.0, 10.0, 30.0),
+        ];
+        let deltas = tidy(&boxes, 5.0);
+        // Two columns: the top two boxes, then the lowest
+        assert_eq!(moved(&boxes, &deltas), [(0.0, 35.0), (0.0, 0.0), (25.0, 0.0)]);
+    }
+}
@@ -0,0 +1,657 @@
+//! Components: frames reused as instances that stay linked to them.
+//!
+//! A main component is a frame marked [`ComponentLink::Main`]. An instance
+//! is a copy of its tree whose root links to the main and whose every other
+//! shape links to the shape it copies. [`ComponentSync`] carries edits to a
+//! main, shapes added to and removed from it included, into every instance.
+//! A copy's fills, strokes and text content can be overridden, after which
+//! edits to the main leave them alone.
+//!
+//! Mains may hold instances of other components. A copy of such an instance
+//! copies it rather than its main, so overrides set in the main carry
+//! through to every instance of it.
+
+use crate::{Fill, Shape, ShapeId, Stroke};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Levels of instances nested in mains brought up to date by one sync.
+/// Each level takes a pass; anything deeper catches up on later syncs.
+const MAX_NESTING: usize = 8;
+
+/// Properties an instance sets for itself instead of taking from its main.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Overrides {
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub fill: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub stroke: bool,
+    /// The text content. Typography still follows the main.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub text: bool,
+}
+
+impl Overrides {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// A shape's part in a component.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "role", rename_all = "snake_case")]
+pub enum ComponentLink {
+    /// A main component, the frame its instances copy.
+    Main,
+    /// The root of an instance of the main component `main`.
+    Instance {
+        main: ShapeId,
+        #[serde(default, skip_serializing_if = "Overrides::is_empty")]
+        overrides: Overrides,
+    },
+    /// A shape inside an instance, copying `source` in the main's tree.
+    Copy {
+        source: ShapeId,
+        #[serde(default, skip_serializing_if = "Overrides::is_empty")]
+        overrides: Overrides,
+    },
+}
+
+impl ComponentLink {
+    /// The shape this one takes its properties from.
+    pub fn source(&self) -> Option<ShapeId> {
+        match self {
+            Self::Main => None,
+            Self::Instance { main, .. } => Some(*main),
+            Self::Copy { source, .. } => Some(*source),
+        }
+    }
+
+    pub fn overrides(&self) -> Overrides {
+        match self {
+            Self::Main => Overrides::default(),
+            Self::Instance { overrides, .. } | Self::Copy { overrides, .. } => *overrides,
+        }
+    }
+
+    pub fn overrides_mut(&mut self) -> Option<&mut Overrides> {
+        match self {
+            Self::Main => None,
+            Self::Instance { overrides, .. } | Self::Copy { overrides, .. } => Some(overrides),
+        }
+    }
+}
+
+/// A new instance of the main component `main`, at the root level where
+/// the main is: its tree copied with fresh IDs, parents first. None if
+/// `main` isn't a main component.
+pub fn instantiate(shapes: &[Shape], main: ShapeId) -> Option<Vec<Shape>> {
+    if find(shapes, main)?.component != Some(ComponentLink::Main) {
+        return None;
+    }
+    let mut tree = copy_tree(shapes, main, None);
+    tree[0].component = Some(ComponentLink::Instance {
+        main,
+        overrides: Overrides::default(),
+    });
+    Some(tree)
+}
+
+/// Unlink the instance rooted at `root` from its main, leaving its shapes
+/// looking as they do. Instances nested in it stay linked to their own
+/// mains. Returns false if `root` isn't an instance.
+pub fn detach_instance(shapes: &mut [Shape], root: ShapeId) -> bool {
+    if !find(shapes, root).is_some_and(Shape::is_instance) {
+        return false;
+    }
+    let sources: HashMap<ShapeId, Shape> = shapes.iter().map(|s| (s.id, s.clone())).collect();
+    let tree: HashSet<ShapeId> = descendants(shapes, root).into_iter().collect();
+
+    for shape in shapes.iter_mut().filter(|s| tree.contains(&s.id)) {
+        let Some(ComponentLink::Copy { source, overrides }) = shape.component else {
+            shape.component = None;
+            continue;
+        };
+        // A copy of a shape that itself copies something now copies that
+        let Some(source) = sources.get(&source) else {
+            shape.component = None;
+            continue;
+        };
+        shape.component = match source.component {
+            Some(ComponentLink::Instance { main, .. }) => Some(ComponentLink::Instance {
+                main,
+                overrides: kept_overrides(shape, overrides, sources.get(&main)),
+            }),
+            Some(ComponentLink::Copy { source, .. }) => Some(ComponentLink::Copy {
+                source,
+                overrides: kept_overrides(shape, overrides, sources.get(&source)),
+            }),
+            _ => None,
+        };
+    }
+    true
+}
+
+/// Overrides for a copy relinked to `source`, so that whatever it shows
+/// that differs from `source` is kept.
+fn kept_overrides(shape: &Shape, overrides: Overrides, source: Option<&Shape>) -> Overrides {
+    let Some(source) = source else {
+        return overrides;
+    };
+    Overrides {
+        fill: overrides.fill || shape.fills != source.fills,
+        stroke: overrides.stroke || shape.strokes != source.strokes,
+        text: overrides.text || text_content(shape) != text_content(source),
+    }
+}
+
+/// Whether putting `child` inside `parent` would put an instance inside
+/// its own main, directly or through other components, which would then
+/// copy itself without end.
+pub fn nests_in_itself(shapes: &[Shape], child: ShapeId, parent: ShapeId) -> bool {
+    let mut enclosing = HashSet::new();
+    let mut ancestor = Some(parent);
+    while let Some(id) = ancestor {
+        let Some(shape) = find(shapes, id) else {
+            break;
+        };
+        if shape.component == Some(ComponentLink::Main) {
+            enclosing.insert(id);
+        }
+        ancestor = shape.parent;
+    }
+    if enclosing.is_empty() {
+        return false;
+    }
+
+    let mut seen = HashSet::new();
+    let mut stack = vec![child];
+    while let Some(root) = stack.pop() {
+        for id in descendants(shapes, root) {
+            if enclosing.contains(&id) {
+                return true;
+            }
+            if let Some(ComponentLink::Instance { main, .. }) = find(shapes, id).and_then(|s| s.component) {
+                if seen.insert(main) {
+                    stack.push(main);
+                }
+            }
+        }
+    }
+    false
+}
+
+/// What a copy last took from its source.
+#[derive(Debug)]
+struct Inherited {
+    fills: Vec<Fill>,
+    strokes: Vec<Stroke>,
+    content: Option<String>,
+}
+
+/// Keeps instances in step with their mains.
+///
+/// Overrides are found rather than declared: an overridable property that
+/// no longer matches what the last sync gave the copy was edited since, so
+/// every editing path marks overrides without knowing about components.
+#[derive(Debug, Default)]
+pub struct ComponentSync {
+    inherited: HashMap<ShapeId, Inherited>,
+}
+
+impl ComponentSync {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forget what copies were last given. Call when shapes are replaced
+    /// wholesale, as by loading or undo, where a copy that differs from its
+    /// last sync wasn't edited.
+    pub fn clear(&mut self) {
+        self.inherited.clear();
+    }
+
+    /// Bring every instance up to date with its main. Returns true if any
+    /// shape was changed, added or removed.
+    pub fn sync(&mut self, shapes: &mut Vec<Shape>) -> bool {
+        if !shapes.iter().any(Shape::is_instance) {
+            return false;
+        }
+        let mut changed = false;
+        for _ in 0..MAX_NESTING {
+            if !self.sync_pass(shapes) {
+                break;
+            }
+            changed = true;
+        }
+        changed
+    }
+
+    fn sync_pass(&mut self, shapes: &mut Vec<Shape>) -> bool {
+        let instances: Vec<(ShapeId, ShapeId)> = shapes
+            .iter()
+            .filter_map(|shape| match shape.component {
+                Some(ComponentLink::Instance { main, .. }) => Some((main, shape.id)),
+                _ => None,
+            })
+            .collect();
+        let mut changed = false;
+        for (main, root) in instances {
+            // An instance inside its own main would copy itself forever
+            if is_within(shapes, root, main) {
+                continue;
+            }
+            changed |= self.sync_copy(shapes, main, root, true);
+        }
+        changed
+    }
+
+    /// Update the copy `copy_id` of `source_id`, and its children. An
+    /// instance's root keeps its own name and place in its parent.
+    fn sync_copy(&mut self, shapes: &mut Vec<Shape>, source_id: ShapeId, copy_id: ShapeId, is_root: bool) -> bool {
+        let (Some(source), Some(index)) = (find(shapes, source_id).cloned(), position(shapes, copy_id)) else {
+            return false;
+        };
+        let copy = &shapes[index];
+        let Some(mut link) = copy.component else {
+            return false;
+        };
+        if let (Some(last), Some(overrides)) = (self.inherited.get(&copy_id), link.overrides_mut()) {
+            overrides.fill |= copy.fills != last.fills;
+            overrides.stroke |= copy.strokes != last.strokes;
+            overrides.text |= text_content(copy) != last.content;
+        }
+        self.inherited.insert(
+            copy_id,
+            Inherited {
+                fills: source.fills.clone(),
+                strokes: source.strokes.clone(),
+                content: text_content(&source),
+            },
+        );
+
+        let overrides = link.overrides();
+        let mut next = source.clone();
+        next.id = copy.id;
+        next.parent = copy.parent;
+        next.component = Some(link);
+        // Locking is how the shape is edited, not how it looks
+        next.locked = copy.locked;
+        next.computed_position = copy.computed_position;
+        next.computed_size = copy.computed_size;
+        if is_root {
+            next.name = copy.name.clone();
+            next.visible = copy.visible;
+            next.opacity = copy.opacity;
+            next.blend_mode = copy.blend_mode;
+            next.position = copy.position;
+            next.rotation = copy.rotation;
+            next.flip_x = copy.flip_x;
+            next.flip_y = copy.flip_y;
+            next.child_layout = copy.child_layout.clone();
+        }
+        if overrides.fill {
+            next.fills = copy.fills.clone();
+        }
+        if overrides.stroke {
+            next.strokes = copy.strokes.clone();
+        }
+        if overrides.text {
+            if let (Some(text), Some(own)) = (next.text.as_mut(), copy.text.as_ref()) {
+                text.content = own.content.clone();
+            }
+            // Fitted to its own content, not the main's
+            next.size = copy.size;
+        }
+
+        // Children follow the source's, in its order. Shapes added to this
+        // instance alone aren't copies and stay, after them.
+        let mut children = Vec::new();
+        let mut pairs = Vec::new();
+        let mut added = Vec::new();
+        let mut matched = HashSet::new();
+        for &source_child in &source.children {
+            let existing = copy
+                .children
+                .iter()
+                .copied()
+                .find(|child| copied_from(shapes, *child) == Some(source_child));
+            match existing {
+                Some(child) => {
+                    matched.insert(child);
+                    children.push(child);
+                    pairs.push((source_child, child));
+                }
+                None => {
+                    let tree = copy_tree(shapes, source_child, Some(copy_id));
+                    children.push(tree[0].id);
+                    added.extend(tree);
+                }
+            }
+        }
+        let mut stale = Vec::new();
+        for &child in &copy.children {
+            if matched.contains(&child) {
+                continue;
+            }
+            match copied_from(shapes, child) {
+                Some(_) => stale.push(child),
+                None => children.push(child),
+            }
+        }
+        next.children = children;
+
+        let mut changed = !added.is_empty() || !stale.is_empty();
+        if shapes[index] != next {
+            shapes[index] = next;
+            changed = true;
+        }
+        for child in stale {
+            self.remove_tree(shapes, child);
+        }
+        shapes.extend(added);
+        for (source_child, child) in pairs {
+            changed |= self.sync_copy(shapes, source_child, child, false);
+        }
+        changed
+    }
+
+    fn remove_tree(&mut self, shapes: &mut Vec<Shape>, root: ShapeId) {
+        let tree: HashSet<ShapeId> = descendants(shapes, root).into_iter().collect();
+        shapes.retain(|shape| !tree.contains(&shape.id));
+        for id in tree {
+            self.inherited.remove(&id);
+        }
+    }
+}
+
+/// The tree under `source` copied with fresh IDs, parents first, each
+/// shape linked to the one it copies. The first is the copy of `source`,
+/// placed under `parent`.
+fn copy_tree(shapes: &[Shape], source: ShapeId, parent: Option<ShapeId>) -> Vec<Shape> {
+    let tree = descendants(shapes, source);
+    let ids: HashMap<ShapeId, ShapeId> = tree.iter().map(|id| (*id, ShapeId::new())).collect();
+    tree.iter()
+        .filter_map(|id| find(shapes, *id))
+        .map(|original| {
+            let mut copy = original.clone();
+            copy.id = ids[&original.id];
+            copy.parent = match original.parent.and_then(|p| ids.get(&p)) {
+                Some(new_parent) => Some(*new_parent),
+                None => parent,
+            };
+            copy.children = original.children.iter().filter_map(|c| ids.get(c).copied()).collect();
+            copy.component = Some(ComponentLink::Copy {
+                source: original.id,
+                overrides: Overrides::default(),
+            });
+            copy
+        })
+        .collect()
+}
+
+/// `root` and every shape under it, parents first.
+fn descendants(shapes: &[Shape], root: ShapeId) -> Vec<ShapeId> {
+    let mut tree = Vec::new();
+    let mut stack = vec![root];
+    while let Some(id) = stack.pop() {
+        let Some(shape) = find(shapes, id) else {
+            continue;
+        };
+        tree.push(id);
+        stack.extend(shape.children.iter().rev());
+    }
+    tree
+}
+
+/// Whether `id` is somewhere under `ancestor`.
+fn is_within(shapes: &[Shape], id: ShapeId, ancestor: ShapeId) -> bool {
+    let mut parent = find(shapes, id).and_then(|s| s.parent);
+    while let Some(p) = parent {
+        if p == ancestor {
+            return true;
+        }
+        parent = find(shapes, p).and_then(|s| s.parent);
+    }
+    false
+}
+
+/// The shape `id` is a copy of, if it's a copy inside an instance.
+fn copied_from(shapes: &[Shape], id: ShapeId) -> Option<ShapeId> {
+    match find(shapes, id)?.component {
+        Some(ComponentLink::Copy { source, .. }) => Some(source),
+        _ => None,
+    }
+}
+
+fn text_content(shape: &Shape) -> Option<String> {
+    shape.text.as_ref().map(|text| text.content.clone())
+}
+
+fn find(shapes: &[Shape], id: ShapeId) -> Option<&Shape> {
+    shapes.iter().find(|s| s.id == id)
+}
+
+fn position(shapes: &[Shape], id: ShapeId) -> Option<usize> {
+    shapes.iter().position(|s| s.id == id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BlendMode, CanvasPoint, CanvasSize, Text};
+    use glam::Vec2;
+
+    fn red() -> gpui::Hsla {
+        gpui::hsla(0.0, 1.0, 0.5, 1.0)
+    }
+
+    fn blue() -> gpui::Hsla {
+        gpui::hsla(0.66, 1.0, 0.5, 1.0)
+    }
+
+    /// A main component frame holding a rectangle and a text.
+    fn button() -> Vec<Shape> {
+        let mut frame = Shape::frame(Vec2::ZERO, Vec2::new(100.0, 40.0));
+        let mut rect = Shape::rectangle(Vec2::ZERO, Vec2::new(100.0, 40.0)).with_fill(red());
+        let mut label = Shape::text(Vec2::new(10.0, 10.0), Text::new("OK"));
+        frame.component = Some(ComponentLink::Main);
+        rect.parent = Some(frame.id);
+        label.parent = Some(frame.id);
+        frame.children = vec![rect.id, label.id];
+        vec![frame, rect, label]
+    }
+
+    fn instance_of(shapes: &mut Vec<Shape>, main: ShapeId) -> ShapeId {
+        let tree = instantiate(shapes, main).unwrap();
+        let root = tree[0].id;
+        shapes.extend(tree);
+        root
+    }
+
+    fn get(shapes: &[Shape], id: ShapeId) -> &Shape {
+        find(shapes, id).unwrap()
+    }
+
+    fn get_mut(shapes: &mut [Shape], id: ShapeId) -> &mut Shape {
+        shapes.iter_mut().find(|s| s.id == id).unwrap()
+    }
+
+    fn child(shapes: &[Shape], parent: ShapeId, index: usize) -> ShapeId {
+        get(shapes, parent).children[index]
+    }
+
+    // === Instances ===
+
+    #[test]
+    fn instances_copy_the_main_tree() {
+        let mut shapes = button();
+        let main = shapes[0].id;
+        let root = instance_of(&mut shapes, main);
+
+        assert_eq!(shapes.len(), 6);
+        let instance = get(&shapes, root);
+        assert!(instance.parent.is_none());
+        assert_eq!(instance.component.unwrap().source(), Some(main));
+        let rect = child(&shapes, root, 0);
+        assert_eq!(get(&shapes, rect).parent, Some(root));
+        assert_eq!(copied_from(&shapes, rect), Some(shapes[1].id));
+        assert!(instantiate(&shapes, rect).is_none());
+    }
+
+    #[test]
+    fn edits_to_the_main_reach_instances() {
+        let mut shapes = button();
+        let (main, rect) = (shapes[0].id, shapes[1].id);
+        let root = instance_of(&mut shapes, main);
+        let mut sync = ComponentSync::new();
+        get_mut(&mut shapes, root).position = CanvasPoint::new(300.0, 0.0);
+        get_mut(&mut shapes, root).opacity = 0.5;
+        assert!(!sync.sync(&mut shapes));
+
+        get_mut(&mut shapes, rect).fills = vec![Fill::new(blue())];
+        get_mut(&mut shapes, rect).corner_radius = 6.0;
+        get_mut(&mut shapes, rect).blend_mode = BlendMode::Multiply;
+        get_mut(&mut shapes, main).size = CanvasSize::new(120.0, 40.0);
+        assert!(sync.sync(&mut shapes));
+
+        let copy = get(&shapes, child(&shapes, root, 0));
+        assert_eq!(copy.fills, [Fill::new(blue())]);
+        assert_eq!(copy.corner_radius, 6.0);
+        assert_eq!(copy.blend_mode, BlendMode::Multiply);
+        assert_eq!(get(&shapes, root).size, CanvasSize::new(120.0, 40.0));
+        // The instance stays where it was put, as it was made to look
+        assert_eq!(get(&shapes, root).position, CanvasPoint::new(300.0, 0.0));
+        assert_eq!(get(&shapes, root).opacity, 0.5);
+        assert!(!sync.sync(&mut shapes));
+    }
+
+    #[test]
+    fn shapes_added_and_removed_in_the_main_follow() {
+        let mut shapes = button();
+        let (main, rect) = (shapes[0].id, shapes[1].id);
+        let root = instance_of(&mut shapes, main);
+        let mut sync = ComponentSync::new();
+        sync.sync(&mut shapes);
+
+        let mut icon = Shape::ellipse(Vec2::new(80.0, 10.0), Vec2::new(20.0, 20.0));
+        icon.parent = Some(main);
+        get_mut(&mut shapes, main).children.push(icon.id);
+        let icon_id = icon.id;
+        shapes.push(icon);
+        get_mut(&mut shapes, main).children.retain(|c| *c != rect);
+        shapes.retain(|s| s.id != rect);
+        assert!(sync.sync(&mut shapes));
+
+        let children = &get(&shapes, root).children;
+        assert_eq!(children.len(), 2);
+        assert_eq!(copied_from(&shapes, children[1]), Some(icon_id));
+        assert_eq!(shapes.len(), 6);
+    }
+
+    // === Overrides ===
+
+    #[test]
+    fn edited_copies_keep_their_overrides() {
+        let mut shapes = button();
+        let (main, rect, label) = (shapes[0].id, shapes[1].id, shapes[2].id);
+        let root = instance_of(&mut shapes, main);
+        let mut sync = ComponentSync::new();
+        sync.sync(&mut shapes);
+
+        let rect_copy = child(&shapes, root, 0);
+        let label_copy = child(&shapes, root, 1);
+        get_mut(&mut shapes, rect_copy).fills = vec![Fill::new(blue())];
+        get_mut(&mut shapes, label_copy).text.as_mut().unwrap().content = "Cancel".into();
+        sync.sync(&mut shapes);
+
+        get_mut(&mut shapes, rect).fills.clear();
+        get_mut(&mut shapes, rect).corner_radius = 4.0;
+        get_mut(&mut shapes, label).text.as_mut().unwrap().font_size = 20.0;
+        sync.sync(&mut shapes);
+
+        let rect_copy = get(&shapes, rect_copy);
+        assert_eq!(rect_copy.fills, [Fill::new(blue())]);
+        assert_eq!(rect_copy.corner_radius, 4.0);
+        assert!(rect_copy.component.unwrap().overrides().fill);
+        let text = get(&shapes, label_copy).text.as_ref().unwrap();
+        assert_eq!(text.content, "Cancel");
+        assert_eq!(text.font_size, 20.0);
+    }
+
+    #[test]
+    fn replaced_shapes_are_not_taken_for_overrides() {
+        let mut shapes = button();
+        let (main, rect) = (shapes[0].id, shapes[1].id);
+        let root = instance_of(&mut shapes, main);
+        let mut sync = ComponentSync::new();
+        sync.sync(&mut shapes);
+        let before = shapes.clone();
+
+        get_mut(&mut shapes, rect).fills = vec![Fill::new(blue())];
+        sync.sync(&mut shapes);
+
+        // Undo puts back both the main and its copies
+        shapes = before;
+        sync.clear();
+        sync.sync(&mut shapes);
+        get_mut(&mut shapes, rect).fills = vec![Fill::new(blue())];
+        sync.sync(&mut shapes);
+
+        let copy = get(&shapes, child(&shapes, root, 0));
+        assert_eq!(copy.fills, [Fill::new(blue())]);
+        assert!(copy.component.unwrap().overrides().is_empty());
+    }
+
+    // === Nesting ===
+
+    #[test]
+    fn nested_instances_take_overrides_from_the_main() {
+        let mut shapes = button();
+        let button_main = shapes[0].id;
+        let mut card = Shape::frame(Vec2::new(0.0, 100.0), Vec2::new(200.0, 100.0));
+        card.component = Some(ComponentLink::Main);
+        let card_main = card.id;
+        shapes.push(card);
+        let nested = instance_of(&mut shapes, button_main);
+        get_mut(&mut shapes, nested).parent = Some(card_main);
+        get_mut(&mut shapes, card_main).children.push(nested);
+        let mut sync = ComponentSync::new();
+        sync.sync(&mut shapes);
+
+        let card_instance = instance_of(&mut shapes, card_main);
+        sync.sync(&mut shapes);
+        let nested_rect = child(&shapes, nested, 0);
+        get_mut(&mut shapes, nested_rect).fills = vec![Fill::new(blue())];
+        sync.sync(&mut shapes);
+
+        let copy_of_nested = child(&shapes, card_instance, 0);
+        let rect = get(&shapes, child(&shapes, copy_of_nested, 0));
+        assert_eq!(rect.fills, [Fill::new(blue())]);
+
+        // Detached, the copy of the nested button becomes a button again
+        assert!(detach_instance(&mut shapes, card_instance));
+        assert!(get(&shapes, card_instance).component.is_none());
+        let relinked = get(&shapes, copy_of_nested).component.unwrap();
+        assert_eq!(relinked.source(), Some(button_main));
+        let rect = get(&shapes, child(&shapes, copy_of_nested, 0)).component.unwrap();
+        assert!(rect.overrides().fill);
+        assert_eq!(rect.source(), Some(shapes[1].id));
+    }
+
+    #[test]
+    fn instances_cannot_nest_in_their_own_main() {
+        let mut shapes = button();
+        let main = shapes[0].id;
+        let root = instance_of(&mut shapes, main);
+        let inner = child(&shapes, main, 0);
+
+        assert!(nests_in_itself(&shapes, root, main));
+        assert!(nests_in_itself(&shapes, root, inner));
+        let loose = Shape::frame(Vec2::ZERO, Vec2::ONE);
+        let loose_id = loose.id;
+        shapes.push(loose);
+        assert!(!nests_in_itself(&shapes, root, loose_id));
+        assert!(!nests_in_itself(&shapes, loose_id, main));
+    }
+}
@@ -0,0 +1,298 @@
+//! How a shape's inside is painted: fills of one color or a gradient,
+//! stacked and painted in order.
+//!
+//! Gradient handles are placed relative to the shape's box, (0, 0) at its
+//! top-left and (1, 1) at its bottom-right, so a gradient stretches and
+//! turns with its shape. A radial gradient is a circle in those units,
+//! which a box that isn't square stretches into an ellipse.
+
+use glam::Vec2;
+use gpui::{Hsla, Rgba};
+use serde::{Deserialize, Serialize};
+use std::f32::consts::TAU;
+
+/// One of a shape's fills.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Fill {
+    pub paint: Paint,
+    /// Hidden fills are kept, but not painted.
+    #[serde(default = "visible_by_default")]
+    pub visible: bool,
+    /// Multiplies the paint's own alpha.
+    #[serde(default = "opaque")]
+    pub opacity: f32,
+}
+
+impl Fill {
+    pub fn new(color: Hsla) -> Self {
+        Self::from(Paint::Solid(color))
+    }
+
+    /// The one color that stands for the fill where a gradient can't be
+    /// painted, such as in text: a gradient's first stop. Opacity is left
+    /// to the caller.
+    pub fn color(&self) -> Hsla {
+        match &self.paint {
+            Paint::Solid(color) => *color,
+            Paint::Gradient(gradient) => gradient.color_at(0.0),
+        }
+    }
+
+    pub fn gradient(&self) -> Option<&Gradient> {
+        match &self.paint {
+            Paint::Solid(_) => None,
+            Paint::Gradient(gradient) => Some(gradient),
+        }
+    }
+
+    pub fn gradient_mut(&mut self) -> Option<&mut Gradient> {
+        match &mut self.paint {
+            Paint::Solid(_) => None,
+            Paint::Gradient(gradient) => Some(gradient),
+        }
+    }
+}
+
+impl From<Paint> for Fill {
+    fn from(paint: Paint) -> Self {
+        Self {
+            paint,
+            visible: true,
+            opacity: 1.0,
+        }
+    }
+}
+
+/// What a fill paints with.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Paint {
+    Solid(Hsla),
+    Gradient(Gradient),
+}
+
+fn visible_by_default() -> bool {
+    true
+}
+
+fn opaque() -> f32 {
+    1.0
+}
+
+/// How colors spread from a gradient's handles.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GradientKind {
+    /// Along the line from start to end.
+    Linear,
+    /// Out from the start, reaching the last stop at the end's distance.
+    Radial,
+    /// Clockwise around the start, beginning and ending toward the end.
+    Angular,
+}
+
+/// A color at an offset along a gradient, from 0 at its start to 1 at its
+/// end.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GradientStop {
+    pub offset: f32,
+    pub color: Hsla,
+}
+
+impl GradientStop {
+    pub fn new(offset: f32, color: Hsla) -> Self {
+        Self { offset, color }
+    }
+}
+
+/// A gradient fill.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Gradient {
+    pub kind: GradientKind,
+    /// Where offset 0 lies, in the box's units.
+    pub start: Vec2,
+    /// Where offset 1 lies, in the box's units. An angular gradient only
+    /// takes its direction from it.
+    pub end: Vec2,
+    /// In order of offset. There's always at least one.
+    pub stops: Vec<GradientStop>,
+}
+
+impl Gradient {
+    /// A gradient from `from` to `to`, left to right across the box, or out
+    /// from or around its center.
+    pub fn new(kind: GradientKind, from: Hsla, to: Hsla) -> Self {
+        let start = match kind {
+            GradientKind::Linear => Vec2::new(0.0, 0.5),
+            GradientKind::Radial | GradientKind::Angular => Vec2::splat(0.5),
+        };
+        Self {
+            kind,
+            start,
+            end: Vec2::new(1.0, 0.5),
+            stops: vec![GradientStop::new(0.0, from), GradientStop::new(1.0, to)],
+        }
+    }
+
+    /// Put stops back in order with offsets from 0 to 1, after editing them
+    /// directly.
+    pub fn sort_stops(&mut self) {
+        for stop in &mut self.stops {
+            stop.offset = stop.offset.clamp(0.0, 1.0);
+        }
+        self.stops.sort_by(|a, b| a.offset.total_cmp(&b.offset));
+    }
+
+    /// The color at `offset`, mixed between the stops either side. Past the
+    /// first and last stops their colors carry on.
+    pub fn color_at(&self, offset: f32) -> Hsla {
+        let (Some(first), Some(last)) = (self.stops.first(), self.stops.last()) else {
+            return gpui::transparent_black();
+        };
+        if offset <= first.offset {
+            return first.color;
+        }
+        if offset >= last.offset {
+            return last.color;
+        }
+        let after = self.stops.iter().position(|stop| stop.offset > offset).unwrap_or(0);
+        let (a, b) = (self.stops[after - 1], self.stops[after]);
+        let t = (offset - a.offset) / (b.offset - a.offset);
+        mix(a.color, b.color, t)
+    }
+
+    /// How far along the gradient a point in the box's units lies. Linear
+    /// and radial offsets run past 0 and 1 beyond the handles; angular ones
+    /// go from 0 to 1 once around.
+    pub fn offset_at(&self, point: Vec2) -> f32 {
+        let axis = self.end - self.start;
+        let from_start = point - self.start;
+        match self.kind {
+            GradientKind::Linear => {
+                let length_squared = axis.length_squared();
+                if length_squared == 0.0 {
+                    return 0.0;
+                }
+                from_start.dot(axis) / length_squared
+            }
+            GradientKind::Radial => {
+                let radius = axis.length();
+                if radius == 0.0 {
+                    return 1.0;
+                }
+                from_start.length() / radius
+            }
+            GradientKind::Angular => {
+                // Y points down, so increasing angles turn clockwise
+                let angle = from_start.y.atan2(from_start.x) - axis.y.atan2(axis.x);
+                (angle / TAU).rem_euclid(1.0)
+            }
+        }
+    }
+
+    /// Add a stop at `offset` in the color the gradient already has there.
+    /// Returns the new stop's index.
+    pub fn add_stop(&mut self, offset: f32) -> usize {
+        let offset = offset.clamp(0.0, 1.0);
+        let stop = GradientStop::new(offset, self.color_at(offset));
+        let index = self.stops.partition_point(|s| s.offset <= offset);
+        self.stops.insert(index, stop);
+        index
+    }
+
+    /// Remove a stop, as long as another is left. Returns whether it was
+    /// removed.
+    pub fn remove_stop(&mut self, index: usize) -> bool {
+        if self.stops.len() < 2 || index >= self.stops.len() {
+            return false;
+        }
+        self.stops.remove(index);
+        true
+    }
+
+    /// Move a stop to `offset`. Stops stay in order, so it may pass others:
+    /// returns its index after the move.
+    pub fn move_stop(&mut self, index: usize, offset: f32) -> usize {
+        if index >= self.stops.len() {
+            return index;
+        }
+        let mut stop = self.stops.remove(index);
+        stop.offset = offset.clamp(0.0, 1.0);
+        let index = self.stops.partition_point(|s| s.offset <= stop.offset);
+        self.stops.insert(index, stop);
+        index
+    }
+}
+
+/// `t` of the way from `a` to `b`, mixed in RGB: mixing hues would pass
+/// through colors neither end has.
+fn mix(a: Hsla, b: Hsla, t: f32) -> Hsla {
+    let (a, b) = (a.to_rgb(), b.to_rgb());
+    let lerp = |a: f32, b: f32| a + (b - a) * t;
+    Rgba {
+        r: lerp(a.r, b.r),
+        g: lerp(a.g, b.g),
+        b: lerp(a.b, b.b),
+        a: lerp(a.a, b.a),
+    }
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn red() -> Hsla {
+        gpui::hsla(0.0, 1.0, 0.5, 1.0)
+    }
+
+    fn blue() -> Hsla {
+        gpui::hsla(2.0 / 3.0, 1.0, 0.5, 1.0)
+    }
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-4
+    }
+
+    #[test]
+    fn colors_mix_between_stops_and_carry_on_past_them() {
+        let mut gradient = Gradient::new(GradientKind::Linear, red(), blue());
+        gradient.stops[0].offset = 0.2;
+        assert_eq!(gradient.color_at(0.0), red());
+        assert_eq!(gradient.color_at(1.5), blue());
+        let middle = gradient.color_at(0.6).to_rgb();
+        assert!(close(middle.r, 0.5) && close(middle.g, 0.0) && close(middle.b, 0.5));
+    }
+
+    #[test]
+    fn offsets_follow_the_handles() {
+        let linear = Gradient::new(GradientKind::Linear, red(), blue());
+        assert!(close(linear.offset_at(Vec2::new(0.25, 0.9)), 0.25));
+        assert!(close(linear.offset_at(Vec2::new(-0.5, 0.5)), -0.5));
+
+        let radial = Gradient::new(GradientKind::Radial, red(), blue());
+        assert!(close(radial.offset_at(Vec2::new(0.5, 0.75)), 0.5));
+
+        let angular = Gradient::new(GradientKind::Angular, red(), blue());
+        // A quarter turn clockwise from the right is straight down
+        assert!(close(angular.offset_at(Vec2::new(0.5, 1.0)), 0.25));
+        assert!(close(angular.offset_at(Vec2::new(0.5, 0.0)), 0.75));
+    }
+
+    #[test]
+    fn stops_stay_in_order() {
+        let mut gradient = Gradient::new(GradientKind::Linear, red(), blue());
+        let added = gradient.add_stop(0.5);
+        assert_eq!(added, 1);
+        assert!(close(gradient.stops[1].color.to_rgb().b, 0.5));
+
+        assert_eq!(gradient.move_stop(0, 0.8), 1);
+        let offsets: Vec<f32> = gradient.stops.iter().map(|s| s.offset).collect();
+        assert_eq!(offsets, [0.5, 0.8, 1.0]);
+
+        assert!(gradient.remove_stop(0));
+        assert!(gradient.remove_stop(0));
+        assert!(!gradient.remove_stop(0));
+        assert_eq!(gradient.stops.len(), 1);
+    }
+}
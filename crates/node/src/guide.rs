@@ -0,0 +1,27 @@
+//! Guides: lines across a page, dragged out of the rulers, that shapes snap
+//! to. A page keeps its guides in order, and they're addressed by index.
+
+use serde::{Deserialize, Serialize};
+
+/// Which way a guide runs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GuideAxis {
+    /// Across the page at a y position, from the top ruler.
+    Horizontal,
+    /// Down the page at an x position, from the left ruler.
+    Vertical,
+}
+
+/// A guide at `position` on canvas, along the axis it crosses.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Guide {
+    pub axis: GuideAxis,
+    pub position: f32,
+}
+
+impl Guide {
+    pub fn new(axis: GuideAxis, position: f32) -> Self {
+        Self { axis, position }
+    }
+}
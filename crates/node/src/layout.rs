@@ -3,6 +3,7 @@
 //! Provides flexbox-inspired layout for arranging children within frames.
 //! Layout is opt-in: frames default to absolute positioning.
 
+use crate::coords::CanvasSize;
 use serde::{Deserialize, Serialize};
 
 /// Layout configuration for a frame.
@@ -25,6 +26,18 @@ pub struct FrameLayout {
 
     /// Padding inside the frame.
     pub padding: Padding,
+
+    /// Whether children that don't fit on the main axis wrap onto new lines.
+    #[serde(default)]
+    pub wrap: bool,
+
+    /// Gap between wrapped lines (in canvas units).
+    #[serde(default)]
+    pub cross_gap: f32,
+
+    /// Distribution of wrapped lines along the cross axis.
+    #[serde(default)]
+    pub line_alignment: LineAlignment,
 }
 
 impl Default for FrameLayout {
@@ -35,6 +48,9 @@ impl Default for FrameLayout {
             cross_axis_alignment: CrossAxisAlignment::Start,
             gap: 0.0,
             padding: Padding::default(),
+            wrap: false,
+            cross_gap: 0.0,
+            line_alignment: LineAlignment::Start,
         }
     }
 }
@@ -76,6 +92,19 @@ impl FrameLayout {
         self.cross_axis_alignment = alignment;
         self
     }
+
+    /// Enable wrapping with the given gap between lines.
+    pub fn with_wrap(mut self, cross_gap: f32) -> Self {
+        self.wrap = true;
+        self.cross_gap = cross_gap;
+        self
+    }
+
+    /// Set how wrapped lines are distributed on the cross axis.
+    pub fn with_line_alignment(mut self, alignment: LineAlignment) -> Self {
+        self.line_alignment = alignment;
+        self
+    }
 }
 
 /// Direction children are laid out.
@@ -119,6 +148,24 @@ pub enum CrossAxisAlignment {
     Stretch,
 }
 
+/// Distribution of wrapped lines along the cross axis.
+///
+/// Only applies when [`FrameLayout::wrap`] is set. Children within a line are
+/// still aligned by [`CrossAxisAlignment`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LineAlignment {
+    /// Pack lines at the start.
+    #[default]
+    Start,
+    /// Center lines.
+    Center,
+    /// Pack lines at the end.
+    End,
+    /// Distribute space between lines.
+    SpaceBetween,
+}
+
 /// Padding (inset) for frame content.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub struct Padding {
@@ -183,15 +230,57 @@ pub enum SizingMode {
     Hug,
 }
 
+/// How an absolutely positioned child follows its parent when it resizes.
+///
+/// Named by edge rather than left/right or top/bottom so one type serves
+/// both axes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Constraint {
+    /// Keep the distance to the parent's start (left/top) edge.
+    #[default]
+    Start,
+    /// Keep the distance to the parent's end (right/bottom) edge.
+    End,
+    /// Keep the offset from the parent's center.
+    Center,
+    /// Keep the distance to both edges, resizing with the parent.
+    Stretch,
+}
+
 /// Child-specific layout settings.
 ///
 /// Controls how a shape behaves when it's a child of a layout frame.
-#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+///
+/// The min/max limits clamp whatever size the sizing mode produces, so a
+/// Fill child can stop growing and a Hug frame can stop shrinking.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub struct ChildLayout {
     /// How width is determined in a layout.
     pub width_mode: SizingMode,
     /// How height is determined in a layout.
     pub height_mode: SizingMode,
+    /// Smallest width the layout may give this shape.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_width: Option<f32>,
+    /// Largest width the layout may give this shape.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_width: Option<f32>,
+    /// Smallest height the layout may give this shape.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_height: Option<f32>,
+    /// Largest height the layout may give this shape.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_height: Option<f32>,
+    /// Take this shape out of the flow and keep its manual position.
+    #[serde(default)]
+    pub absolute: bool,
+    /// How an absolute shape follows horizontal changes to its parent's size.
+    #[serde(default)]
+    pub horizontal_constraint: Constraint,
+    /// How an absolute shape follows vertical changes to its parent's size.
+    #[serde(default)]
+    pub vertical_constraint: Constraint,
 }
 
 impl ChildLayout {
@@ -205,6 +294,7 @@ impl ChildLayout {
         Self {
             width_mode: SizingMode::Fill,
             height_mode: SizingMode::Fixed,
+            ..Default::default()
         }
     }
 
@@ -213,6 +303,7 @@ impl ChildLayout {
         Self {
             width_mode: SizingMode::Fill,
             height_mode: SizingMode::Fill,
+            ..Default::default()
         }
     }
 
@@ -227,6 +318,54 @@ impl ChildLayout {
         self.height_mode = mode;
         self
     }
+
+    /// Position this shape absolutely with the given constraints.
+    pub fn absolute(mut self, horizontal: Constraint, vertical: Constraint) -> Self {
+        self.absolute = true;
+        self.horizontal_constraint = horizontal;
+        self.vertical_constraint = vertical;
+        self
+    }
+
+    /// Set width limits.
+    pub fn with_width_limits(mut self, min: Option<f32>, max: Option<f32>) -> Self {
+        self.min_width = min;
+        self.max_width = max;
+        self
+    }
+
+    /// Set height limits.
+    pub fn with_height_limits(mut self, min: Option<f32>, max: Option<f32>) -> Self {
+        self.min_height = min;
+        self.max_height = max;
+        self
+    }
+
+    /// Lower size limit, zero on unconstrained axes.
+    pub fn min_size(&self) -> CanvasSize {
+        CanvasSize::new(self.min_width.unwrap_or(0.0), self.min_height.unwrap_or(0.0))
+    }
+
+    /// Upper size limit, infinite on unconstrained axes.
+    pub fn max_size(&self) -> CanvasSize {
+        CanvasSize::new(
+            self.max_width.unwrap_or(f32::INFINITY),
+            self.max_height.unwrap_or(f32::INFINITY),
+        )
+    }
+
+    /// Clamp `size` to the min/max limits. The minimum wins if they conflict.
+    pub fn clamp_size(&self, size: CanvasSize) -> CanvasSize {
+        clamp_size(size, self.min_size(), self.max_size())
+    }
+}
+
+/// Clamp each axis of `size` into `min..=max`, preferring `min` on conflict.
+pub(crate) fn clamp_size(size: CanvasSize, min: CanvasSize, max: CanvasSize) -> CanvasSize {
+    CanvasSize::new(
+        size.width().min(max.width()).max(min.width()),
+        size.height().min(max.height()).max(min.height()),
+    )
 }
 
 #[cfg(test)]
@@ -343,6 +482,73 @@ mod tests {
         assert_eq!(ChildLayout::default().height_mode, SizingMode::Fixed);
     }
 
+    #[test]
+    fn child_layout_default_is_unconstrained() {
+        let child = ChildLayout::default();
+        let size = CanvasSize::new(1.0, 10_000.0);
+        assert_eq!(child.clamp_size(size), size);
+    }
+
+    #[test]
+    fn child_layout_default_is_in_flow() {
+        let child = ChildLayout::default();
+        assert!(!child.absolute);
+        assert_eq!(child.horizontal_constraint, Constraint::Start);
+        assert_eq!(child.vertical_constraint, Constraint::Start);
+    }
+
+    #[test]
+    fn absolute_sets_flag_and_constraints() {
+        let child = ChildLayout::default().absolute(Constraint::End, Constraint::Start);
+        assert!(child.absolute);
+        assert_eq!(child.horizontal_constraint, Constraint::End);
+    }
+
+    // === ChildLayout limits ===
+
+    #[test]
+    fn clamp_size_applies_min_and_max() {
+        let child = ChildLayout::default()
+            .with_width_limits(Some(20.0), Some(80.0))
+            .with_height_limits(None, Some(40.0));
+
+        assert_eq!(child.clamp_size(CanvasSize::new(10.0, 100.0)), CanvasSize::new(20.0, 40.0));
+        assert_eq!(child.clamp_size(CanvasSize::new(100.0, 5.0)), CanvasSize::new(80.0, 5.0));
+    }
+
+    #[test]
+    fn clamp_size_prefers_min_when_limits_conflict() {
+        let child = ChildLayout::default().with_width_limits(Some(50.0), Some(30.0));
+        assert_eq!(child.clamp_size(CanvasSize::new(40.0, 0.0)).width(), 50.0);
+    }
+
+    // === Wrap ===
+
+    #[test]
+    fn default_wrap_is_off() {
+        let layout = FrameLayout::default();
+        assert!(!layout.wrap);
+        assert_eq!(layout.cross_gap, 0.0);
+        assert_eq!(layout.line_alignment, LineAlignment::Start);
+    }
+
+    #[test]
+    fn with_wrap_enables_wrap_and_sets_cross_gap() {
+        let layout = FrameLayout::row().with_wrap(8.0);
+        assert!(layout.wrap);
+        assert_eq!(layout.cross_gap, 8.0);
+    }
+
+    #[test]
+    fn layout_without_wrap_fields_deserializes() {
+        let json = r#"{"direction":"row","main_axis_alignment":"start","cross_axis_alignment":"start","gap":4.0,"padding":{"top":0.0,"right":0.0,"bottom":0.0,"left":0.0}}"#;
+        let parsed: FrameLayout = serde_json::from_str(json).unwrap();
+        assert!(!parsed.wrap);
+        assert_eq!(parsed.gap, 4.0);
+    }
+
+    // === Serialization ===
+
     #[test]
     fn test_serde_roundtrip() {
         let layout = FrameLayout::column()
@@ -5,9 +5,11 @@
 
 use crate::coords::{CanvasPoint, CanvasSize};
 use crate::layout::{
-    CrossAxisAlignment, FrameLayout, LayoutDirection, MainAxisAlignment, SizingMode,
+    clamp_size, ChildLayout, Constraint, CrossAxisAlignment, FrameLayout, LayoutDirection, LineAlignment,
+    MainAxisAlignment, SizingMode,
 };
 use crate::ShapeId;
+use std::ops::Range;
 
 /// Input for layout computation.
 #[derive(Clone, Debug)]
@@ -20,6 +22,42 @@ pub struct LayoutInput {
     pub width_mode: SizingMode,
     /// How height is determined in layout.
     pub height_mode: SizingMode,
+    /// Smallest size layout may assign.
+    pub min_size: CanvasSize,
+    /// Largest size layout may assign.
+    pub max_size: CanvasSize,
+}
+
+impl LayoutInput {
+    /// Create an input with no size limits.
+    pub fn new(id: ShapeId, size: CanvasSize, width_mode: SizingMode, height_mode: SizingMode) -> Self {
+        Self {
+            id,
+            size,
+            width_mode,
+            height_mode,
+            min_size: CanvasSize::new(0.0, 0.0),
+            max_size: CanvasSize::new(f32::INFINITY, f32::INFINITY),
+        }
+    }
+
+    /// Create an input from a shape's size and its [`ChildLayout`] settings.
+    pub fn from_child_layout(id: ShapeId, size: CanvasSize, child_layout: &ChildLayout) -> Self {
+        Self::new(id, size, child_layout.width_mode, child_layout.height_mode)
+            .with_limits(child_layout.min_size(), child_layout.max_size())
+    }
+
+    /// Set the min/max size limits.
+    pub fn with_limits(mut self, min: CanvasSize, max: CanvasSize) -> Self {
+        self.min_size = min;
+        self.max_size = max;
+        self
+    }
+
+    /// The shape's own size within its limits.
+    fn clamped_size(&self) -> CanvasSize {
+        clamp_size(self.size, self.min_size, self.max_size)
+    }
 }
 
 /// Output from layout computation.
@@ -38,6 +76,10 @@ pub struct LayoutOutput {
 /// Returns new positions and sizes for each child, relative to frame origin
 /// (accounting for padding).
 ///
+/// With `layout.wrap` set, children that don't fit on the main axis move to a
+/// new line. Each line is as thick as its largest child, children align
+/// within their line, and lines are distributed by `line_alignment`.
+///
 /// # Arguments
 /// * `frame_size` - Size of the containing frame
 /// * `layout` - Layout configuration (direction, alignment, gap, padding)
@@ -65,46 +107,270 @@ pub fn compute_layout(
         LayoutDirection::Column => (content_height, content_width),
     };
 
-    // Calculate sizes first (handle Fill children)
-    let child_sizes = compute_child_sizes(children, layout, main_size, cross_size);
+    if !layout.wrap {
+        // Calculate sizes first (handle Fill children)
+        let child_sizes = compute_child_sizes(children, layout, main_size, cross_size);
+
+        // Calculate positions
+        return compute_positions(layout, &child_sizes, main_size, cross_size, 0.0, children);
+    }
+
+    let lines = break_lines(layout, children, main_size);
+    let extents: Vec<f32> = lines
+        .iter()
+        .map(|line| {
+            children[line.clone()]
+                .iter()
+                .map(|c| cross_of(layout.direction, c.clamped_size()))
+                .fold(0.0, f32::max)
+        })
+        .collect();
+
+    let mut outputs = Vec::with_capacity(children.len());
+    for ((line, extent), offset) in lines
+        .iter()
+        .zip(&extents)
+        .zip(line_offsets(layout, &extents, cross_size))
+    {
+        let line_children = &children[line.clone()];
+        let child_sizes = compute_child_sizes(line_children, layout, main_size, *extent);
+        outputs.extend(compute_positions(
+            layout,
+            &child_sizes,
+            main_size,
+            *extent,
+            offset,
+            line_children,
+        ));
+    }
+    outputs
+}
+
+/// Compute the size a frame needs to hug its children on a single line.
+///
+/// On the main axis this is the sum of child sizes plus gaps, on the cross
+/// axis the largest child; padding is added to both. Fill children count at
+/// their own size, since a hugging frame has no spare room to hand out.
+pub fn compute_hug_size(layout: &FrameLayout, children: &[LayoutInput]) -> CanvasSize {
+    let (main, cross) = line_extent(layout, children);
+    padded_size(layout, main, cross)
+}
+
+/// Resolve a layout frame's own size from its sizing settings.
+///
+/// Axes in Hug mode take their size from the children, clamped to the
+/// frame's own min/max limits; all other axes keep `frame_size`. A frame with
+/// no children keeps its size so an empty hugging frame doesn't collapse to
+/// its padding.
+///
+/// A wrapping frame with a fixed main axis breaks its children into lines at
+/// that size and hugs the stacked lines on the cross axis. If the main axis
+/// hugs too, there is nothing to wrap against and the children stay on one
+/// line.
+pub fn resolve_frame_size(
+    frame_size: CanvasSize,
+    sizing: &ChildLayout,
+    layout: &FrameLayout,
+    children: &[LayoutInput],
+) -> CanvasSize {
+    if children.is_empty() {
+        return frame_size;
+    }
+    let main_mode = match layout.direction {
+        LayoutDirection::Row => sizing.width_mode,
+        LayoutDirection::Column => sizing.height_mode,
+    };
+    let hug = if layout.wrap && main_mode != SizingMode::Hug {
+        compute_wrapped_hug_size(frame_size, layout, children)
+    } else {
+        compute_hug_size(layout, children)
+    };
+    let hug = sizing.clamp_size(hug);
+    CanvasSize::new(
+        if sizing.width_mode == SizingMode::Hug { hug.width() } else { frame_size.width() },
+        if sizing.height_mode == SizingMode::Hug { hug.height() } else { frame_size.height() },
+    )
+}
+
+/// Move and resize an absolutely positioned child after its parent changed
+/// size from `old_frame_size` to `new_frame_size`.
+///
+/// Returns the child's new position (relative to the parent) and size.
+pub fn apply_constraints(
+    position: CanvasPoint,
+    size: CanvasSize,
+    old_frame_size: CanvasSize,
+    new_frame_size: CanvasSize,
+    child_layout: &ChildLayout,
+) -> (CanvasPoint, CanvasSize) {
+    let (x, width) = constrain_axis(
+        position.x(),
+        size.width(),
+        new_frame_size.width() - old_frame_size.width(),
+        child_layout.horizontal_constraint,
+    );
+    let (y, height) = constrain_axis(
+        position.y(),
+        size.height(),
+        new_frame_size.height() - old_frame_size.height(),
+        child_layout.vertical_constraint,
+    );
+    (CanvasPoint::new(x, y), CanvasSize::new(width, height))
+}
+
+fn constrain_axis(offset: f32, length: f32, delta: f32, constraint: Constraint) -> (f32, f32) {
+    match constraint {
+        Constraint::Start => (offset, length),
+        Constraint::End => (offset + delta, length),
+        Constraint::Center => (offset + delta / 2.0, length),
+        Constraint::Stretch => (offset, (length + delta).max(0.0)),
+    }
+}
+
+/// Hug size for a wrapping layout whose main axis is fixed at `frame_size`.
+fn compute_wrapped_hug_size(
+    frame_size: CanvasSize,
+    layout: &FrameLayout,
+    children: &[LayoutInput],
+) -> CanvasSize {
+    let padding_main = match layout.direction {
+        LayoutDirection::Row => layout.padding.horizontal(),
+        LayoutDirection::Column => layout.padding.vertical(),
+    };
+    let main_size = (main_of(layout.direction, frame_size) - padding_main).max(0.0);
+
+    let lines = break_lines(layout, children, main_size);
+    let mut main: f32 = 0.0;
+    let mut cross = layout.cross_gap * (lines.len() - 1) as f32;
+    for line in lines {
+        let (line_main, line_cross) = line_extent(layout, &children[line]);
+        main = main.max(line_main);
+        cross += line_cross;
+    }
+    padded_size(layout, main, cross)
+}
+
+/// Main-axis length (children plus gaps) and cross-axis thickness of a line.
+fn line_extent(layout: &FrameLayout, children: &[LayoutInput]) -> (f32, f32) {
+    let total_gap = if children.len() > 1 {
+        layout.gap * (children.len() - 1) as f32
+    } else {
+        0.0
+    };
+    let main: f32 = children
+        .iter()
+        .map(|c| main_of(layout.direction, c.clamped_size()))
+        .sum::<f32>()
+        + total_gap;
+    let cross = children
+        .iter()
+        .map(|c| cross_of(layout.direction, c.clamped_size()))
+        .fold(0.0, f32::max);
+    (main, cross)
+}
+
+/// Frame size for content of the given main/cross extent, including padding.
+fn padded_size(layout: &FrameLayout, main: f32, cross: f32) -> CanvasSize {
+    match layout.direction {
+        LayoutDirection::Row => CanvasSize::new(
+            main + layout.padding.horizontal(),
+            cross + layout.padding.vertical(),
+        ),
+        LayoutDirection::Column => CanvasSize::new(
+            cross + layout.padding.horizontal(),
+            main + layout.padding.vertical(),
+        ),
+    }
+}
+
+/// Split children into consecutive lines that fit within `main_size`.
+///
+/// Children are measured at their own (clamped) size. Every line holds at
+/// least one child, so a child wider than the frame gets a line to itself
+/// and overflows it.
+fn break_lines(layout: &FrameLayout, children: &[LayoutInput], main_size: f32) -> Vec<Range<usize>> {
+    let mut lines = Vec::new();
+    let mut start = 0;
+    let mut used = 0.0;
+    for (i, child) in children.iter().enumerate() {
+        let child_main = main_of(layout.direction, child.clamped_size());
+        if i > start && used + layout.gap + child_main > main_size {
+            lines.push(start..i);
+            start = i;
+            used = child_main;
+        } else if i > start {
+            used += layout.gap + child_main;
+        } else {
+            used = child_main;
+        }
+    }
+    lines.push(start..children.len());
+    lines
+}
+
+/// Cross-axis offset of each wrapped line.
+///
+/// Like main-axis alignment, offsets never go negative when the lines
+/// overflow the frame.
+fn line_offsets(layout: &FrameLayout, extents: &[f32], cross_size: f32) -> Vec<f32> {
+    let total_lines: f32 = extents.iter().sum();
+    let total_gap = layout.cross_gap * extents.len().saturating_sub(1) as f32;
+    let free = cross_size - total_lines - total_gap;
+
+    let (start, gap) = match layout.line_alignment {
+        LineAlignment::Start => (0.0, layout.cross_gap),
+        LineAlignment::Center => ((free / 2.0).max(0.0), layout.cross_gap),
+        LineAlignment::End => (free.max(0.0), layout.cross_gap),
+        LineAlignment::SpaceBetween => {
+            if extents.len() <= 1 || free < 0.0 {
+                (0.0, layout.cross_gap)
+            } else {
+                (0.0, layout.cross_gap + free / (extents.len() - 1) as f32)
+            }
+        }
+    };
+
+    let mut current = start;
+    extents
+        .iter()
+        .map(|extent| {
+            let offset = current;
+            current += extent + gap;
+            offset
+        })
+        .collect()
+}
+
+fn main_of(direction: LayoutDirection, size: CanvasSize) -> f32 {
+    match direction {
+        LayoutDirection::Row => size.width(),
+        LayoutDirection::Column => size.height(),
+    }
+}
 
-    // Calculate positions
-    compute_positions(layout, &child_sizes, main_size, cross_size, children)
+fn cross_of(direction: LayoutDirection, size: CanvasSize) -> f32 {
+    match direction {
+        LayoutDirection::Row => size.height(),
+        LayoutDirection::Column => size.width(),
+    }
 }
 
-/// Compute final sizes for all children, handling Fill mode.
+/// Compute final sizes for all children, handling Fill mode and size limits.
 fn compute_child_sizes(
     children: &[LayoutInput],
     layout: &FrameLayout,
     main_size: f32,
     cross_size: f32,
 ) -> Vec<CanvasSize> {
+    let direction = layout.direction;
+
     // Determine which sizing mode applies to which axis based on direction
     let (main_mode_fn, cross_mode_fn): (fn(&LayoutInput) -> SizingMode, fn(&LayoutInput) -> SizingMode) =
-        match layout.direction {
+        match direction {
             LayoutDirection::Row => (|c| c.width_mode, |c| c.height_mode),
             LayoutDirection::Column => (|c| c.height_mode, |c| c.width_mode),
         };
 
-    let (main_size_fn, cross_size_fn): (fn(&CanvasSize) -> f32, fn(&CanvasSize) -> f32) =
-        match layout.direction {
-            LayoutDirection::Row => (|s| s.width(), |s| s.height()),
-            LayoutDirection::Column => (|s| s.height(), |s| s.width()),
-        };
-
-    // Count fill children and calculate fixed space usage
-    let mut fill_count = 0;
-    let mut fixed_main = 0.0;
-
-    for child in children {
-        match main_mode_fn(child) {
-            SizingMode::Fill => fill_count += 1,
-            SizingMode::Fixed | SizingMode::Hug => {
-                fixed_main += main_size_fn(&child.size);
-            }
-        }
-    }
-
     // Calculate total gap space
     let total_gap = if children.len() > 1 {
         layout.gap * (children.len() - 1) as f32
@@ -112,36 +378,70 @@ fn compute_child_sizes(
         0.0
     };
 
-    // Calculate space available for fill children
-    let available_for_fill = (main_size - fixed_main - total_gap).max(0.0);
-    let fill_size = if fill_count > 0 {
-        available_for_fill / fill_count as f32
-    } else {
-        0.0
-    };
+    // Fixed and Hug children keep their own size; Fill children start unresolved
+    let mut mains: Vec<Option<f32>> = children
+        .iter()
+        .map(|child| match main_mode_fn(child) {
+            SizingMode::Fill => None,
+            SizingMode::Fixed | SizingMode::Hug => Some(main_of(direction, child.clamped_size())),
+        })
+        .collect();
+
+    // Share the remaining space between unresolved Fill children. If the share
+    // breaks some children's limits, pin the ones on the side of the larger
+    // total violation and share again among the rest (as CSS flexbox does).
+    loop {
+        let open: Vec<usize> = (0..children.len()).filter(|i| mains[*i].is_none()).collect();
+        if open.is_empty() {
+            break;
+        }
+        let used: f32 = mains.iter().flatten().sum();
+        let share = (main_size - used - total_gap).max(0.0) / open.len() as f32;
+
+        let clamped: Vec<f32> = open
+            .iter()
+            .map(|i| {
+                let child = &children[*i];
+                share
+                    .min(main_of(direction, child.max_size))
+                    .max(main_of(direction, child.min_size))
+            })
+            .collect();
+        let violation: f32 = clamped.iter().map(|size| size - share).sum();
+
+        if violation == 0.0 {
+            for i in open {
+                mains[i] = Some(share);
+            }
+            break;
+        }
+        for (i, size) in open.into_iter().zip(clamped) {
+            let pin = if violation > 0.0 { size > share } else { size < share };
+            if pin {
+                mains[i] = Some(size);
+            }
+        }
+    }
 
     // Compute final sizes
     children
         .iter()
-        .map(|child| {
-            let main = match main_mode_fn(child) {
-                SizingMode::Fill => fill_size,
-                SizingMode::Fixed | SizingMode::Hug => main_size_fn(&child.size),
-            };
-
-            let cross = match cross_mode_fn(child) {
-                SizingMode::Fill => cross_size,
-                SizingMode::Fixed | SizingMode::Hug => cross_size_fn(&child.size),
-            };
-
-            // Handle Stretch on cross axis
-            let cross = if layout.cross_axis_alignment == CrossAxisAlignment::Stretch {
+        .zip(mains)
+        .map(|(child, main)| {
+            let main = main.unwrap_or(0.0);
+
+            // Fill and Stretch both take the cross size, within limits
+            let fills_cross = cross_mode_fn(child) == SizingMode::Fill
+                || layout.cross_axis_alignment == CrossAxisAlignment::Stretch;
+            let cross = if fills_cross {
                 cross_size
+                    .min(cross_of(direction, child.max_size))
+                    .max(cross_of(direction, child.min_size))
             } else {
-                cross
+                cross_of(direction, child.clamped_size())
             };
 
-            match layout.direction {
+            match direction {
                 LayoutDirection::Row => CanvasSize::new(main, cross),
                 LayoutDirection::Column => CanvasSize::new(cross, main),
             }
@@ -149,12 +449,16 @@ fn compute_child_sizes(
         .collect()
 }
 
-/// Compute positions for all children based on alignment.
+/// Compute positions for all children in one line based on alignment.
+///
+/// `cross_size` is the thickness of the line and `cross_offset` its start
+/// within the content area.
 fn compute_positions(
     layout: &FrameLayout,
     child_sizes: &[CanvasSize],
     main_size: f32,
     cross_size: f32,
+    cross_offset: f32,
     children: &[LayoutInput],
 ) -> Vec<LayoutOutput> {
     let main_size_fn: fn(&CanvasSize) -> f32 = match layout.direction {
@@ -213,12 +517,13 @@ fn compute_positions(
             let child_cross = cross_size_fn(size);
 
             // Cross-axis position based on alignment
-            let cross_pos = match layout.cross_axis_alignment {
-                CrossAxisAlignment::Start => 0.0,
-                CrossAxisAlignment::Center => (cross_size - child_cross) / 2.0,
-                CrossAxisAlignment::End => cross_size - child_cross,
-                CrossAxisAlignment::Stretch => 0.0, // Size already stretched
-            };
+            let cross_pos = cross_offset
+                + match layout.cross_axis_alignment {
+                    CrossAxisAlignment::Start => 0.0,
+                    CrossAxisAlignment::Center => (cross_size - child_cross) / 2.0,
+                    CrossAxisAlignment::End => cross_size - child_cross,
+                    CrossAxisAlignment::Stretch => 0.0, // Size already stretched
+                };
 
             // Create position based on direction
             let position = match layout.direction {
@@ -258,35 +563,35 @@ mod tests {
     /// Create a child with Fixed sizing on both axes.
     /// The child will maintain its specified size regardless of layout alignment.
     fn fixed_child(id: u128, width: f32, height: f32) -> LayoutInput {
-        LayoutInput {
-            id: ShapeId::from_u128(id),
-            size: CanvasSize::new(width, height),
-            width_mode: SizingMode::Fixed,
-            height_mode: SizingMode::Fixed,
-        }
+        LayoutInput::new(
+            ShapeId::from_u128(id),
+            CanvasSize::new(width, height),
+            SizingMode::Fixed,
+            SizingMode::Fixed,
+        )
     }
 
     /// Create a child that fills available width (stretches horizontally).
     /// Height remains fixed at the specified value.
     fn fill_width_child(id: u128, width: f32, height: f32) -> LayoutInput {
-        LayoutInput {
-            id: ShapeId::from_u128(id),
-            size: CanvasSize::new(width, height),
-            width_mode: SizingMode::Fill,
-            height_mode: SizingMode::Fixed,
-        }
+        LayoutInput::new(
+            ShapeId::from_u128(id),
+            CanvasSize::new(width, height),
+            SizingMode::Fill,
+            SizingMode::Fixed,
+        )
     }
 
     /// Create a child that fills available height (stretches vertically).
     /// Width remains fixed at the specified value.
     #[allow(dead_code)]
     fn fill_height_child(id: u128, width: f32, height: f32) -> LayoutInput {
-        LayoutInput {
-            id: ShapeId::from_u128(id),
-            size: CanvasSize::new(width, height),
-            width_mode: SizingMode::Fixed,
-            height_mode: SizingMode::Fill,
-        }
+        LayoutInput::new(
+            ShapeId::from_u128(id),
+            CanvasSize::new(width, height),
+            SizingMode::Fixed,
+            SizingMode::Fill,
+        )
     }
 
     #[test]
@@ -511,4 +816,465 @@ mod tests {
         // Should clamp to 0, not negative
         assert_eq!(result[0].position, CanvasPoint::new(0.0, 0.0));
     }
+
+    // === Hug sizing ===
+
+    #[test]
+    fn test_hug_size_row() {
+        let layout = FrameLayout::row().with_gap(10.0);
+        let children = vec![fixed_child(1, 50.0, 30.0), fixed_child(2, 40.0, 60.0)];
+
+        let size = compute_hug_size(&layout, &children);
+
+        // 50 + 10 + 40 wide, tallest child 60
+        assert_eq!(size, CanvasSize::new(100.0, 60.0));
+    }
+
+    #[test]
+    fn test_hug_size_column() {
+        let layout = FrameLayout::column().with_gap(5.0);
+        let children = vec![fixed_child(1, 50.0, 30.0), fixed_child(2, 40.0, 60.0)];
+
+        let size = compute_hug_size(&layout, &children);
+
+        assert_eq!(size, CanvasSize::new(50.0, 95.0));
+    }
+
+    #[test]
+    fn test_hug_size_includes_padding() {
+        let layout = FrameLayout {
+            padding: Padding::new(1.0, 2.0, 3.0, 4.0),
+            ..FrameLayout::row()
+        };
+        let children = vec![fixed_child(1, 50.0, 30.0)];
+
+        let size = compute_hug_size(&layout, &children);
+
+        assert_eq!(size, CanvasSize::new(56.0, 34.0));
+    }
+
+    #[test]
+    fn test_hug_size_counts_fill_children_at_own_size() {
+        let layout = FrameLayout::row();
+        let children = vec![fixed_child(1, 50.0, 30.0), fill_width_child(2, 20.0, 30.0)];
+
+        let size = compute_hug_size(&layout, &children);
+
+        assert_eq!(size.width(), 70.0);
+    }
+
+    #[test]
+    fn test_resolve_frame_size_hugs_only_hug_axes() {
+        let layout = FrameLayout::row().with_gap(10.0).with_padding(8.0);
+        let children = vec![fixed_child(1, 50.0, 30.0), fixed_child(2, 50.0, 30.0)];
+
+        let size = resolve_frame_size(
+            CanvasSize::new(400.0, 200.0),
+            &ChildLayout::default().with_width(SizingMode::Hug),
+            &layout,
+            &children,
+        );
+
+        assert_eq!(size, CanvasSize::new(126.0, 200.0));
+    }
+
+    #[test]
+    fn test_resolve_frame_size_keeps_size_without_children() {
+        let size = resolve_frame_size(
+            CanvasSize::new(400.0, 200.0),
+            &ChildLayout::default().with_width(SizingMode::Hug).with_height(SizingMode::Hug),
+            &FrameLayout::default(),
+            &[],
+        );
+
+        assert_eq!(size, CanvasSize::new(400.0, 200.0));
+    }
+
+    #[test]
+    fn test_hugged_frame_fits_children_exactly() {
+        // Laying out children in the hugged size leaves no slack, so
+        // alignment has no effect and nothing overflows
+        let layout = FrameLayout::row()
+            .with_gap(10.0)
+            .with_padding(8.0)
+            .with_main_axis(MainAxisAlignment::End);
+        let children = vec![fixed_child(1, 50.0, 30.0), fixed_child(2, 40.0, 20.0)];
+
+        let frame_size = compute_hug_size(&layout, &children);
+        let result = compute_layout(frame_size, &layout, &children);
+
+        assert_eq!(result[0].position, CanvasPoint::new(8.0, 8.0));
+        assert_eq!(result[1].position, CanvasPoint::new(68.0, 8.0));
+    }
+
+    #[test]
+    fn test_resolve_frame_size_clamps_hug_to_limits() {
+        let layout = FrameLayout::row();
+        let children = vec![fixed_child(1, 50.0, 30.0), fixed_child(2, 50.0, 30.0)];
+        let sizing = ChildLayout::default()
+            .with_width(SizingMode::Hug)
+            .with_height(SizingMode::Hug)
+            .with_width_limits(None, Some(80.0))
+            .with_height_limits(Some(40.0), None);
+
+        let size = resolve_frame_size(CanvasSize::new(400.0, 200.0), &sizing, &layout, &children);
+
+        assert_eq!(size, CanvasSize::new(80.0, 40.0));
+    }
+
+    // === Min/max limits ===
+
+    #[test]
+    fn test_fill_child_stops_at_max() {
+        let layout = FrameLayout::row();
+        let children = vec![
+            fill_width_child(1, 0.0, 30.0).with_limits(
+                CanvasSize::new(0.0, 0.0),
+                CanvasSize::new(50.0, f32::INFINITY),
+            ),
+            fill_width_child(2, 0.0, 30.0),
+        ];
+
+        let result = compute_layout(CanvasSize::new(200.0, 100.0), &layout, &children);
+
+        // The capped child gives up its share to the other one
+        assert_eq!(result[0].size.width(), 50.0);
+        assert_eq!(result[1].size.width(), 150.0);
+        assert_eq!(result[1].position, CanvasPoint::new(50.0, 0.0));
+    }
+
+    #[test]
+    fn test_fill_child_grows_to_min() {
+        let layout = FrameLayout::row();
+        let children = vec![
+            fill_width_child(1, 0.0, 30.0).with_limits(
+                CanvasSize::new(150.0, 0.0),
+                CanvasSize::new(f32::INFINITY, f32::INFINITY),
+            ),
+            fill_width_child(2, 0.0, 30.0),
+        ];
+
+        let result = compute_layout(CanvasSize::new(200.0, 100.0), &layout, &children);
+
+        assert_eq!(result[0].size.width(), 150.0);
+        assert_eq!(result[1].size.width(), 50.0);
+    }
+
+    #[test]
+    fn test_fill_limits_only_pin_the_dominant_violation() {
+        // Share is 50: child 1 wants to shrink by 30, child 2 to grow by 10.
+        // Shrinking dominates, so only child 1 is pinned and the rest re-split.
+        let layout = FrameLayout::row();
+        let children = vec![
+            fill_width_child(1, 0.0, 30.0).with_limits(
+                CanvasSize::new(0.0, 0.0),
+                CanvasSize::new(20.0, f32::INFINITY),
+            ),
+            fill_width_child(2, 0.0, 30.0).with_limits(
+                CanvasSize::new(60.0, 0.0),
+                CanvasSize::new(f32::INFINITY, f32::INFINITY),
+            ),
+            fill_width_child(3, 0.0, 30.0),
+        ];
+
+        let result = compute_layout(CanvasSize::new(150.0, 100.0), &layout, &children);
+
+        assert_eq!(result[0].size.width(), 20.0);
+        assert_eq!(result[1].size.width(), 65.0);
+        assert_eq!(result[2].size.width(), 65.0);
+    }
+
+    #[test]
+    fn test_stretch_respects_cross_max() {
+        let layout = FrameLayout::row().with_cross_axis(CrossAxisAlignment::Stretch);
+        let children = vec![fixed_child(1, 50.0, 30.0).with_limits(
+            CanvasSize::new(0.0, 0.0),
+            CanvasSize::new(f32::INFINITY, 60.0),
+        )];
+
+        let result = compute_layout(CanvasSize::new(200.0, 100.0), &layout, &children);
+
+        assert_eq!(result[0].size, CanvasSize::new(50.0, 60.0));
+    }
+
+    #[test]
+    fn test_fixed_child_is_clamped() {
+        let layout = FrameLayout::row();
+        let children = vec![fixed_child(1, 50.0, 30.0).with_limits(
+            CanvasSize::new(80.0, 0.0),
+            CanvasSize::new(f32::INFINITY, 20.0),
+        )];
+
+        let result = compute_layout(CanvasSize::new(200.0, 100.0), &layout, &children);
+
+        assert_eq!(result[0].size, CanvasSize::new(80.0, 20.0));
+    }
+
+    #[test]
+    fn test_from_child_layout_carries_limits() {
+        let child_layout = ChildLayout::fill_main().with_width_limits(Some(10.0), Some(90.0));
+        let input = LayoutInput::from_child_layout(
+            ShapeId::from_u128(1),
+            CanvasSize::new(50.0, 50.0),
+            &child_layout,
+        );
+
+        assert_eq!(input.width_mode, SizingMode::Fill);
+        assert_eq!(input.min_size, CanvasSize::new(10.0, 0.0));
+        assert_eq!(input.max_size, CanvasSize::new(90.0, f32::INFINITY));
+    }
+
+    // === Wrap ===
+
+    #[test]
+    fn test_wrap_breaks_into_lines() {
+        let layout = FrameLayout::row().with_gap(10.0).with_wrap(5.0);
+        let children = vec![
+            fixed_child(1, 50.0, 30.0),
+            fixed_child(2, 50.0, 20.0),
+            fixed_child(3, 50.0, 30.0),
+        ];
+
+        // 50 + 10 + 50 fits in 120, the third child does not
+        let result = compute_layout(CanvasSize::new(120.0, 200.0), &layout, &children);
+
+        assert_eq!(result[0].position, CanvasPoint::new(0.0, 0.0));
+        assert_eq!(result[1].position, CanvasPoint::new(60.0, 0.0));
+        // First line is 30 thick, plus 5 line gap
+        assert_eq!(result[2].position, CanvasPoint::new(0.0, 35.0));
+    }
+
+    #[test]
+    fn test_wrap_column_breaks_on_height() {
+        let layout = FrameLayout::column().with_wrap(10.0);
+        let children = vec![
+            fixed_child(1, 40.0, 60.0),
+            fixed_child(2, 20.0, 60.0),
+            fixed_child(3, 20.0, 60.0),
+        ];
+
+        let result = compute_layout(CanvasSize::new(200.0, 130.0), &layout, &children);
+
+        assert_eq!(result[1].position, CanvasPoint::new(0.0, 60.0));
+        assert_eq!(result[2].position, CanvasPoint::new(50.0, 0.0));
+    }
+
+    #[test]
+    fn test_wrap_oversized_child_gets_own_line() {
+        let layout = FrameLayout::row().with_wrap(0.0);
+        let children = vec![fixed_child(1, 300.0, 30.0), fixed_child(2, 50.0, 30.0)];
+
+        let result = compute_layout(CanvasSize::new(100.0, 200.0), &layout, &children);
+
+        assert_eq!(result[0].position, CanvasPoint::new(0.0, 0.0));
+        assert_eq!(result[1].position, CanvasPoint::new(0.0, 30.0));
+    }
+
+    #[test]
+    fn test_wrap_aligns_within_line() {
+        let layout = FrameLayout::row()
+            .with_wrap(0.0)
+            .with_cross_axis(CrossAxisAlignment::End);
+        let children = vec![
+            fixed_child(1, 50.0, 40.0),
+            fixed_child(2, 50.0, 20.0),
+            fixed_child(3, 50.0, 10.0),
+        ];
+
+        let result = compute_layout(CanvasSize::new(100.0, 200.0), &layout, &children);
+
+        // Child 2 sits at the bottom of the 40-thick first line, not the frame
+        assert_eq!(result[1].position, CanvasPoint::new(50.0, 20.0));
+        assert_eq!(result[2].position, CanvasPoint::new(0.0, 40.0));
+    }
+
+    #[test]
+    fn test_wrap_stretch_fills_line() {
+        let layout = FrameLayout::row()
+            .with_wrap(0.0)
+            .with_cross_axis(CrossAxisAlignment::Stretch);
+        let children = vec![fixed_child(1, 50.0, 40.0), fixed_child(2, 50.0, 20.0)];
+
+        let result = compute_layout(CanvasSize::new(100.0, 200.0), &layout, &children);
+
+        assert_eq!(result[1].size, CanvasSize::new(50.0, 40.0));
+    }
+
+    #[test]
+    fn test_wrap_fill_children_share_their_line() {
+        let layout = FrameLayout::row().with_wrap(0.0);
+        let children = vec![
+            fixed_child(1, 60.0, 30.0),
+            fill_width_child(2, 30.0, 30.0),
+            fixed_child(3, 80.0, 30.0),
+        ];
+
+        let result = compute_layout(CanvasSize::new(100.0, 200.0), &layout, &children);
+
+        assert_eq!(result[1].size.width(), 40.0);
+        assert_eq!(result[2].position, CanvasPoint::new(0.0, 30.0));
+    }
+
+    #[test]
+    fn test_wrap_line_alignment_end() {
+        let layout = FrameLayout::row()
+            .with_wrap(10.0)
+            .with_line_alignment(LineAlignment::End);
+        let children = vec![fixed_child(1, 80.0, 30.0), fixed_child(2, 80.0, 30.0)];
+
+        let result = compute_layout(CanvasSize::new(100.0, 100.0), &layout, &children);
+
+        // Lines take 70 of 100
+        assert_eq!(result[0].position, CanvasPoint::new(0.0, 30.0));
+        assert_eq!(result[1].position, CanvasPoint::new(0.0, 70.0));
+    }
+
+    #[test]
+    fn test_wrap_line_alignment_center() {
+        let layout = FrameLayout::row()
+            .with_wrap(10.0)
+            .with_line_alignment(LineAlignment::Center);
+        let children = vec![fixed_child(1, 80.0, 30.0), fixed_child(2, 80.0, 30.0)];
+
+        let result = compute_layout(CanvasSize::new(100.0, 100.0), &layout, &children);
+
+        assert_eq!(result[0].position, CanvasPoint::new(0.0, 15.0));
+        assert_eq!(result[1].position, CanvasPoint::new(0.0, 55.0));
+    }
+
+    #[test]
+    fn test_wrap_line_alignment_space_between() {
+        let layout = FrameLayout::row()
+            .with_wrap(0.0)
+            .with_line_alignment(LineAlignment::SpaceBetween);
+        let children = vec![
+            fixed_child(1, 80.0, 20.0),
+            fixed_child(2, 80.0, 20.0),
+            fixed_child(3, 80.0, 20.0),
+        ];
+
+        let result = compute_layout(CanvasSize::new(100.0, 100.0), &layout, &children);
+
+        assert_eq!(result[0].position.y(), 0.0);
+        assert_eq!(result[1].position.y(), 40.0);
+        assert_eq!(result[2].position.y(), 80.0);
+    }
+
+    #[test]
+    fn test_wrap_overflowing_lines_clamp_to_start() {
+        let layout = FrameLayout::row()
+            .with_wrap(0.0)
+            .with_line_alignment(LineAlignment::End);
+        let children = vec![fixed_child(1, 80.0, 60.0), fixed_child(2, 80.0, 60.0)];
+
+        let result = compute_layout(CanvasSize::new(100.0, 100.0), &layout, &children);
+
+        assert_eq!(result[0].position, CanvasPoint::new(0.0, 0.0));
+        assert_eq!(result[1].position, CanvasPoint::new(0.0, 60.0));
+    }
+
+    #[test]
+    fn test_wrap_includes_padding() {
+        let layout = FrameLayout::row().with_padding(10.0).with_wrap(5.0);
+        let children = vec![fixed_child(1, 50.0, 20.0), fixed_child(2, 50.0, 20.0)];
+
+        // Content width is 80, so the children can't share a line
+        let result = compute_layout(CanvasSize::new(100.0, 100.0), &layout, &children);
+
+        assert_eq!(result[0].position, CanvasPoint::new(10.0, 10.0));
+        assert_eq!(result[1].position, CanvasPoint::new(10.0, 35.0));
+    }
+
+    #[test]
+    fn test_resolve_frame_size_hugs_wrapped_lines() {
+        let layout = FrameLayout::row().with_gap(10.0).with_wrap(5.0).with_padding(4.0);
+        let children = vec![
+            fixed_child(1, 50.0, 30.0),
+            fixed_child(2, 50.0, 20.0),
+            fixed_child(3, 50.0, 25.0),
+        ];
+
+        let size = resolve_frame_size(
+            CanvasSize::new(128.0, 10.0),
+            &ChildLayout::default().with_height(SizingMode::Hug),
+            &layout,
+            &children,
+        );
+
+        // Two lines (30 and 25 thick) + line gap + padding
+        assert_eq!(size, CanvasSize::new(128.0, 68.0));
+    }
+
+    #[test]
+    fn test_resolve_frame_size_wrap_with_hug_main_stays_on_one_line() {
+        let layout = FrameLayout::row().with_gap(10.0).with_wrap(5.0);
+        let children = vec![fixed_child(1, 50.0, 30.0), fixed_child(2, 50.0, 20.0)];
+
+        let size = resolve_frame_size(
+            CanvasSize::new(20.0, 10.0),
+            &ChildLayout::default().with_width(SizingMode::Hug).with_height(SizingMode::Hug),
+            &layout,
+            &children,
+        );
+
+        assert_eq!(size, CanvasSize::new(110.0, 30.0));
+    }
+
+    // === Absolute constraints ===
+
+    #[test]
+    fn test_constraints_start_keeps_position() {
+        let (position, size) = apply_constraints(
+            CanvasPoint::new(10.0, 10.0),
+            CanvasSize::new(20.0, 20.0),
+            CanvasSize::new(100.0, 100.0),
+            CanvasSize::new(150.0, 80.0),
+            &ChildLayout::default().absolute(Constraint::Start, Constraint::Start),
+        );
+
+        assert_eq!(position, CanvasPoint::new(10.0, 10.0));
+        assert_eq!(size, CanvasSize::new(20.0, 20.0));
+    }
+
+    #[test]
+    fn test_constraints_end_follows_far_edge() {
+        // A badge 8 units in from the top-right corner stays there
+        let (position, _) = apply_constraints(
+            CanvasPoint::new(72.0, 8.0),
+            CanvasSize::new(20.0, 20.0),
+            CanvasSize::new(100.0, 100.0),
+            CanvasSize::new(150.0, 100.0),
+            &ChildLayout::default().absolute(Constraint::End, Constraint::Start),
+        );
+
+        assert_eq!(position, CanvasPoint::new(122.0, 8.0));
+    }
+
+    #[test]
+    fn test_constraints_center_moves_half_the_delta() {
+        let (position, _) = apply_constraints(
+            CanvasPoint::new(40.0, 40.0),
+            CanvasSize::new(20.0, 20.0),
+            CanvasSize::new(100.0, 100.0),
+            CanvasSize::new(100.0, 60.0),
+            &ChildLayout::default().absolute(Constraint::Start, Constraint::Center),
+        );
+
+        assert_eq!(position, CanvasPoint::new(40.0, 20.0));
+    }
+
+    #[test]
+    fn test_constraints_stretch_resizes_and_never_goes_negative() {
+        let child_layout = ChildLayout::default().absolute(Constraint::Stretch, Constraint::Stretch);
+        let (position, size) = apply_constraints(
+            CanvasPoint::new(10.0, 10.0),
+            CanvasSize::new(80.0, 30.0),
+            CanvasSize::new(100.0, 100.0),
+            CanvasSize::new(120.0, 50.0),
+            &child_layout,
+        );
+
+        assert_eq!(position, CanvasPoint::new(10.0, 10.0));
+        assert_eq!(size, CanvasSize::new(100.0, 0.0));
+    }
 }
@@ -3,17 +3,42 @@
 //! This crate provides a flat, non-hierarchical shape model.
 //! Shapes are rendered in z-order (index in the list).
 
+pub mod align;
+mod blend_mode;
+pub mod component;
 pub mod coords;
+mod fill;
+mod guide;
 pub mod layout;
 pub mod layout_engine;
+mod naming;
+pub mod path;
 mod shape;
 mod shape_id;
+mod stroke;
+pub mod text;
+pub mod transform;
 
+pub use align::{AlignTo, Alignment, DistributeAxis, DEFAULT_TIDY_SPACING};
+pub use blend_mode::BlendMode;
+pub use component::{
+    detach_instance, instantiate, nests_in_itself, ComponentLink, ComponentSync, Overrides,
+};
 pub use coords::{CanvasDelta, CanvasPoint, CanvasSize, LocalPoint, ScreenPoint};
+pub use guide::{Guide, GuideAxis};
 pub use layout::{
-    ChildLayout, CrossAxisAlignment, FrameLayout, LayoutDirection, MainAxisAlignment, Padding,
-    SizingMode,
+    ChildLayout, Constraint, CrossAxisAlignment, FrameLayout, LayoutDirection, LineAlignment,
+    MainAxisAlignment, Padding, SizingMode,
+};
+pub use layout_engine::{
+    apply_constraints, compute_hug_size, compute_layout, resolve_frame_size, LayoutInput,
+    LayoutOutput,
 };
-pub use layout_engine::{compute_layout, LayoutInput, LayoutOutput};
-pub use shape::{Fill, Shape, ShapeKind, Stroke};
+pub use naming::{name_unnamed, next_name};
+pub use path::{HandleMode, HandleSide, PathData, PathVertex};
+pub use fill::{Fill, Gradient, GradientKind, GradientStop, Paint};
+pub use shape::{Shape, ShapeKind};
 pub use shape_id::ShapeId;
+pub use stroke::{Stroke, StrokeAlign, StrokeCap, StrokeJoin};
+pub use text::{Text, TextAlign, TextSizing};
+pub use transform::{normalize_degrees, FlipAxis, Placement};
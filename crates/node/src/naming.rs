@@ -0,0 +1,81 @@
+//! Default shape names.
+//!
+//! New shapes are named after their kind and numbered past the highest
+//! number already in use for it on the page, "Rectangle 3" after
+//! "Rectangle 2", so names stay unique until someone renames a shape to
+//! match another. Numbers aren't reused when shapes are deleted.
+
+use crate::{Shape, ShapeKind};
+use std::collections::HashMap;
+
+impl ShapeKind {
+    /// The kind as it reads in shape names.
+    pub fn label(self) -> &'static str {
+        match self {
+            ShapeKind::Rectangle => "Rectangle",
+            ShapeKind::Ellipse => "Ellipse",
+            ShapeKind::Frame => "Frame",
+            ShapeKind::Text => "Text",
+            ShapeKind::Path => "Path",
+        }
+    }
+}
+
+/// The name a new shape of `kind` gets among `shapes`.
+pub fn next_name(shapes: &[Shape], kind: ShapeKind) -> String {
+    format!("{} {}", kind.label(), highest_number(shapes, kind) + 1)
+}
+
+/// Give every unnamed shape a default name, numbered in list order after
+/// the names already in use.
+pub fn name_unnamed(shapes: &mut [Shape]) {
+    let mut last = HashMap::new();
+    for i in 0..shapes.len() {
+        if !shapes[i].name.is_empty() {
+            continue;
+        }
+        let kind = shapes[i].kind;
+        let n = last.entry(kind.label()).or_insert_with(|| highest_number(shapes, kind));
+        *n += 1;
+        shapes[i].name = format!("{} {}", kind.label(), n);
+    }
+}
+
+/// The highest N of the shapes named "<Kind> N", or 0.
+fn highest_number(shapes: &[Shape], kind: ShapeKind) -> u32 {
+    shapes
+        .iter()
+        .filter_map(|shape| number_in(&shape.name, kind))
+        .max()
+        .unwrap_or(0)
+}
+fn number_in(name: &str, kind: ShapeKind) -> Option<u32> {
+    name.strip_prefix(kind.label())?.strip_prefix(' ')?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::Vec2;
+
+    fn rect(name: &str) -> Shape {
+        let mut shape = Shape::rectangle(Vec2::ZERO, Vec2::ONE);
+        shape.name = name.to_string();
+        shape
+    }
+
+    #[test]
+    fn names_are_numbered_past_the_highest_in_use() {
+        let shapes = vec![rect("Rectangle 4"), rect("Rectangle 2"), rect("Rectangle 9 copy")];
+        assert_eq!(next_name(&shapes, ShapeKind::Rectangle), "Rectangle 5");
+        assert_eq!(next_name(&shapes, ShapeKind::Frame), "Frame 1");
+    }
+
+    #[test]
+    fn unnamed_shapes_are_numbered_in_order() {
+        let mut shapes = vec![rect(""), rect("Rectangle 2"), rect(""), rect("Logo")];
+        name_unnamed(&mut shapes);
+        let names: Vec<_> = shapes.iter().map(|shape| shape.name.as_str()).collect();
+        assert_eq!(names, ["Rectangle 3", "Rectangle 2", "Rectangle 4", "Logo"]);
+    }
+}
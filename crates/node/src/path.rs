@@ -0,0 +1,440 @@
+//! Vector geometry for path shapes.
+//!
+//! A path is a chain of vertices joined by cubic Bézier segments. Handles are
+//! stored as offsets from their vertex, so moving a vertex carries its
+//! handles along and a zero handle is a sharp corner. Points are in the
+//! shape's local space, like a frame's children. Curve math is delegated to
+//! kurbo; this module only maps between its types and ours.
+
+use glam::{Affine2, Vec2};
+use kurbo::{BezPath, CubicBez, ParamCurve, ParamCurveExtrema, ParamCurveNearest, Point};
+use serde::{Deserialize, Serialize};
+
+/// Accuracy for nearest-point searches, in canvas units.
+const NEAREST_ACCURACY: f64 = 1e-3;
+
+/// How a vertex's handles follow each other when one of them moves.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HandleMode {
+    /// Handles move independently. A vertex with no handles is a corner.
+    #[default]
+    Free,
+    /// Handles stay opposite each other but keep their own lengths.
+    Aligned,
+    /// Handles stay opposite each other at equal lengths.
+    Mirrored,
+}
+
+/// One of the two handles on a vertex.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HandleSide {
+    /// Shapes the segment arriving at the vertex.
+    In,
+    /// Shapes the segment leaving the vertex.
+    Out,
+}
+
+/// A vertex on a path.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PathVertex {
+    pub point: Vec2,
+    /// Control point of the arriving segment, relative to `point`.
+    #[serde(default)]
+    pub handle_in: Vec2,
+    /// Control point of the leaving segment, relative to `point`.
+    #[serde(default)]
+    pub handle_out: Vec2,
+    #[serde(default)]
+    pub mode: HandleMode,
+}
+
+impl PathVertex {
+    /// A vertex with no handles.
+    pub fn corner(point: Vec2) -> Self {
+        Self {
+            point,
+            handle_in: Vec2::ZERO,
+            handle_out: Vec2::ZERO,
+            mode: HandleMode::Free,
+        }
+    }
+
+    /// A vertex with mirrored handles, `handle_out` leaving it.
+    pub fn smooth(point: Vec2, handle_out: Vec2) -> Self {
+        Self {
+            point,
+            handle_in: -handle_out,
+            handle_out,
+            mode: HandleMode::Mirrored,
+        }
+    }
+
+    pub fn is_corner(&self) -> bool {
+        self.handle_in == Vec2::ZERO && self.handle_out == Vec2::ZERO
+    }
+
+    pub fn handle(&self, side: HandleSide) -> Vec2 {
+        match side {
+            HandleSide::In => self.handle_in,
+            HandleSide::Out => self.handle_out,
+        }
+    }
+
+    /// Move one handle, updating the other as the vertex's mode requires.
+    pub fn set_handle(&mut self, side: HandleSide, offset: Vec2) {
+        let (moved, other) = match side {
+            HandleSide::In => (&mut self.handle_in, &mut self.handle_out),
+            HandleSide::Out => (&mut self.handle_out, &mut self.handle_in),
+        };
+        *moved = offset;
+        match self.mode {
+            HandleMode::Free => {}
+            HandleMode::Aligned => *other = -offset.normalize_or_zero() * other.length(),
+            HandleMode::Mirrored => *other = -offset,
+        }
+    }
+}
+
+/// An open or closed chain of cubic Bézier segments.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PathData {
+    pub vertices: Vec<PathVertex>,
+    /// Whether a segment joins the last vertex back to the first.
+    #[serde(default)]
+    pub closed: bool,
+}
+
+impl PathData {
+    pub fn new(vertices: Vec<PathVertex>, closed: bool) -> Self {
+        Self { vertices, closed }
+    }
+
+    /// Number of segments. A closed path has one more than an open one.
+    pub fn segment_count(&self) -> usize {
+        match self.vertices.len() {
+            0 | 1 => 0,
+            n if self.closed => n,
+            n => n - 1,
+        }
+    }
+
+    /// Control points of segment `index`, the one leaving vertex `index`.
+    pub fn segment(&self, index: usize) -> [Vec2; 4] {
+        let from = &self.vertices[index];
+        let to = &self.vertices[(index + 1) % self.vertices.len()];
+        [
+            from.point,
+            from.point + from.handle_out,
+            to.point + to.handle_in,
+            to.point,
+        ]
+    }
+
+    pub fn segments(&self) -> impl Iterator<Item = [Vec2; 4]> + '_ {
+        (0..self.segment_count()).map(|i| self.segment(i))
+    }
+
+    /// Tight bounds of the curve as (min, max), or None for an empty path.
+    ///
+    /// Handles don't count; only the curve they shape does.
+    pub fn bounds(&self) -> Option<(Vec2, Vec2)> {
+        let first = self.vertices.first()?.point;
+        let mut min = first;
+        let mut max = first;
+        for vertex in &self.vertices {
+            min = min.min(vertex.point);
+            max = max.max(vertex.point);
+        }
+        for segment in self.segments() {
+            let rect = to_cubic(segment).bounding_box();
+            min = min.min(Vec2::new(rect.x0 as f32, rect.y0 as f32));
+            max = max.max(Vec2::new(rect.x1 as f32, rect.y1 as f32));
+        }
+        Some((min, max))
+    }
+
+    pub fn translate(&mut self, delta: Vec2) {
+        for vertex in &mut self.vertices {
+            vertex.point += delta;
+        }
+    }
+
+    /// Scale about the origin. Handles scale with their vertices, so the
+    /// curve is scaled exactly.
+    pub fn scale(&mut self, factor: Vec2) {
+        for vertex in &mut self.vertices {
+            vertex.point *= factor;
+            vertex.handle_in *= factor;
+            vertex.handle_out *= factor;
+        }
+    }
+
+    /// Map through an affine transform. Handles are offsets, so they take
+    /// only its linear part.
+    pub fn transform(&mut self, affine: Affine2) {
+        for vertex in &mut self.vertices {
+            vertex.point = affine.transform_point2(vertex.point);
+            vertex.handle_in = affine.transform_vector2(vertex.handle_in);
+            vertex.handle_out = affine.transform_vector2(vertex.handle_out);
+        }
+    }
+
+    /// The closest point on the curve to `point`, as (segment index,
+    /// parameter along the segment, distance).
+    pub fn nearest(&self, point: Vec2) -> Option<(usize, f32, f32)> {
+        let target = to_point(point);
+        self.segments()
+            .enumerate()
+            .map(|(i, segment)| {
+                let nearest = to_cubic(segment).nearest(target, NEAREST_ACCURACY);
+                (i, nearest.t as f32, nearest.distance_sq.sqrt() as f32)
+            })
+            .min_by(|a, b| a.2.total_cmp(&b.2))
+    }
+
+    /// Whether `point` lies in the area the path encloses, using the
+    /// non-zero winding rule. Open paths enclose the area their fill
+    /// would cover, as if closed by a straight line.
+    pub fn encloses(&self, point: Vec2) -> bool {
+        if self.vertices.len() < 3 {
+            return false;
+        }
+        kurbo::Shape::contains(&self.to_closed_bez_path(), to_point(point))
+    }
+
+    /// Split segment `index` at parameter `t` without changing the curve's
+    /// shape. Returns the index of the new vertex.
+    pub fn split_segment(&mut self, index: usize, t: f32) -> usize {
+        let cubic = to_cubic(self.segment(index));
+        let before = cubic.subsegment(0.0..t as f64);
+        let after = cubic.subsegment(t as f64..1.0);
+        let point = to_vec2(before.p3);
+        let inserted = PathVertex {
+            point,
+            handle_in: to_vec2(before.p2) - point,
+            handle_out: to_vec2(after.p1) - point,
+            mode: if before.p2 == before.p3 && after.p1 == after.p0 {
+                HandleMode::Free
+            } else {
+                HandleMode::Aligned
+            },
+        };
+
+        let next = (index + 1) % self.vertices.len();
+        let from = &mut self.vertices[index];
+        from.handle_out = to_vec2(before.p1) - from.point;
+        // Shortening one handle breaks mirroring, but not alignment
+        if from.mode == HandleMode::Mirrored {
+            from.mode = HandleMode::Aligned;
+        }
+        let to = &mut self.vertices[next];
+        to.handle_in = to_vec2(after.p2) - to.point;
+        if to.mode == HandleMode::Mirrored {
+            to.mode = HandleMode::Aligned;
+        }
+
+        self.vertices.insert(index + 1, inserted);
+        index + 1
+    }
+
+    /// Remove a vertex, joining its neighbours directly. A closed path left
+    /// with fewer than three vertices opens.
+    pub fn remove_vertex(&mut self, index: usize) {
+        self.vertices.remove(index);
+        if self.vertices.len() < 3 {
+            self.closed = false;
+        }
+    }
+
+    /// The curve as a kurbo path, closed if the path is. Segments without
+    /// handles are straight lines.
+    pub fn to_bez_path(&self) -> BezPath {
+        let mut path = BezPath::new();
+        let Some(first) = self.vertices.first() else {
+            return path;
+        };
+        path.move_to(to_point(first.point));
+        for [p0, c1, c2, p] in self.segments() {
+            if c1 == p0 && c2 == p {
+                path.line_to(to_point(p));
+            } else {
+                path.curve_to(to_point(c1), to_point(c2), to_point(p));
+            }
+        }
+        if self.closed {
+            path.close_path();
+        }
+        path
+    }
+
+    fn to_closed_bez_path(&self) -> BezPath {
+        let mut path = BezPath::new();
+        path.move_to(to_point(self.vertices[0].point));
+        for [_, c1, c2, p] in self.segments() {
+            path.curve_to(to_point(c1), to_point(c2), to_point(p));
+        }
+        path.close_path();
+        path
+    }
+}
+
+fn to_point(v: Vec2) -> Point {
+    Point::new(v.x as f64, v.y as f64)
+}
+
+fn to_vec2(p: Point) -> Vec2 {
+    Vec2::new(p.x as f32, p.y as f32)
+}
+
+fn to_cubic([p0, p1, p2, p3]: [Vec2; 4]) -> CubicBez {
+    CubicBez::new(to_point(p0), to_point(p1), to_point(p2), to_point(p3))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn triangle() -> PathData {
+        PathData::new(
+            vec![
+                PathVertex::corner(Vec2::new(0.0, 0.0)),
+                PathVertex::corner(Vec2::new(100.0, 0.0)),
+                PathVertex::corner(Vec2::new(0.0, 100.0)),
+            ],
+            true,
+        )
+    }
+
+    /// An arch from (0, 0) to (100, 0) bulging up through y = -75.
+    fn arch() -> PathData {
+        PathData::new(
+            vec![
+                PathVertex {
+                    handle_out: Vec2::new(0.0, -100.0),
+                    ..PathVertex::corner(Vec2::new(0.0, 0.0))
+                },
+                PathVertex {
+                    handle_in: Vec2::new(0.0, -100.0),
+                    ..PathVertex::corner(Vec2::new(100.0, 0.0))
+                },
+            ],
+            false,
+        )
+    }
+
+    fn assert_near(a: Vec2, b: Vec2) {
+        assert!((a - b).length() < 1e-3, "{a} != {b}");
+    }
+
+    // === Segments ===
+
+    #[test]
+    fn closed_path_has_a_closing_segment() {
+        let mut path = triangle();
+        assert_eq!(path.segment_count(), 3);
+        assert_eq!(path.segment(2)[3], Vec2::new(0.0, 0.0));
+        path.closed = false;
+        assert_eq!(path.segment_count(), 2);
+    }
+
+    #[test]
+    fn single_vertex_has_no_segments() {
+        let path = PathData::new(vec![PathVertex::corner(Vec2::ZERO)], true);
+        assert_eq!(path.segment_count(), 0);
+    }
+
+    // === Bounds ===
+
+    #[test]
+    fn bounds_follow_the_curve_not_the_handles() {
+        let (min, max) = arch().bounds().unwrap();
+        assert_near(min, Vec2::new(0.0, -75.0));
+        assert_near(max, Vec2::new(100.0, 0.0));
+    }
+
+    #[test]
+    fn empty_path_has_no_bounds() {
+        assert_eq!(PathData::default().bounds(), None);
+    }
+
+    // === Handles ===
+
+    #[test]
+    fn mirrored_handles_move_together() {
+        let mut vertex = PathVertex::smooth(Vec2::ZERO, Vec2::new(10.0, 0.0));
+        vertex.set_handle(HandleSide::In, Vec2::new(0.0, 20.0));
+        assert_eq!(vertex.handle_out, Vec2::new(0.0, -20.0));
+    }
+
+    #[test]
+    fn aligned_handles_keep_their_length() {
+        let mut vertex = PathVertex {
+            handle_in: Vec2::new(-5.0, 0.0),
+            handle_out: Vec2::new(10.0, 0.0),
+            ..PathVertex::corner(Vec2::ZERO)
+        };
+        vertex.mode = HandleMode::Aligned;
+        vertex.set_handle(HandleSide::Out, Vec2::new(0.0, 30.0));
+        assert_near(vertex.handle_in, Vec2::new(0.0, -5.0));
+    }
+
+    #[test]
+    fn free_handles_are_independent() {
+        let mut vertex = PathVertex::smooth(Vec2::ZERO, Vec2::new(10.0, 0.0));
+        vertex.mode = HandleMode::Free;
+        vertex.set_handle(HandleSide::Out, Vec2::new(0.0, 30.0));
+        assert_eq!(vertex.handle_in, Vec2::new(-10.0, 0.0));
+    }
+
+    // === Hit testing ===
+
+    #[test]
+    fn nearest_finds_distance_to_curve() {
+        let (segment, t, distance) = arch().nearest(Vec2::new(50.0, -85.0)).unwrap();
+        assert_eq!(segment, 0);
+        assert!((t - 0.5).abs() < 1e-3);
+        assert!((distance - 10.0).abs() < 1e-2);
+    }
+
+    #[test]
+    fn encloses_uses_the_curve() {
+        let path = triangle();
+        assert!(path.encloses(Vec2::new(20.0, 20.0)));
+        // Inside the bounding box, outside the triangle
+        assert!(!path.encloses(Vec2::new(80.0, 80.0)));
+    }
+
+    // === Editing ===
+
+    #[test]
+    fn split_segment_preserves_the_curve() {
+        let original = arch();
+        let mut path = original.clone();
+        let index = path.split_segment(0, 0.5);
+
+        assert_eq!(index, 1);
+        assert_eq!(path.vertices.len(), 3);
+        assert_near(path.vertices[1].point, Vec2::new(50.0, -75.0));
+        // A point a quarter of the way along the original is on the new curve
+        let quarter = to_vec2(to_cubic(original.segment(0)).eval(0.25));
+        let (_, _, distance) = path.nearest(quarter).unwrap();
+        assert!(distance < 1e-2);
+    }
+
+    #[test]
+    fn removing_a_vertex_opens_a_degenerate_closed_path() {
+        let mut path = triangle();
+        path.remove_vertex(1);
+        assert_eq!(path.vertices.len(), 2);
+        assert!(!path.closed);
+    }
+
+    #[test]
+    fn scale_scales_handles() {
+        let mut path = arch();
+        path.scale(Vec2::new(2.0, 0.5));
+        assert_eq!(path.vertices[1].point, Vec2::new(200.0, 0.0));
+        assert_eq!(path.vertices[0].handle_out, Vec2::new(0.0, -50.0));
+    }
+}
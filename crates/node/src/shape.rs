@@ -1,5 +1,12 @@
+use crate::blend_mode::BlendMode;
+use crate::component::ComponentLink;
 use crate::coords::{CanvasDelta, CanvasPoint, CanvasSize};
+use crate::fill::Fill;
+use crate::stroke::{Stroke, StrokeAlign};
 use crate::layout::{ChildLayout, FrameLayout};
+use crate::path::PathData;
+use crate::text::Text;
+use crate::transform::{normalize_degrees, FlipAxis, Placement};
 use crate::ShapeId;
 use glam::Vec2;
 use gpui::Hsla;
@@ -11,6 +18,8 @@ pub enum ShapeKind {
     Rectangle,
     Ellipse,
     Frame,
+    Text,
+    Path,
 }
 
 impl Default for ShapeKind {
@@ -19,42 +28,12 @@ impl Default for ShapeKind {
     }
 }
 
-/// Fill style for a shape.
-#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
-pub struct Fill {
-    pub color: Hsla,
-}
-
-impl Fill {
-    pub fn new(color: Hsla) -> Self {
-        Self { color }
-    }
-
-    pub fn none() -> Option<Self> {
-        None
-    }
+fn visible_by_default() -> bool {
+    true
 }
 
-/// Stroke style for a shape.
-#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
-pub struct Stroke {
-    pub color: Hsla,
-    pub width: f32,
-}
-
-impl Stroke {
-    pub fn new(color: Hsla, width: f32) -> Self {
-        Self { color, width }
-    }
-}
-
-impl Default for Stroke {
-    fn default() -> Self {
-        Self {
-            color: gpui::black(),
-            width: 2.0,
-        }
-    }
+fn opaque() -> f32 {
+    1.0
 }
 
 /// A shape on the canvas.
@@ -62,10 +41,21 @@ impl Default for Stroke {
 /// Shapes can be hierarchical - frames contain child shapes with
 /// relative positioning. Z-order is determined by position in the
 /// containing list, with children always rendering on top of parents.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Shape {
     pub id: ShapeId,
     pub kind: ShapeKind,
+    /// Shown in the layer list and matched by name queries. Not unique.
+    #[serde(default)]
+    pub name: String,
+    /// Hidden shapes, and everything inside them, aren't painted, picked,
+    /// laid out or exported.
+    #[serde(default = "visible_by_default")]
+    pub visible: bool,
+    /// Locked shapes, and everything inside them, can't be picked on the
+    /// canvas, so they stay put while what's around them is edited.
+    #[serde(default)]
+    pub locked: bool,
 
     // Geometry (using typed coordinates)
     /// Position in canvas space (for root shapes) or relative to parent (for children).
@@ -73,6 +63,15 @@ pub struct Shape {
     pub position: CanvasPoint,
     /// User-specified size. May be overridden by layout (see computed_size).
     pub size: CanvasSize,
+    /// Clockwise rotation about the box's center, in degrees.
+    #[serde(default)]
+    pub rotation: f32,
+    /// Mirrored left to right about the box's center.
+    #[serde(default)]
+    pub flip_x: bool,
+    /// Mirrored top to bottom about the box's center.
+    #[serde(default)]
+    pub flip_y: bool,
 
     // Layout-computed geometry (set by layout engine, cleared when layout is disabled)
     /// Position computed by the layout engine. When Some, this overrides `position` for rendering.
@@ -98,9 +97,34 @@ pub struct Shape {
     pub child_layout: ChildLayout,
 
     // Style
-    pub fill: Option<Fill>,
-    pub stroke: Option<Stroke>,
+    /// How much the shape and everything inside it show, from 0 to 1. It
+    /// applies to them as one group, on top of their own opacities.
+    #[serde(default = "opaque")]
+    pub opacity: f32,
+    /// How the shape, as one group with everything inside it, mixes with
+    /// what's beneath it.
+    #[serde(default)]
+    pub blend_mode: BlendMode,
+    /// Fills, painted in order, so the last is on top.
+    #[serde(default)]
+    pub fills: Vec<Fill>,
+    /// Strokes, painted in order over the fills.
+    #[serde(default)]
+    pub strokes: Vec<Stroke>,
     pub corner_radius: f32,
+
+    // Content
+    /// Text content and typography (only for Text shapes).
+    /// The top fill is the text color; see [`Shape::text_color`].
+    pub text: Option<Text>,
+    /// Vector geometry (only for Path shapes), in local space. The shape's
+    /// box is the path's bounds; see [`Shape::fit_path`].
+    pub path: Option<PathData>,
+
+    // Components
+    /// Whether this is a main component, or part of an instance of one.
+    #[serde(default)]
+    pub component: Option<ComponentLink>,
 }
 
 impl Shape {
@@ -108,8 +132,14 @@ impl Shape {
         Self {
             id: ShapeId::new(),
             kind,
+            name: String::new(),
+            visible: true,
+            locked: false,
             position,
             size,
+            rotation: 0.0,
+            flip_x: false,
+            flip_y: false,
             computed_position: None,
             computed_size: None,
             parent: None,
@@ -117,9 +147,14 @@ impl Shape {
             clip_children: false,
             layout: None,
             child_layout: ChildLayout::default(),
-            fill: None,
-            stroke: Some(Stroke::default()),
+            opacity: 1.0,
+            blend_mode: BlendMode::default(),
+            fills: Vec::new(),
+            strokes: vec![Stroke::default().with_align(StrokeAlign::for_kind(kind))],
             corner_radius: 0.0,
+            text: None,
+            path: None,
+            component: None,
         }
     }
 
@@ -163,16 +198,113 @@ impl Shape {
         shape
     }
 
+    /// A text shape with a black fill and no stroke. The size is a starting
+    /// point; the canvas fits it to the text on its automatic axes.
+    pub fn text(position: Vec2, text: Text) -> Self {
+        let height = text.line_height_px();
+        let mut shape = Self::new(
+            ShapeKind::Text,
+            CanvasPoint(position),
+            CanvasSize::new(0.0, height),
+        );
+        shape.text = Some(text);
+        shape.fills = vec![Fill::new(gpui::black())];
+        shape.strokes.clear();
+        shape
+    }
+
+    /// A path shape from a path in canvas space. The shape's box is fitted
+    /// to the path's bounds.
+    pub fn path(path: PathData) -> Self {
+        let mut shape = Self::new(ShapeKind::Path, CanvasPoint::default(), CanvasSize::default());
+        shape.path = Some(path);
+        shape.fit_path();
+        shape
+    }
+
+    /// Refit the shape's box to its path after the path was edited, moving
+    /// the origin to the bounds' top-left so the path stays put on canvas.
+    pub fn fit_path(&mut self) {
+        let Some(path) = self.path.as_mut() else {
+            return;
+        };
+        let Some((min, max)) = path.bounds() else {
+            self.size = CanvasSize::default();
+            return;
+        };
+        path.translate(-min);
+        let placement = self.local_placement();
+        if placement.is_turned() {
+            // Keep the path still on canvas as the box, and with it the
+            // center the shape turns about, moves
+            let old_center = self.size.0 / 2.0;
+            let new_center = (max - min) / 2.0;
+            let offset = placement.affine().transform_vector2(min + new_center - old_center);
+            self.position = self.position + CanvasDelta(old_center - new_center + offset);
+        } else {
+            self.position = self.position + CanvasDelta(min);
+        }
+        self.size = CanvasSize(max - min);
+    }
+
+    /// Set the user-specified size, scaling path geometry to fit.
+    ///
+    /// An axis a path is flat on (a horizontal line's height) can't be
+    /// scaled and stays flat.
+    pub fn resize(&mut self, size: CanvasSize) {
+        if let Some(path) = self.path.as_mut() {
+            path.scale(axis_scale(size.0, self.size.0));
+        }
+        self.size = size;
+    }
+
+    /// The path in its box's local space, at the shape's effective size.
+    /// Includes any layout resizing of the shape.
+    pub fn sized_path(&self) -> Option<PathData> {
+        let mut path = self.path.clone()?;
+        path.scale(axis_scale(self.effective_size().0, self.size.0));
+        Some(path)
+    }
+
+    /// The path in canvas space, for a shape placed at `placement`.
+    pub fn path_in_canvas(&self, placement: &Placement) -> Option<PathData> {
+        let mut path = self.sized_path()?;
+        path.transform(placement.affine());
+        Some(path)
+    }
+
+    /// Inverse of [`path_in_canvas`](Self::path_in_canvas): a canvas point
+    /// in the path's local space.
+    pub fn canvas_to_path(&self, point: CanvasPoint, placement: &Placement) -> Vec2 {
+        placement.to_local(point.0) / axis_scale(self.effective_size().0, self.size.0)
+    }
+
+    /// The shape filled with just `color`.
     pub fn with_fill(mut self, color: Hsla) -> Self {
-        self.fill = Some(Fill::new(color));
+        self.fills = vec![Fill::new(color)];
         self
     }
 
+    /// The shape with just one stroke, lying where strokes on its kind go.
     pub fn with_stroke(mut self, color: Hsla, width: f32) -> Self {
-        self.stroke = Some(Stroke::new(color, width));
+        let align = StrokeAlign::for_kind(self.kind);
+        self.strokes = vec![Stroke::new(color, width).with_align(align)];
         self
     }
 
+    /// The index of the top visible fill, the one that stands for them all
+    /// where only one can be shown or edited.
+    pub fn top_fill(&self) -> Option<usize> {
+        self.fills.iter().rposition(|fill| fill.visible)
+    }
+
+    /// The color text is set in: its top fill's, faded by the fill's
+    /// opacity.
+    pub fn text_color(&self) -> Option<Hsla> {
+        let fill = &self.fills[self.top_fill()?];
+        Some(fill.color().opacity(fill.opacity))
+    }
+
     pub fn with_corner_radius(mut self, radius: f32) -> Self {
         self.corner_radius = radius;
         self
@@ -183,6 +315,13 @@ impl Shape {
         self
     }
 
+    /// Add `child` as this frame's frontmost child, setting its parent.
+    pub fn with_child(mut self, child: &mut Shape) -> Self {
+        child.parent = Some(self.id);
+        self.children.push(child.id);
+        self
+    }
+
     /// Enable autolayout on this frame.
     pub fn with_layout(mut self, layout: FrameLayout) -> Self {
         self.layout = Some(layout);
@@ -195,13 +334,27 @@ impl Shape {
         self
     }
 
+    /// Check if this is a main component.
+    pub fn is_main_component(&self) -> bool {
+        self.component == Some(ComponentLink::Main)
+    }
+
+    /// Check if this is the root of a component instance.
+    pub fn is_instance(&self) -> bool {
+        matches!(self.component, Some(ComponentLink::Instance { .. }))
+    }
+
     /// Check if this shape has autolayout enabled.
     pub fn has_layout(&self) -> bool {
         self.layout.is_some()
     }
 
-    /// Check if this shape is in a layout (has a parent with layout).
+    /// Check if this shape is positioned by a layout: its parent has layout
+    /// and it isn't absolutely positioned.
     pub fn is_in_layout(&self, shapes: &[Shape]) -> bool {
+        if self.child_layout.absolute {
+            return false;
+        }
         match self.parent {
             None => false,
             Some(parent_id) => shapes
@@ -217,35 +370,65 @@ impl Shape {
     /// For root shapes, returns effective position.
     /// For child shapes, walks up the parent chain to compute absolute position.
     /// Uses computed position if available (from layout).
+    ///
+    /// Rotated or flipped ancestors carry the shape round with them; the
+    /// result is where the shape's unturned box sits, as in [`Placement`].
     pub fn world_position(&self, shapes: &[Shape]) -> CanvasPoint {
-        let pos = self.effective_position();
-        match self.parent {
-            None => pos,
-            Some(parent_id) => {
-                let parent = shapes.iter().find(|s| s.id == parent_id);
-                match parent {
-                    Some(p) => {
-                        let parent_world = p.world_position(shapes);
-                        CanvasPoint(pos.0 + parent_world.0)
-                    }
-                    None => pos, // Orphaned child, treat as root
-                }
-            }
+        self.world_placement(shapes).position
+    }
+
+    /// The shape's box in its parent's space (canvas space for root
+    /// shapes), using effective position/size.
+    pub fn local_placement(&self) -> Placement {
+        Placement {
+            position: self.effective_position(),
+            size: self.effective_size(),
+            rotation: self.rotation,
+            flip_x: self.flip_x,
+            flip_y: self.flip_y,
+        }
+    }
+
+    /// Mirror the shape across its center. Rotation reverses too, so a
+    /// rotated shape mirrors as it appears on screen.
+    pub fn flip(&mut self, axis: FlipAxis) {
+        self.rotation = normalize_degrees(-self.rotation);
+        match axis {
+            FlipAxis::Horizontal => self.flip_x = !self.flip_x,
+            FlipAxis::Vertical => self.flip_y = !self.flip_y,
+        }
+    }
+
+    /// Move and turn the shape to `placement`, given in its parent's space.
+    /// The size is left alone.
+    pub fn set_local_placement(&mut self, placement: Placement) {
+        self.position = placement.position;
+        self.rotation = placement.rotation;
+        self.flip_x = placement.flip_x;
+        self.flip_y = placement.flip_y;
+    }
+
+    /// The shape's box in canvas space, turned by its own rotation and
+    /// flips and those of its ancestors.
+    pub fn world_placement(&self, shapes: &[Shape]) -> Placement {
+        let local = self.local_placement();
+        match self.parent.and_then(|id| shapes.iter().find(|s| s.id == id)) {
+            Some(parent) => parent.world_placement(shapes).place_child(&local),
+            // Root shape, or an orphaned child treated as one
+            None => local,
         }
     }
 
-    /// Returns the bounding box as (min, max) corners using effective position/size.
+    /// Returns the axis-aligned bounds of the rotated box as (min, max),
+    /// in the parent's space, using effective position/size.
     pub fn bounds(&self) -> (CanvasPoint, CanvasPoint) {
-        let pos = self.effective_position();
-        let size = self.effective_size();
-        let max = CanvasPoint(pos.0 + size.0);
-        (pos, max)
+        self.local_placement().bounds()
     }
 
-    /// Check if a point is inside this shape's bounding box.
+    /// Check if a point in the parent's space is inside this shape's
+    /// rotated box.
     pub fn contains_point(&self, point: CanvasPoint) -> bool {
-        let (min, max) = self.bounds();
-        point.0.x >= min.0.x && point.0.x <= max.0.x && point.0.y >= min.0.y && point.0.y <= max.0.y
+        self.local_placement().contains(point)
     }
 
     /// Move the shape by a delta.
@@ -254,6 +437,14 @@ impl Shape {
     }
 }
 
+/// Per-axis `to / from`, leaving axes with no extent to scale unscaled.
+fn axis_scale(to: Vec2, from: Vec2) -> Vec2 {
+    Vec2::new(
+        if from.x > 0.0 { to.x / from.x } else { 1.0 },
+        if from.y > 0.0 { to.y / from.y } else { 1.0 },
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -397,4 +588,163 @@ mod tests {
         // The user-set size should be preserved
         assert_eq!(shape.size, CanvasSize::new(50.0, 75.0));
     }
+
+    // === Text ===
+
+    #[test]
+    fn text_shape_is_filled_and_one_line_tall() {
+        let shape = Shape::text(Vec2::ZERO, Text::new("hi").with_font_size(10.0));
+        assert_eq!(shape.kind, ShapeKind::Text);
+        assert_eq!(shape.fills.len(), 1);
+        assert!(shape.strokes.is_empty());
+        assert_eq!(shape.size.height(), 12.0);
+    }
+
+    // === Path ===
+
+    fn line(from: Vec2, to: Vec2) -> PathData {
+        PathData::new(
+            vec![crate::PathVertex::corner(from), crate::PathVertex::corner(to)],
+            false,
+        )
+    }
+
+    #[test]
+    fn path_shape_box_fits_its_path() {
+        let shape = Shape::path(line(Vec2::new(10.0, 40.0), Vec2::new(50.0, 20.0)));
+        assert_eq!(shape.kind, ShapeKind::Path);
+        assert_eq!(shape.position, CanvasPoint::new(10.0, 20.0));
+        assert_eq!(shape.size, CanvasSize::new(40.0, 20.0));
+        let path = shape.path.unwrap();
+        assert_eq!(path.vertices[0].point, Vec2::new(0.0, 20.0));
+    }
+
+    #[test]
+    fn resize_scales_path() {
+        let mut shape = Shape::path(line(Vec2::ZERO, Vec2::new(40.0, 20.0)));
+        shape.resize(CanvasSize::new(80.0, 10.0));
+        let path = shape.path.as_ref().unwrap();
+        assert_eq!(path.vertices[1].point, Vec2::new(80.0, 10.0));
+    }
+
+    #[test]
+    fn resize_leaves_flat_axis_flat() {
+        let mut shape = Shape::path(line(Vec2::ZERO, Vec2::new(40.0, 0.0)));
+        shape.resize(CanvasSize::new(80.0, 10.0));
+        let path = shape.path.as_ref().unwrap();
+        assert_eq!(path.vertices[1].point, Vec2::new(80.0, 0.0));
+    }
+
+    #[test]
+    fn path_in_canvas_applies_layout_size_and_position() {
+        let mut shape = Shape::path(line(Vec2::ZERO, Vec2::new(40.0, 20.0)));
+        shape.computed_size = Some(CanvasSize::new(20.0, 20.0));
+        let placement = Placement::new(CanvasPoint::new(100.0, 100.0), shape.effective_size());
+        let path = shape.path_in_canvas(&placement).unwrap();
+        assert_eq!(path.vertices[1].point, Vec2::new(120.0, 120.0));
+    }
+
+    #[test]
+    fn canvas_to_path_inverts_path_in_canvas() {
+        let mut shape = Shape::path(line(Vec2::ZERO, Vec2::new(40.0, 20.0)));
+        shape.computed_size = Some(CanvasSize::new(20.0, 20.0));
+        let placement = Placement::new(CanvasPoint::new(100.0, 100.0), shape.effective_size());
+        let local = shape.canvas_to_path(CanvasPoint::new(120.0, 120.0), &placement);
+        assert_eq!(local, Vec2::new(40.0, 20.0));
+    }
+
+    #[test]
+    fn refitting_a_rotated_path_keeps_it_still() {
+        let mut shape = Shape::path(line(Vec2::ZERO, Vec2::new(40.0, 20.0)));
+        shape.rotation = 30.0;
+        let end = shape.path_in_canvas(&shape.local_placement()).unwrap().vertices[1].point;
+
+        // Extend the path past its box, as the pen does
+        let path = shape.path.as_mut().unwrap();
+        path.vertices.push(crate::PathVertex::corner(Vec2::new(-30.0, 50.0)));
+        shape.fit_path();
+
+        let moved = shape.path_in_canvas(&shape.local_placement()).unwrap().vertices[1].point;
+        assert!(moved.distance(end) < 1e-3, "{moved} != {end}");
+    }
+
+    // === Rotation and flip ===
+
+    #[test]
+    fn contains_point_follows_rotation() {
+        let mut shape = Shape::rectangle(Vec2::ZERO, Vec2::new(100.0, 20.0));
+        assert!(shape.contains_point(CanvasPoint::new(5.0, 10.0)));
+        shape.rotation = 90.0;
+        assert!(!shape.contains_point(CanvasPoint::new(5.0, 10.0)));
+        assert!(shape.contains_point(CanvasPoint::new(50.0, -30.0)));
+    }
+
+    #[test]
+    fn bounds_of_rotated_shape_cover_its_corners() {
+        let mut shape = Shape::rectangle(Vec2::ZERO, Vec2::new(100.0, 20.0));
+        shape.rotation = 90.0;
+        let (min, max) = shape.bounds();
+        assert!(min.0.distance(Vec2::new(40.0, -40.0)) < 1e-3);
+        assert!(max.0.distance(Vec2::new(60.0, 60.0)) < 1e-3);
+    }
+
+    #[test]
+    fn flip_mirrors_rotated_shape_on_screen() {
+        let mut shape = Shape::rectangle(Vec2::ZERO, Vec2::new(100.0, 20.0));
+        shape.rotation = 30.0;
+        let corner = shape.local_placement().to_canvas(Vec2::ZERO);
+        shape.flip(FlipAxis::Horizontal);
+        assert_eq!(shape.rotation, -30.0);
+        assert!(shape.flip_x);
+        // The corner lands mirrored across the vertical line through the center
+        let mirrored = shape.local_placement().to_canvas(Vec2::ZERO);
+        assert!(mirrored.distance(Vec2::new(100.0 - corner.x, corner.y)) < 1e-3);
+    }
+
+    #[test]
+    fn child_world_position_turns_with_parent() {
+        let mut frame = Shape::frame(Vec2::ZERO, Vec2::new(100.0, 100.0));
+        frame.rotation = 180.0;
+        let mut child = Shape::rectangle(Vec2::new(10.0, 10.0), Vec2::new(20.0, 20.0));
+        child.parent = Some(frame.id);
+        frame.children.push(child.id);
+        let shapes = vec![frame, child.clone()];
+
+        // Half a turn carries the child to the opposite corner
+        let placement = child.world_placement(&shapes);
+        assert!(placement.position.0.distance(Vec2::new(70.0, 70.0)) < 1e-3);
+        assert_eq!(placement.rotation, 180.0);
+    }
+
+    #[test]
+    fn unturned_parent_offsets_children_exactly() {
+        let frame = Shape::frame(Vec2::new(0.1, 0.2), Vec2::new(100.0, 100.0));
+        let mut child = Shape::rectangle(Vec2::new(0.3, 0.7), Vec2::new(20.0, 20.0));
+        child.parent = Some(frame.id);
+        let shapes = vec![frame, child.clone()];
+        assert_eq!(child.world_position(&shapes), CanvasPoint(Vec2::new(0.1, 0.2) + Vec2::new(0.3, 0.7)));
+    }
+
+    // === Layout membership ===
+
+    #[test]
+    fn child_of_layout_frame_is_in_layout() {
+        let mut frame = Shape::frame(Vec2::ZERO, Vec2::new(100.0, 100.0));
+        frame.layout = Some(crate::FrameLayout::row());
+        let mut child = Shape::rectangle(Vec2::ZERO, Vec2::new(10.0, 10.0));
+        child.parent = Some(frame.id);
+
+        assert!(child.is_in_layout(&[frame]));
+    }
+
+    #[test]
+    fn absolute_child_is_not_in_layout() {
+        let mut frame = Shape::frame(Vec2::ZERO, Vec2::new(100.0, 100.0));
+        frame.layout = Some(crate::FrameLayout::row());
+        let mut child = Shape::rectangle(Vec2::ZERO, Vec2::new(10.0, 10.0));
+        child.parent = Some(frame.id);
+        child.child_layout.absolute = true;
+
+        assert!(!child.is_in_layout(&[frame]));
+    }
 }
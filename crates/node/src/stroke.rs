@@ -0,0 +1,151 @@
+//! Outlines drawn along a shape's edge, stacked and painted in order over
+//! its fills.
+
+use crate::shape::ShapeKind;
+use gpui::Hsla;
+use serde::{Deserialize, Serialize};
+
+/// One of a shape's strokes.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Stroke {
+    pub color: Hsla,
+    pub width: f32,
+    /// Hidden strokes are kept, but not painted.
+    #[serde(default = "visible_by_default")]
+    pub visible: bool,
+    /// Multiplies the color's own alpha.
+    #[serde(default = "opaque")]
+    pub opacity: f32,
+    #[serde(default)]
+    pub align: StrokeAlign,
+    /// Lengths of dashes and the gaps between them, in turn, repeated
+    /// along the stroke. Empty for a solid line.
+    #[serde(default)]
+    pub dash: Vec<f32>,
+    #[serde(default)]
+    pub cap: StrokeCap,
+    #[serde(default)]
+    pub join: StrokeJoin,
+}
+
+impl Stroke {
+    pub fn new(color: Hsla, width: f32) -> Self {
+        Self {
+            color,
+            width,
+            visible: true,
+            opacity: 1.0,
+            align: StrokeAlign::default(),
+            dash: Vec::new(),
+            cap: StrokeCap::default(),
+            join: StrokeJoin::default(),
+        }
+    }
+
+    pub fn with_align(mut self, align: StrokeAlign) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Whether a stroke of this kind of shape's own box, square-joined and
+    /// solid, can be drawn as a border on the box.
+    pub fn is_border(&self) -> bool {
+        self.align == StrokeAlign::Inside && self.dash.is_empty() && self.join == StrokeJoin::Miter
+    }
+
+    /// The dash pattern as drawn: gaps of nothing and patterns that add up
+    /// to nothing leave a solid line.
+    pub fn dash_pattern(&self) -> Option<&[f32]> {
+        let valid = self.dash.iter().all(|length| length.is_finite() && *length >= 0.0);
+        let total: f32 = self.dash.iter().sum();
+        (valid && total > 0.0).then_some(self.dash.as_slice())
+    }
+}
+
+impl Default for Stroke {
+    fn default() -> Self {
+        Self::new(gpui::black(), 2.0)
+    }
+}
+
+/// Where a stroke lies against the edge it follows.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StrokeAlign {
+    /// Within the shape, so it never grows past its box.
+    #[default]
+    Inside,
+    /// Straddling the edge.
+    Center,
+    /// Around the shape.
+    Outside,
+}
+
+impl StrokeAlign {
+    /// Where new strokes on a kind of shape go: inside boxes, and centered
+    /// on paths, which needn't be closed.
+    pub fn for_kind(kind: ShapeKind) -> Self {
+        match kind {
+            ShapeKind::Path => Self::Center,
+            _ => Self::Inside,
+        }
+    }
+}
+
+/// How the ends of open lines and dashes are drawn.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StrokeCap {
+    /// Square, ending at the line's end.
+    #[default]
+    Butt,
+    Round,
+    /// Square, reaching half the width past the line's end.
+    Square,
+}
+
+/// How a stroke turns corners.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StrokeJoin {
+    #[default]
+    Miter,
+    Round,
+    Bevel,
+}
+
+fn visible_by_default() -> bool {
+    true
+}
+
+fn opaque() -> f32 {
+    1.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dashes_that_draw_nothing_leave_a_solid_line() {
+        let mut stroke = Stroke::default();
+        assert_eq!(stroke.dash_pattern(), None);
+        stroke.dash = vec![0.0, 0.0];
+        assert_eq!(stroke.dash_pattern(), None);
+        stroke.dash = vec![4.0, -2.0];
+        assert_eq!(stroke.dash_pattern(), None);
+        stroke.dash = vec![4.0, 2.0];
+        assert_eq!(stroke.dash_pattern(), Some([4.0, 2.0].as_slice()));
+    }
+
+    #[test]
+    fn old_strokes_deserialize_with_defaults() {
+        let mut json = serde_json::to_value(Stroke::default()).unwrap();
+        json.as_object_mut().unwrap().retain(|key, _| key == "color" || key == "width");
+        let stroke: Stroke = serde_json::from_value(json).unwrap();
+        assert!(stroke.visible);
+        assert_eq!(stroke.opacity, 1.0);
+        assert_eq!(stroke.align, StrokeAlign::Inside);
+        assert!(stroke.is_border());
+    }
+}
@@ -0,0 +1,195 @@
+//! Text content and typography for text shapes.
+//!
+//! The shape's fill is the text color, so text carries only what is specific
+//! to setting type. Glyph shaping and line breaking need a font system and
+//! happen in the canvas; this module only describes the result wanted.
+
+use crate::coords::CanvasSize;
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_FONT_FAMILY: &str = "Inter";
+pub const DEFAULT_FONT_SIZE: f32 = 16.0;
+
+/// Text content and styling for a [`ShapeKind::Text`](crate::ShapeKind::Text) shape.
+///
+/// Missing fields deserialize to their defaults, so `{"content": "Hi"}` is
+/// a complete text.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Text {
+    /// The text itself. `\n` starts a new paragraph.
+    pub content: String,
+
+    pub font_family: String,
+
+    /// CSS-style weight, 100 (thin) to 900 (black).
+    pub font_weight: u16,
+
+    /// Font size in canvas units.
+    pub font_size: f32,
+
+    /// Line height as a multiple of the font size.
+    pub line_height: f32,
+
+    /// Extra space after each character, in canvas units. May be negative.
+    pub letter_spacing: f32,
+
+    pub align: TextAlign,
+
+    pub sizing: TextSizing,
+}
+
+impl Default for Text {
+    fn default() -> Self {
+        Self {
+            content: String::new(),
+            font_family: DEFAULT_FONT_FAMILY.to_string(),
+            font_weight: 400,
+            font_size: DEFAULT_FONT_SIZE,
+            line_height: 1.2,
+            letter_spacing: 0.0,
+            align: TextAlign::Left,
+            sizing: TextSizing::AutoWidth,
+        }
+    }
+}
+
+impl Text {
+    pub fn new(content: impl Into<String>) -> Self {
+        Self {
+            content: content.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_font_family(mut self, family: impl Into<String>) -> Self {
+        self.font_family = family.into();
+        self
+    }
+
+    pub fn with_font_weight(mut self, weight: u16) -> Self {
+        self.font_weight = weight;
+        self
+    }
+
+    pub fn with_font_size(mut self, size: f32) -> Self {
+        self.font_size = size;
+        self
+    }
+
+    pub fn with_align(mut self, align: TextAlign) -> Self {
+        self.align = align;
+        self
+    }
+
+    pub fn with_sizing(mut self, sizing: TextSizing) -> Self {
+        self.sizing = sizing;
+        self
+    }
+
+    /// Distance between baselines, in canvas units.
+    pub fn line_height_px(&self) -> f32 {
+        self.font_size * self.line_height
+    }
+
+    /// Width that lines wrap at, given the shape's width. Auto-width text
+    /// never wraps; it grows to fit its longest line instead.
+    pub fn wrap_width(&self, shape_width: f32) -> Option<f32> {
+        match self.sizing {
+            TextSizing::AutoWidth => None,
+            TextSizing::AutoHeight | TextSizing::Fixed => Some(shape_width),
+        }
+    }
+
+    /// The shape size for text that measured `measured`, given the current
+    /// shape size. Each sizing mode owns a different set of axes.
+    pub fn fit_size(&self, current: CanvasSize, measured: CanvasSize) -> CanvasSize {
+        match self.sizing {
+            TextSizing::AutoWidth => measured,
+            TextSizing::AutoHeight => CanvasSize::new(current.width(), measured.height()),
+            TextSizing::Fixed => current,
+        }
+    }
+}
+
+/// Horizontal alignment of lines within a text box.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TextAlign {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+/// How a text box's size follows its content.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TextSizing {
+    /// Width and height grow to fit; lines only break at `\n`.
+    #[default]
+    AutoWidth,
+    /// Width is fixed and lines wrap to it; height grows to fit.
+    AutoHeight,
+    /// Both axes fixed; lines wrap and may overflow the bottom.
+    Fixed,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // === Sizing ===
+
+    #[test]
+    fn auto_width_does_not_wrap() {
+        let text = Text::new("hello");
+        assert_eq!(text.wrap_width(100.0), None);
+    }
+
+    #[test]
+    fn auto_height_and_fixed_wrap_at_shape_width() {
+        let text = Text::new("hello").with_sizing(TextSizing::AutoHeight);
+        assert_eq!(text.wrap_width(100.0), Some(100.0));
+        let text = text.with_sizing(TextSizing::Fixed);
+        assert_eq!(text.wrap_width(100.0), Some(100.0));
+    }
+
+    #[test]
+    fn fit_size_only_changes_automatic_axes() {
+        let current = CanvasSize::new(100.0, 20.0);
+        let measured = CanvasSize::new(42.0, 57.6);
+
+        let auto_width = Text::new("a");
+        assert_eq!(auto_width.fit_size(current, measured), measured);
+
+        let auto_height = auto_width.clone().with_sizing(TextSizing::AutoHeight);
+        assert_eq!(auto_height.fit_size(current, measured), CanvasSize::new(100.0, 57.6));
+
+        let fixed = auto_width.with_sizing(TextSizing::Fixed);
+        assert_eq!(fixed.fit_size(current, measured), current);
+    }
+
+    #[test]
+    fn line_height_scales_with_font_size() {
+        let text = Text::new("a").with_font_size(20.0);
+        assert_eq!(text.line_height_px(), 24.0);
+    }
+
+    // === Serialization ===
+
+    #[test]
+    fn partial_text_deserializes_with_defaults() {
+        let text: Text = serde_json::from_str(r#"{"content": "Hi", "font_size": 24}"#).unwrap();
+        assert_eq!(text.content, "Hi");
+        assert_eq!(text.font_size, 24.0);
+        assert_eq!(text.font_family, DEFAULT_FONT_FAMILY);
+        assert_eq!(text.sizing, TextSizing::AutoWidth);
+    }
+
+    #[test]
+    fn enums_serialize_snake_case() {
+        assert_eq!(serde_json::to_string(&TextAlign::Center).unwrap(), "\"center\"");
+        assert_eq!(serde_json::to_string(&TextSizing::AutoHeight).unwrap(), "\"auto_height\"");
+    }
+}
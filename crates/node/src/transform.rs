@@ -0,0 +1,298 @@
+//! Rotation and flip of shapes.
+//!
+//! A shape's position and size describe its box before it's turned. The
+//! box is rotated and flipped about its center, so rotating a shape never
+//! moves it. Children live in their parent's unturned box and turn with it.
+
+use crate::coords::{CanvasPoint, CanvasSize};
+use glam::{Affine2, Vec2};
+use serde::{Deserialize, Serialize};
+
+/// An axis to mirror a shape across.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FlipAxis {
+    /// Left to right.
+    Horizontal,
+    /// Top to bottom.
+    Vertical,
+}
+
+/// An angle in degrees brought into (-180, 180].
+pub fn normalize_degrees(degrees: f32) -> f32 {
+    let degrees = degrees.rem_euclid(360.0);
+    // Adding zero turns -0.0 into 0.0
+    if degrees > 180.0 {
+        degrees - 360.0
+    } else {
+        degrees + 0.0
+    }
+}
+
+/// A shape's box as placed on the canvas: where the unturned box sits,
+/// and how it is turned about its center.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Placement {
+    /// Top-left of the unturned box.
+    pub position: CanvasPoint,
+    pub size: CanvasSize,
+    /// Clockwise, in degrees.
+    pub rotation: f32,
+    /// Mirrored left to right.
+    pub flip_x: bool,
+    /// Mirrored top to bottom.
+    pub flip_y: bool,
+}
+
+impl Placement {
+    /// An unturned box.
+    pub fn new(position: CanvasPoint, size: CanvasSize) -> Self {
+        Self {
+            position,
+            size,
+            ..Default::default()
+        }
+    }
+
+    pub fn center(&self) -> Vec2 {
+        self.position.0 + self.size.0 / 2.0
+    }
+
+    /// Whether the box is rotated. Flips alone leave the box where it was.
+    pub fn is_rotated(&self) -> bool {
+        self.rotation.rem_euclid(360.0) != 0.0
+    }
+
+    pub fn is_turned(&self) -> bool {
+        self.is_rotated() || self.flip_x || self.flip_y
+    }
+
+    /// Maps box-local points (0,0 at the top-left, `size` at the
+    /// bottom-right) to canvas space.
+    pub fn affine(&self) -> Affine2 {
+        let flip = Vec2::new(
+            if self.flip_x { -1.0 } else { 1.0 },
+            if self.flip_y { -1.0 } else { 1.0 },
+        );
+        Affine2::from_translation(self.center())
+            * Affine2::from_angle(self.rotation.to_radians())
+            * Affine2::from_scale(flip)
+            * Affine2::from_translation(-self.size.0 / 2.0)
+    }
+
+    pub fn to_canvas(&self, local: Vec2) -> Vec2 {
+        // Exact for the common unturned case, where the affine's round trip
+        // through the center would add float noise
+        if !self.is_turned() {
+            return self.position.0 + local;
+        }
+        self.affine().transform_point2(local)
+    }
+
+    pub fn to_local(&self, point: Vec2) -> Vec2 {
+        if !self.is_turned() {
+            return point - self.position.0;
+        }
+        self.affine().inverse().transform_point2(point)
+    }
+
+    /// The same box without its flips. Handles and outlines follow this
+    /// frame, so a flipped shape's top-left handle is still on screen top-left.
+    pub fn unflipped(&self) -> Self {
+        Self {
+            flip_x: false,
+            flip_y: false,
+            ..*self
+        }
+    }
+
+    /// Corners of the box in canvas space: top-left, top-right,
+    /// bottom-right, bottom-left of the unflipped box.
+    pub fn corners(&self) -> [Vec2; 4] {
+        let frame = self.unflipped();
+        let size = self.size.0;
+        [
+            Vec2::ZERO,
+            Vec2::new(size.x, 0.0),
+            size,
+            Vec2::new(0.0, size.y),
+        ]
+        .map(|corner| frame.to_canvas(corner))
+    }
+
+    /// Axis-aligned bounds of the turned box as (min, max).
+    pub fn bounds(&self) -> (CanvasPoint, CanvasPoint) {
+        let corners = self.corners();
+        let min = corners.iter().fold(Vec2::MAX, |min, c| min.min(*c));
+        let max = corners.iter().fold(Vec2::MIN, |max, c| max.max(*c));
+        (CanvasPoint(min), CanvasPoint(max))
+    }
+
+    /// Whether `point` is inside the turned box.
+    pub fn contains(&self, point: CanvasPoint) -> bool {
+        let local = self.to_local(point.0);
+        let size = self.size.0;
+        local.x >= 0.0 && local.x <= size.x && local.y >= 0.0 && local.y <= size.y
+    }
+
+    /// Place a child box given in this box's local space.
+    ///
+    /// The child's rotation adds to this one's, reversed if this box is
+    /// mirrored on one axis, and flips combine.
+    pub fn place_child(&self, child: &Placement) -> Placement {
+        let mirrored = self.flip_x != self.flip_y;
+        let child_rotation = if mirrored { -child.rotation } else { child.rotation };
+        let position = if self.is_turned() {
+            self.to_canvas(child.center()) - child.size.0 / 2.0
+        } else {
+            self.position.0 + child.position.0
+        };
+        Placement {
+            position: CanvasPoint(position),
+            size: child.size,
+            rotation: self.rotation + child_rotation,
+            flip_x: self.flip_x != child.flip_x,
+            flip_y: self.flip_y != child.flip_y,
+        }
+    }
+
+    /// A child placed at `child` on canvas, in this box's local space.
+    /// Inverse of [`place_child`](Self::place_child).
+    pub fn local_child(&self, child: &Placement) -> Placement {
+        let mirrored = self.flip_x != self.flip_y;
+        let rotation = child.rotation - self.rotation;
+        let position = if self.is_turned() {
+            self.to_local(child.center()) - child.size.0 / 2.0
+        } else {
+            child.position.0 - self.position.0
+        };
+        Placement {
+            position: CanvasPoint(position),
+            size: child.size,
+            rotation: if mirrored { -rotation } else { rotation },
+            flip_x: self.flip_x != child.flip_x,
+            flip_y: self.flip_y != child.flip_y,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // === Angles ===
+
+    #[test]
+    fn normalize_degrees_wraps_into_half_turns() {
+        assert_eq!(normalize_degrees(270.0), -90.0);
+        assert_eq!(normalize_degrees(-190.0), 170.0);
+        assert_eq!(normalize_degrees(180.0), 180.0);
+        assert!(normalize_degrees(-0.0).is_sign_positive());
+    }
+
+    fn assert_near(a: Vec2, b: Vec2) {
+        assert!(a.distance(b) < 1e-3, "{a} != {b}");
+    }
+
+    fn square(rotation: f32) -> Placement {
+        Placement {
+            rotation,
+            ..Placement::new(CanvasPoint::new(0.0, 0.0), CanvasSize::new(100.0, 50.0))
+        }
+    }
+
+    // === Mapping ===
+
+    #[test]
+    fn unturned_box_maps_by_translation() {
+        let placement = Placement::new(CanvasPoint::new(10.0, 20.0), CanvasSize::new(100.0, 50.0));
+        assert!(!placement.is_turned());
+        assert_near(placement.to_canvas(Vec2::new(5.0, 5.0)), Vec2::new(15.0, 25.0));
+    }
+
+    #[test]
+    fn rotation_is_clockwise_about_the_center() {
+        let placement = square(90.0);
+        // Top-left swings round to the top-right of the center
+        assert_near(placement.to_canvas(Vec2::ZERO), Vec2::new(75.0, -25.0));
+        assert_near(placement.to_canvas(Vec2::new(50.0, 25.0)), Vec2::new(50.0, 25.0));
+    }
+
+    #[test]
+    fn flip_mirrors_about_the_center() {
+        let placement = Placement {
+            flip_x: true,
+            ..square(0.0)
+        };
+        assert_near(placement.to_canvas(Vec2::new(10.0, 10.0)), Vec2::new(90.0, 10.0));
+        assert_near(placement.to_local(Vec2::new(90.0, 10.0)), Vec2::new(10.0, 10.0));
+    }
+
+    // === Bounds and hit testing ===
+
+    #[test]
+    fn bounds_cover_the_rotated_box() {
+        let (min, max) = square(90.0).bounds();
+        assert_near(min.0, Vec2::new(25.0, -25.0));
+        assert_near(max.0, Vec2::new(75.0, 75.0));
+    }
+
+    #[test]
+    fn contains_follows_rotation() {
+        let placement = square(90.0);
+        // Inside the unrotated box but outside the rotated one
+        assert!(!placement.contains(CanvasPoint::new(5.0, 25.0)));
+        assert!(placement.contains(CanvasPoint::new(50.0, -20.0)));
+    }
+
+    // === Children ===
+
+    #[test]
+    fn child_turns_with_its_parent() {
+        let parent = Placement::new(CanvasPoint::new(0.0, 0.0), CanvasSize::new(100.0, 100.0));
+        let parent = Placement {
+            rotation: 90.0,
+            ..parent
+        };
+        let child = Placement {
+            rotation: 10.0,
+            ..Placement::new(CanvasPoint::new(0.0, 0.0), CanvasSize::new(20.0, 20.0))
+        };
+        let placed = parent.place_child(&child);
+        assert_eq!(placed.rotation, 100.0);
+        // The child's center (10, 10) is carried round to the top-right
+        assert_near(placed.center(), Vec2::new(90.0, 10.0));
+    }
+
+    #[test]
+    fn mirrored_parent_reverses_child_rotation() {
+        let parent = Placement {
+            flip_x: true,
+            ..square(0.0)
+        };
+        let child = Placement {
+            rotation: 30.0,
+            ..Placement::new(CanvasPoint::new(0.0, 0.0), CanvasSize::new(10.0, 10.0))
+        };
+        let placed = parent.place_child(&child);
+        assert_eq!(placed.rotation, -30.0);
+        assert!(placed.flip_x);
+    }
+
+    #[test]
+    fn local_child_inverts_place_child() {
+        let parent = Placement {
+            flip_y: true,
+            ..square(45.0)
+        };
+        let child = Placement {
+            rotation: 20.0,
+            flip_x: true,
+            ..Placement::new(CanvasPoint::new(12.0, 8.0), CanvasSize::new(10.0, 6.0))
+        };
+        let local = parent.local_child(&parent.place_child(&child));
+        assert_near(local.position.0, child.position.0);
+        assert!((local.rotation - child.rotation).abs() < 1e-3);
+        assert_eq!((local.flip_x, local.flip_y), (true, false));
+    }
+}
@@ -22,6 +22,9 @@ pub struct Theme {
     /// Grid lines (if shown)
     pub grid: Hsla,
 
+    /// Snapping guides
+    pub guide: Hsla,
+
     /// UI background
     pub ui_background: Hsla,
 
@@ -49,6 +52,7 @@ impl Theme {
             hover: hsla(0.58, 0.9, 0.5, 0.3),      // Blue transparent
             default_stroke: gpui::black(),
             grid: hsla(0.0, 0.0, 0.9, 1.0),        // Light gray
+            guide: hsla(0.83, 1.0, 0.5, 1.0),      // Magenta
             ui_background: hsla(0.0, 0.0, 0.98, 1.0),
             ui_border: hsla(0.0, 0.0, 0.9, 1.0),
             ui_text: hsla(0.0, 0.0, 0.1, 1.0),
@@ -63,6 +67,7 @@ impl Theme {
             hover: hsla(0.58, 0.9, 0.5, 0.3),
             default_stroke: gpui::white(),
             grid: hsla(0.0, 0.0, 0.2, 1.0),
+            guide: hsla(0.83, 1.0, 0.6, 1.0),
             ui_background: hsla(0.0, 0.0, 0.12, 1.0),
             ui_border: hsla(0.0, 0.0, 0.2, 1.0),
             ui_text: hsla(0.0, 0.0, 0.9, 1.0),
@@ -1,6 +1,6 @@
 //! Basic UI components.
 
-use gpui::{div, px, Div, ElementId, InteractiveElement, IntoElement, ParentElement, SharedString, Styled};
+use gpui::{div, px, Div, ElementId, InteractiveElement, ParentElement, SharedString, Stateful, Styled};
 use theme::Theme;
 
 /// Horizontal stack layout.
@@ -28,7 +28,7 @@ pub fn button(
     id: impl Into<ElementId>,
     label: impl Into<SharedString>,
     theme: &Theme,
-) -> impl IntoElement {
+) -> Stateful<Div> {
     let label = label.into();
     let bg = theme.ui_background;
     let border = theme.ui_border;
@@ -55,7 +55,7 @@ pub fn icon_button(
     id: impl Into<ElementId>,
     icon: impl Into<SharedString>,
     theme: &Theme,
-) -> impl IntoElement {
+) -> Stateful<Div> {
     let icon = icon.into();
     let bg = theme.ui_background;
     let border = theme.ui_border;
@@ -0,0 +1,236 @@
+//! The selected shape's fills in the properties panel.
+//!
+//! Fills are listed top first, as they stack, each with a toggle to show
+//! or hide it, its color in hex or its gradient's kind, and its opacity.
+//! Whether the top fill is a color or a gradient is picked below the list.
+
+use crate::components::{h_stack, icon_button, v_stack};
+use crate::gradient_editor::parse_percent;
+use crate::input::{InputColors, InputState, InputStateEvent};
+use crate::properties::{color_swatch, hex_to_hsla, hsla_to_hex, input_field};
+use canvas::Canvas;
+use gpui::{
+    div, px, AppContext, Context, Entity, Focusable, Hsla, IntoElement, ParentElement, Render,
+    StatefulInteractiveElement, Styled, Subscription, Window,
+};
+use node::{Fill, GradientKind, Paint, ShapeId};
+use theme::{Palette, Theme};
+
+pub struct FillList {
+    canvas: Entity<Canvas>,
+    theme: Theme,
+    /// Inputs for each fill, by its index from the bottom. Rows are made as
+    /// fills are added and kept when they're removed.
+    rows: Vec<FillRow>,
+    /// The fills the inputs last showed, so they're only rewritten when
+    /// they change.
+    shown: Option<(ShapeId, Vec<Fill>)>,
+    _subscriptions: Vec<Subscription>,
+}
+
+struct FillRow {
+    color: Entity<InputState>,
+    opacity: Entity<InputState>,
+}
+
+impl FillList {
+    pub fn new(canvas: Entity<Canvas>, theme: Theme, cx: &mut Context<Self>) -> Self {
+        let canvas_sub = cx.observe(&canvas, |_, _, cx| cx.notify());
+        Self {
+            canvas,
+            theme,
+            rows: Vec::new(),
+            shown: None,
+            _subscriptions: vec![canvas_sub],
+        }
+    }
+
+    /// The selected shape's fills, if exactly one shape is selected.
+    fn target<'a>(&self, cx: &'a gpui::App) -> Option<(ShapeId, &'a [Fill])> {
+        let canvas = self.canvas.read(cx);
+        let mut selection = canvas.selection.iter();
+        let (Some(id), None) = (selection.next(), selection.next()) else {
+            return None;
+        };
+        Some((*id, &canvas.get_shape(*id)?.fills))
+    }
+
+    /// Edit the selected shape's fills as an undo step.
+    fn update_fills(
+        &mut self,
+        coalesce: Option<&'static str>,
+        cx: &mut Context<Self>,
+        edit: impl FnOnce(&mut Vec<Fill>),
+    ) {
+        let Some((id, _)) = self.target(cx) else {
+            return;
+        };
+        self.canvas.update(cx, |canvas, cx| {
+            canvas.edit_shape(id, coalesce, cx, |shape| edit(&mut shape.fills));
+        });
+    }
+
+    /// Add a gray fill on top.
+    fn add_fill(&mut self, cx: &mut Context<Self>) {
+        self.update_fills(None, cx, |fills| fills.push(Fill::new(Palette::gray())));
+    }
+
+    fn remove_fill(&mut self, index: usize, cx: &mut Context<Self>) {
+        self.update_fills(None, cx, |fills| {
+            if index < fills.len() {
+                fills.remove(index);
+            }
+        });
+    }
+
+    fn toggle_fill(&mut self, index: usize, cx: &mut Context<Self>) {
+        self.update_fills(None, cx, |fills| {
+            if let Some(fill) = fills.get_mut(index) {
+                fill.visible = !fill.visible;
+            }
+        });
+    }
+
+    /// Make input rows until there's one for each of `count` fills.
+    fn ensure_rows(&mut self, count: usize, cx: &mut Context<Self>) {
+        while self.rows.len() < count {
+            let index = self.rows.len();
+            let color = cx.new(InputState::new_singleline);
+            let opacity = cx.new(InputState::new_singleline);
+            self._subscriptions.push(cx.subscribe(&color, move |this, input, event, cx| {
+                this.on_color_event(index, input, event, cx)
+            }));
+            self._subscriptions.push(cx.subscribe(&opacity, move |this, input, event, cx| {
+                this.on_opacity_event(index, input, event, cx)
+            }));
+            self.rows.push(FillRow { color, opacity });
+        }
+    }
+
+    fn on_color_event(
+        &mut self,
+        index: usize,
+        input: Entity<InputState>,
+        event: &InputStateEvent,
+        cx: &mut Context<Self>,
+    ) {
+        if !matches!(event, InputStateEvent::TextChanged) || !input.read(cx).was_focused() {
+            return;
+        }
+        let Some(color) = hex_to_hsla(input.read(cx).content()) else {
+            return;
+        };
+        self.update_fills(Some("fill_color"), cx, |fills| {
+            if let Some(Fill { paint: Paint::Solid(own), .. }) = fills.get_mut(index) {
+                // Hex has no alpha, so the color keeps its own
+                *own = Hsla { a: own.a, ..color };
+            }
+        });
+    }
+
+    fn on_opacity_event(
+        &mut self,
+        index: usize,
+        input: Entity<InputState>,
+        event: &InputStateEvent,
+        cx: &mut Context<Self>,
+    ) {
+        if !matches!(event, InputStateEvent::TextChanged) || !input.read(cx).was_focused() {
+            return;
+        }
+        let Some(opacity) = parse_percent(input.read(cx).content()) else {
+            return;
+        };
+        self.update_fills(Some("fill_opacity"), cx, |fills| {
+            if let Some(fill) = fills.get_mut(index) {
+                fill.opacity = opacity.clamp(0.0, 1.0);
+            }
+        });
+    }
+
+    fn sync_inputs(&mut self, window: &Window, cx: &mut Context<Self>) {
+        let Some((id, fills)) = self.target(cx).map(|(id, fills)| (id, fills.to_vec())) else {
+            self.shown = None;
+            return;
+        };
+        if self.shown.as_ref() == Some(&(id, fills.clone())) {
+            return;
+        }
+        self.ensure_rows(fills.len(), cx);
+        for (fill, row) in fills.iter().zip(&self.rows) {
+            if !row.color.focus_handle(cx).is_focused(window) {
+                let content = match &fill.paint {
+                    Paint::Solid(color) => hsla_to_hex(*color),
+                    Paint::Gradient(_) => String::new(),
+                };
+                row.color.update(cx, |input, cx| input.set_content(content, cx));
+            }
+            if !row.opacity.focus_handle(cx).is_focused(window) {
+                let percent = format!("{}%", (fill.opacity * 100.0).round());
+                row.opacity.update(cx, |input, cx| input.set_content(percent, cx));
+            }
+        }
+        self.shown = Some((id, fills));
+    }
+}
+
+impl Render for FillList {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        self.sync_inputs(window, cx);
+        let theme = &self.theme;
+        let colors = InputColors {
+            selection: theme.selection.opacity(0.3),
+            cursor: theme.selection,
+            placeholder: theme.ui_text_muted,
+        };
+        let fills = self.target(cx).map(|(_, fills)| fills.to_vec()).unwrap_or_default();
+        let this = cx.entity();
+
+        let header = h_stack()
+            .justify_between()
+            .child(div().text_xs().text_color(theme.ui_text_muted).child("Fill"))
+            .child(icon_button("add-fill", "+", theme).on_click({
+                let this = this.clone();
+                move |_, _, cx| this.update(cx, |list, cx| list.add_fill(cx))
+            }));
+
+        let rows = fills.iter().zip(&self.rows).enumerate().rev().map(|(index, (fill, row))| {
+            let paint = match &fill.paint {
+                Paint::Solid(_) => input_field("", &row.color, theme, &colors, cx).into_any_element(),
+                Paint::Gradient(gradient) => div()
+                    .flex_1()
+                    .text_xs()
+                    .text_color(theme.ui_text)
+                    .child(gradient_kind_name(gradient.kind))
+                    .into_any_element(),
+            };
+            h_stack()
+                .gap(px(8.0))
+                .opacity(if fill.visible { 1.0 } else { 0.5 })
+                .child(
+                    icon_button(("toggle-fill", index), if fill.visible { "◉" } else { "◌" }, theme)
+                        .on_click({
+                            let this = this.clone();
+                            move |_, _, cx| this.update(cx, |list, cx| list.toggle_fill(index, cx))
+                        }),
+                )
+                .child(color_swatch(Some(fill.color().opacity(fill.opacity)), theme))
+                .child(paint)
+                .child(h_stack().w(px(56.0)).child(input_field("", &row.opacity, theme, &colors, cx)))
+                .child(icon_button(("remove-fill", index), "−", theme).on_click({
+                    let this = this.clone();
+                    move |_, _, cx| this.update(cx, |list, cx| list.remove_fill(index, cx))
+                }))
+        });
+
+        v_stack().gap(px(4.0)).child(header).children(rows)
+    }
+}
+
+fn gradient_kind_name(kind: GradientKind) -> &'static str {
+    match kind {
+        GradientKind::Linear => "Linear",
+        GradientKind::Radial => "Radial",
+        GradientKind::Angular => "Angular",
+    }
+}
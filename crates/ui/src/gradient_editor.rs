@@ -0,0 +1,232 @@
+//! Gradient stop editing in the properties panel.
+//!
+//! Lists the selected shape's gradient stops, each with its color in hex
+//! and its offset as a percentage, with buttons to add and remove stops.
+//! Where the gradient runs is set by dragging its handles on the canvas.
+
+use crate::components::{h_stack, icon_button, v_stack};
+use crate::input::{InputColors, InputState, InputStateEvent};
+use crate::properties::{color_swatch, hex_to_hsla, hsla_to_hex, input_field};
+use canvas::Canvas;
+use gpui::{
+    px, AppContext, Context, Entity, Focusable, Hsla, IntoElement, ParentElement, Render,
+    StatefulInteractiveElement, Styled, Subscription, Window,
+};
+use node::{Gradient, ShapeId};
+use theme::Theme;
+
+pub struct GradientEditor {
+    canvas: Entity<Canvas>,
+    theme: Theme,
+    /// Inputs for each stop, in order. Rows are made as stops are added
+    /// and kept when they're removed.
+    rows: Vec<StopRow>,
+    /// The gradient the inputs last showed, so they're only rewritten when
+    /// it changes.
+    shown: Option<(ShapeId, Gradient)>,
+    _subscriptions: Vec<Subscription>,
+}
+
+struct StopRow {
+    color: Entity<InputState>,
+    offset: Entity<InputState>,
+}
+
+impl GradientEditor {
+    pub fn new(canvas: Entity<Canvas>, theme: Theme, cx: &mut Context<Self>) -> Self {
+        let canvas_sub = cx.observe(&canvas, |_, _, cx| cx.notify());
+        Self {
+            canvas,
+            theme,
+            rows: Vec::new(),
+            shown: None,
+            _subscriptions: vec![canvas_sub],
+        }
+    }
+
+    /// The selected shape's gradient, if exactly one shape is selected and
+    /// its top fill is one.
+    fn target<'a>(&self, cx: &'a gpui::App) -> Option<(ShapeId, &'a Gradient)> {
+        let canvas = self.canvas.read(cx);
+        let mut selection = canvas.selection.iter();
+        let (Some(id), None) = (selection.next(), selection.next()) else {
+            return None;
+        };
+        let shape = canvas.get_shape(*id)?;
+        let gradient = shape.fills[shape.top_fill()?].gradient()?;
+        Some((*id, gradient))
+    }
+
+    /// Edit the gradient in the selected shape's top fill as an undo step.
+    fn update_gradient(
+        &mut self,
+        coalesce: Option<&'static str>,
+        cx: &mut Context<Self>,
+        edit: impl FnOnce(&mut Gradient),
+    ) {
+        let Some((id, _)) = self.target(cx) else {
+            return;
+        };
+        self.canvas.update(cx, |canvas, cx| {
+            canvas.edit_shape(id, coalesce, cx, |shape| {
+                let index = shape.top_fill();
+                if let Some(gradient) = index.and_then(|index| shape.fills[index].gradient_mut()) {
+                    edit(gradient);
+                }
+            });
+        });
+    }
+
+    /// Add a stop in the middle of the widest gap between stops.
+    fn add_stop(&mut self, cx: &mut Context<Self>) {
+        self.update_gradient(None, cx, |gradient| {
+            let offset = widest_gap(gradient);
+            gradient.add_stop(offset);
+        });
+    }
+
+    fn remove_stop(&mut self, index: usize, cx: &mut Context<Self>) {
+        self.update_gradient(None, cx, |gradient| {
+            gradient.remove_stop(index);
+        });
+    }
+
+    /// Make input rows until there's one for each of `count` stops.
+    fn ensure_rows(&mut self, count: usize, cx: &mut Context<Self>) {
+        while self.rows.len() < count {
+            let index = self.rows.len();
+            let color = cx.new(InputState::new_singleline);
+            let offset = cx.new(InputState::new_singleline);
+            self._subscriptions.push(cx.subscribe(&color, move |this, input, event, cx| {
+                this.on_color_event(index, input, event, cx)
+            }));
+            self._subscriptions.push(cx.subscribe(&offset, move |this, input, event, cx| {
+                this.on_offset_event(index, input, event, cx)
+            }));
+            self.rows.push(StopRow { color, offset });
+        }
+    }
+
+    fn on_color_event(
+        &mut self,
+        index: usize,
+        input: Entity<InputState>,
+        event: &InputStateEvent,
+        cx: &mut Context<Self>,
+    ) {
+        if !matches!(event, InputStateEvent::TextChanged) || !input.read(cx).was_focused() {
+            return;
+        }
+        let Some(color) = hex_to_hsla(input.read(cx).content()) else {
+            return;
+        };
+        self.update_gradient(Some("gradient_stop_color"), cx, |gradient| {
+            if let Some(stop) = gradient.stops.get_mut(index) {
+                // Hex has no alpha, so the stop keeps its own
+                stop.color = Hsla { a: stop.color.a, ..color };
+            }
+        });
+    }
+
+    /// Offsets apply once they're entered: stops stay in order, so one
+    /// applied while typing could jump to another row under the cursor.
+    fn on_offset_event(
+        &mut self,
+        index: usize,
+        input: Entity<InputState>,
+        event: &InputStateEvent,
+        cx: &mut Context<Self>,
+    ) {
+        if !matches!(event, InputStateEvent::Submit | InputStateEvent::Blur) {
+            return;
+        }
+        if let Some(offset) = parse_percent(input.read(cx).content()) {
+            self.update_gradient(None, cx, |gradient| {
+                gradient.move_stop(index, offset);
+            });
+        }
+        // Show what the stop ended up at, or put it back over a typo
+        self.shown = None;
+        cx.notify();
+    }
+
+    fn sync_inputs(&mut self, window: &Window, cx: &mut Context<Self>) {
+        let Some((id, gradient)) = self.target(cx).map(|(id, g)| (id, g.clone())) else {
+            self.shown = None;
+            return;
+        };
+        if self.shown.as_ref() == Some(&(id, gradient.clone())) {
+            return;
+        }
+        self.ensure_rows(gradient.stops.len(), cx);
+        for (stop, row) in gradient.stops.iter().zip(&self.rows) {
+            if !row.color.focus_handle(cx).is_focused(window) {
+                row.color.update(cx, |input, cx| input.set_content(hsla_to_hex(stop.color), cx));
+            }
+            if !row.offset.focus_handle(cx).is_focused(window) {
+                let percent = format!("{}%", (stop.offset * 100.0).round());
+                row.offset.update(cx, |input, cx| input.set_content(percent, cx));
+            }
+        }
+        self.shown = Some((id, gradient));
+    }
+}
+
+impl Render for GradientEditor {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        self.sync_inputs(window, cx);
+        let theme = &self.theme;
+        let colors = InputColors {
+            selection: theme.selection.opacity(0.3),
+            cursor: theme.selection,
+            placeholder: theme.ui_text_muted,
+        };
+        let stops = self.target(cx).map(|(_, gradient)| gradient.stops.clone()).unwrap_or_default();
+        let removable = stops.len() > 1;
+        let this = cx.entity();
+
+        v_stack()
+            .gap(px(4.0))
+            .children(stops.iter().zip(&self.rows).enumerate().map(|(index, (stop, row))| {
+                let this = this.clone();
+                h_stack()
+                    .gap(px(8.0))
+                    .child(color_swatch(Some(stop.color), theme))
+                    .child(input_field("", &row.color, theme, &colors, cx))
+                    .child(
+                        h_stack().w(px(64.0)).child(input_field("", &row.offset, theme, &colors, cx)),
+                    )
+                    .children(removable.then(|| {
+                        icon_button(("remove-gradient-stop", index), "−", theme).on_click(
+                            move |_, _, cx| {
+                                this.update(cx, |editor, cx| editor.remove_stop(index, cx));
+                            },
+                        )
+                    }))
+            }))
+            .child(h_stack().child(icon_button("add-gradient-stop", "+", theme).on_click(
+                move |_, _, cx| {
+                    this.update(cx, |editor, cx| editor.add_stop(cx));
+                },
+            )))
+    }
+}
+
+/// The middle of the widest gap between `gradient`'s stops, counting the
+/// stretches before the first and after the last.
+fn widest_gap(gradient: &Gradient) -> f32 {
+    let offsets: Vec<f32> = std::iter::once(0.0)
+        .chain(gradient.stops.iter().map(|stop| stop.offset))
+        .chain(std::iter::once(1.0))
+        .collect();
+    offsets
+        .windows(2)
+        .max_by(|a, b| (a[1] - a[0]).total_cmp(&(b[1] - b[0])))
+        .map_or(0.5, |gap| (gap[0] + gap[1]) / 2.0)
+}
+
+/// An offset typed as a percentage, with or without the sign.
+pub(crate) fn parse_percent(text: &str) -> Option<f32> {
+    let percent: f32 = text.trim().trim_end_matches('%').trim_end().parse().ok()?;
+    percent.is_finite().then_some(percent / 100.0)
+}
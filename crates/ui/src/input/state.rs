@@ -30,6 +30,8 @@ pub enum InputStateEvent {
     Focus,
     Blur,
     TextChanged,
+    /// Enter pressed in a single-line input.
+    Submit,
     Undo,
     Redo,
 }
@@ -204,6 +206,14 @@ impl InputState {
         }
     }
 
+    /// Whether the input held focus when it was last painted.
+    ///
+    /// Lets subscribers tell user edits apart from programmatic
+    /// [`InputState::set_content`] calls without needing a `Window`.
+    pub fn was_focused(&self) -> bool {
+        self.was_focused
+    }
+
     pub fn content(&self) -> &str {
         &self.content
     }
@@ -564,6 +574,8 @@ impl InputState {
     pub(crate) fn enter(&mut self, _: &Enter, window: &mut Window, cx: &mut Context<Self>) {
         if self.multiline {
             self.replace_text_in_range(None, "\n", window, cx);
+        } else {
+            cx.emit(InputStateEvent::Submit);
         }
     }
 
@@ -1,57 +1,332 @@
 //! Layer list showing shapes on the canvas.
 
 use crate::components::{panel, v_stack};
-use canvas::Canvas;
+use crate::input::{bindings::Enter, input, InputColors, InputState, InputStateEvent};
+use canvas::{Canvas, CanvasEvent};
 use gpui::{
-    div, px, Context, Div, Entity, InteractiveElement, IntoElement, ParentElement, Render,
-    SharedString, StatefulInteractiveElement, Styled, Window,
+    div, prelude::FluentBuilder, px, AnyElement, AppContext, ClickEvent, Context, Div,
+    DragMoveEvent, Entity, Focusable, InteractiveElement, IntoElement, Modifiers, ParentElement,
+    Render, SharedString, StatefulInteractiveElement, Styled, Subscription, Window,
 };
 use node::{ShapeId, ShapeKind};
+use std::collections::HashSet;
 use theme::Theme;
 
-/// Layer list panel showing all shapes.
+/// Indent per level of nesting.
+const INDENT: f32 = 12.0;
+
+/// Layer list panel showing the shape tree, front first. Double-click a
+/// layer to rename it, drag layers to restack them or move them into and
+/// out of frames.
 pub struct LayerList {
     canvas: Entity<Canvas>,
     theme: Theme,
+    /// Shared by whichever layer is being renamed.
+    name_input: Entity<InputState>,
+    renaming: Option<ShapeId>,
+    /// Frames whose children are folded away.
+    collapsed: HashSet<ShapeId>,
+    /// Where shift-click ranges start from.
+    anchor: Option<ShapeId>,
+    /// The layer under a drag, and where the dragged layers would land.
+    drop_target: Option<(ShapeId, DropPlace)>,
+    /// The rows as last rendered, front first.
+    rows: Vec<ShapeId>,
+    _subscriptions: Vec<Subscription>,
+}
+
+/// Where dragged layers land relative to the layer they're dropped on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DropPlace {
+    /// In front of it, which is above it in the list.
+    Above,
+    Below,
+    /// Inside it, in front of its children.
+    Inside,
+}
+
+/// Layers being dragged, which is also the view following the cursor.
+#[derive(Clone)]
+struct DraggedLayers {
+    ids: Vec<ShapeId>,
+    label: SharedString,
+    theme: Theme,
+}
+
+impl Render for DraggedLayers {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .px(px(8.0))
+            .py(px(4.0))
+            .rounded(px(4.0))
+            .bg(self.theme.ui_background)
+            .border_1()
+            .border_color(self.theme.selection)
+            .text_sm()
+            .text_color(self.theme.ui_text)
+            .child(self.label.clone())
+    }
 }
 
 impl LayerList {
-    pub fn new(canvas: Entity<Canvas>, theme: Theme) -> Self {
-        Self { canvas, theme }
+    pub fn new(canvas: Entity<Canvas>, theme: Theme, cx: &mut Context<Self>) -> Self {
+        let name_input = cx.new(InputState::new_singleline);
+        let input_sub = cx.subscribe(&name_input, Self::on_input_event);
+
+        Self {
+            canvas,
+            theme,
+            name_input,
+            renaming: None,
+            collapsed: HashSet::new(),
+            anchor: None,
+            drop_target: None,
+            rows: Vec::new(),
+            _subscriptions: vec![input_sub],
+        }
+    }
+
+    /// Edit a shape's name in place.
+    pub fn rename_shape(&mut self, id: ShapeId, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(name) = self.canvas.read(cx).get_shape(id).map(|shape| shape.name.clone()) else {
+            return;
+        };
+        self.name_input.update(cx, |input, cx| {
+            let len = name.len();
+            input.set_content(name, cx);
+            input.set_selected_range(0..len);
+        });
+        self.renaming = Some(id);
+        window.focus(&self.name_input.focus_handle(cx));
+        cx.notify();
+    }
+
+    /// Select a clicked layer. Cmd toggles it in the selection, shift
+    /// selects every row from the last clicked layer to it.
+    fn click_layer(
+        &mut self,
+        id: ShapeId,
+        modifiers: Modifiers,
+        click_count: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let range = self.anchor.filter(|_| modifiers.shift).and_then(|anchor| {
+            let from = self.rows.iter().position(|&row| row == anchor)?;
+            let to = self.rows.iter().position(|&row| row == id)?;
+            Some(self.rows[from.min(to)..=from.max(to)].to_vec())
+        });
+        if range.is_none() {
+            self.anchor = Some(id);
+        }
+        self.canvas.update(cx, |canvas, cx| match range {
+            Some(range) => {
+                canvas.clear_selection(cx);
+                for row in range {
+                    canvas.select(row, true, cx);
+                }
+            }
+            None if modifiers.platform && canvas.selection.contains(&id) => canvas.deselect(id, cx),
+            None => canvas.select(id, modifiers.platform, cx),
+        });
+        if click_count > 1 && !modifiers.shift && !modifiers.platform {
+            self.rename_shape(id, window, cx);
+        }
+    }
+
+    fn toggle_collapsed(&mut self, id: ShapeId, cx: &mut Context<Self>) {
+        if !self.collapsed.remove(&id) {
+            self.collapsed.insert(id);
+        }
+        cx.notify();
+    }
+
+    /// The layers a drag starting on `id` carries: the selection if `id` is
+    /// in it, or just `id`.
+    fn dragged_layers(&self, id: ShapeId, canvas: &Canvas) -> DraggedLayers {
+        let ids: Vec<ShapeId> = if canvas.selection.contains(&id) {
+            self.rows.iter().copied().filter(|row| canvas.selection.contains(row)).collect()
+        } else {
+            vec![id]
+        };
+        let label = match ids.as_slice() {
+            [only] => canvas.get_shape(*only).map(|shape| shape.name.clone()).unwrap_or_default(),
+            ids => format!("{} layers", ids.len()),
+        };
+        DraggedLayers { ids, label: label.into(), theme: self.theme.clone() }
+    }
+
+    fn drag_over(&mut self, id: ShapeId, event: &DragMoveEvent<DraggedLayers>, cx: &mut Context<Self>) {
+        let bounds = event.bounds;
+        let position = event.event.position;
+        if !bounds.contains(&position) {
+            return;
+        }
+        let is_frame = self
+            .canvas
+            .read(cx)
+            .get_shape(id)
+            .is_some_and(|shape| shape.kind == ShapeKind::Frame);
+        // Frames take drops in their middle half, other layers split in two
+        let fraction = (position.y - bounds.top()) / bounds.size.height;
+        let place = match fraction {
+            f if is_frame && f > 0.25 && f < 0.75 => DropPlace::Inside,
+            f if f < 0.5 => DropPlace::Above,
+            _ => DropPlace::Below,
+        };
+        if self.drop_target != Some((id, place)) {
+            self.drop_target = Some((id, place));
+            cx.notify();
+        }
+    }
+
+    /// Move dragged layers to the drop target, as one undo step.
+    fn drop_layers(&mut self, dragged: &DraggedLayers, cx: &mut Context<Self>) {
+        let Some((target, place)) = self.drop_target.take() else {
+            return;
+        };
+        let collapsed = self.collapsed.contains(&target);
+        self.canvas.update(cx, |canvas, cx| {
+            let Some(shape) = canvas.get_shape(target) else {
+                return;
+            };
+            // Just below an open frame is in front of its children
+            let place = match place {
+                DropPlace::Below if !collapsed && !shape.children.is_empty() => DropPlace::Inside,
+                place => place,
+            };
+            let (parent, index) = match place {
+                DropPlace::Inside => (Some(target), usize::MAX),
+                DropPlace::Above | DropPlace::Below => {
+                    // Indexes count the siblings that stay put
+                    let Some(position) = canvas
+                        .siblings(shape.parent)
+                        .into_iter()
+                        .filter(|id| !dragged.ids.contains(id))
+                        .position(|id| id == target)
+                    else {
+                        return;
+                    };
+                    let index = if place == DropPlace::Above { position + 1 } else { position };
+                    (shape.parent, index)
+                }
+            };
+            let before = canvas.snapshot();
+            canvas.move_to(&dragged.ids, parent, index, cx);
+            canvas.push_undo(before);
+        });
+        cx.notify();
+    }
+
+    /// The rows of the list, front first, with how deeply each is nested.
+    /// Children of collapsed frames are left out.
+    fn tree_rows(&self, canvas: &Canvas) -> Vec<(ShapeId, usize)> {
+        let mut rows = Vec::new();
+        // Siblings are stored back to front, so popping visits the front first
+        let mut stack: Vec<(ShapeId, usize)> =
+            canvas.siblings(None).into_iter().map(|id| (id, 0)).collect();
+        while let Some((id, depth)) = stack.pop() {
+            rows.push((id, depth));
+            if let Some(shape) = canvas.get_shape(id).filter(|_| !self.collapsed.contains(&id)) {
+                stack.extend(shape.children.iter().map(|&child| (child, depth + 1)));
+            }
+        }
+        rows
+    }
+
+    fn on_input_event(
+        &mut self,
+        input: Entity<InputState>,
+        event: &InputStateEvent,
+        cx: &mut Context<Self>,
+    ) {
+        // Renamed once when done rather than as typed, so the rename is a
+        // single undo step
+        let InputStateEvent::Blur = event else {
+            return;
+        };
+        let Some(id) = self.renaming.take() else {
+            return;
+        };
+        let name = input.read(cx).content().trim().to_string();
+        self.canvas.update(cx, |canvas, cx| {
+            let unchanged = canvas.get_shape(id).is_none_or(|shape| shape.name == name);
+            if name.is_empty() || unchanged {
+                return;
+            }
+            let before = canvas.snapshot();
+            if let Some(shape) = canvas.get_shape_mut(id) {
+                shape.name = name;
+            }
+            canvas.push_undo(before);
+            cx.emit(CanvasEvent::ContentChanged);
+            cx.notify();
+        });
+        cx.notify();
     }
 }
 
 impl Render for LayerList {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let list = cx.entity();
         let canvas = self.canvas.read(cx);
-        let shapes = &canvas.shapes;
+        let rows = self.tree_rows(canvas);
+        self.rows = rows.iter().map(|&(id, _)| id).collect();
         let selection = &canvas.selection;
         let theme = &self.theme;
+        // A drag that ended off the list leaves its last target behind
+        let drop_target = self.drop_target.filter(|_| cx.has_active_drag());
 
-        // Build layer items (reverse for top-to-bottom display, top layer first)
-        let items: Vec<_> = shapes
-            .iter()
-            .rev()
-            .enumerate()
-            .map(|(idx, shape)| {
+        let items: Vec<_> = rows
+            .into_iter()
+            .filter_map(|(id, depth)| Some((canvas.get_shape(id)?, depth)))
+            .map(|(shape, depth)| {
                 let id = shape.id;
                 let is_selected = selection.contains(&id);
                 let kind_icon = match shape.kind {
+                    _ if shape.is_main_component() => "❖",
+                    _ if shape.is_instance() => "◇",
                     ShapeKind::Rectangle => "▢",
                     ShapeKind::Ellipse => "○",
                     ShapeKind::Frame => "▣",
+                    ShapeKind::Text => "T",
+                    ShapeKind::Path => "✎",
+                };
+                let name = if self.renaming == Some(id) {
+                    input(&self.name_input, cx)
+                        .colors(InputColors {
+                            selection: theme.selection.alpha(0.3),
+                            cursor: theme.ui_text,
+                            placeholder: theme.ui_text_muted,
+                        })
+                        .into_any_element()
+                } else {
+                    SharedString::from(shape.name.clone()).into_any_element()
                 };
-                let name: SharedString = format!("Shape {}", shapes.len() - idx).into();
                 let item_id: SharedString = format!("layer-{}", id).into();
+                let in_layout_frame = shape
+                    .parent
+                    .and_then(|parent| canvas.get_shape(parent))
+                    .is_some_and(|parent| parent.has_layout());
 
                 LayerItem {
                     id: item_id,
                     shape_id: id,
+                    depth,
+                    expanded: (!shape.children.is_empty()).then(|| !self.collapsed.contains(&id)),
                     icon: kind_icon.into(),
                     name,
                     is_selected,
+                    dragged: self.dragged_layers(id, canvas),
+                    drop_place: drop_target
+                        .filter(|(target, _)| *target == id)
+                        .map(|(_, place)| place),
+                    absolute: in_layout_frame.then_some(shape.child_layout.absolute),
+                    visible: shape.visible,
+                    locked: shape.locked,
                     theme: theme.clone(),
                     canvas: self.canvas.clone(),
+                    list: list.clone(),
                 }
             })
             .collect();
@@ -75,11 +350,25 @@ impl Render for LayerList {
 struct LayerItem {
     id: SharedString,
     shape_id: ShapeId,
+    /// How many frames it's nested in.
+    depth: usize,
+    /// Whether its children are shown, if it has any.
+    expanded: Option<bool>,
     icon: SharedString,
-    name: SharedString,
+    /// The name, or the field it's being edited in.
+    name: AnyElement,
     is_selected: bool,
+    /// What dragging it picks up.
+    dragged: DraggedLayers,
+    /// Where layers dragged over it would land.
+    drop_place: Option<DropPlace>,
+    /// Whether the shape is absolutely positioned, if its parent has layout.
+    absolute: Option<bool>,
+    visible: bool,
+    locked: bool,
     theme: Theme,
     canvas: Entity<Canvas>,
+    list: Entity<LayerList>,
 }
 
 impl IntoElement for LayerItem {
@@ -102,29 +391,124 @@ impl IntoElement for LayerItem {
         let muted = self.theme.ui_text_muted;
         let shape_id = self.shape_id;
         let canvas = self.canvas;
+        let list = self.list;
+        let toggle = |suffix: &str, glyph: &'static str, on: bool| {
+            div()
+                .id(SharedString::from(format!("{}-{}", self.id, suffix)))
+                .px(px(4.0))
+                .rounded(px(2.0))
+                .text_xs()
+                .text_color(if on { self.theme.selection } else { muted.opacity(0.5) })
+                .hover(move |d| d.bg(hover_bg))
+                .child(glyph)
+        };
+        let visible = self.visible;
+        let visibility_toggle = toggle("visible", if visible { "◉" } else { "◌" }, !visible).on_click({
+            let canvas = canvas.clone();
+            move |_, _window, cx| {
+                cx.stop_propagation();
+                canvas.update(cx, |canvas, cx| {
+                    let before = canvas.snapshot();
+                    canvas.set_visible(&[shape_id], !visible, cx);
+                    canvas.push_undo(before);
+                });
+            }
+        });
+        let locked = self.locked;
+        let lock_toggle = toggle("locked", if locked { "⊠" } else { "□" }, locked).on_click({
+            let canvas = canvas.clone();
+            move |_, _window, cx| {
+                cx.stop_propagation();
+                canvas.update(cx, |canvas, cx| {
+                    let before = canvas.snapshot();
+                    canvas.set_locked(&[shape_id], !locked, cx);
+                    canvas.push_undo(before);
+                });
+            }
+        });
+        let absolute_toggle = self.absolute.map(|absolute| {
+            let canvas = canvas.clone();
+            toggle("absolute", "⌖", absolute).on_click(move |_, _window, cx| {
+                cx.stop_propagation();
+                canvas.update(cx, |canvas, cx| {
+                    let before = canvas.snapshot();
+                    canvas.set_absolute(shape_id, !absolute);
+                    canvas.push_undo(before);
+                    cx.emit(CanvasEvent::ContentChanged);
+                    cx.notify();
+                });
+            })
+        });
+
+        let disclosure = self.expanded.map(|expanded| {
+            let list = list.clone();
+            div()
+                .id(SharedString::from(format!("{}-disclosure", self.id)))
+                .text_xs()
+                .child(if expanded { "▾" } else { "▸" })
+                .on_click(move |_, _window, cx| {
+                    cx.stop_propagation();
+                    list.update(cx, |list, cx| list.toggle_collapsed(shape_id, cx));
+                })
+        });
+        let selection_color = self.theme.selection;
+        // Drawn over the row rather than as a border, so rows don't shift
+        // under the cursor as the target changes
+        let drop_line = |place: DropPlace| {
+            div()
+                .absolute()
+                .left_0()
+                .right_0()
+                .h(px(2.0))
+                .bg(selection_color)
+                .map(|d| if place == DropPlace::Above { d.top_0() } else { d.bottom_0() })
+        };
 
         div()
             .id(self.id.clone())
+            .relative()
             .w_full()
             .flex()
             .flex_row()
             .items_center()
             .gap(px(6.0))
-            .px(px(8.0))
+            .pl(px(8.0 + self.depth as f32 * INDENT))
+            .pr(px(8.0))
             .py(px(4.0))
             .bg(bg)
             .border_l_2()
             .border_color(border_color)
             .rounded_r(px(4.0))
             .text_sm()
-            .text_color(self.theme.ui_text)
+            .text_color(if visible { self.theme.ui_text } else { muted })
             .cursor_pointer()
             .hover(move |d| d.bg(hover_bg))
-            .on_click(move |_, _window, cx| {
-                canvas.update(cx, |canvas, cx| {
-                    canvas.select(shape_id, false, cx);
-                });
+            .when(self.drop_place == Some(DropPlace::Inside), |d| {
+                d.bg(selection_color.alpha(0.2))
+            })
+            .on_click({
+                let list = list.clone();
+                move |event: &ClickEvent, window, cx| {
+                    list.update(cx, |list, cx| {
+                        list.click_layer(shape_id, event.modifiers(), event.click_count(), window, cx)
+                    });
+                }
             })
+            .on_drag(self.dragged, |dragged, _offset, _window, cx| {
+                cx.new(|_| dragged.clone())
+            })
+            .on_drag_move({
+                let list = list.clone();
+                move |event: &DragMoveEvent<DraggedLayers>, _window, cx| {
+                    list.update(cx, |list, cx| list.drag_over(shape_id, event, cx));
+                }
+            })
+            .on_drop(move |dragged: &DraggedLayers, _window, cx| {
+                list.update(cx, |list, cx| list.drop_layers(dragged, cx));
+            })
+            .capture_action(|_: &Enter, window, _| window.blur())
+            .children(self.drop_place.filter(|place| *place != DropPlace::Inside).map(drop_line))
+            .child(div().w(px(8.0)).text_color(muted).children(disclosure))
             // Fixed-width icon container
             .child(
                 div()
@@ -136,5 +520,14 @@ impl IntoElement for LayerItem {
                     .child(self.icon),
             )
             .child(self.name)
+            .child(
+                div()
+                    .ml_auto()
+                    .flex()
+                    .flex_row()
+                    .children(absolute_toggle)
+                    .child(lock_toggle)
+                    .child(visibility_toggle),
+            )
     }
 }
@@ -1,10 +1,16 @@
 //! Simplified UI components for Luna.
 
 mod components;
+mod fill_list;
+mod gradient_editor;
 pub mod input;
 mod layer_list;
+mod page_list;
 mod properties;
+mod stroke_list;
+mod text_editor;
 mod tool_rail;
+mod zoom_control;
 
 pub use components::{button, h_stack, icon_button, panel, v_stack};
 pub use input::{
@@ -12,5 +18,8 @@ pub use input::{
     InputState, InputStateEvent, TextDirection, INPUT_CONTEXT,
 };
 pub use layer_list::LayerList;
+pub use page_list::PageList;
 pub use properties::PropertiesPanel;
+pub use text_editor::TextEditor;
 pub use tool_rail::ToolRail;
+pub use zoom_control::ZoomControl;
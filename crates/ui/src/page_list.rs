@@ -0,0 +1,173 @@
+//! Page list for switching between and managing the document's pages.
+
+use crate::components::{icon_button, panel, v_stack};
+use crate::input::{bindings::Enter, input, InputColors, InputState, InputStateEvent};
+use canvas::{Canvas, CanvasEvent};
+use gpui::{
+    div, prelude::FluentBuilder, px, AppContext, ClickEvent, Context, Entity, Focusable,
+    InteractiveElement, IntoElement, ParentElement, Render, SharedString,
+    StatefulInteractiveElement, Styled, Subscription, Window,
+};
+use theme::Theme;
+
+/// Page list panel. Click a page to open it, double-click to rename it.
+pub struct PageList {
+    canvas: Entity<Canvas>,
+    theme: Theme,
+    /// Shared by whichever page is being renamed.
+    name_input: Entity<InputState>,
+    /// The page being renamed and its name before the rename started.
+    renaming: Option<(usize, String)>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl PageList {
+    pub fn new(canvas: Entity<Canvas>, theme: Theme, cx: &mut Context<Self>) -> Self {
+        let name_input = cx.new(InputState::new_singleline);
+        let input_sub = cx.subscribe(&name_input, Self::on_input_event);
+        let canvas_sub = cx.subscribe(&canvas, Self::on_canvas_event);
+
+        Self {
+            canvas,
+            theme,
+            name_input,
+            renaming: None,
+            _subscriptions: vec![input_sub, canvas_sub],
+        }
+    }
+
+    /// Edit the active page's name in place.
+    pub fn rename_active_page(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let active = self.canvas.read(cx).pages().active();
+        self.start_rename(active, window, cx);
+    }
+
+    fn start_rename(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(name) = self.canvas.read(cx).pages().name(index).map(str::to_string) else {
+            return;
+        };
+        self.name_input.update(cx, |input, cx| {
+            let len = name.len();
+            input.set_content(name.clone(), cx);
+            input.set_selected_range(0..len);
+        });
+        self.renaming = Some((index, name));
+        window.focus(&self.name_input.focus_handle(cx));
+        cx.notify();
+    }
+
+    fn on_input_event(
+        &mut self,
+        input: Entity<InputState>,
+        event: &InputStateEvent,
+        cx: &mut Context<Self>,
+    ) {
+        let Some((index, original)) = self.renaming.clone() else {
+            return;
+        };
+        match event {
+            // Renamed as typed, so the change shows everywhere the name does
+            InputStateEvent::TextChanged => {
+                let name = input.read(cx).content().trim().to_string();
+                let name = if name.is_empty() { original } else { name };
+                self.canvas.update(cx, |canvas, cx| {
+                    canvas.rename_page(index, name, cx);
+                });
+            }
+            InputStateEvent::Blur => {
+                self.renaming = None;
+                cx.notify();
+            }
+            _ => {}
+        }
+    }
+
+    fn on_canvas_event(&mut self, _canvas: Entity<Canvas>, event: &CanvasEvent, cx: &mut Context<Self>) {
+        // Typing renames the page as it goes, so if the page being renamed
+        // no longer has either name, it was moved or deleted from under the
+        // rename and the index now points at another page
+        if matches!(event, CanvasEvent::PagesChanged) {
+            if let Some((index, original)) = &self.renaming {
+                let pages = self.canvas.read(cx).pages();
+                let name = self.name_input.read(cx).content().trim();
+                if pages.name(*index) != Some(name) && pages.name(*index) != Some(original) {
+                    self.renaming = None;
+                }
+            }
+        }
+        cx.notify();
+    }
+
+    fn add_page(&mut self, _: &ClickEvent, _window: &mut Window, cx: &mut Context<Self>) {
+        self.canvas.update(cx, |canvas, cx| {
+            canvas.add_page(None, cx);
+        });
+    }
+}
+
+impl Render for PageList {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let canvas = self.canvas.read(cx);
+        let pages = canvas.pages();
+        let theme = &self.theme;
+        let renaming = self.renaming.as_ref().map(|(index, _)| *index);
+
+        let items: Vec<_> = pages
+            .names()
+            .enumerate()
+            .map(|(index, name)| {
+                let is_active = index == pages.active();
+                let item = div()
+                    .id(SharedString::from(format!("page-{}", index)))
+                    .w_full()
+                    .px(px(8.0))
+                    .py(px(4.0))
+                    .rounded(px(4.0))
+                    .text_sm()
+                    .text_color(if is_active { theme.ui_text } else { theme.ui_text_muted })
+                    .when(is_active, |d| d.bg(theme.selection.alpha(0.2)));
+
+                if renaming == Some(index) {
+                    return item
+                        .capture_action(cx.listener(|_, _: &Enter, window, _| window.blur()))
+                        .child(input(&self.name_input, cx).colors(InputColors {
+                            selection: theme.selection.alpha(0.3),
+                            cursor: theme.ui_text,
+                            placeholder: theme.ui_text_muted,
+                        }));
+                }
+
+                let hover_bg = theme.hover;
+                item.cursor_pointer()
+                    .hover(move |d| d.bg(hover_bg))
+                    .on_click(cx.listener(move |this, event: &ClickEvent, window, cx| {
+                        if event.click_count() > 1 {
+                            this.start_rename(index, window, cx);
+                        } else {
+                            this.canvas.update(cx, |canvas, cx| {
+                                canvas.switch_page(index, cx);
+                            });
+                        }
+                    }))
+                    .child(SharedString::from(name.to_string()))
+            })
+            .collect();
+
+        panel(theme)
+            .w(px(200.0))
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .justify_between()
+                    .pb(px(8.0))
+                    .child(div().text_xs().text_color(theme.ui_text_muted).child("Pages"))
+                    .child(
+                        icon_button("add-page", "+", theme)
+                            .on_click(cx.listener(Self::add_page)),
+                    ),
+            )
+            .child(v_stack().gap(px(2.0)).children(items))
+    }
+}
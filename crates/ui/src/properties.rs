@@ -1,15 +1,19 @@
 //! Properties panel for selected shapes.
 
-use crate::components::{h_stack, panel, v_stack};
+use crate::components::{h_stack, icon_button, panel, v_stack};
+use crate::fill_list::FillList;
+use crate::gradient_editor::{parse_percent, GradientEditor};
 use crate::input::{input, InputColors, InputState, InputStateEvent};
+use crate::stroke_list::StrokeList;
 use canvas::{Canvas, CanvasEvent};
 use gpui::{
-    div, px, AppContext, Context, Entity, Focusable, Hsla, InteractiveElement, IntoElement,
+    div, px, AppContext, Axis, Context, Entity, Focusable, Hsla, InteractiveElement, IntoElement,
     ParentElement, Render, StatefulInteractiveElement, Styled, Subscription, Window,
 };
 use node::{
-    CanvasPoint, CanvasSize, CrossAxisAlignment, Fill, FrameLayout, LayoutDirection,
-    MainAxisAlignment, Padding, ShapeId, ShapeKind, Stroke,
+    Alignment, BlendMode, CanvasPoint, DistributeAxis, DEFAULT_TIDY_SPACING, CanvasSize, ChildLayout, Constraint, CrossAxisAlignment, Fill, FrameLayout, Gradient, GradientKind, LayoutDirection,
+    LineAlignment, MainAxisAlignment, Padding, Paint, ShapeId, ShapeKind, SizingMode, Text,
+    TextAlign, TextSizing,
 };
 use theme::Theme;
 
@@ -23,24 +27,36 @@ pub struct PropertiesPanel {
     // Input states for size
     w_input: Entity<InputState>,
     h_input: Entity<InputState>,
-    // Input states for fill
-    fill_color_input: Entity<InputState>,
-    // Input states for stroke
-    stroke_width_input: Entity<InputState>,
-    stroke_color_input: Entity<InputState>,
+    fill_list: Entity<FillList>,
+    gradient_editor: Entity<GradientEditor>,
+    stroke_list: Entity<StrokeList>,
     // Input state for corner radius
     corner_radius_input: Entity<InputState>,
+    opacity_input: Entity<InputState>,
     // Layout inputs (for frames)
     layout_gap_input: Entity<InputState>,
     layout_padding_input: Entity<InputState>,
+    layout_line_gap_input: Entity<InputState>,
+    // Size limit inputs (for shapes in or with a layout)
+    min_w_input: Entity<InputState>,
+    max_w_input: Entity<InputState>,
+    min_h_input: Entity<InputState>,
+    max_h_input: Entity<InputState>,
+    // Typography inputs (for text)
+    font_family_input: Entity<InputState>,
+    font_size_input: Entity<InputState>,
+    font_weight_input: Entity<InputState>,
+    line_height_input: Entity<InputState>,
+    letter_spacing_input: Entity<InputState>,
     // Track current selection and values to know when to update inputs
     last_selection_id: Option<ShapeId>,
     last_position: CanvasPoint,
     last_size: CanvasSize,
-    last_fill: Option<Fill>,
-    last_stroke: Option<Stroke>,
     last_corner_radius: f32,
+    last_opacity: f32,
     last_layout: Option<FrameLayout>,
+    last_child_layout: ChildLayout,
+    last_text: Option<Text>,
     // Track computed vs user values for display styling
     position_is_computed: bool,
     size_is_computed: (bool, bool), // (width_computed, height_computed)
@@ -56,28 +72,62 @@ impl PropertiesPanel {
         let y_input = cx.new(|cx| InputState::new_singleline(cx));
         let w_input = cx.new(|cx| InputState::new_singleline(cx));
         let h_input = cx.new(|cx| InputState::new_singleline(cx));
-        let fill_color_input = cx.new(|cx| InputState::new_singleline(cx));
-        let stroke_width_input = cx.new(|cx| InputState::new_singleline(cx));
-        let stroke_color_input = cx.new(|cx| InputState::new_singleline(cx));
         let corner_radius_input = cx.new(|cx| InputState::new_singleline(cx));
+        let opacity_input = cx.new(InputState::new_singleline);
         let layout_gap_input = cx.new(|cx| InputState::new_singleline(cx));
         let layout_padding_input = cx.new(|cx| InputState::new_singleline(cx));
+        let layout_line_gap_input = cx.new(InputState::new_singleline);
+        let min_w_input = cx.new(InputState::new_singleline);
+        let max_w_input = cx.new(InputState::new_singleline);
+        let min_h_input = cx.new(InputState::new_singleline);
+        let max_h_input = cx.new(InputState::new_singleline);
+        let font_family_input = cx.new(InputState::new_singleline);
+        let font_size_input = cx.new(InputState::new_singleline);
+        let font_weight_input = cx.new(InputState::new_singleline);
+        let line_height_input = cx.new(InputState::new_singleline);
+        let letter_spacing_input = cx.new(InputState::new_singleline);
 
         // Subscribe to input changes
         let x_sub = cx.subscribe(&x_input, Self::on_x_changed);
         let y_sub = cx.subscribe(&y_input, Self::on_y_changed);
         let w_sub = cx.subscribe(&w_input, Self::on_w_changed);
         let h_sub = cx.subscribe(&h_input, Self::on_h_changed);
-        let fill_color_sub = cx.subscribe(&fill_color_input, Self::on_fill_color_changed);
-        let stroke_width_sub = cx.subscribe(&stroke_width_input, Self::on_stroke_width_changed);
-        let stroke_color_sub = cx.subscribe(&stroke_color_input, Self::on_stroke_color_changed);
         let corner_radius_sub =
             cx.subscribe(&corner_radius_input, Self::on_corner_radius_changed);
+        let opacity_sub = cx.subscribe(&opacity_input, Self::on_opacity_changed);
         let layout_gap_sub = cx.subscribe(&layout_gap_input, Self::on_layout_gap_changed);
         let layout_padding_sub = cx.subscribe(&layout_padding_input, Self::on_layout_padding_changed);
+        let layout_line_gap_sub =
+            cx.subscribe(&layout_line_gap_input, Self::on_layout_line_gap_changed);
+        let limit_subs = [
+            (&min_w_input, SizeLimit::MinWidth),
+            (&max_w_input, SizeLimit::MaxWidth),
+            (&min_h_input, SizeLimit::MinHeight),
+            (&max_h_input, SizeLimit::MaxHeight),
+        ]
+        .map(|(input, limit)| {
+            cx.subscribe(input, move |this, input, event, cx| {
+                this.on_size_limit_changed(limit, input, event, cx)
+            })
+        });
+        let text_subs = [
+            (&font_family_input, TextField::FontFamily),
+            (&font_size_input, TextField::FontSize),
+            (&font_weight_input, TextField::FontWeight),
+            (&line_height_input, TextField::LineHeight),
+            (&letter_spacing_input, TextField::LetterSpacing),
+        ]
+        .map(|(input, field)| {
+            cx.subscribe(input, move |this, input, event, cx| {
+                this.on_text_field_changed(field, input, event, cx)
+            })
+        });
 
         // Subscribe to canvas changes to update inputs
         let canvas_sub = cx.subscribe(&canvas, Self::on_canvas_changed);
+        let fill_list = cx.new(|cx| FillList::new(canvas.clone(), theme.clone(), cx));
+        let gradient_editor = cx.new(|cx| GradientEditor::new(canvas.clone(), theme.clone(), cx));
+        let stroke_list = cx.new(|cx| StrokeList::new(canvas.clone(), theme.clone(), cx));
 
         Self {
             canvas,
@@ -86,19 +136,31 @@ impl PropertiesPanel {
             y_input,
             w_input,
             h_input,
-            fill_color_input,
-            stroke_width_input,
-            stroke_color_input,
+            fill_list,
+            gradient_editor,
+            stroke_list,
             corner_radius_input,
+            opacity_input,
             layout_gap_input,
             layout_padding_input,
+            layout_line_gap_input,
+            min_w_input,
+            max_w_input,
+            min_h_input,
+            max_h_input,
+            font_family_input,
+            font_size_input,
+            font_weight_input,
+            line_height_input,
+            letter_spacing_input,
             last_selection_id: None,
             last_position: CanvasPoint::default(),
             last_size: CanvasSize::default(),
-            last_fill: None,
-            last_stroke: None,
             last_corner_radius: 0.0,
+            last_opacity: 1.0,
             last_layout: None,
+            last_child_layout: ChildLayout::default(),
+            last_text: None,
             position_is_computed: false,
             size_is_computed: (false, false),
             user_position: CanvasPoint::default(),
@@ -108,14 +170,17 @@ impl PropertiesPanel {
                 y_sub,
                 w_sub,
                 h_sub,
-                fill_color_sub,
-                stroke_width_sub,
-                stroke_color_sub,
                 corner_radius_sub,
+                opacity_sub,
                 layout_gap_sub,
                 layout_padding_sub,
+                layout_line_gap_sub,
                 canvas_sub,
-            ],
+            ]
+            .into_iter()
+            .chain(limit_subs)
+            .chain(text_subs)
+            .collect(),
         }
     }
 
@@ -142,10 +207,11 @@ impl PropertiesPanel {
                         shape.id,
                         shape.effective_position(), // Use computed position if available
                         shape.effective_size(),     // Use computed size if available
-                        shape.fill.clone(),
-                        shape.stroke.clone(),
                         shape.corner_radius,
+                        shape.opacity,
                         shape.layout.clone(),
+                        shape.child_layout.clone(),
+                        shape.text.clone(),
                         // Track computed state
                         shape.has_computed_position(),
                         shape.computed_size.is_some(),
@@ -156,7 +222,7 @@ impl PropertiesPanel {
                 })
         };
 
-        if let Some((shape_id, position, size, fill, stroke, corner_radius, layout, pos_computed, size_computed, user_pos, user_sz)) = shape_data {
+        if let Some((shape_id, position, size, corner_radius, opacity, layout, child_layout, text, pos_computed, size_computed, user_pos, user_sz)) = shape_data {
             // Update computed state tracking
             self.position_is_computed = pos_computed;
             self.size_is_computed = (size_computed, size_computed);
@@ -165,19 +231,21 @@ impl PropertiesPanel {
             let selection_changed = self.last_selection_id != Some(shape_id);
             let position_changed = self.last_position != position;
             let size_changed = self.last_size != size;
-            let fill_changed = self.last_fill != fill;
-            let stroke_changed = self.last_stroke != stroke;
             let corner_radius_changed = self.last_corner_radius != corner_radius;
+            let opacity_changed = self.last_opacity != opacity;
             let layout_changed = self.last_layout != layout;
+            let child_layout_changed = self.last_child_layout != child_layout;
+            let text_changed = self.last_text != text;
 
             // Update tracking
             self.last_selection_id = Some(shape_id);
             self.last_position = position;
             self.last_size = size;
-            self.last_fill = fill.clone();
-            self.last_stroke = stroke.clone();
             self.last_corner_radius = corner_radius;
+            self.last_opacity = opacity;
             self.last_layout = layout.clone();
+            self.last_child_layout = child_layout.clone();
+            self.last_text = text.clone();
 
             // Update inputs if values changed, but only if not focused (avoid fighting with user)
             if selection_changed || position_changed {
@@ -206,33 +274,6 @@ impl PropertiesPanel {
                 }
             }
 
-            if selection_changed || fill_changed {
-                if !self.fill_color_input.focus_handle(cx).is_focused(window) {
-                    let content = fill
-                        .as_ref()
-                        .map(|f| hsla_to_hex(f.color))
-                        .unwrap_or_default();
-                    self.fill_color_input.update(cx, |input, cx| {
-                        input.set_content(content, cx);
-                    });
-                }
-            }
-
-            if selection_changed || stroke_changed {
-                if let Some(s) = &stroke {
-                    if !self.stroke_width_input.focus_handle(cx).is_focused(window) {
-                        self.stroke_width_input.update(cx, |input, cx| {
-                            input.set_content(format!("{:.0}", s.width), cx);
-                        });
-                    }
-                    if !self.stroke_color_input.focus_handle(cx).is_focused(window) {
-                        self.stroke_color_input.update(cx, |input, cx| {
-                            input.set_content(hsla_to_hex(s.color), cx);
-                        });
-                    }
-                }
-            }
-
             if selection_changed || corner_radius_changed {
                 if !self.corner_radius_input.focus_handle(cx).is_focused(window) {
                     self.corner_radius_input.update(cx, |input, cx| {
@@ -241,6 +282,14 @@ impl PropertiesPanel {
                 }
             }
 
+            if (selection_changed || opacity_changed)
+                && !self.opacity_input.focus_handle(cx).is_focused(window)
+            {
+                self.opacity_input.update(cx, |input, cx| {
+                    input.set_content(format!("{:.0}%", opacity * 100.0), cx);
+                });
+            }
+
             // Sync layout inputs
             if selection_changed || layout_changed {
                 if let Some(ref l) = layout {
@@ -249,6 +298,11 @@ impl PropertiesPanel {
                             input.set_content(format!("{:.0}", l.gap), cx);
                         });
                     }
+                    if !self.layout_line_gap_input.focus_handle(cx).is_focused(window) {
+                        self.layout_line_gap_input.update(cx, |input, cx| {
+                            input.set_content(format!("{:.0}", l.cross_gap), cx);
+                        });
+                    }
                     if !self.layout_padding_input.focus_handle(cx).is_focused(window) {
                         // Show uniform padding if all sides are equal, otherwise show "mixed"
                         let p = &l.padding;
@@ -274,16 +328,51 @@ impl PropertiesPanel {
                             input.set_content("0".to_string(), cx);
                         });
                     }
+                    if !self.layout_line_gap_input.focus_handle(cx).is_focused(window) {
+                        self.layout_line_gap_input.update(cx, |input, cx| {
+                            input.set_content("0".to_string(), cx);
+                        });
+                    }
+                }
+            }
+
+            // Sync size limits; an empty field means no limit
+            if selection_changed || child_layout_changed {
+                for limit in SizeLimit::ALL {
+                    let input = self.size_limit_input(limit).clone();
+                    if !input.focus_handle(cx).is_focused(window) {
+                        let content = limit
+                            .get(&child_layout)
+                            .map(|v| format!("{:.0}", v))
+                            .unwrap_or_default();
+                        input.update(cx, |input, cx| {
+                            input.set_content(content, cx);
+                        });
+                    }
+                }
+            }
+
+            if selection_changed || text_changed {
+                if let Some(text) = &text {
+                    for field in TextField::ALL {
+                        let input = self.text_field_input(field).clone();
+                        if !input.focus_handle(cx).is_focused(window) {
+                            input.update(cx, |input, cx| {
+                                input.set_content(field.format(text), cx);
+                            });
+                        }
+                    }
                 }
             }
         } else {
             self.last_selection_id = None;
             self.last_position = CanvasPoint::default();
             self.last_size = CanvasSize::default();
-            self.last_fill = None;
-            self.last_stroke = None;
             self.last_corner_radius = 0.0;
+            self.last_opacity = 1.0;
             self.last_layout = None;
+            self.last_child_layout = ChildLayout::default();
+            self.last_text = None;
             self.position_is_computed = false;
             self.size_is_computed = (false, false);
             self.user_position = CanvasPoint::default();
@@ -291,58 +380,69 @@ impl PropertiesPanel {
         }
     }
 
-    fn on_x_changed(
+    /// Apply a field edit, recording it in undo history if the user typed it.
+    ///
+    /// `set_content` during sync also emits `TextChanged`, and syncs round to
+    /// whole pixels, so without the focus check selecting a shape could push
+    /// an undo step of its own.
+    fn apply_field_edit(
         &mut self,
-        _input: Entity<InputState>,
-        event: &InputStateEvent,
+        input: &Entity<InputState>,
+        key: &'static str,
+        apply: impl FnOnce(&mut Self, &mut Context<Self>),
         cx: &mut Context<Self>,
     ) {
-        if matches!(event, InputStateEvent::TextChanged) {
-            self.apply_position_x(cx);
+        if !input.read(cx).was_focused() {
+            apply(self, cx);
+            return;
         }
+        let before = self.canvas.read(cx).snapshot();
+        apply(self, cx);
+        self.canvas
+            .update(cx, |canvas, _| canvas.push_undo_coalesced(before, key));
     }
 
-    fn on_y_changed(
+    fn on_x_changed(
         &mut self,
-        _input: Entity<InputState>,
+        input: Entity<InputState>,
         event: &InputStateEvent,
         cx: &mut Context<Self>,
     ) {
         if matches!(event, InputStateEvent::TextChanged) {
-            self.apply_position_y(cx);
+            self.apply_field_edit(&input, "x", Self::apply_position_x, cx);
         }
     }
 
-    fn on_w_changed(
+    fn on_y_changed(
         &mut self,
-        _input: Entity<InputState>,
+        input: Entity<InputState>,
         event: &InputStateEvent,
         cx: &mut Context<Self>,
     ) {
         if matches!(event, InputStateEvent::TextChanged) {
-            self.apply_size_w(cx);
+            self.apply_field_edit(&input, "y", Self::apply_position_y, cx);
         }
     }
 
-    fn on_h_changed(
+    fn on_w_changed(
         &mut self,
-        _input: Entity<InputState>,
+        input: Entity<InputState>,
         event: &InputStateEvent,
         cx: &mut Context<Self>,
     ) {
         if matches!(event, InputStateEvent::TextChanged) {
-            self.apply_size_h(cx);
+            self.apply_field_edit(&input, "w", Self::apply_size_w, cx);
         }
     }
 
-    fn on_fill_color_changed(
+    fn on_h_changed(
         &mut self,
-        _input: Entity<InputState>,
+        input: Entity<InputState>,
         event: &InputStateEvent,
         cx: &mut Context<Self>,
     ) {
         if matches!(event, InputStateEvent::TextChanged) {
-            self.apply_fill_color(cx);
+            self.apply_field_edit(&input, "h", Self::apply_size_h, cx);
         }
     }
 
@@ -417,7 +517,7 @@ impl PropertiesPanel {
                 let parent_frame_id = self.canvas.update(cx, |canvas, cx| {
                     let shape_id = canvas.selection.iter().next().copied();
                     if let Some(shape) = shape_id.and_then(|id| canvas.get_shape_mut(id)) {
-                        shape.size.0.x = w;
+                        shape.resize(CanvasSize::new(w, shape.size.height()));
                         cx.emit(CanvasEvent::ContentChanged);
                         cx.notify();
                         // Check if shape is in a layout frame
@@ -451,7 +551,7 @@ impl PropertiesPanel {
                 let parent_frame_id = self.canvas.update(cx, |canvas, cx| {
                     let shape_id = canvas.selection.iter().next().copied();
                     if let Some(shape) = shape_id.and_then(|id| canvas.get_shape_mut(id)) {
-                        shape.size.0.y = h;
+                        shape.resize(CanvasSize::new(shape.size.width(), h));
                         cx.emit(CanvasEvent::ContentChanged);
                         cx.notify();
                         // Check if shape is in a layout frame
@@ -478,135 +578,77 @@ impl PropertiesPanel {
         }
     }
 
-    fn apply_fill_color(&mut self, cx: &mut Context<Self>) {
-        let value = self.fill_color_input.read(cx).content().to_string();
-        if value.is_empty() {
-            // Empty value removes fill
-            self.canvas.update(cx, |canvas, cx| {
-                if let Some(shape) = canvas
-                    .shapes
-                    .iter_mut()
-                    .find(|s| canvas.selection.contains(&s.id))
-                {
-                    shape.fill = None;
-                    cx.emit(CanvasEvent::ContentChanged);
-                    cx.notify();
-                }
-            });
-        } else if let Some(color) = hex_to_hsla(&value) {
-            self.canvas.update(cx, |canvas, cx| {
-                if let Some(shape) = canvas
-                    .shapes
-                    .iter_mut()
-                    .find(|s| canvas.selection.contains(&s.id))
-                {
-                    shape.fill = Some(Fill::new(color));
-                    cx.emit(CanvasEvent::ContentChanged);
-                    cx.notify();
-                }
-            });
-        }
-    }
-
-    fn on_stroke_width_changed(
+    fn on_corner_radius_changed(
         &mut self,
-        _input: Entity<InputState>,
+        input: Entity<InputState>,
         event: &InputStateEvent,
         cx: &mut Context<Self>,
     ) {
         if matches!(event, InputStateEvent::TextChanged) {
-            self.apply_stroke_width(cx);
+            self.apply_field_edit(&input, "corner_radius", Self::apply_corner_radius, cx);
         }
     }
 
-    fn on_stroke_color_changed(
+    fn on_opacity_changed(
         &mut self,
-        _input: Entity<InputState>,
+        input: Entity<InputState>,
         event: &InputStateEvent,
         cx: &mut Context<Self>,
     ) {
-        if matches!(event, InputStateEvent::TextChanged) {
-            self.apply_stroke_color(cx);
-        }
-    }
-
-    fn apply_stroke_width(&mut self, cx: &mut Context<Self>) {
-        let value = self.stroke_width_input.read(cx).content().to_string();
-        let default_color = self.theme.default_stroke;
-        if let Ok(width) = value.parse::<f32>() {
-            if width >= 0.0 {
-                self.canvas.update(cx, |canvas, cx| {
-                    if let Some(shape) = canvas
-                        .shapes
-                        .iter_mut()
-                        .find(|s| canvas.selection.contains(&s.id))
-                    {
-                        if let Some(stroke) = &mut shape.stroke {
-                            stroke.width = width;
-                        } else {
-                            // Create stroke with default color if none exists
-                            shape.stroke = Some(Stroke::new(default_color, width));
-                        }
-                        cx.emit(CanvasEvent::ContentChanged);
-                        cx.notify();
-                    }
-                });
-            }
+        // Syncing the field to the selection rounds to whole percents, so
+        // only typed values are applied
+        if matches!(event, InputStateEvent::TextChanged) && input.read(cx).was_focused() {
+            self.apply_field_edit(&input, "opacity", Self::apply_opacity, cx);
         }
     }
 
-    fn apply_stroke_color(&mut self, cx: &mut Context<Self>) {
-        let value = self.stroke_color_input.read(cx).content().to_string();
-        if let Some(color) = hex_to_hsla(&value) {
-            self.canvas.update(cx, |canvas, cx| {
-                if let Some(shape) = canvas
-                    .shapes
-                    .iter_mut()
-                    .find(|s| canvas.selection.contains(&s.id))
-                {
-                    if let Some(stroke) = &mut shape.stroke {
-                        stroke.color = color;
-                    } else {
-                        // Create stroke with default width if none exists
-                        shape.stroke = Some(Stroke::new(color, 2.0));
-                    }
-                    cx.emit(CanvasEvent::ContentChanged);
-                    cx.notify();
-                }
-            });
+    fn on_layout_gap_changed(
+        &mut self,
+        input: Entity<InputState>,
+        event: &InputStateEvent,
+        cx: &mut Context<Self>,
+    ) {
+        if matches!(event, InputStateEvent::TextChanged) {
+            self.apply_field_edit(&input, "layout_gap", Self::apply_layout_gap, cx);
         }
     }
 
-    fn on_corner_radius_changed(
+    fn on_layout_padding_changed(
         &mut self,
-        _input: Entity<InputState>,
+        input: Entity<InputState>,
         event: &InputStateEvent,
         cx: &mut Context<Self>,
     ) {
         if matches!(event, InputStateEvent::TextChanged) {
-            self.apply_corner_radius(cx);
+            self.apply_field_edit(&input, "layout_padding", Self::apply_layout_padding, cx);
         }
     }
 
-    fn on_layout_gap_changed(
+    fn on_layout_line_gap_changed(
         &mut self,
-        _input: Entity<InputState>,
+        input: Entity<InputState>,
         event: &InputStateEvent,
         cx: &mut Context<Self>,
     ) {
         if matches!(event, InputStateEvent::TextChanged) {
-            self.apply_layout_gap(cx);
+            self.apply_field_edit(&input, "layout_line_gap", Self::apply_layout_line_gap, cx);
         }
     }
 
-    fn on_layout_padding_changed(
+    fn on_size_limit_changed(
         &mut self,
-        _input: Entity<InputState>,
+        limit: SizeLimit,
+        input: Entity<InputState>,
         event: &InputStateEvent,
         cx: &mut Context<Self>,
     ) {
         if matches!(event, InputStateEvent::TextChanged) {
-            self.apply_layout_padding(cx);
+            self.apply_field_edit(
+                &input,
+                limit.key(),
+                |panel, cx| panel.apply_size_limit(limit, cx),
+                cx,
+            );
         }
     }
 
@@ -631,6 +673,21 @@ impl PropertiesPanel {
         }
     }
 
+    /// Set the opacity of every selected shape, as the number keys do.
+    fn apply_opacity(&mut self, cx: &mut Context<Self>) {
+        let Some(opacity) = parse_percent(self.opacity_input.read(cx).content()) else {
+            return;
+        };
+        self.canvas.update(cx, |canvas, cx| {
+            let selection = &canvas.selection;
+            for shape in canvas.shapes.iter_mut().filter(|s| selection.contains(&s.id)) {
+                shape.opacity = opacity.clamp(0.0, 1.0);
+            }
+            cx.emit(CanvasEvent::ContentChanged);
+            cx.notify();
+        });
+    }
+
     fn apply_layout_gap(&mut self, cx: &mut Context<Self>) {
         let value = self.layout_gap_input.read(cx).content().to_string();
         if let Ok(gap) = value.parse::<f32>() {
@@ -702,8 +759,110 @@ impl PropertiesPanel {
         }
     }
 
+    fn apply_layout_line_gap(&mut self, cx: &mut Context<Self>) {
+        let value = self.layout_line_gap_input.read(cx).content().to_string();
+        let Ok(gap) = value.parse::<f32>() else {
+            return;
+        };
+        if gap < 0.0 {
+            return;
+        }
+        self.canvas.update(cx, |canvas, cx| {
+            let Some(shape_id) = canvas.selection.iter().next().copied() else {
+                return;
+            };
+            let Some(layout) = canvas.get_shape_mut(shape_id).and_then(|s| s.layout.as_mut()) else {
+                return;
+            };
+            layout.cross_gap = gap;
+            canvas.apply_layout_for_frame(shape_id);
+            cx.emit(CanvasEvent::ContentChanged);
+            cx.notify();
+        });
+    }
+
+    fn apply_size_limit(&mut self, limit: SizeLimit, cx: &mut Context<Self>) {
+        let value = self.size_limit_input(limit).read(cx).content().trim().to_string();
+        let new_limit = if value.is_empty() {
+            None
+        } else {
+            match value.parse::<f32>() {
+                Ok(v) if v >= 0.0 => Some(v),
+                _ => return,
+            }
+        };
+        self.canvas.update(cx, |canvas, cx| {
+            let Some(shape_id) = canvas.selection.iter().next().copied() else {
+                return;
+            };
+            if let Some(shape) = canvas.get_shape_mut(shape_id) {
+                *limit.get_mut(&mut shape.child_layout) = new_limit;
+            }
+            canvas.apply_layout_for_frame(shape_id);
+            cx.emit(CanvasEvent::ContentChanged);
+            cx.notify();
+        });
+    }
+
+    fn on_text_field_changed(
+        &mut self,
+        field: TextField,
+        input: Entity<InputState>,
+        event: &InputStateEvent,
+        cx: &mut Context<Self>,
+    ) {
+        if matches!(event, InputStateEvent::TextChanged) {
+            self.apply_field_edit(
+                &input,
+                field.key(),
+                |panel, cx| panel.apply_text_field(field, cx),
+                cx,
+            );
+        }
+    }
+
+    fn apply_text_field(&mut self, field: TextField, cx: &mut Context<Self>) {
+        let value = self.text_field_input(field).read(cx).content().trim().to_string();
+        self.canvas.update(cx, |canvas, cx| {
+            let Some(text) = canvas
+                .selection
+                .iter()
+                .next()
+                .copied()
+                .and_then(|id| canvas.get_shape_mut(id))
+                .and_then(|s| s.text.as_mut())
+            else {
+                return;
+            };
+            if field.apply(text, &value) {
+                cx.emit(CanvasEvent::ContentChanged);
+                cx.notify();
+            }
+        });
+    }
+
+    fn text_field_input(&self, field: TextField) -> &Entity<InputState> {
+        match field {
+            TextField::FontFamily => &self.font_family_input,
+            TextField::FontSize => &self.font_size_input,
+            TextField::FontWeight => &self.font_weight_input,
+            TextField::LineHeight => &self.line_height_input,
+            TextField::LetterSpacing => &self.letter_spacing_input,
+        }
+    }
+
+    fn size_limit_input(&self, limit: SizeLimit) -> &Entity<InputState> {
+        match limit {
+            SizeLimit::MinWidth => &self.min_w_input,
+            SizeLimit::MaxWidth => &self.max_w_input,
+            SizeLimit::MinHeight => &self.min_h_input,
+            SizeLimit::MaxHeight => &self.max_h_input,
+        }
+    }
+
     /// Toggle autolayout on/off for selected frame
     pub fn toggle_autolayout(&mut self, cx: &mut Context<Self>) {
+        let before = self.canvas.read(cx).snapshot();
         // Returns (should_apply_layout, frame_id_to_clear)
         let (apply_frame_id, clear_frame_id) = self.canvas.update(cx, |canvas, cx| {
             let shape_id = canvas.selection.iter().next().copied();
@@ -747,10 +906,12 @@ impl PropertiesPanel {
                 cx.notify();
             });
         }
+        self.canvas.update(cx, |canvas, _| canvas.push_undo(before));
     }
 
     /// Set layout direction for selected frame
     pub fn set_layout_direction(&mut self, direction: LayoutDirection, cx: &mut Context<Self>) {
+        let before = self.canvas.read(cx).snapshot();
         let frame_id = self.canvas.update(cx, |canvas, cx| {
             let shape_id = canvas.selection.iter().next().copied();
             if let Some(shape) = shape_id.and_then(|id| {
@@ -772,10 +933,12 @@ impl PropertiesPanel {
                 cx.notify();
             });
         }
+        self.canvas.update(cx, |canvas, _| canvas.push_undo(before));
     }
 
     /// Set main axis alignment for selected frame
     pub fn set_main_axis_alignment(&mut self, alignment: MainAxisAlignment, cx: &mut Context<Self>) {
+        let before = self.canvas.read(cx).snapshot();
         let frame_id = self.canvas.update(cx, |canvas, cx| {
             let shape_id = canvas.selection.iter().next().copied();
             if let Some(shape) = shape_id.and_then(|id| {
@@ -797,10 +960,12 @@ impl PropertiesPanel {
                 cx.notify();
             });
         }
+        self.canvas.update(cx, |canvas, _| canvas.push_undo(before));
     }
 
     /// Set cross axis alignment for selected frame
     pub fn set_cross_axis_alignment(&mut self, alignment: CrossAxisAlignment, cx: &mut Context<Self>) {
+        let before = self.canvas.read(cx).snapshot();
         let frame_id = self.canvas.update(cx, |canvas, cx| {
             let shape_id = canvas.selection.iter().next().copied();
             if let Some(shape) = shape_id.and_then(|id| {
@@ -822,6 +987,211 @@ impl PropertiesPanel {
                 cx.notify();
             });
         }
+        self.canvas.update(cx, |canvas, _| canvas.push_undo(before));
+    }
+
+    /// Take the selected shape out of its parent's layout flow, or put it back.
+    pub fn set_absolute(&mut self, absolute: bool, cx: &mut Context<Self>) {
+        let before = self.canvas.read(cx).snapshot();
+        self.canvas.update(cx, |canvas, cx| {
+            let Some(shape_id) = canvas.selection.iter().next().copied() else {
+                return;
+            };
+            canvas.set_absolute(shape_id, absolute);
+            canvas.push_undo(before);
+            cx.emit(CanvasEvent::ContentChanged);
+            cx.notify();
+        });
+    }
+
+    /// Line the selection up against the key shape, a lone shape's parent or
+    /// the selection's own bounds.
+    pub fn align_selection(&mut self, alignment: Alignment, cx: &mut Context<Self>) {
+        self.canvas.update(cx, |canvas, cx| {
+            let ids: Vec<_> = canvas.selection.iter().copied().collect();
+            let to = canvas.align_reference();
+            let before = canvas.snapshot();
+            canvas.align(&ids, alignment, to, cx);
+            canvas.push_undo(before);
+        });
+    }
+
+    pub fn distribute_selection(&mut self, axis: DistributeAxis, cx: &mut Context<Self>) {
+        self.canvas.update(cx, |canvas, cx| {
+            let ids: Vec<_> = canvas.selection.iter().copied().collect();
+            let before = canvas.snapshot();
+            canvas.distribute(&ids, axis, cx);
+            canvas.push_undo(before);
+        });
+    }
+
+    pub fn tidy_selection(&mut self, cx: &mut Context<Self>) {
+        self.canvas.update(cx, |canvas, cx| {
+            let ids: Vec<_> = canvas.selection.iter().copied().collect();
+            let before = canvas.snapshot();
+            canvas.tidy(&ids, DEFAULT_TIDY_SPACING, cx);
+            canvas.push_undo(before);
+        });
+    }
+
+    /// Set how the selected absolute shape follows its parent along one axis.
+    pub fn set_constraint(&mut self, axis: Axis, constraint: Constraint, cx: &mut Context<Self>) {
+        let before = self.canvas.read(cx).snapshot();
+        self.canvas.update(cx, |canvas, cx| {
+            let Some(shape) = canvas
+                .selection
+                .iter()
+                .next()
+                .copied()
+                .and_then(|id| canvas.get_shape_mut(id))
+            else {
+                return;
+            };
+            match axis {
+                Axis::Horizontal => shape.child_layout.horizontal_constraint = constraint,
+                Axis::Vertical => shape.child_layout.vertical_constraint = constraint,
+            }
+            canvas.push_undo(before);
+            cx.emit(CanvasEvent::ContentChanged);
+            cx.notify();
+        });
+    }
+
+    /// Turn line wrapping on or off for the selected frame.
+    pub fn set_layout_wrap(&mut self, wrap: bool, cx: &mut Context<Self>) {
+        self.update_selected_layout(cx, |layout| layout.wrap = wrap);
+    }
+
+    /// Set how wrapped lines are distributed in the selected frame.
+    pub fn set_line_alignment(&mut self, alignment: LineAlignment, cx: &mut Context<Self>) {
+        self.update_selected_layout(cx, |layout| layout.line_alignment = alignment);
+    }
+
+    /// Edit the selected frame's layout, re-run it and record an undo step.
+    fn update_selected_layout(&mut self, cx: &mut Context<Self>, edit: impl FnOnce(&mut FrameLayout)) {
+        let before = self.canvas.read(cx).snapshot();
+        self.canvas.update(cx, |canvas, cx| {
+            let Some(shape_id) = canvas.selection.iter().next().copied() else {
+                return;
+            };
+            let Some(layout) = canvas.get_shape_mut(shape_id).and_then(|s| s.layout.as_mut()) else {
+                return;
+            };
+            edit(layout);
+            canvas.apply_layout_for_frame(shape_id);
+            canvas.push_undo(before);
+            cx.emit(CanvasEvent::ContentChanged);
+            cx.notify();
+        });
+    }
+
+    /// Set how the selected shape is sized along one axis.
+    pub fn set_sizing_mode(&mut self, axis: Axis, mode: SizingMode, cx: &mut Context<Self>) {
+        let before = self.canvas.read(cx).snapshot();
+        self.canvas.update(cx, |canvas, cx| {
+            let Some(shape_id) = canvas.selection.iter().next().copied() else {
+                return;
+            };
+            if let Some(shape) = canvas.get_shape_mut(shape_id) {
+                match axis {
+                    Axis::Horizontal => shape.child_layout.width_mode = mode,
+                    Axis::Vertical => shape.child_layout.height_mode = mode,
+                }
+            }
+            canvas.apply_layout_for_frame(shape_id);
+            canvas.push_undo(before);
+            cx.emit(CanvasEvent::ContentChanged);
+            cx.notify();
+        });
+    }
+
+    /// Set the horizontal alignment of the selected text.
+    pub fn set_text_align(&mut self, align: TextAlign, cx: &mut Context<Self>) {
+        self.update_selected_text(cx, |text| text.align = align);
+    }
+
+    /// Paint the selected shape's top fill with one color, or with a
+    /// gradient of `kind`, adding a fill if it has none showing. A color
+    /// becomes a gradient fading it out; a gradient keeps its stops as its
+    /// kind changes, with the new kind's handles.
+    pub fn set_fill_kind(&mut self, kind: Option<GradientKind>, cx: &mut Context<Self>) {
+        let before = self.canvas.read(cx).snapshot();
+        self.canvas.update(cx, |canvas, cx| {
+            let Some(shape) = canvas.selection.iter().next().copied().and_then(|id| canvas.get_shape_mut(id))
+            else {
+                return;
+            };
+            let index = shape.top_fill().unwrap_or_else(|| {
+                shape.fills.push(Fill::new(gpui::black()));
+                shape.fills.len() - 1
+            });
+            let fill = &mut shape.fills[index];
+            let color = fill.color();
+            fill.paint = match (kind, &fill.paint) {
+                (None, _) => Paint::Solid(color),
+                (Some(kind), Paint::Gradient(gradient)) => {
+                    let handles = Gradient::new(kind, color, color);
+                    Paint::Gradient(Gradient {
+                        kind,
+                        start: handles.start,
+                        end: handles.end,
+                        ..gradient.clone()
+                    })
+                }
+                (Some(kind), Paint::Solid(_)) => {
+                    Paint::Gradient(Gradient::new(kind, color, color.opacity(0.0)))
+                }
+            };
+            canvas.push_undo(before);
+            cx.emit(CanvasEvent::ContentChanged);
+            cx.notify();
+        });
+    }
+
+    /// Step the selected shape's blend mode to the next or previous one in
+    /// [`BlendMode::ALL`], wrapping around.
+    pub fn step_blend_mode(&mut self, forward: bool, cx: &mut Context<Self>) {
+        let before = self.canvas.read(cx).snapshot();
+        self.canvas.update(cx, |canvas, cx| {
+            let Some(shape) = canvas.selection.iter().next().copied().and_then(|id| canvas.get_shape_mut(id))
+            else {
+                return;
+            };
+            let count = BlendMode::ALL.len();
+            let index = BlendMode::ALL.iter().position(|mode| *mode == shape.blend_mode).unwrap_or(0);
+            let step = if forward { 1 } else { count - 1 };
+            shape.blend_mode = BlendMode::ALL[(index + step) % count];
+            canvas.push_undo(before);
+            cx.emit(CanvasEvent::ContentChanged);
+            cx.notify();
+        });
+    }
+
+    /// Set how the selected text box's size follows its content.
+    pub fn set_text_sizing(&mut self, sizing: TextSizing, cx: &mut Context<Self>) {
+        self.update_selected_text(cx, |text| text.sizing = sizing);
+    }
+
+    /// Edit the selected text shape's typography and record an undo step.
+    /// Auto sizes are refit when the canvas next paints.
+    fn update_selected_text(&mut self, cx: &mut Context<Self>, edit: impl FnOnce(&mut Text)) {
+        let before = self.canvas.read(cx).snapshot();
+        self.canvas.update(cx, |canvas, cx| {
+            let Some(text) = canvas
+                .selection
+                .iter()
+                .next()
+                .copied()
+                .and_then(|id| canvas.get_shape_mut(id))
+                .and_then(|s| s.text.as_mut())
+            else {
+                return;
+            };
+            edit(text);
+            canvas.push_undo(before);
+            cx.emit(CanvasEvent::ContentChanged);
+            cx.notify();
+        });
     }
 
     fn input_colors(&self) -> InputColors {
@@ -849,6 +1219,7 @@ impl Render for PropertiesPanel {
             .collect();
 
         let colors = self.input_colors();
+        let align_controls = (!selected.is_empty()).then(|| align_row(&cx.entity(), theme));
 
         let content = if selected.is_empty() {
             div()
@@ -861,6 +1232,8 @@ impl Render for PropertiesPanel {
                 ShapeKind::Rectangle => "Rectangle",
                 ShapeKind::Ellipse => "Ellipse",
                 ShapeKind::Frame => "Frame",
+                ShapeKind::Text => "Text",
+                ShapeKind::Path => "Path",
             };
 
             v_stack()
@@ -938,6 +1311,118 @@ impl Render for PropertiesPanel {
                                 )),
                         ),
                 )
+                // Sizing modes (only where a layout can act on them)
+                .children({
+                    let in_layout = canvas.is_in_autolayout(shape.id);
+                    let has_layout = shape.has_layout();
+                    if in_layout || has_layout {
+                        let mut modes = vec![SizingMode::Fixed];
+                        if in_layout {
+                            modes.push(SizingMode::Fill);
+                        }
+                        if has_layout {
+                            modes.push(SizingMode::Hug);
+                        }
+                        let this = cx.entity().clone();
+                        Some(
+                            v_stack()
+                                .gap(px(4.0))
+                                .child(
+                                    div()
+                                        .text_xs()
+                                        .text_color(theme.ui_text_muted)
+                                        .child("Sizing"),
+                                )
+                                .child(sizing_row(
+                                    Axis::Horizontal,
+                                    shape.child_layout.width_mode,
+                                    &modes,
+                                    &this,
+                                    theme,
+                                ))
+                                .child(sizing_row(
+                                    Axis::Vertical,
+                                    shape.child_layout.height_mode,
+                                    &modes,
+                                    &this,
+                                    theme,
+                                ))
+                                .child(limit_row(
+                                    "Min",
+                                    &self.min_w_input,
+                                    &self.min_h_input,
+                                    theme,
+                                    &colors,
+                                    cx,
+                                ))
+                                .child(limit_row(
+                                    "Max",
+                                    &self.max_w_input,
+                                    &self.max_h_input,
+                                    theme,
+                                    &colors,
+                                    cx,
+                                )),
+                        )
+                    } else {
+                        None
+                    }
+                })
+                // Positioning (only for children of layout frames)
+                .children({
+                    let parent_has_layout = shape
+                        .parent
+                        .and_then(|parent| canvas.get_shape(parent))
+                        .is_some_and(|parent| parent.has_layout());
+                    if parent_has_layout {
+                        let absolute = shape.child_layout.absolute;
+                        let this = cx.entity().clone();
+                        Some(
+                            v_stack()
+                                .gap(px(4.0))
+                                .child(
+                                    div()
+                                        .text_xs()
+                                        .text_color(theme.ui_text_muted)
+                                        .child("Positioning"),
+                                )
+                                .child(
+                                    h_stack()
+                                        .gap(px(2.0))
+                                        .child({
+                                            let this = this.clone();
+                                            clickable_toggle("Auto", !absolute, theme, "position-auto", move |_, cx| {
+                                                this.update(cx, |panel, cx| panel.set_absolute(false, cx));
+                                            })
+                                        })
+                                        .child({
+                                            let this = this.clone();
+                                            clickable_toggle("Absolute", absolute, theme, "position-absolute", move |_, cx| {
+                                                this.update(cx, |panel, cx| panel.set_absolute(true, cx));
+                                            })
+                                        }),
+                                )
+                                .children(absolute.then(|| {
+                                    v_stack()
+                                        .gap(px(4.0))
+                                        .child(constraint_row(
+                                            Axis::Horizontal,
+                                            shape.child_layout.horizontal_constraint,
+                                            &this,
+                                            theme,
+                                        ))
+                                        .child(constraint_row(
+                                            Axis::Vertical,
+                                            shape.child_layout.vertical_constraint,
+                                            &this,
+                                            theme,
+                                        ))
+                                })),
+                        )
+                    } else {
+                        None
+                    }
+                })
                 // Corner Radius (only for rectangles)
                 .children(if shape.kind == ShapeKind::Rectangle {
                     Some(
@@ -960,67 +1445,142 @@ impl Render for PropertiesPanel {
                 } else {
                     None
                 })
-                // Fill
-                .child(
+                // Layer
+                .child({
+                    let this = cx.entity().clone();
+                    let step = |id: &'static str, label: &'static str, forward: bool| {
+                        let this = this.clone();
+                        icon_button(id, label, theme).on_click(move |_, _, cx| {
+                            this.update(cx, |panel, cx| panel.step_blend_mode(forward, cx));
+                        })
+                    };
                     v_stack()
                         .gap(px(4.0))
-                        .child(
-                            div()
-                                .text_xs()
-                                .text_color(theme.ui_text_muted)
-                                .child("Fill"),
-                        )
+                        .child(div().text_xs().text_color(theme.ui_text_muted).child("Layer"))
                         .child(
                             h_stack()
                                 .gap(px(8.0))
                                 .items_center()
-                                .child(color_swatch(
-                                    shape.fill.as_ref().map(|f| f.color),
-                                    theme,
-                                ))
-                                .child(input_field(
+                                .child(h_stack().w(px(56.0)).child(input_field(
                                     "",
-                                    &self.fill_color_input,
+                                    &self.opacity_input,
                                     theme,
                                     &colors,
                                     cx,
-                                )),
-                        ),
-                )
-                // Stroke
-                .child(
+                                )))
+                                .child(step("blend-previous", "‹", false))
+                                .child(
+                                    div()
+                                        .flex_1()
+                                        .text_xs()
+                                        .text_color(theme.ui_text)
+                                        .child(blend_mode_label(shape.blend_mode)),
+                                )
+                                .child(step("blend-next", "›", true)),
+                        )
+                })
+                // Typography (only for text)
+                .children(shape.text.as_ref().map(|text| {
+                    let this = cx.entity().clone();
                     v_stack()
                         .gap(px(4.0))
                         .child(
                             div()
                                 .text_xs()
                                 .text_color(theme.ui_text_muted)
-                                .child("Stroke"),
+                                .child("Text"),
                         )
+                        .child(input_field("", &self.font_family_input, theme, &colors, cx))
                         .child(
                             h_stack()
                                 .gap(px(8.0))
-                                .items_center()
-                                .child(color_swatch(
-                                    shape.stroke.as_ref().map(|s| s.color),
-                                    theme,
-                                ))
-                                .child(input_field(
-                                    "",
-                                    &self.stroke_color_input,
-                                    theme,
-                                    &colors,
-                                    cx,
-                                ))
-                                .child(input_field(
-                                    "W",
-                                    &self.stroke_width_input,
-                                    theme,
-                                    &colors,
-                                    cx,
-                                )),
-                        ),
-                )
+                                .child(input_field("S", &self.font_size_input, theme, &colors, cx))
+                                .child(input_field("W", &self.font_weight_input, theme, &colors, cx)),
+                        )
+                        .child(
+                            h_stack()
+                                .gap(px(8.0))
+                                .child(input_field("LH", &self.line_height_input, theme, &colors, cx))
+                                .child(input_field("LS", &self.letter_spacing_input, theme, &colors, cx)),
+                        )
+                        .child(
+                            h_stack().gap(px(2.0)).children(
+                                [
+                                    ("Left", TextAlign::Left),
+                                    ("Center", TextAlign::Center),
+                                    ("Right", TextAlign::Right),
+                                ]
+                                .map(|(name, align)| {
+                                    let this = this.clone();
+                                    clickable_toggle(
+                                        name,
+                                        text.align == align,
+                                        theme,
+                                        format!("text-align-{}", name.to_lowercase()),
+                                        move |_, cx| {
+                                            this.update(cx, |panel, cx| panel.set_text_align(align, cx));
+                                        },
+                                    )
+                                }),
+                            ),
+                        )
+                        .child(
+                            h_stack().gap(px(2.0)).children(
+                                [
+                                    ("Auto W", TextSizing::AutoWidth),
+                                    ("Auto H", TextSizing::AutoHeight),
+                                    ("Fixed", TextSizing::Fixed),
+                                ]
+                                .map(|(name, sizing)| {
+                                    let this = this.clone();
+                                    clickable_toggle(
+                                        name,
+                                        text.sizing == sizing,
+                                        theme,
+                                        format!("text-sizing-{}", name.to_lowercase().replace(' ', "-")),
+                                        move |_, cx| {
+                                            this.update(cx, |panel, cx| panel.set_text_sizing(sizing, cx));
+                                        },
+                                    )
+                                }),
+                            ),
+                        )
+                }))
+                // Fill
+                .child({
+                    let this = cx.entity().clone();
+                    let top_fill = shape.top_fill().map(|index| &shape.fills[index]);
+                    let gradient_kind = top_fill.and_then(Fill::gradient).map(|g| g.kind);
+                    v_stack()
+                        .gap(px(4.0))
+                        .child(self.fill_list.clone())
+                        .child(
+                            h_stack().gap(px(2.0)).children(
+                                [
+                                    ("Solid", None),
+                                    ("Linear", Some(GradientKind::Linear)),
+                                    ("Radial", Some(GradientKind::Radial)),
+                                    ("Angular", Some(GradientKind::Angular)),
+                                ]
+                                .map(|(name, kind)| {
+                                    let this = this.clone();
+                                    let selected = top_fill.is_some() && gradient_kind == kind;
+                                    clickable_toggle(
+                                        name,
+                                        selected,
+                                        theme,
+                                        format!("fill-{}", name.to_lowercase()),
+                                        move |_, cx| {
+                                            this.update(cx, |panel, cx| panel.set_fill_kind(kind, cx));
+                                        },
+                                    )
+                                }),
+                            ),
+                        )
+                        .children(gradient_kind.map(|_| self.gradient_editor.clone()))
+                })
+                // Stroke
+                .child(self.stroke_list.clone())
                 // Autolayout (only for frames)
                 .children(if shape.kind == ShapeKind::Frame {
                     let has_layout = shape.layout.is_some();
@@ -1144,6 +1704,105 @@ impl Render for PropertiesPanel {
                                                 )
                                                 .child(input_field("", &self.layout_padding_input, &theme_clone, &colors, cx)),
                                         )
+                                        // Wrap toggle
+                                        .child(
+                                            h_stack()
+                                                .gap(px(4.0))
+                                                .child(
+                                                    div()
+                                                        .text_xs()
+                                                        .text_color(theme_clone.ui_text_muted)
+                                                        .w(px(50.0))
+                                                        .child("Wrap"),
+                                                )
+                                                .child(
+                                                    h_stack()
+                                                        .gap(px(4.0))
+                                                        .child({
+                                                            let this = this.clone();
+                                                            clickable_toggle(
+                                                                "Off",
+                                                                !layout.wrap,
+                                                                &theme_clone,
+                                                                "wrap-off",
+                                                                move |_, cx| {
+                                                                    this.update(cx, |panel, cx| {
+                                                                        panel.set_layout_wrap(false, cx);
+                                                                    });
+                                                                },
+                                                            )
+                                                        })
+                                                        .child({
+                                                            let this = this.clone();
+                                                            clickable_toggle(
+                                                                "On",
+                                                                layout.wrap,
+                                                                &theme_clone,
+                                                                "wrap-on",
+                                                                move |_, cx| {
+                                                                    this.update(cx, |panel, cx| {
+                                                                        panel.set_layout_wrap(true, cx);
+                                                                    });
+                                                                },
+                                                            )
+                                                        }),
+                                                ),
+                                        )
+                                        // Line gap and line alignment (only when wrapping)
+                                        .children(if layout.wrap {
+                                            Some(
+                                                v_stack()
+                                                    .gap(px(8.0))
+                                                    .child(
+                                                        h_stack()
+                                                            .gap(px(4.0))
+                                                            .child(
+                                                                div()
+                                                                    .text_xs()
+                                                                    .text_color(theme_clone.ui_text_muted)
+                                                                    .w(px(50.0))
+                                                                    .child("Line Gap"),
+                                                            )
+                                                            .child(input_field("", &self.layout_line_gap_input, &theme_clone, &colors, cx)),
+                                                    )
+                                                    .child(
+                                                        v_stack()
+                                                            .gap(px(4.0))
+                                                            .child(
+                                                                div()
+                                                                    .text_xs()
+                                                                    .text_color(theme_clone.ui_text_muted)
+                                                                    .child("Lines"),
+                                                            )
+                                                            .child(
+                                                                h_stack().gap(px(2.0)).children(
+                                                                    [
+                                                                        ("Start", LineAlignment::Start),
+                                                                        ("Center", LineAlignment::Center),
+                                                                        ("End", LineAlignment::End),
+                                                                        ("Space", LineAlignment::SpaceBetween),
+                                                                    ]
+                                                                    .map(|(label, alignment)| {
+                                                                        let this = this.clone();
+                                                                        clickable_toggle(
+                                                                            label,
+                                                                            layout.line_alignment == alignment,
+                                                                            &theme_clone,
+                                                                            format!("lines-{}", label.to_lowercase()),
+                                                                            move |_, cx| {
+                                                                                this.update(cx, |panel, cx| {
+                                                                                    panel.set_line_alignment(alignment, cx);
+                                                                                });
+                                                                            },
+                                                                        )
+                                                                    }),
+                                                                ),
+                                                            ),
+                                                    ),
+                                            )
+                                        } else {
+                                            None
+                                        })
                                         // Main axis alignment
                                         .child(
                                             v_stack()
@@ -1311,11 +1970,12 @@ impl Render for PropertiesPanel {
                     .pb(px(8.0))
                     .child("Properties"),
             )
+            .children(align_controls)
             .child(content)
     }
 }
 
-fn input_field(
+pub(crate) fn input_field(
     label: &str,
     input_state: &Entity<InputState>,
     theme: &Theme,
@@ -1390,7 +2050,28 @@ fn input_field_with_computed(
     field
 }
 
-fn color_swatch(color: Option<Hsla>, theme: &Theme) -> impl IntoElement {
+fn blend_mode_label(mode: BlendMode) -> &'static str {
+    match mode {
+        BlendMode::Normal => "Normal",
+        BlendMode::Multiply => "Multiply",
+        BlendMode::Screen => "Screen",
+        BlendMode::Overlay => "Overlay",
+        BlendMode::Darken => "Darken",
+        BlendMode::Lighten => "Lighten",
+        BlendMode::ColorDodge => "Color Dodge",
+        BlendMode::ColorBurn => "Color Burn",
+        BlendMode::HardLight => "Hard Light",
+        BlendMode::SoftLight => "Soft Light",
+        BlendMode::Difference => "Difference",
+        BlendMode::Exclusion => "Exclusion",
+        BlendMode::Hue => "Hue",
+        BlendMode::Saturation => "Saturation",
+        BlendMode::Color => "Color",
+        BlendMode::Luminosity => "Luminosity",
+    }
+}
+
+pub(crate) fn color_swatch(color: Option<Hsla>, theme: &Theme) -> impl IntoElement {
     div()
         .size(px(20.0))
         .rounded(px(2.0))
@@ -1399,7 +2080,7 @@ fn color_swatch(color: Option<Hsla>, theme: &Theme) -> impl IntoElement {
         .bg(color.unwrap_or(gpui::hsla(0.0, 0.0, 0.9, 1.0)))
 }
 
-fn clickable_toggle<F>(
+pub(crate) fn clickable_toggle<F>(
     label: &str,
     selected: bool,
     theme: &Theme,
@@ -1432,15 +2113,282 @@ where
         .on_click(move |event, _, cx| on_click(event, cx))
 }
 
+/// Buttons to align, distribute and tidy the selection.
+fn align_row(panel: &Entity<PropertiesPanel>, theme: &Theme) -> impl IntoElement {
+    let alignments = [
+        (Alignment::Left, "align-left", "⇤"),
+        (Alignment::Center, "align-center", "⇹"),
+        (Alignment::Right, "align-right", "⇥"),
+        (Alignment::Top, "align-top", "⤒"),
+        (Alignment::Middle, "align-middle", "⇳"),
+        (Alignment::Bottom, "align-bottom", "⤓"),
+    ];
+    let distributions = [
+        (DistributeAxis::Horizontal, "distribute-horizontal", "⋯"),
+        (DistributeAxis::Vertical, "distribute-vertical", "⋮"),
+    ];
+
+    h_stack()
+        .flex_wrap()
+        .gap(px(2.0))
+        .pb(px(12.0))
+        .children(alignments.map(|(alignment, id, glyph)| {
+            let panel = panel.clone();
+            icon_button(id, glyph, theme).on_click(move |_, _, cx| {
+                panel.update(cx, |panel, cx| panel.align_selection(alignment, cx));
+            })
+        }))
+        .children(distributions.map(|(axis, id, glyph)| {
+            let panel = panel.clone();
+            icon_button(id, glyph, theme).on_click(move |_, _, cx| {
+                panel.update(cx, |panel, cx| panel.distribute_selection(axis, cx));
+            })
+        }))
+        .child({
+            let panel = panel.clone();
+            icon_button("tidy-up", "⊞", theme).on_click(move |_, _, cx| {
+                panel.update(cx, |panel, cx| panel.tidy_selection(cx));
+            })
+        })
+}
+
+/// A row of Fixed/Fill/Hug toggles for one axis.
+fn sizing_row(
+    axis: Axis,
+    current: SizingMode,
+    modes: &[SizingMode],
+    panel: &Entity<PropertiesPanel>,
+    theme: &Theme,
+) -> impl IntoElement {
+    let (label, id_prefix) = match axis {
+        Axis::Horizontal => ("W", "sizing-w"),
+        Axis::Vertical => ("H", "sizing-h"),
+    };
+    h_stack()
+        .gap(px(2.0))
+        .items_center()
+        .child(
+            div()
+                .text_xs()
+                .text_color(theme.ui_text_muted)
+                .w(px(14.0))
+                .child(label),
+        )
+        .children(modes.iter().map(|&mode| {
+            let name = match mode {
+                SizingMode::Fixed => "Fixed",
+                SizingMode::Fill => "Fill",
+                SizingMode::Hug => "Hug",
+            };
+            let panel = panel.clone();
+            clickable_toggle(
+                name,
+                current == mode,
+                theme,
+                format!("{}-{}", id_prefix, name.to_lowercase()),
+                move |_, cx| {
+                    panel.update(cx, |panel, cx| {
+                        panel.set_sizing_mode(axis, mode, cx);
+                    });
+                },
+            )
+        }))
+}
+
+/// A row of constraint toggles for one axis of an absolute shape.
+fn constraint_row(
+    axis: Axis,
+    current: Constraint,
+    panel: &Entity<PropertiesPanel>,
+    theme: &Theme,
+) -> impl IntoElement {
+    let (label, id_prefix, start, end) = match axis {
+        Axis::Horizontal => ("H", "constraint-h", "Left", "Right"),
+        Axis::Vertical => ("V", "constraint-v", "Top", "Bottom"),
+    };
+    h_stack()
+        .gap(px(2.0))
+        .items_center()
+        .child(
+            div()
+                .text_xs()
+                .text_color(theme.ui_text_muted)
+                .w(px(14.0))
+                .child(label),
+        )
+        .children(
+            [
+                (start, Constraint::Start),
+                ("Center", Constraint::Center),
+                (end, Constraint::End),
+                ("Stretch", Constraint::Stretch),
+            ]
+            .map(|(name, constraint)| {
+                let panel = panel.clone();
+                clickable_toggle(
+                    name,
+                    current == constraint,
+                    theme,
+                    format!("{}-{}", id_prefix, name.to_lowercase()),
+                    move |_, cx| {
+                        panel.update(cx, |panel, cx| {
+                            panel.set_constraint(axis, constraint, cx);
+                        });
+                    },
+                )
+            }),
+        )
+}
+
+/// A labelled pair of W/H inputs for min or max size limits.
+fn limit_row(
+    label: &str,
+    w_input: &Entity<InputState>,
+    h_input: &Entity<InputState>,
+    theme: &Theme,
+    colors: &InputColors,
+    cx: &gpui::App,
+) -> impl IntoElement {
+    h_stack()
+        .gap(px(4.0))
+        .items_center()
+        .child(
+            div()
+                .text_xs()
+                .text_color(theme.ui_text_muted)
+                .w(px(24.0))
+                .child(label.to_string()),
+        )
+        .child(input_field("W", w_input, theme, colors, cx))
+        .child(input_field("H", h_input, theme, colors, cx))
+}
+
+/// One of the min/max size limits on a shape's [`ChildLayout`].
+#[derive(Clone, Copy)]
+enum SizeLimit {
+    MinWidth,
+    MaxWidth,
+    MinHeight,
+    MaxHeight,
+}
+
+impl SizeLimit {
+    const ALL: [SizeLimit; 4] = [
+        SizeLimit::MinWidth,
+        SizeLimit::MaxWidth,
+        SizeLimit::MinHeight,
+        SizeLimit::MaxHeight,
+    ];
+
+    /// Undo coalescing key for edits to this limit.
+    fn key(self) -> &'static str {
+        match self {
+            SizeLimit::MinWidth => "min_width",
+            SizeLimit::MaxWidth => "max_width",
+            SizeLimit::MinHeight => "min_height",
+            SizeLimit::MaxHeight => "max_height",
+        }
+    }
+
+    fn get(self, child_layout: &ChildLayout) -> Option<f32> {
+        match self {
+            SizeLimit::MinWidth => child_layout.min_width,
+            SizeLimit::MaxWidth => child_layout.max_width,
+            SizeLimit::MinHeight => child_layout.min_height,
+            SizeLimit::MaxHeight => child_layout.max_height,
+        }
+    }
+
+    fn get_mut(self, child_layout: &mut ChildLayout) -> &mut Option<f32> {
+        match self {
+            SizeLimit::MinWidth => &mut child_layout.min_width,
+            SizeLimit::MaxWidth => &mut child_layout.max_width,
+            SizeLimit::MinHeight => &mut child_layout.min_height,
+            SizeLimit::MaxHeight => &mut child_layout.max_height,
+        }
+    }
+}
+
+/// One of the numeric or string typography fields on a [`Text`].
+#[derive(Clone, Copy)]
+enum TextField {
+    FontFamily,
+    FontSize,
+    FontWeight,
+    LineHeight,
+    LetterSpacing,
+}
+
+impl TextField {
+    const ALL: [TextField; 5] = [
+        TextField::FontFamily,
+        TextField::FontSize,
+        TextField::FontWeight,
+        TextField::LineHeight,
+        TextField::LetterSpacing,
+    ];
+
+    /// Undo coalescing key for edits to this field.
+    fn key(self) -> &'static str {
+        match self {
+            TextField::FontFamily => "font_family",
+            TextField::FontSize => "font_size",
+            TextField::FontWeight => "font_weight",
+            TextField::LineHeight => "line_height",
+            TextField::LetterSpacing => "letter_spacing",
+        }
+    }
+
+    fn format(self, text: &Text) -> String {
+        match self {
+            TextField::FontFamily => text.font_family.clone(),
+            TextField::FontSize => format!("{}", text.font_size),
+            TextField::FontWeight => text.font_weight.to_string(),
+            TextField::LineHeight => format!("{}", text.line_height),
+            TextField::LetterSpacing => format!("{}", text.letter_spacing),
+        }
+    }
+
+    /// Parse `value` into the field. Returns false, leaving `text` as it
+    /// was, for values that are partially typed or out of range.
+    fn apply(self, text: &mut Text, value: &str) -> bool {
+        match self {
+            TextField::FontFamily => {
+                if value.is_empty() {
+                    return false;
+                }
+                text.font_family = value.to_string();
+            }
+            TextField::FontSize => match value.parse::<f32>() {
+                Ok(size) if size > 0.0 => text.font_size = size,
+                _ => return false,
+            },
+            TextField::FontWeight => match value.parse::<u16>() {
+                Ok(weight) if (1..=1000).contains(&weight) => text.font_weight = weight,
+                _ => return false,
+            },
+            TextField::LineHeight => match value.parse::<f32>() {
+                Ok(height) if height > 0.0 => text.line_height = height,
+                _ => return false,
+            },
+            TextField::LetterSpacing => match value.parse::<f32>() {
+                Ok(spacing) => text.letter_spacing = spacing,
+                _ => return false,
+            },
+        }
+        true
+    }
+}
+
 /// Convert HSLA color to hex string (e.g., "#FF0000")
-fn hsla_to_hex(c: Hsla) -> String {
+pub(crate) fn hsla_to_hex(c: Hsla) -> String {
     // Convert HSL to RGB
     let (r, g, b) = hsl_to_rgb(c.h, c.s, c.l);
     format!("#{:02X}{:02X}{:02X}", r, g, b)
 }
 
 /// Convert hex string to HSLA color
-fn hex_to_hsla(hex: &str) -> Option<Hsla> {
+pub(crate) fn hex_to_hsla(hex: &str) -> Option<Hsla> {
     let hex = hex.trim_start_matches('#');
     if hex.len() != 6 {
         return None;
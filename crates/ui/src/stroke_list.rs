@@ -0,0 +1,366 @@
+//! The selected shape's strokes in the properties panel.
+//!
+//! Strokes are listed top first, as they stack. Each has a toggle to show
+//! or hide it, its color in hex, width and opacity, then where it lies
+//! against the edge and its dash pattern, then its caps and joins.
+
+use crate::components::{h_stack, icon_button, v_stack};
+use crate::gradient_editor::parse_percent;
+use crate::input::{InputColors, InputState, InputStateEvent};
+use crate::properties::{clickable_toggle, color_swatch, hex_to_hsla, hsla_to_hex, input_field};
+use canvas::Canvas;
+use gpui::{
+    div, px, AppContext, Context, Entity, Focusable, Hsla, IntoElement, ParentElement, Render,
+    StatefulInteractiveElement, Styled, Subscription, Window,
+};
+use node::{ShapeId, Stroke, StrokeAlign, StrokeCap, StrokeJoin};
+use theme::Theme;
+
+pub struct StrokeList {
+    canvas: Entity<Canvas>,
+    theme: Theme,
+    /// Inputs for each stroke, by its index from the bottom. Rows are made
+    /// as strokes are added and kept when they're removed.
+    rows: Vec<StrokeRow>,
+    /// The strokes the inputs last showed, so they're only rewritten when
+    /// they change.
+    shown: Option<(ShapeId, Vec<Stroke>)>,
+    _subscriptions: Vec<Subscription>,
+}
+
+struct StrokeRow {
+    color: Entity<InputState>,
+    width: Entity<InputState>,
+    opacity: Entity<InputState>,
+    dash: Entity<InputState>,
+}
+
+#[derive(Clone, Copy)]
+enum StrokeField {
+    Color,
+    Width,
+    Opacity,
+    Dash,
+}
+
+impl StrokeField {
+    /// Keystrokes in one field coalesce into one undo step.
+    fn undo_key(self) -> &'static str {
+        match self {
+            StrokeField::Color => "stroke_color",
+            StrokeField::Width => "stroke_width",
+            StrokeField::Opacity => "stroke_opacity",
+            StrokeField::Dash => "stroke_dash",
+        }
+    }
+
+    /// Apply what's typed to `stroke`, or return false if it doesn't parse.
+    fn apply(self, text: &str, stroke: &mut Stroke) -> bool {
+        match self {
+            StrokeField::Color => {
+                let Some(color) = hex_to_hsla(text) else {
+                    return false;
+                };
+                // Hex has no alpha, so the stroke keeps its own
+                stroke.color = Hsla { a: stroke.color.a, ..color };
+            }
+            StrokeField::Width => match text.trim().parse::<f32>() {
+                Ok(width) if width >= 0.0 => stroke.width = width,
+                _ => return false,
+            },
+            StrokeField::Opacity => {
+                let Some(opacity) = parse_percent(text) else {
+                    return false;
+                };
+                stroke.opacity = opacity.clamp(0.0, 1.0);
+            }
+            StrokeField::Dash => {
+                let Some(dash) = parse_dash(text) else {
+                    return false;
+                };
+                stroke.dash = dash;
+            }
+        }
+        true
+    }
+
+    fn format(self, stroke: &Stroke) -> String {
+        match self {
+            StrokeField::Color => hsla_to_hex(stroke.color),
+            StrokeField::Width => format!("{}", stroke.width),
+            StrokeField::Opacity => format!("{}%", (stroke.opacity * 100.0).round()),
+            StrokeField::Dash => {
+                let lengths: Vec<String> = stroke.dash.iter().map(f32::to_string).collect();
+                lengths.join(" ")
+            }
+        }
+    }
+}
+
+impl StrokeRow {
+    fn input(&self, field: StrokeField) -> &Entity<InputState> {
+        match field {
+            StrokeField::Color => &self.color,
+            StrokeField::Width => &self.width,
+            StrokeField::Opacity => &self.opacity,
+            StrokeField::Dash => &self.dash,
+        }
+    }
+}
+
+const FIELDS: [StrokeField; 4] =
+    [StrokeField::Color, StrokeField::Width, StrokeField::Opacity, StrokeField::Dash];
+
+impl StrokeList {
+    pub fn new(canvas: Entity<Canvas>, theme: Theme, cx: &mut Context<Self>) -> Self {
+        let canvas_sub = cx.observe(&canvas, |_, _, cx| cx.notify());
+        Self {
+            canvas,
+            theme,
+            rows: Vec::new(),
+            shown: None,
+            _subscriptions: vec![canvas_sub],
+        }
+    }
+
+    /// The selected shape's strokes, if exactly one shape is selected.
+    fn target<'a>(&self, cx: &'a gpui::App) -> Option<(ShapeId, &'a [Stroke])> {
+        let canvas = self.canvas.read(cx);
+        let mut selection = canvas.selection.iter();
+        let (Some(id), None) = (selection.next(), selection.next()) else {
+            return None;
+        };
+        Some((*id, &canvas.get_shape(*id)?.strokes))
+    }
+
+    /// Edit the selected shape's strokes as an undo step.
+    fn update_strokes(
+        &mut self,
+        coalesce: Option<&'static str>,
+        cx: &mut Context<Self>,
+        edit: impl FnOnce(&mut Vec<Stroke>),
+    ) {
+        let Some((id, _)) = self.target(cx) else {
+            return;
+        };
+        self.canvas.update(cx, |canvas, cx| {
+            canvas.edit_shape(id, coalesce, cx, |shape| edit(&mut shape.strokes));
+        });
+    }
+
+    fn update_stroke(&mut self, index: usize, cx: &mut Context<Self>, edit: impl FnOnce(&mut Stroke)) {
+        self.update_strokes(None, cx, |strokes| {
+            if let Some(stroke) = strokes.get_mut(index) {
+                edit(stroke);
+            }
+        });
+    }
+
+    /// Add a stroke on top, as the draw tools would give a new shape.
+    fn add_stroke(&mut self, cx: &mut Context<Self>) {
+        let Some((id, _)) = self.target(cx) else {
+            return;
+        };
+        let canvas = self.canvas.read(cx);
+        let Some(shape) = canvas.get_shape(id) else {
+            return;
+        };
+        let stroke = canvas.default_stroke.clone().with_align(StrokeAlign::for_kind(shape.kind));
+        self.update_strokes(None, cx, |strokes| strokes.push(stroke));
+    }
+
+    fn remove_stroke(&mut self, index: usize, cx: &mut Context<Self>) {
+        self.update_strokes(None, cx, |strokes| {
+            if index < strokes.len() {
+                strokes.remove(index);
+            }
+        });
+    }
+
+    /// Make input rows until there's one for each of `count` strokes.
+    fn ensure_rows(&mut self, count: usize, cx: &mut Context<Self>) {
+        while self.rows.len() < count {
+            let index = self.rows.len();
+            let row = StrokeRow {
+                color: cx.new(InputState::new_singleline),
+                width: cx.new(InputState::new_singleline),
+                opacity: cx.new(InputState::new_singleline),
+                dash: cx.new(InputState::new_singleline),
+            };
+            for field in FIELDS {
+                self._subscriptions.push(cx.subscribe(row.input(field), move |this, input, event, cx| {
+                    this.on_field_event(index, field, input, event, cx)
+                }));
+            }
+            self.rows.push(row);
+        }
+    }
+
+    fn on_field_event(
+        &mut self,
+        index: usize,
+        field: StrokeField,
+        input: Entity<InputState>,
+        event: &InputStateEvent,
+        cx: &mut Context<Self>,
+    ) {
+        if !matches!(event, InputStateEvent::TextChanged) || !input.read(cx).was_focused() {
+            return;
+        }
+        let text = input.read(cx).content().to_string();
+        let mut stroke = match self.target(cx).and_then(|(_, strokes)| strokes.get(index)) {
+            Some(stroke) => stroke.clone(),
+            None => return,
+        };
+        if !field.apply(&text, &mut stroke) {
+            return;
+        }
+        self.update_strokes(Some(field.undo_key()), cx, |strokes| {
+            if let Some(own) = strokes.get_mut(index) {
+                *own = stroke;
+            }
+        });
+    }
+
+    fn sync_inputs(&mut self, window: &Window, cx: &mut Context<Self>) {
+        let Some((id, strokes)) = self.target(cx).map(|(id, strokes)| (id, strokes.to_vec())) else {
+            self.shown = None;
+            return;
+        };
+        if self.shown.as_ref() == Some(&(id, strokes.clone())) {
+            return;
+        }
+        self.ensure_rows(strokes.len(), cx);
+        for (stroke, row) in strokes.iter().zip(&self.rows) {
+            for field in FIELDS {
+                let input = row.input(field);
+                if !input.focus_handle(cx).is_focused(window) {
+                    let content = field.format(stroke);
+                    input.update(cx, |input, cx| input.set_content(content, cx));
+                }
+            }
+        }
+        self.shown = Some((id, strokes));
+    }
+}
+
+impl Render for StrokeList {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        self.sync_inputs(window, cx);
+        let theme = &self.theme;
+        let colors = InputColors {
+            selection: theme.selection.opacity(0.3),
+            cursor: theme.selection,
+            placeholder: theme.ui_text_muted,
+        };
+        let strokes = self.target(cx).map(|(_, strokes)| strokes.to_vec()).unwrap_or_default();
+        let this = cx.entity();
+
+        let header = h_stack()
+            .justify_between()
+            .child(div().text_xs().text_color(theme.ui_text_muted).child("Stroke"))
+            .child(icon_button("add-stroke", "+", theme).on_click({
+                let this = this.clone();
+                move |_, _, cx| this.update(cx, |list, cx| list.add_stroke(cx))
+            }));
+
+        let rows = strokes.iter().zip(&self.rows).enumerate().rev().map(|(index, (stroke, row))| {
+            let toggle = |group, name, selected, edit| {
+                style_toggle(&this, index, group, name, selected, theme, edit)
+            };
+            v_stack()
+                .gap(px(4.0))
+                .opacity(if stroke.visible { 1.0 } else { 0.5 })
+                .child(
+                    h_stack()
+                        .gap(px(8.0))
+                        .child(
+                            icon_button(("toggle-stroke", index), visibility_icon(stroke.visible), theme)
+                                .on_click({
+                                    let this = this.clone();
+                                    move |_, _, cx| {
+                                        this.update(cx, |list, cx| {
+                                            list.update_stroke(index, cx, |s| s.visible = !s.visible)
+                                        })
+                                    }
+                                }),
+                        )
+                        .child(color_swatch(Some(stroke.color.opacity(stroke.opacity)), theme))
+                        .child(input_field("", &row.color, theme, &colors, cx))
+                        .child(icon_button(("remove-stroke", index), "−", theme).on_click({
+                            let this = this.clone();
+                            move |_, _, cx| this.update(cx, |list, cx| list.remove_stroke(index, cx))
+                        })),
+                )
+                .child(
+                    h_stack()
+                        .gap(px(8.0))
+                        .child(input_field("W", &row.width, theme, &colors, cx))
+                        .child(input_field("%", &row.opacity, theme, &colors, cx))
+                        .child(input_field("- -", &row.dash, theme, &colors, cx)),
+                )
+                .child(h_stack().gap(px(2.0)).children(
+                    [
+                        ("Inside", StrokeAlign::Inside),
+                        ("Center", StrokeAlign::Center),
+                        ("Outside", StrokeAlign::Outside),
+                    ]
+                    .map(|(name, align)| {
+                        toggle("align", name, stroke.align == align, Box::new(move |s| s.align = align))
+                    }),
+                ))
+                .child(h_stack().gap(px(2.0)).children(
+                    [("Butt", StrokeCap::Butt), ("Round", StrokeCap::Round), ("Square", StrokeCap::Square)]
+                        .map(|(name, cap)| {
+                            toggle("cap", name, stroke.cap == cap, Box::new(move |s| s.cap = cap))
+                        }),
+                ))
+                .child(h_stack().gap(px(2.0)).children(
+                    [("Miter", StrokeJoin::Miter), ("Round", StrokeJoin::Round), ("Bevel", StrokeJoin::Bevel)]
+                        .map(|(name, join)| {
+                            toggle("join", name, stroke.join == join, Box::new(move |s| s.join = join))
+                        }),
+                ))
+        });
+
+        v_stack().gap(px(8.0)).child(header).children(rows)
+    }
+}
+
+fn visibility_icon(visible: bool) -> &'static str {
+    if visible {
+        "◉"
+    } else {
+        "◌"
+    }
+}
+
+/// A toggle for one choice of a stroke's style, such as its cap, which
+/// `edit` makes.
+fn style_toggle(
+    list: &Entity<StrokeList>,
+    index: usize,
+    group: &str,
+    name: &'static str,
+    selected: bool,
+    theme: &Theme,
+    edit: Box<dyn Fn(&mut Stroke)>,
+) -> impl IntoElement {
+    let list = list.clone();
+    clickable_toggle(
+        name,
+        selected,
+        theme,
+        format!("stroke-{group}-{}-{index}", name.to_lowercase()),
+        move |_, cx| list.update(cx, |list, cx| list.update_stroke(index, cx, &edit)),
+    )
+}
+
+/// Dash and gap lengths typed with spaces or commas between them. Nothing
+/// typed is a solid line.
+fn parse_dash(text: &str) -> Option<Vec<f32>> {
+    text.split([' ', ','])
+        .filter(|part| !part.is_empty())
+        .map(|part| part.parse::<f32>().ok().filter(|length| length.is_finite() && *length >= 0.0))
+        .collect()
+}
@@ -0,0 +1,158 @@
+//! In-place editor for text shapes.
+//!
+//! While the canvas is editing a text shape, a text area is laid over it
+//! at the shape's screen bounds and styled to match, so typing looks like
+//! editing the shape itself. The canvas hides the shape's own text until
+//! the edit finishes.
+
+use crate::input::{text_area, InputColors, InputState, InputStateEvent};
+use canvas::{Canvas, CanvasEvent};
+use gpui::{
+    div, px, AppContext, Context, Entity, FontWeight, Focusable, InteractiveElement, IntoElement,
+    ParentElement, Render, Styled, Subscription, Window,
+};
+use node::TextSizing;
+use theme::Theme;
+
+/// Overlay that edits the canvas's [`editing_text`](Canvas::editing_text).
+///
+/// Render it over the canvas element, in a container with the same origin.
+pub struct TextEditor {
+    canvas: Entity<Canvas>,
+    theme: Theme,
+    input: Entity<InputState>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl TextEditor {
+    pub fn new(
+        canvas: Entity<Canvas>,
+        theme: Theme,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let input = cx.new(InputState::new_multiline);
+        let input_sub = cx.subscribe(&input, Self::on_input_event);
+        let canvas_sub = cx.subscribe_in(&canvas, window, Self::on_canvas_event);
+
+        Self {
+            canvas,
+            theme,
+            input,
+            _subscriptions: vec![input_sub, canvas_sub],
+        }
+    }
+
+    fn on_canvas_event(
+        &mut self,
+        canvas: &Entity<Canvas>,
+        event: &CanvasEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            CanvasEvent::TextEditStarted(shape_id) => {
+                let content = canvas
+                    .read(cx)
+                    .get_shape(*shape_id)
+                    .and_then(|s| s.text.as_ref())
+                    .map(|text| text.content.clone())
+                    .unwrap_or_default();
+                self.input.update(cx, |input, cx| {
+                    let len = content.len();
+                    input.set_content(content, cx);
+                    input.set_selected_range(0..len);
+                });
+                window.focus(&self.input.focus_handle(cx));
+            }
+            // The canvas ended the edit itself, e.g. on a click elsewhere
+            _ if canvas.read(cx).editing_text.is_none()
+                && self.input.focus_handle(cx).is_focused(window) =>
+            {
+                window.blur();
+            }
+            _ => {}
+        }
+        cx.notify();
+    }
+
+    fn on_input_event(
+        &mut self,
+        input: Entity<InputState>,
+        event: &InputStateEvent,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            InputStateEvent::TextChanged => {
+                let content = input.read(cx).content().to_string();
+                self.canvas.update(cx, |canvas, cx| {
+                    if let Some(shape_id) = canvas.editing_text {
+                        canvas.set_text_content(shape_id, &content, cx);
+                    }
+                });
+            }
+            InputStateEvent::Blur => {
+                self.canvas.update(cx, |canvas, cx| canvas.finish_text_edit(cx));
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Render for TextEditor {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        // Fill the container so the editor's offsets are relative to the
+        // canvas origin, like the shape's screen bounds are
+        div()
+            .absolute()
+            .top_0()
+            .left_0()
+            .size_full()
+            .children(self.render_editor(cx))
+    }
+}
+
+impl TextEditor {
+    fn render_editor(&self, cx: &Context<Self>) -> Option<impl IntoElement> {
+        let canvas = self.canvas.read(cx);
+        let shape = canvas.editing_text.and_then(|id| canvas.get_shape(id))?;
+        let text = shape.text.as_ref()?;
+
+        let zoom = canvas.viewport.zoom;
+        let world_pos = canvas
+            .get_cached_world_position(shape.id)
+            .unwrap_or_else(|| shape.world_position(&canvas.shapes));
+        let bounds = canvas
+            .viewport
+            .canvas_to_screen_bounds(world_pos, shape.effective_size());
+        // Auto-width text is refit after each keystroke, a frame behind the
+        // input. Slack keeps the input from wrapping in the meantime.
+        let slack = if text.sizing == TextSizing::AutoWidth {
+            text.font_size * zoom
+        } else {
+            0.0
+        };
+        let color = shape.text_color().unwrap_or(gpui::black());
+
+        Some(
+            div()
+                .id("text-editor")
+                .absolute()
+                .left(px(bounds.origin.x))
+                .top(px(bounds.origin.y))
+                .w(px(bounds.size.width + slack))
+                .h(px(bounds.size.height))
+                .occlude()
+                .font_family(text.font_family.clone())
+                .font_weight(FontWeight(text.font_weight as f32))
+                .text_size(px(text.font_size * zoom))
+                .line_height(px(text.line_height_px() * zoom))
+                .text_color(color)
+                .child(text_area(&self.input, cx).colors(InputColors {
+                    selection: self.theme.selection.opacity(0.3),
+                    cursor: color,
+                    placeholder: self.theme.ui_text_muted,
+                })),
+        )
+    }
+}
@@ -0,0 +1,115 @@
+//! Zoom control overlaid on the canvas.
+//!
+//! Shows the zoom as a percentage that can be typed over, between buttons
+//! that step it down and up a level.
+
+use crate::components::{h_stack, icon_button};
+use crate::input::{input, InputColors, InputState, InputStateEvent};
+use canvas::{Canvas, MAX_ZOOM, MIN_ZOOM};
+use gpui::{
+    px, AppContext, Context, Entity, Focusable, IntoElement, ParentElement, Render,
+    StatefulInteractiveElement, Styled, Subscription, Window,
+};
+use theme::Theme;
+
+pub struct ZoomControl {
+    canvas: Entity<Canvas>,
+    theme: Theme,
+    input: Entity<InputState>,
+    /// Zoom the field last showed, so it's only rewritten when it changes.
+    shown_zoom: Option<f32>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl ZoomControl {
+    pub fn new(canvas: Entity<Canvas>, theme: Theme, cx: &mut Context<Self>) -> Self {
+        let input = cx.new(InputState::new_singleline);
+        let input_sub = cx.subscribe(&input, Self::on_input_event);
+        // The view changes without any canvas event, so follow every update
+        let canvas_sub = cx.observe(&canvas, |_, _, cx| cx.notify());
+
+        Self {
+            canvas,
+            theme,
+            input,
+            shown_zoom: None,
+            _subscriptions: vec![input_sub, canvas_sub],
+        }
+    }
+
+    fn on_input_event(
+        &mut self,
+        input: Entity<InputState>,
+        event: &InputStateEvent,
+        cx: &mut Context<Self>,
+    ) {
+        if !matches!(event, InputStateEvent::Submit | InputStateEvent::Blur) {
+            return;
+        }
+        let zoom = parse_zoom(input.read(cx).content()).map(|zoom| zoom.clamp(MIN_ZOOM, MAX_ZOOM));
+        if let Some(zoom) = zoom {
+            self.canvas.update(cx, |canvas, cx| canvas.set_zoom(zoom, true, cx));
+        }
+        // Show the zoom it's headed to, or put back the one it's at over a typo
+        let shown = zoom.unwrap_or(self.canvas.read(cx).viewport.zoom);
+        input.update(cx, |input, cx| input.set_content(format_zoom(shown), cx));
+        self.shown_zoom = None;
+        cx.notify();
+    }
+
+    fn sync_input(&mut self, window: &Window, cx: &mut Context<Self>) {
+        let zoom = self.canvas.read(cx).viewport.zoom;
+        if self.shown_zoom == Some(zoom) || self.input.focus_handle(cx).is_focused(window) {
+            return;
+        }
+        self.shown_zoom = Some(zoom);
+        self.input.update(cx, |input, cx| input.set_content(format_zoom(zoom), cx));
+    }
+}
+
+impl Render for ZoomControl {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        self.sync_input(window, cx);
+        let theme = &self.theme;
+        let colors = InputColors {
+            selection: theme.selection.opacity(0.3),
+            cursor: theme.selection,
+            placeholder: theme.ui_text_muted,
+        };
+        let zoom_out = self.canvas.clone();
+        let zoom_in = self.canvas.clone();
+
+        h_stack()
+            .gap(px(2.0))
+            .p(px(2.0))
+            .bg(theme.ui_background)
+            .border_1()
+            .border_color(theme.ui_border)
+            .rounded(px(4.0))
+            .child(icon_button("zoom-out", "-", theme).on_click(move |_, _, cx| {
+                zoom_out.update(cx, |canvas, cx| canvas.step_zoom(false, true, cx));
+            }))
+            .child(
+                input(&self.input, cx)
+                    .colors(colors)
+                    .w(px(52.0))
+                    .h(px(22.0))
+                    .px(px(6.0))
+                    .text_xs(),
+            )
+            .child(icon_button("zoom-in", "+", theme).on_click(move |_, _, cx| {
+                zoom_in.update(cx, |canvas, cx| canvas.step_zoom(true, true, cx));
+            }))
+    }
+}
+
+/// A zoom as a whole percentage.
+fn format_zoom(zoom: f32) -> String {
+    format!("{}%", (zoom * 100.0).round())
+}
+
+/// A zoom typed as a percentage, with or without the sign.
+fn parse_zoom(text: &str) -> Option<f32> {
+    let percent: f32 = text.trim().trim_end_matches('%').trim_end().parse().ok()?;
+    (percent.is_finite() && percent > 0.0).then_some(percent / 100.0)
+}